@@ -0,0 +1,296 @@
+// ============================================
+// HISTORY HUB CLI
+// list / search / export / stats over Claude Code session files,
+// built on history-core so scripts and CI don't need Node
+// ============================================
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use history_core::{parse_session, select_range, set_thinking_policy, summarize, ExportRange, Message, SessionSummary, ThinkingPolicy};
+
+#[derive(Parser)]
+#[command(name = "history-hub-cli", version, about = "Query Claude Code session history")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// How thinking blocks appear in searched and exported text: include, exclude or placeholder
+    #[arg(long, global = true, default_value = "include", value_parser = parse_thinking_policy)]
+    thinking: ThinkingPolicy,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List session files with their summaries
+    List {
+        /// Directory to scan (default ~/.claude/projects)
+        root: Option<PathBuf>,
+        /// Print JSON instead of a table
+        #[arg(long)]
+        json: bool,
+    },
+    /// Find messages containing a text (case-insensitive)
+    Search {
+        query: String,
+        /// Directory to scan (default ~/.claude/projects)
+        #[arg(long)]
+        root: Option<PathBuf>,
+        /// Stop after this many matches
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+    },
+    /// Convert one session file to Markdown or JSON on stdout
+    Export {
+        file: PathBuf,
+        #[arg(long, value_enum, default_value_t = ExportFormat::Markdown)]
+        format: ExportFormat,
+        /// First message to include (message id)
+        #[arg(long)]
+        from: Option<String>,
+        /// Last message to include (message id)
+        #[arg(long)]
+        to: Option<String>,
+        /// First message to include (0-based index, ignored with --from)
+        #[arg(long)]
+        from_index: Option<usize>,
+        /// Last message to include (0-based index, ignored with --to)
+        #[arg(long)]
+        to_index: Option<usize>,
+    },
+    /// Totals across all sessions
+    Stats {
+        /// Directory to scan (default ~/.claude/projects)
+        root: Option<PathBuf>,
+        /// Print JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ExportFormat {
+    Markdown,
+    Json,
+}
+
+const SNIPPET_CHARS: usize = 120;
+
+fn parse_thinking_policy(value: &str) -> Result<ThinkingPolicy, String> {
+    ThinkingPolicy::parse(value).ok_or_else(|| format!("expected include, exclude or placeholder, got {}", value))
+}
+
+fn default_root() -> PathBuf {
+    let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE")).unwrap_or_default();
+    PathBuf::from(home).join(".claude").join("projects")
+}
+
+/// *.jsonl files up to three levels below root, sorted (same walk as the addon)
+fn collect_session_files(root: &Path) -> Result<Vec<PathBuf>, String> {
+    fn walk(dir: &Path, depth: usize, files: &mut Vec<PathBuf>) {
+        let Ok(entries) = std::fs::read_dir(dir) else { return };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                if depth < 3 {
+                    walk(&path, depth + 1, files);
+                }
+            } else if path.extension().is_some_and(|ext| ext == "jsonl") {
+                files.push(path);
+            }
+        }
+    }
+
+    if !root.is_dir() {
+        return Err(format!("Not a directory: {}", root.display()));
+    }
+
+    let mut files = Vec::new();
+    walk(root, 0, &mut files);
+    files.sort();
+    Ok(files)
+}
+
+fn read_session(path: &Path) -> Result<Vec<Message>, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("Cannot open file: {}", e))?;
+    Ok(parse_session(&String::from_utf8_lossy(&bytes)))
+}
+
+/// Every readable session under root (unreadable files are skipped)
+fn read_sessions(root: Option<PathBuf>) -> Result<Vec<(PathBuf, Vec<Message>)>, String> {
+    let root = root.unwrap_or_else(default_root);
+    Ok(collect_session_files(&root)?
+        .into_iter()
+        .filter_map(|path| read_session(&path).ok().map(|messages| (path, messages)))
+        .collect())
+}
+
+/// One line of text around the first match
+fn snippet(content: &str, query_lower: &str) -> String {
+    let lower = content.to_lowercase();
+    let start_byte = lower.find(query_lower).unwrap_or(0);
+    // Lowercasing can shift byte offsets, so work in chars from here
+    let start_char = lower[..start_byte].chars().count().saturating_sub(SNIPPET_CHARS / 4);
+
+    let text: String = content
+        .chars()
+        .skip(start_char)
+        .take(SNIPPET_CHARS)
+        .map(|c| if c.is_whitespace() { ' ' } else { c })
+        .collect();
+    if start_char > 0 {
+        format!("…{}", text.trim())
+    } else {
+        text.trim().to_string()
+    }
+}
+
+fn to_markdown(summary: &SessionSummary, messages: &[Message]) -> String {
+    let mut out = format!("# Session {}\n\n", summary.session_id);
+    if let (Some(first), Some(last)) = (&summary.first_timestamp, &summary.last_timestamp) {
+        out.push_str(&format!("_{} – {}_\n\n", first, last));
+    }
+
+    for message in messages {
+        let who = if message.role == "user" { "User" } else { "Assistant" };
+        out.push_str(&format!("## {} · {}\n\n{}\n\n", who, message.timestamp, message.content.trim()));
+    }
+    out
+}
+
+// ============================================
+// SUBCOMMANDS
+// ============================================
+
+fn list(root: Option<PathBuf>, json: bool) -> Result<(), String> {
+    let sessions = read_sessions(root)?;
+
+    if json {
+        let rows: Vec<serde_json::Value> = sessions
+            .iter()
+            .map(|(path, messages)| {
+                let mut row = serde_json::to_value(summarize(messages)).unwrap_or_default();
+                row["filePath"] = serde_json::Value::String(path.display().to_string());
+                row
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&rows).unwrap_or_default());
+        return Ok(());
+    }
+
+    for (path, messages) in &sessions {
+        let summary = summarize(messages);
+        println!(
+            "{}\t{}\t{}\t{}",
+            summary.session_id,
+            summary.message_count,
+            summary.last_timestamp.as_deref().unwrap_or("-"),
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+fn search(query: &str, root: Option<PathBuf>, limit: usize) -> Result<(), String> {
+    let query_lower = query.to_lowercase();
+    let mut found = 0;
+
+    for (path, messages) in read_sessions(root)? {
+        for message in messages.iter().filter(|m| m.content.to_lowercase().contains(&query_lower)) {
+            println!(
+                "{}\t{}\t{}\t{}\t{}",
+                message.session_id,
+                message.timestamp,
+                message.role,
+                snippet(&message.content, &query_lower),
+                path.display()
+            );
+            found += 1;
+            if found >= limit {
+                return Ok(());
+            }
+        }
+    }
+    Ok(())
+}
+
+fn export(file: &Path, format: ExportFormat, range: &ExportRange) -> Result<(), String> {
+    let messages = read_session(file)?;
+    let selected = select_range(&messages, |m| &m.message_id, range)?;
+    match format {
+        ExportFormat::Json => println!("{}", serde_json::to_string_pretty(selected).unwrap_or_default()),
+        // Header still describes the whole session
+        ExportFormat::Markdown => print!("{}", to_markdown(&summarize(&messages), selected)),
+    }
+    Ok(())
+}
+
+fn stats(root: Option<PathBuf>, json: bool) -> Result<(), String> {
+    let sessions = read_sessions(root)?;
+    let summaries: Vec<SessionSummary> = sessions.iter().map(|(_, messages)| summarize(messages)).collect();
+
+    let messages: i64 = summaries.iter().map(|s| s.message_count as i64).sum();
+    let input: i64 = summaries.iter().map(|s| s.total_input_tokens).sum();
+    let output: i64 = summaries.iter().map(|s| s.total_output_tokens).sum();
+
+    if json {
+        let value = serde_json::json!({
+            "sessions": summaries.len(),
+            "messages": messages,
+            "inputTokens": input,
+            "outputTokens": output,
+        });
+        println!("{}", serde_json::to_string_pretty(&value).unwrap_or_default());
+    } else {
+        println!("sessions\t{}", summaries.len());
+        println!("messages\t{}", messages);
+        println!("input_tokens\t{}", input);
+        println!("output_tokens\t{}", output);
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    set_thinking_policy(cli.thinking);
+    let result = match cli.command {
+        Command::List { root, json } => list(root, json),
+        Command::Search { query, root, limit } => search(&query, root, limit),
+        Command::Export { file, format, from, to, from_index, to_index } => {
+            let range = ExportRange { from_message_id: from, to_message_id: to, from_index, to_index };
+            export(&file, format, &range)
+        }
+        Command::Stats { root, json } => stats(root, json),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("history-hub-cli: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snippet_and_markdown() {
+        let long = format!("{} needle here\nand more", "word ".repeat(60));
+        let text = snippet(&long, "needle");
+        assert!(text.starts_with('…') && text.contains("needle here and more"));
+
+        let messages = parse_session(
+            r#"{"type":"user","uuid":"1","sessionId":"s1","timestamp":"2024-01-01T10:00:00Z","message":{"role":"user","content":"Fix the bug"}}"#,
+        );
+        let markdown = to_markdown(&summarize(&messages), &messages);
+        assert!(markdown.starts_with("# Session s1"));
+        assert!(markdown.contains("## User · 2024-01-01T10:00:00Z\n\nFix the bug"));
+    }
+}
@@ -0,0 +1,891 @@
+// ============================================
+// HISTORY CORE
+// Session log types and parsing shared by the NAPI addon, the WASM
+// build and the CLI - no napi and no filesystem access
+// ============================================
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+// ============================================
+// DATA STRUCTURES
+// ============================================
+
+/// Content item variants - supports all Claude content types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(tag = "type")]
+pub enum ContentItem {
+    #[serde(rename = "text")]
+    Text {
+        text: String
+    },
+
+    #[serde(rename = "thinking")]
+    Thinking {
+        thinking: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        signature: Option<String>,
+    },
+
+    #[serde(rename = "tool_use")]
+    ToolUse {
+        id: String,
+        name: String,
+        input: serde_json::Value,
+    },
+
+    #[serde(rename = "tool_result")]
+    ToolResult {
+        tool_use_id: String,
+        content: serde_json::Value,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        is_error: Option<bool>,
+    },
+
+    #[serde(rename = "image")]
+    Image {
+        source: ImageSource,
+    },
+}
+
+/// Image source data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ImageSource {
+    #[serde(rename = "type")]
+    pub source_type: String,  // "base64"
+    pub media_type: String,   // "image/png", "image/jpeg", etc.
+    pub data: String,         // base64 encoded data
+}
+
+/// Token usage with cache support
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub input_tokens: i32,
+    pub output_tokens: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_creation_input_tokens: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_read_input_tokens: Option<i32>,
+}
+
+/// Message object - supports both string and array content
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageObject {
+    pub role: String,
+
+    // Content can be string (user) or array (assistant)
+    #[serde(deserialize_with = "deserialize_content")]
+    pub content: Vec<ContentItem>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_reason: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<TokenUsage>,
+}
+
+/// Custom deserializer for content field (handles both string and array)
+fn deserialize_content<'de, D>(deserializer: D) -> std::result::Result<Vec<ContentItem>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::Error;
+
+    let value: serde_json::Value = serde::Deserialize::deserialize(deserializer)?;
+
+    match value {
+        // String content (user messages)
+        serde_json::Value::String(s) => Ok(vec![ContentItem::Text { text: s }]),
+
+        // Array content (assistant messages)
+        serde_json::Value::Array(arr) => {
+            let items: std::result::Result<Vec<ContentItem>, _> = arr
+                .into_iter()
+                .map(|v| serde_json::from_value(v).map_err(Error::custom))
+                .collect();
+            items
+        }
+
+        _ => Err(Error::custom("Content must be string or array")),
+    }
+}
+
+/// Raw log entry from JSONL
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RawLogEntry {
+    #[serde(rename = "type")]
+    pub entry_type: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "parentUuid")]
+    pub parent_uuid: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "sessionId")]
+    pub session_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
+
+    // API request the entry came from, shared by the entries split from one response
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "requestId")]
+    pub request_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<MessageObject>,
+
+    // Additional fields for completeness
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "leafUuid")]
+    pub leaf_uuid: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "isSidechain")]
+    pub is_sidechain: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "userType")]
+    pub user_type: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "isMeta")]
+    pub is_meta: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "isApiErrorMessage")]
+    pub is_api_error_message: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "isCompactSummary")]
+    pub is_compact_summary: Option<bool>,
+
+    // System entry subtype, e.g. "compact_boundary"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subtype: Option<String>,
+}
+
+/// A user or assistant message ready for display
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Message {
+    pub message_id: String,
+    /// Stable across re-parses and merged files, see canonical_message_id
+    pub canonical_id: String,
+    pub session_id: String,
+    pub role: String,
+    pub content: String,  // Main text content (merged)
+    pub timestamp: String,
+
+    // Content details (serialized as JSON)
+    pub raw_content: String,  // Full content array as JSON
+    pub has_thinking: bool,
+    pub has_tool_use: bool,
+    pub has_images: bool,
+
+    // Optional fields
+    pub parent_id: Option<String>,
+    pub model: Option<String>,
+    pub stop_reason: Option<String>,
+
+    // Token usage
+    pub input_tokens: Option<i32>,
+    pub output_tokens: Option<i32>,
+    pub cache_creation_tokens: Option<i32>,
+    pub cache_read_tokens: Option<i32>,
+
+    // Additional metadata
+    pub is_sidechain: Option<bool>,
+    pub user_type: Option<String>,
+
+    // True when the timestamp was missing/invalid and inferred from neighbors
+    pub timestamp_inferred: bool,
+
+    // Size metrics (bytes as stored in the log, images base64-encoded)
+    pub content_bytes: i64,
+    pub tool_result_bytes: i64,
+    pub image_bytes: i64,
+}
+
+/// Byte sizes of a content array, as returned by content_sizes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ContentSizes {
+    /// All blocks together
+    pub content_bytes: i64,
+    pub tool_result_bytes: i64,
+    pub image_bytes: i64,
+}
+
+/// Counts and totals for one session
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSummary {
+    pub session_id: String,
+    pub message_count: i32,
+    pub user_message_count: i32,
+    pub assistant_message_count: i32,
+    pub first_timestamp: Option<String>,
+    pub last_timestamp: Option<String>,
+    pub total_input_tokens: i64,
+    pub total_output_tokens: i64,
+    pub has_thinking: bool,
+    pub has_tool_use: bool,
+}
+
+/// Part of a conversation to export; ids win over indexes, bounds are inclusive
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportRange {
+    pub from_message_id: Option<String>,
+    pub to_message_id: Option<String>,
+    /// 0-based message index
+    pub from_index: Option<usize>,
+    pub to_index: Option<usize>,
+}
+
+/// What happened in a session, independent of how the log spelled it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case", rename_all_fields = "camelCase")]
+pub enum SessionEvent {
+    /// A user or assistant message; its tool calls and results follow as their own events
+    MessageAdded {
+        uuid: Option<String>,
+        parent_uuid: Option<String>,
+        role: String,
+        timestamp: Option<String>,
+        text: String,
+        model: Option<String>,
+        is_sidechain: bool,
+        input_tokens: Option<i32>,
+        output_tokens: Option<i32>,
+    },
+    ToolInvoked {
+        tool_use_id: String,
+        name: String,
+        input: serde_json::Value,
+        message_uuid: Option<String>,
+        timestamp: Option<String>,
+    },
+    ToolCompleted {
+        tool_use_id: String,
+        /// Name of the matching ToolInvoked, None when the call was not seen
+        name: Option<String>,
+        is_error: bool,
+        output: String,
+        message_uuid: Option<String>,
+        timestamp: Option<String>,
+    },
+    SummaryWritten {
+        summary: String,
+        leaf_uuid: Option<String>,
+    },
+    /// A compact_boundary and the summary message that follows it, as one event
+    Compaction {
+        uuid: Option<String>,
+        timestamp: Option<String>,
+        summary: Option<String>,
+    },
+}
+
+// ============================================
+// THINKING POLICY
+// ============================================
+
+/// How thinking blocks show up in extracted text (and so in message
+/// content, exports and search)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ThinkingPolicy {
+    /// "[Thinking]" followed by the thinking text
+    #[default]
+    Include,
+    /// Left out entirely
+    Exclude,
+    /// A bare "[Thinking]" marker where the block was
+    Placeholder,
+}
+
+impl ThinkingPolicy {
+    pub fn parse(value: &str) -> Option<ThinkingPolicy> {
+        match value.trim().to_lowercase().as_str() {
+            "include" => Some(ThinkingPolicy::Include),
+            "exclude" => Some(ThinkingPolicy::Exclude),
+            "placeholder" => Some(ThinkingPolicy::Placeholder),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ThinkingPolicy::Include => "include",
+            ThinkingPolicy::Exclude => "exclude",
+            ThinkingPolicy::Placeholder => "placeholder",
+        }
+    }
+
+    /// Text standing for one thinking block, None when it is left out
+    pub fn render(self, thinking: &str) -> Option<String> {
+        match self {
+            ThinkingPolicy::Include => Some(format!("[Thinking]\n{}", thinking)),
+            ThinkingPolicy::Exclude => None,
+            ThinkingPolicy::Placeholder => Some("[Thinking]".to_string()),
+        }
+    }
+}
+
+static THINKING_POLICY: AtomicU8 = AtomicU8::new(ThinkingPolicy::Include as u8);
+
+/// Process-wide policy used by extract_text_content
+pub fn set_thinking_policy(policy: ThinkingPolicy) {
+    THINKING_POLICY.store(policy as u8, Ordering::Relaxed);
+}
+
+pub fn thinking_policy() -> ThinkingPolicy {
+    match THINKING_POLICY.load(Ordering::Relaxed) {
+        1 => ThinkingPolicy::Exclude,
+        2 => ThinkingPolicy::Placeholder,
+        _ => ThinkingPolicy::Include,
+    }
+}
+
+// ============================================
+// PARSING FUNCTIONS
+// ============================================
+
+/// Parse a JSONL line with better error handling
+pub fn parse_jsonl_line(line: &str) -> std::result::Result<RawLogEntry, serde_json::Error> {
+    serde_json::from_str(line)
+}
+
+/// Extract all text content from content array, thinking as set by set_thinking_policy
+pub fn extract_text_content(content_items: &[ContentItem]) -> String {
+    extract_text_content_with(content_items, thinking_policy())
+}
+
+/// extract_text_content with an explicit thinking policy
+pub fn extract_text_content_with(content_items: &[ContentItem], policy: ThinkingPolicy) -> String {
+    content_items
+        .iter()
+        .filter_map(|item| match item {
+            ContentItem::Text { text } => Some(text.clone()),
+            ContentItem::Thinking { thinking, .. } => policy.render(thinking),
+            _ => None,
+        })
+        .collect::<Vec<String>>()
+        .join("\n\n")
+}
+
+/// Every block in file order, each under a "[#index type]" header (index into
+/// the content array, so it lines up with raw_content), thinking as set by
+/// set_thinking_policy
+pub fn ordered_content(content_items: &[ContentItem]) -> String {
+    let policy = thinking_policy();
+    content_items
+        .iter()
+        .enumerate()
+        .filter_map(|(index, item)| {
+            let (header, body) = match item {
+                ContentItem::Text { text } => ("text".to_string(), Some(text.clone())),
+                ContentItem::Thinking { thinking, .. } => match policy {
+                    ThinkingPolicy::Include => ("thinking".to_string(), Some(thinking.clone())),
+                    ThinkingPolicy::Exclude => return None,
+                    ThinkingPolicy::Placeholder => ("thinking".to_string(), None),
+                },
+                ContentItem::ToolUse { name, input, .. } => (format!("tool_use {}", name), Some(input.to_string())),
+                ContentItem::ToolResult { tool_use_id, content, is_error } => {
+                    let error = if *is_error == Some(true) { " error" } else { "" };
+                    (format!("tool_result {}{}", tool_use_id, error), Some(tool_result_text(content)))
+                }
+                ContentItem::Image { source } => (format!("image {}", source.media_type), None),
+            };
+            Some(match body {
+                Some(body) => format!("[#{} {}]\n{}", index, header, body),
+                None => format!("[#{} {}]", index, header),
+            })
+        })
+        .collect::<Vec<String>>()
+        .join("\n\n")
+}
+
+/// Plain text of a tool_result payload (string or array of text blocks)
+pub fn tool_result_text(content: &serde_json::Value) -> String {
+    match content {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Array(items) => items
+            .iter()
+            .filter_map(|item| item.get("text").and_then(|t| t.as_str()))
+            .collect::<Vec<&str>>()
+            .join("\n"),
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Check if content has thinking
+pub fn has_thinking(content_items: &[ContentItem]) -> bool {
+    content_items.iter().any(|item| matches!(item, ContentItem::Thinking { .. }))
+}
+
+/// Check if content has tool use
+pub fn has_tool_use(content_items: &[ContentItem]) -> bool {
+    content_items.iter().any(|item| matches!(item, ContentItem::ToolUse { .. }))
+}
+
+/// Check if content has images
+pub fn has_images(content_items: &[ContentItem]) -> bool {
+    content_items.iter().any(|item| matches!(item, ContentItem::Image { .. }))
+}
+
+/// How many bytes each kind of block takes in the log
+pub fn content_sizes(content_items: &[ContentItem]) -> ContentSizes {
+    let json_len = |value: &serde_json::Value| match value {
+        serde_json::Value::String(s) => s.len() as i64,
+        serde_json::Value::Null => 0,
+        other => other.to_string().len() as i64,
+    };
+
+    let mut sizes = ContentSizes::default();
+    for item in content_items {
+        let bytes = match item {
+            ContentItem::Text { text } => text.len() as i64,
+            ContentItem::Thinking { thinking, .. } => thinking.len() as i64,
+            ContentItem::ToolUse { input, .. } => json_len(input),
+            ContentItem::ToolResult { content, .. } => {
+                let bytes = json_len(content);
+                sizes.tool_result_bytes += bytes;
+                bytes
+            }
+            ContentItem::Image { source } => {
+                sizes.image_bytes += source.data.len() as i64;
+                source.data.len() as i64
+            }
+        };
+        sizes.content_bytes += bytes;
+    }
+    sizes
+}
+
+/// FNV-1a, stable across runs and Rust versions (unlike DefaultHasher)
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// Stable message id: a hash of sessionId and uuid, or of the text and
+/// timestamp when the entry has no uuid
+pub fn canonical_message_id(session_id: Option<&str>, uuid: Option<&str>, text: &str, timestamp: Option<&str>) -> String {
+    let key = match uuid {
+        Some(uuid) => format!("{}\0{}", session_id.unwrap_or(""), uuid),
+        None => format!("\0{}\0{}", text, timestamp.unwrap_or("")),
+    };
+    format!("m-{:016x}", fnv1a(key.as_bytes()))
+}
+
+/// canonical_message_id of a raw entry. Thinking is always hashed in, so
+/// the id does not change with set_thinking_policy
+pub fn entry_canonical_id(entry: &RawLogEntry) -> String {
+    let text = match (&entry.uuid, &entry.message) {
+        (None, Some(message)) => extract_text_content_with(&message.content, ThinkingPolicy::Include),
+        _ => String::new(),
+    };
+    canonical_message_id(entry.session_id.as_deref(), entry.uuid.as_deref(), &text, entry.timestamp.as_deref())
+}
+
+/// Convert RawLogEntry to Message with full content support
+pub fn entry_to_message(entry: RawLogEntry) -> Option<Message> {
+    entry_to_message_with(entry, true)
+}
+
+/// entry_to_message, optionally without the raw_content JSON
+pub fn entry_to_message_with(entry: RawLogEntry, include_raw_content: bool) -> Option<Message> {
+    // Only process user and assistant messages
+    if entry.entry_type != "user" && entry.entry_type != "assistant" {
+        return None;
+    }
+
+    let canonical_id = entry_canonical_id(&entry);
+    let message = entry.message?;
+
+    // Extract text content
+    let content = extract_text_content(&message.content);
+
+    // Serialize full content as JSON for frontend
+    let raw_content = if include_raw_content {
+        serde_json::to_string(&message.content).unwrap_or_default()
+    } else {
+        String::new()
+    };
+
+    // Detect content features
+    let has_thinking_flag = has_thinking(&message.content);
+    let has_tool_use_flag = has_tool_use(&message.content);
+    let has_images_flag = has_images(&message.content);
+    let sizes = content_sizes(&message.content);
+
+    // Get token usage
+    let (input_tokens, output_tokens, cache_creation, cache_read) = if let Some(usage) = message.usage {
+        (
+            Some(usage.input_tokens),
+            Some(usage.output_tokens),
+            usage.cache_creation_input_tokens,
+            usage.cache_read_input_tokens,
+        )
+    } else {
+        (None, None, None, None)
+    };
+
+    Some(Message {
+        message_id: entry.uuid.unwrap_or_else(|| "unknown".to_string()),
+        canonical_id,
+        session_id: entry.session_id.unwrap_or_else(|| "unknown".to_string()),
+        role: message.role,
+        content,
+        timestamp: entry.timestamp.unwrap_or_else(|| "unknown".to_string()),
+        raw_content,
+        has_thinking: has_thinking_flag,
+        has_tool_use: has_tool_use_flag,
+        has_images: has_images_flag,
+        parent_id: entry.parent_uuid,
+        model: message.model,
+        stop_reason: message.stop_reason,
+        input_tokens,
+        output_tokens,
+        cache_creation_tokens: cache_creation,
+        cache_read_tokens: cache_read,
+        is_sidechain: entry.is_sidechain,
+        user_type: entry.user_type,
+        timestamp_inferred: false,
+        content_bytes: sizes.content_bytes,
+        tool_result_bytes: sizes.tool_result_bytes,
+        image_bytes: sizes.image_bytes,
+    })
+}
+
+/// Parse a whole JSONL session held in memory; bad lines are skipped
+pub fn parse_session(text: &str) -> Vec<Message> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| parse_jsonl_line(line).ok())
+        .filter_map(entry_to_message)
+        .collect()
+}
+
+/// Summarize already parsed messages
+pub fn summarize(messages: &[Message]) -> SessionSummary {
+    let mut summary = SessionSummary {
+        session_id: String::from("unknown"),
+        ..Default::default()
+    };
+
+    for message in messages {
+        if message.session_id != "unknown" {
+            summary.session_id = message.session_id.clone();
+        }
+        summary.message_count += 1;
+        match message.role.as_str() {
+            "user" => summary.user_message_count += 1,
+            "assistant" => summary.assistant_message_count += 1,
+            _ => {}
+        }
+        if message.timestamp != "unknown" {
+            if summary.first_timestamp.is_none() {
+                summary.first_timestamp = Some(message.timestamp.clone());
+            }
+            summary.last_timestamp = Some(message.timestamp.clone());
+        }
+        summary.total_input_tokens += message.input_tokens.unwrap_or(0) as i64;
+        summary.total_output_tokens += message.output_tokens.unwrap_or(0) as i64;
+        summary.has_thinking |= message.has_thinking;
+        summary.has_tool_use |= message.has_tool_use;
+    }
+
+    summary
+}
+
+/// The messages inside range (everything when no bound is set)
+pub fn select_range<'a, T>(
+    items: &'a [T],
+    message_id: impl Fn(&T) -> &str,
+    range: &ExportRange,
+) -> std::result::Result<&'a [T], String> {
+    let position = |id: &String| {
+        items
+            .iter()
+            .position(|item| message_id(item) == id)
+            .ok_or_else(|| format!("Message not found: {}", id))
+    };
+
+    let start = match &range.from_message_id {
+        Some(id) => position(id)?,
+        None => range.from_index.unwrap_or(0),
+    };
+    let end = match &range.to_message_id {
+        Some(id) => position(id)? + 1,
+        None => range.to_index.map_or(items.len(), |i| i.saturating_add(1)),
+    }
+    .min(items.len());
+
+    if start >= end {
+        return Ok(&[]);
+    }
+    Ok(&items[start..end])
+}
+
+// ============================================
+// EVENT NORMALIZATION
+// ============================================
+
+/// Turns raw entries into SessionEvents one entry at a time, so files can
+/// be normalized while they are read
+#[derive(Debug, Default)]
+pub struct EventNormalizer {
+    events: Vec<SessionEvent>,
+    tool_names: HashMap<String, String>,
+    /// Index of the last Compaction while it still waits for its summary
+    open_compaction: Option<usize>,
+}
+
+impl EventNormalizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, entry: &RawLogEntry) {
+        if entry.entry_type == "summary" {
+            if let Some(summary) = &entry.summary {
+                self.events.push(SessionEvent::SummaryWritten { summary: summary.clone(), leaf_uuid: entry.leaf_uuid.clone() });
+            }
+            return;
+        }
+        if entry.subtype.as_deref() == Some("compact_boundary") {
+            self.open_compaction = Some(self.events.len());
+            self.events.push(SessionEvent::Compaction { uuid: entry.uuid.clone(), timestamp: entry.timestamp.clone(), summary: None });
+            return;
+        }
+        if entry.entry_type != "user" && entry.entry_type != "assistant" {
+            return;
+        }
+        let Some(message) = &entry.message else { return };
+
+        if entry.is_compact_summary == Some(true) {
+            let text = extract_text_content(&message.content);
+            match self.open_compaction.take().and_then(|i| self.events.get_mut(i)) {
+                Some(SessionEvent::Compaction { summary, .. }) => *summary = Some(text),
+                _ => self.events.push(SessionEvent::Compaction {
+                    uuid: entry.uuid.clone(),
+                    timestamp: entry.timestamp.clone(),
+                    summary: Some(text),
+                }),
+            }
+            return;
+        }
+        self.open_compaction = None;
+
+        let usage = message.usage.as_ref();
+        self.events.push(SessionEvent::MessageAdded {
+            uuid: entry.uuid.clone(),
+            parent_uuid: entry.parent_uuid.clone(),
+            role: message.role.clone(),
+            timestamp: entry.timestamp.clone(),
+            text: extract_text_content(&message.content),
+            model: message.model.clone(),
+            is_sidechain: entry.is_sidechain.unwrap_or(false),
+            input_tokens: usage.map(|u| u.input_tokens),
+            output_tokens: usage.map(|u| u.output_tokens),
+        });
+
+        for item in &message.content {
+            match item {
+                ContentItem::ToolUse { id, name, input } => {
+                    self.tool_names.insert(id.clone(), name.clone());
+                    self.events.push(SessionEvent::ToolInvoked {
+                        tool_use_id: id.clone(),
+                        name: name.clone(),
+                        input: input.clone(),
+                        message_uuid: entry.uuid.clone(),
+                        timestamp: entry.timestamp.clone(),
+                    });
+                }
+                ContentItem::ToolResult { tool_use_id, content, is_error } => {
+                    self.events.push(SessionEvent::ToolCompleted {
+                        tool_use_id: tool_use_id.clone(),
+                        name: self.tool_names.get(tool_use_id).cloned(),
+                        is_error: is_error.unwrap_or(false),
+                        output: tool_result_text(content),
+                        message_uuid: entry.uuid.clone(),
+                        timestamp: entry.timestamp.clone(),
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Events normalized so far, in log order
+    pub fn events(&self) -> &[SessionEvent] {
+        &self.events
+    }
+
+    pub fn finish(self) -> Vec<SessionEvent> {
+        self.events
+    }
+}
+
+/// Normalize a whole parsed session
+pub fn normalize_entries<'a>(entries: impl IntoIterator<Item = &'a RawLogEntry>) -> Vec<SessionEvent> {
+    let mut normalizer = EventNormalizer::new();
+    for entry in entries {
+        normalizer.push(entry);
+    }
+    normalizer.finish()
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_summarize_session() {
+        let text = [
+            r#"{"type":"summary","summary":"Fix bug","leafUuid":"2"}"#,
+            r#"{"type":"user","uuid":"1","sessionId":"s1","timestamp":"2024-01-01T10:00:00Z","message":{"role":"user","content":"Hello"}}"#,
+            "not json",
+            r#"{"type":"assistant","uuid":"2","sessionId":"s1","timestamp":"2024-01-01T10:00:05Z","message":{"role":"assistant","content":[{"type":"thinking","thinking":"hm"},{"type":"text","text":"Hi"}],"usage":{"input_tokens":10,"output_tokens":3}}}"#,
+        ]
+        .join("\n");
+
+        let messages = parse_session(&text);
+        assert_eq!(messages.len(), 2);
+        assert!(messages[1].content.ends_with("Hi"));
+
+        assert_eq!(messages[0].canonical_id, canonical_message_id(Some("s1"), Some("1"), "ignored", None));
+        assert_ne!(messages[0].canonical_id, messages[1].canonical_id);
+        let no_uuid = parse_jsonl_line(r#"{"type":"user","timestamp":"t","message":{"role":"user","content":"x"}}"#).unwrap();
+        assert_eq!(entry_canonical_id(&no_uuid), entry_to_message(no_uuid.clone()).unwrap().canonical_id);
+
+        let summary = summarize(&messages);
+        assert_eq!(summary.session_id, "s1");
+        assert_eq!(summary.user_message_count, 1);
+        assert_eq!(summary.total_input_tokens, 10);
+        assert_eq!(summary.last_timestamp.as_deref(), Some("2024-01-01T10:00:05Z"));
+        assert!(summary.has_thinking && !summary.has_tool_use);
+    }
+
+    #[test]
+    fn test_content_sizes() {
+        let line = r#"{"type":"user","message":{"role":"user","content":[
+            {"type":"text","text":"see"},
+            {"type":"tool_result","tool_use_id":"t1","content":"0123456789"},
+            {"type":"image","source":{"type":"base64","media_type":"image/png","data":"AAAA"}}
+        ]}}"#;
+        let message = entry_to_message(parse_jsonl_line(line).unwrap()).unwrap();
+        assert_eq!((message.content_bytes, message.tool_result_bytes, message.image_bytes), (17, 10, 4));
+    }
+
+    #[test]
+    fn test_ordered_content() {
+        let line = r#"{"type":"assistant","message":{"role":"assistant","content":[
+            {"type":"text","text":"Checking."},
+            {"type":"tool_use","id":"t1","name":"Bash","input":{"command":"ls"}},
+            {"type":"text","text":"Found it."}
+        ]}}"#;
+        let content = parse_jsonl_line(line).unwrap().message.unwrap().content;
+        assert_eq!(ordered_content(&content), "[#0 text]\nChecking.\n\n[#1 tool_use Bash]\n{\"command\":\"ls\"}\n\n[#2 text]\nFound it.");
+    }
+
+    #[test]
+    fn test_thinking_policy() {
+        let line = r#"{"type":"assistant","message":{"role":"assistant","content":[
+            {"type":"thinking","thinking":"hmm","signature":"x"},
+            {"type":"text","text":"Done"}
+        ]}}"#;
+        let content = parse_jsonl_line(line).unwrap().message.unwrap().content;
+        assert_eq!(extract_text_content_with(&content, ThinkingPolicy::Include), "[Thinking]\nhmm\n\nDone");
+        assert_eq!(extract_text_content_with(&content, ThinkingPolicy::Exclude), "Done");
+        assert_eq!(extract_text_content_with(&content, ThinkingPolicy::Placeholder), "[Thinking]\n\nDone");
+        assert_eq!(ThinkingPolicy::parse(" Placeholder"), Some(ThinkingPolicy::Placeholder));
+        assert_eq!(ThinkingPolicy::parse("hide"), None);
+    }
+
+    #[test]
+    fn test_select_range() {
+        let ids = ["a", "b", "c", "d"];
+        let select = |range: ExportRange| select_range(&ids, |id| id, &range);
+
+        assert_eq!(select(ExportRange::default()).unwrap(), &ids);
+        let by_id = ExportRange { from_message_id: Some("b".into()), to_message_id: Some("c".into()), ..Default::default() };
+        assert_eq!(select(by_id).unwrap(), &["b", "c"]);
+        assert_eq!(select(ExportRange { from_index: Some(2), to_index: Some(9), ..Default::default() }).unwrap(), &["c", "d"]);
+        assert!(select(ExportRange { to_message_id: Some("zz".into()), ..Default::default() }).is_err());
+    }
+
+    #[test]
+    fn test_normalize_entries() {
+        let lines = [
+            r#"{"type":"summary","summary":"Fix bug","leafUuid":"a1"}"#,
+            r#"{"type":"assistant","uuid":"a1","message":{"role":"assistant","content":[{"type":"text","text":"Listing"},{"type":"tool_use","id":"t1","name":"Bash","input":{"command":"ls"}}]}}"#,
+            r#"{"type":"user","uuid":"u1","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"a.rs"}]}}"#,
+            r#"{"type":"system","uuid":"c1","subtype":"compact_boundary","timestamp":"2024-01-01T11:00:00Z"}"#,
+            r#"{"type":"user","uuid":"u2","isCompactSummary":true,"message":{"role":"user","content":"Earlier we listed files"}}"#,
+        ];
+        let entries: Vec<RawLogEntry> = lines.iter().map(|l| parse_jsonl_line(l).unwrap()).collect();
+        let events = normalize_entries(&entries);
+
+        let kinds: Vec<&str> = events
+            .iter()
+            .map(|e| match e {
+                SessionEvent::MessageAdded { .. } => "message",
+                SessionEvent::ToolInvoked { .. } => "invoked",
+                SessionEvent::ToolCompleted { .. } => "completed",
+                SessionEvent::SummaryWritten { .. } => "summary",
+                SessionEvent::Compaction { .. } => "compaction",
+            })
+            .collect();
+        assert_eq!(kinds, vec!["summary", "message", "invoked", "message", "completed", "compaction"]);
+        assert!(matches!(&events[4], SessionEvent::ToolCompleted { name: Some(name), output, .. } if name == "Bash" && output == "a.rs"));
+        assert!(matches!(&events[5], SessionEvent::Compaction { uuid: Some(uuid), summary: Some(_), .. } if uuid == "c1"));
+        assert_eq!(serde_json::to_value(&events[2]).unwrap()["toolUseId"], "t1");
+    }
+
+    #[test]
+    fn test_canonical_id_ignores_thinking_policy() {
+        let line = r#"{"type":"assistant","timestamp":"t","message":{"role":"assistant","content":[{"type":"thinking","thinking":"hm"},{"type":"text","text":"Hi"}]}}"#;
+        let entry = parse_jsonl_line(line).unwrap();
+        let included = entry_to_message(entry.clone()).unwrap().canonical_id;
+
+        set_thinking_policy(ThinkingPolicy::Exclude);
+        let excluded = (entry_canonical_id(&entry), entry_to_message(entry.clone()).unwrap());
+        set_thinking_policy(ThinkingPolicy::Include);
+        assert_eq!(excluded.1.content, "Hi");
+        assert_eq!((excluded.0.as_str(), excluded.1.canonical_id.as_str()), (included.as_str(), included.as_str()));
+    }
+}
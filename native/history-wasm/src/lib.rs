@@ -0,0 +1,36 @@
+// ============================================
+// WASM BINDINGS
+// history-core for the web viewer: parse user-uploaded session files
+// in the browser (build with wasm-pack build --target web)
+// ============================================
+
+use wasm_bindgen::prelude::*;
+
+fn to_js<T: serde::Serialize>(value: &T) -> Result<JsValue, JsValue> {
+    serde_wasm_bindgen::to_value(value).map_err(|e| JsValue::from_str(&format!("Cannot convert result: {}", e)))
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Parse the text of a .jsonl session file into messages
+#[wasm_bindgen(js_name = parseSession)]
+pub fn parse_session(text: &str) -> Result<JsValue, JsValue> {
+    to_js(&history_core::parse_session(text))
+}
+
+/// Counts, timestamps and token totals of a .jsonl session file
+#[wasm_bindgen(js_name = summarizeSession)]
+pub fn summarize_session(text: &str) -> Result<JsValue, JsValue> {
+    to_js(&history_core::summarize(&history_core::parse_session(text)))
+}
+
+/// How thinking blocks appear in message content: "include" (default), "exclude" or "placeholder"
+#[wasm_bindgen(js_name = setThinkingPolicy)]
+pub fn set_thinking_policy(policy: &str) -> Result<(), JsValue> {
+    let policy = history_core::ThinkingPolicy::parse(policy)
+        .ok_or_else(|| JsValue::from_str(&format!("Unknown thinking policy: {}", policy)))?;
+    history_core::set_thinking_policy(policy);
+    Ok(())
+}
@@ -3,21 +3,1845 @@
 
 /* auto-generated by NAPI-RS */
 
-/** Token usage with cache support */
-export interface TokenUsage {
+/** Report of one analyzer */
+export interface AnalyzerReport {
+  name: string
+  report: any
+}
+/** Names accepted by run_analyzers */
+export declare function listAnalyzers(): Array<string>
+/** Run the named analyzers (all of them when not given) over one read of a session */
+export declare function runAnalyzers(filePath: string, analyzerNames?: Array<string> | undefined | null): Array<AnalyzerReport>
+/** A run of text in one style */
+export interface OutputSpan {
+  text: string
+  /** "red", "bright_red"... for the 16 theme colors, "#rrggbb" otherwise */
+  fg?: string
+  bg?: string
+  bold: boolean
+  dim: boolean
+  italic: boolean
+  underline: boolean
+  inverse: boolean
+}
+/** Result of get_tool_output_spans */
+export interface ToolOutputSpans {
+  toolUseId: string
+  toolName?: string
+  isError: boolean
+  /** The output without any escape sequences */
+  plainText: string
+  spans: Array<OutputSpan>
+}
+/**
+ * The result of one tool call as styled spans, None if the session has no
+ * result for tool_use_id
+ */
+export declare function getToolOutputSpans(filePath: string, toolUseId: string): ToolOutputSpans | null
+/** Something the user attached to a prompt */
+export interface AttachmentInfo {
+  /** "pasted_text", "image" or "file" */
+  kind: string
+  /** "Pasted text #1", "Image #2", the media type or the mentioned path */
+  name?: string
+  /** Lines for pasted text, approximate bytes for images, None for files */
+  size?: number
+}
+/** One path a tool call read, wrote or passed to a command */
+export interface FileAccess {
+  /** "Read", "Glob", "Grep", ... */
+  tool: string
+  /** "read", "write" or "command" (a path argument of a Bash command) */
+  access: string
+  /** Path as passed to the tool, None when it defaulted to the cwd */
+  path?: string
+  /** Absolute path after resolving ~, the cwd and ".." */
+  resolvedPath: string
+  /** Glob or Grep pattern */
+  pattern?: string
+  /** The Bash command the path appeared in */
+  command?: string
+  toolUseId: string
+  /** uuid of the assistant entry that made the call */
+  messageId?: string
+  timestamp?: string
+}
+/** Result of check_scope_violations */
+export interface ScopeReport {
+  /** The allowed roots after resolving ~ and ".." */
+  allowedRoots: Array<string>
+  /** Paths checked across all tool calls */
+  checked: number
+  violations: Array<FileAccess>
+  /** Distinct resolved paths of the violations, first seen first */
+  outsidePaths: Array<string>
+}
+/** Every path the agent read, globbed or grepped in a session, in order */
+export declare function extractFileReads(filePath: string): Array<FileAccess>
+/**
+ * Tool calls of a session that touched paths outside allowed_roots (reads,
+ * writes and absolute paths in Bash commands)
+ */
+export declare function checkScopeViolations(filePath: string, allowedRoots: Array<string>): ScopeReport
+/** Options for get_cost_by_author */
+export interface AuthorCostOptions {
+  /** "day", "week", "month" (rolling) or "all" (default "all") */
+  period?: string
+  /** Price overrides, checked before the built-in list */
+  prices?: Array<ModelPrice>
+}
+/** Usage attributed to one git identity */
+export interface AuthorCost {
+  /**
+   * user.email in effect for the repos (repo config, else the global
+   * one); None for sessions outside a repo or with no email set
+   */
+  email?: string
+  /** user.name next to the email, as first seen */
+  name?: string
+  /** Work trees of the repositories, sorted */
+  repos: Array<string>
+  sessions: number
+  messages: number
+  inputTokens: number
+  outputTokens: number
+  cacheCreationTokens: number
+  cacheReadTokens: number
+  costUsd: number
+}
+/** Result of get_cost_by_author */
+export interface AuthorCostReport {
+  /** Highest cost first, the unattributed row among them */
+  authors: Array<AuthorCost>
+  totalCostUsd: number
+  /** Models with usage but no price, counted at $0 */
+  unpricedModels: Array<string>
+}
+/**
+ * Usage and estimated cost under root_dir per git user.email of the repo
+ * each session's cwd belongs to
+ */
+export declare function getCostByAuthor(rootDir: string, options?: AuthorCostOptions | undefined | null): AuthorCostReport
+/** One continuation of the conversation */
+export interface BranchInfo {
+  /** uuid of the branch's last entry; pass it to diff_branches */
+  branchId: string
+  /** 1-based, in the order the branches end in the file */
+  index: number
+  /** Excerpt of the first message after the divergence point */
+  label: string
+  /** Last entry shared with an earlier branch, None for the first branch */
+  forkUuid?: string
+  /** User and assistant messages from the root to the leaf */
+  messageCount: number
+  /** The messages after fork_uuid */
+  uniqueMessageCount: number
+  lastTimestamp?: string
+}
+/** One side of diff_branches, from the fork to the leaf */
+export interface BranchSide {
+  branchId: string
+  messageCount: number
+  inputTokens: number
+  outputTokens: number
+  toolCalls: number
+  /** Distinct tool names, first used first */
+  tools: Array<string>
+  filesModified: Array<string>
+  firstExcerpt?: string
+  lastExcerpt?: string
+  lastTimestamp?: string
+}
+/** How two branches differ after their common history */
+export interface BranchDiff {
+  /** Last entry both share, None when they have different roots */
+  forkUuid?: string
+  sharedMessageCount: number
+  a: BranchSide
+  b: BranchSide
+  toolsOnlyInA: Array<string>
+  toolsOnlyInB: Array<string>
+  filesOnlyInA: Array<string>
+  filesOnlyInB: Array<string>
+}
+/**
+ * Every branch of a session (one per leaf of the message tree, subagent
+ * threads excluded), labelled by where it diverged
+ */
+export declare function listBranches(filePath: string): Array<BranchInfo>
+/** Compare two branches (branch ids from list_branches) after their shared history */
+export declare function diffBranches(filePath: string, branchA: string, branchB: string): BranchDiff
+/** Monthly limits for one project (cwd path) */
+export interface ProjectCap {
+  project: string
+  monthlyTokens?: number
+  monthlyCostUsd?: number
+}
+/** Thresholds for check_budget; unset limits are not checked */
+export interface BudgetConfig {
+  /** Input + output tokens since local midnight */
+  dailyTokens?: number
+  /** Estimated cost since the first of the month */
+  monthlyCostUsd?: number
+  projectCaps?: Array<ProjectCap>
+  /** Share of a limit that raises a warning (default 0.8) */
+  warnRatio?: number
+  /** IANA timezone for "today" and "this month" (default UTC) */
+  timezone?: string
+  /** Price overrides, checked before the built-in list */
+  prices?: Array<ModelPrice>
+}
+/** A threshold that was reached */
+export interface BudgetAlert {
+  /** "daily_tokens", "monthly_cost", "project_tokens" or "project_cost" */
+  kind: string
+  /** "warning" or "exceeded" */
+  level: string
+  /** Project path for per-project alerts */
+  project?: string
+  used: number
+  limit: number
+  message: string
+}
+/** Current usage and the alerts it triggers */
+export interface BudgetStatus {
+  todayTokens: number
+  monthTokens: number
+  monthCostUsd: number
+  /** Month-to-date cost extrapolated to the whole month */
+  projectedMonthCostUsd: number
+  alerts: Array<BudgetAlert>
+}
+/** Evaluate budget thresholds against usage under root_dir */
+export declare function checkBudget(rootDir: string, budgetConfig: BudgetConfig): BudgetStatus
+/** The CI run a set of session logs came from */
+export interface CiMetadata {
+  /** e.g. "github-actions" (the default), "gitlab-ci" */
+  provider?: string
+  /** GITHUB_RUN_ID or the provider's equivalent */
+  runId: string
+  workflow?: string
+  /** "owner/name" */
+  repository?: string
+  branch?: string
+  /** Pull request number, for runs triggered by one */
+  pullRequest?: number
+  commitSha?: string
+  /** Link to the run page */
+  runUrl?: string
+}
+/** An imported CI session, as kept in the store */
+export interface CiSession {
+  sessionId: string
+  /** The copy under the automation folder */
+  filePath: string
+  metadata: CiMetadata
+  importedAt: number
+}
+/** Result of import_ci_artifacts */
+export interface CiImport {
+  /**
+   * Root of every imported CI session; use it as root_dir for
+   * automation-only listings and analytics
+   */
+  automationDir: string
+  sessions: Array<CiSession>
+  skipped: Array<SkippedPath>
+}
+/**
+ * Import the session logs of a CI run (e.g. a downloaded GitHub Actions
+ * artifact) into the automation folder, tagged with the run's metadata.
+ * Sessions imported before are skipped unless overwrite is set (default false)
+ */
+export declare function importCiArtifacts(dir: string, metadata: CiMetadata, overwrite?: boolean | undefined | null): CiImport
+/** Folder the imported CI sessions live in, kept out of the user's history */
+export declare function getAutomationDir(): string
+/** Imported CI sessions, optionally of one run, most recent import first */
+export declare function listCiSessions(runId?: string | undefined | null): Array<CiSession>
+/** Options for find_stale_artifacts */
+export interface StaleArtifactOptions {
+  /** Delete what was found (default false, report only) */
+  cleanup?: boolean
+  /** Ignore anything modified more recently than this (default 60 minutes) */
+  minAgeMinutes?: number
+}
+/** A leftover file or directory under the projects root */
+export interface StaleArtifact {
+  path: string
+  /** "empty_session", "empty_dir" or "temp_file" */
+  kind: string
+  bytes: number
+  /** True when cleanup removed it */
+  removed: boolean
+}
+/** Zero-message sessions, empty project dirs and crash leftovers under root_dir */
+export declare function findStaleArtifacts(rootDir: string, options?: StaleArtifactOptions | undefined | null): Array<StaleArtifact>
+/** Settings passed to configure; missing fields keep their current value */
+export interface ConfigureOptions {
+  /** Worker threads for parallel parsing (0 = one per core) */
+  threads?: number
+  /** Open message cursors (and replays) kept before the oldest is closed (default 64) */
+  maxOpenCursors?: number
+  /** "off", "error", "warn" (default), "info" or "debug" */
+  logLevel?: string
+  /**
+   * How thinking blocks appear in message content, exports and search:
+   * "include" (default, prefixed "[Thinking]"), "exclude" or "placeholder"
+   */
+  thinkingPolicy?: string
+  /** Descend into symlinked project folders when scanning a root (default true) */
+  followSymlinks?: boolean
+  /**
+   * Milliseconds one directory listing or stat may take while scanning a
+   * root before the path is skipped (default 10000, 0 = wait forever)
+   */
+  scanTimeoutMs?: number
+}
+/** Effective configuration, also kept as napi instance data */
+export interface RuntimeConfig {
+  /** Configured thread count, 0 = one per core */
+  threads: number
+  maxOpenCursors: number
+  logLevel: string
+  thinkingPolicy: string
+  followSymlinks: boolean
+  scanTimeoutMs: number
+}
+/**
+ * Tune resource usage once per process (thread count, cursor cache, log level,
+ * thinking policy, directory scanning)
+ */
+export declare function configure(options: ConfigureOptions): RuntimeConfig
+/** The configuration set by configure, or the defaults */
+export declare function getConfig(): RuntimeConfig
+/** Options for resolve_conflicts */
+export interface ConflictOptions {
+  /** Rewrite the copies with the resolved content (default false, report only) */
+  apply?: boolean
+  /**
+   * Let apply replace the copies in place (default true); false fails with
+   * FILE_EXISTS rather than write over an existing copy
+   */
+  overwrite?: boolean
+}
+/** One copy of a conflicting session */
+export interface ConflictCopy {
+  filePath: string
+  bytes: number
+  lines: number
+  /** Latest entry timestamp */
+  lastTimestamp?: string
+  /** Entry uuids no other copy has */
+  uniqueIds: Array<string>
+  /** True when the resolution differs from this copy, so applying rewrites it */
+  changed: boolean
+}
+/** A session whose copies disagree */
+export interface SessionConflict {
+  sessionId: string
+  /** In root order */
+  copies: Array<ConflictCopy>
+  /** Entries present in every copy */
+  sharedLines: number
+  /** Copy whose content wins, None when merged */
+  winner?: string
+  resolvedLines: number
+  /** Files rewritten with the resolution (empty unless applied) */
+  written: Array<string>
+}
+/** Result of resolve_conflicts */
+export interface ConflictReport {
+  strategy: string
+  applied: boolean
+  filesScanned: number
+  conflicts: Array<SessionConflict>
+  /** Copies that could not be read, left out of the resolutions */
+  skipped: Array<SkippedPath>
+}
+/**
+ * Sessions found under more than one of roots (same file name, i.e.
+ * sessionId) whose copies differ, resolved with strategy: "keep-longest",
+ * "keep-newest" or "merge-by-uuid". Only reports unless options.apply is set
+ */
+export declare function resolveConflicts(roots: Array<string>, strategy: string, options?: ConflictOptions | undefined | null): ConflictReport
+/** Which messages a cursor yields */
+export interface MessageFilter {
+  /** "user" or "assistant" */
+  role?: string
+  /** Include subagent (sidechain) messages (default true) */
+  includeSidechain?: boolean
+  /** Case-insensitive substring of the merged content */
+  query?: string
+}
+/** One batch read from a cursor */
+export interface MessageBatch {
+  messages: Array<ClaudeMessage>
+  /** True once the end of the file was reached (the cursor is closed) */
+  done: boolean
+  /** Lines consumed so far, for progress reporting */
+  linesRead: number
+}
+/** Open a cursor over a session file, returns a cursor id for next_batch */
+export declare function openMessageCursor(filePath: string, filter?: MessageFilter | undefined | null): number
+/** Read up to n more messages; the cursor closes itself at end of file */
+export declare function nextBatch(cursor: number, n: number): MessageBatch
+/** Close a cursor early, returns false if it was already closed */
+export declare function closeCursor(cursor: number): boolean
+/** Options for delete_session */
+export interface DeleteOptions {
+  /** Move to the OS trash / recycle bin instead of deleting for good (default true) */
+  toTrash?: boolean
+}
+/** What delete_session did */
+export interface DeleteResult {
+  filePath: string
+  bytes: number
+  /** True when the file went to the trash, false when deleted permanently */
+  trashed: boolean
+  /** Open cursors on the file that were closed */
+  closedCursors: number
+}
+/** Delete a session file (to the OS trash by default) and drop cached state for it */
+export declare function deleteSession(filePath: string, options?: DeleteOptions | undefined | null): DeleteResult
+/** Options for find_duplicate_attachments */
+export interface DuplicateOptions {
+  /** Payloads smaller than this many bytes are ignored (default 1024) */
+  minBytes?: number
+}
+/** Where one copy is stored */
+export interface AttachmentOccurrence {
+  filePath: string
+  sessionId: string
+  messageId: string
+  /** Image number within the message, as in get_image_data */
+  imageIndex?: number
+  /** Set for tool results */
+  toolUseId?: string
+}
+/** One payload stored more than once */
+export interface DuplicateAttachment {
+  /** "image" or "tool_result" */
+  kind: string
+  sha256: string
+  mediaType?: string
+  /** Size of one copy as stored (base64 for images) */
+  bytes: number
+  copies: number
+  /** bytes times every copy but the first */
+  wastedBytes: number
+  /** In file order */
+  occurrences: Array<AttachmentOccurrence>
+}
+/** Result of find_duplicate_attachments */
+export interface DuplicateReport {
+  filesScanned: number
+  payloadsScanned: number
+  wastedBytes: number
+  /** Most wasted bytes first */
+  duplicates: Array<DuplicateAttachment>
+}
+/**
+ * Images and tool results stored more than once in the sessions under
+ * root_dir, with every place they appear
+ */
+export declare function findDuplicateAttachments(rootDir: string, options?: DuplicateOptions | undefined | null): DuplicateReport
+/** Result of describe_error */
+export interface ErrorInfo {
+  /** "FILE_NOT_FOUND", "PARSE_ERROR"... or "UNKNOWN" for messages without a code */
+  code: string
+  message: string
+  path?: string
+  line?: number
+}
+/**
+ * Code, message and context of an error in its raw form (e.g. a logged
+ * message); errors thrown to JS already carry them as properties
+ */
+export declare function describeError(message: string): ErrorInfo
+/** One normalized event; only the fields of its kind are set */
+export interface SessionEvent {
+  /** "message_added", "tool_invoked", "tool_completed", "summary_written" or "compaction" */
+  kind: string
+  /** 0-based position in the session */
+  sequence: number
+  /** uuid of the message, compaction boundary, or message carrying the tool block */
+  uuid?: string
+  parentUuid?: string
+  timestamp?: string
+  role?: string
+  /** Message text, tool output or compaction summary */
+  text?: string
+  model?: string
+  isSidechain?: boolean
+  inputTokens?: number
+  outputTokens?: number
+  toolUseId?: string
+  toolName?: string
+  toolInput?: any
+  isError?: boolean
+  summary?: string
+  leafUuid?: string
+}
+/** Every message, tool call, tool result, summary and compaction of a session, in order */
+export declare function getSessionEvents(filePath: string): Array<SessionEvent>
+/** Part of a session to export (uuids or canonical ids; ids win over indexes, bounds inclusive) */
+export interface ExportOptions {
+  fromMessageId?: string
+  toMessageId?: string
+  /** 0-based message index */
+  fromIndex?: number
+  toIndex?: number
+  /** One line per tool call in text transcripts (default true) */
+  includeToolCalls?: boolean
+  /**
+   * Text transcripts keep only the prompts and each turn's final answer,
+   * without intermediate replies, thinking or tool calls (default false)
+   */
+  finalAnswersOnly?: boolean
+  /**
+   * Remove ANSI escape sequences and other terminal control characters,
+   * e.g. colors in tool output (default false)
+   */
+  stripControlChars?: boolean
+  /** "lf" or "crlf" to normalize line endings (default: left as they are) */
+  lineEndings?: string
+  /** Replacements applied in order to the finished export (default none) */
+  substitutions?: Array<Substitution>
+}
+/** One find-and-replace, e.g. an internal hostname or project codename */
+export interface Substitution {
+  pattern: string
+  replacement: string
+  /** pattern is a regular expression and replacement may use $1 or ${name} (default false) */
+  regex?: boolean
+  /** Match regardless of case (default false) */
+  ignoreCase?: boolean
+}
+/** A note written by export_obsidian_vault */
+export interface ObsidianNote {
+  sessionId: string
+  sourcePath: string
+  notePath: string
+  /** Note name without .md, as used in [[wiki-links]] */
+  noteName: string
+  /** Note name of the session this one resumed */
+  resumedFrom?: string
+  /** Files in the vault's assets/ directory the note embeds */
+  assets: Array<string>
+}
+/** One session as an Obsidian note (YAML frontmatter + transcript) */
+export declare function exportSessionObsidian(filePath: string, options?: ExportOptions | undefined | null): string
+/** Minimal "User:" / "Assistant:" transcript with one line per tool call */
+export declare function exportSessionText(filePath: string, options?: ExportOptions | undefined | null): string
+/**
+ * Text transcript with substitutions applied after any in options, for
+ * scrubbing hostnames or codenames before sharing outside the team
+ */
+export declare function exportWithSubstitutions(filePath: string, substitutions: Array<Substitution>, options?: ExportOptions | undefined | null): string
+/** Options for export_highlights */
+export interface HighlightOptions {
+  /** Messages shown before and after each highlight (default 1) */
+  contextMessages?: number
+  /** Cleanup and substitutions, as for export_session_text; the range is ignored */
+  export?: ExportOptions
+}
+/**
+ * The bookmarked messages of a session with a little context around each,
+ * as a markdown digest. file_path_or_root is the session file or a directory
+ * to look for it in
+ */
+export declare function exportHighlights(sessionId: string, filePathOrRoot: string, options?: HighlightOptions | undefined | null): string
+/**
+ * Every Bash tool command of a session as an annotated shell script; options
+ * pick the range and the cleanup and substitutions, as for export_session_text
+ */
+export declare function exportBashScript(filePath: string, options?: ExportOptions | undefined | null): string
+/**
+ * Write one note per session under root_dir into output_dir, linking resumed
+ * sessions; images go once into output_dir/assets/, named by hash. Notes
+ * already in the vault are only replaced with overwrite. options clean up
+ * the notes as for export_session_text; the range is ignored
+ */
+export declare function exportObsidianVault(rootDir: string, outputDir: string, overwrite?: boolean | undefined | null, options?: ExportOptions | undefined | null): Array<ObsidianNote>
+/** Where a failure was seen */
+export interface ErrorOccurrence {
+  sessionId: string
+  filePath: string
+  messageId?: string
+  timestamp?: string
+}
+/** Failures sharing the same kind, tool and signature */
+export interface ErrorGroup {
+  /** "tool_error", "api_error" or "retry" */
+  kind: string
+  tool?: string
+  signature: string
+  count: number
+  /** First few occurrences */
+  examples: Array<ErrorOccurrence>
+}
+/** Error rate of one tool */
+export interface ToolErrorStats {
+  tool: string
+  calls: number
+  errors: number
+  errorRate: number
+}
+/** Aggregated failure report */
+export interface ErrorReport {
+  filesScanned: number
+  totalErrors: number
+  groups: Array<ErrorGroup>
+  tools: Array<ToolErrorStats>
+}
+/** Heuristic health of a session, 100 = smooth run */
+export interface SessionHealth {
+  score: number
+  isTroubled: boolean
+  toolCalls: number
+  toolErrors: number
+  interruptions: number
+  retries: number
+  apiErrors: number
+  compactions: number
+}
+/** Aggregate failures in one session file or every session under a directory */
+export declare function getErrorReport(filePathOrRoot: string): ErrorReport
+/** Result of get_file_details */
+export interface FileInfo {
+  filePath: string
+  size: number
+  /** Lines of the (decompressed) content, None when it cannot be decoded */
+  lineCount?: number
+  /** RFC 3339 */
+  modifiedAt?: string
+  /** RFC 3339, None where the file system does not record it */
+  createdAt?: string
+  isCompressed: boolean
+  /** "gzip", "zstd", "bzip2", "xz" or "zip" */
+  compression?: string
+  /**
+   * Of the decompressed content: "claude-jsonl" (session entries), "jsonl",
+   * "json", "text", "binary", "empty", or "unknown" for undecodable archives
+   */
+  detectedFormat: string
+}
+/** Size, line count, timestamps, compression and format of a file */
+export declare function getFileDetails(filePath: string): FileInfo
+/** Options for follow_session */
+export interface FollowOptions {
+  /** Also push the messages already in the file (default false) */
+  fromStart?: boolean
+  /** How often the file is checked (default 500 ms) */
+  pollIntervalMs?: number
+}
+/**
+ * Push each message appended to a session file to callback until
+ * stop_following is called, returns the follower id
+ */
+export declare function followSession(filePath: string, callback: (message: ClaudeMessage) => void, options?: FollowOptions | undefined | null): number
+/** Stop a follower, returns false if it was already stopped */
+export declare function stopFollowing(follower: number): boolean
+/**
+ * Render the message tree of a session; format is "dot" or "mermaid".
+ * options pick the range and the cleanup and substitutions, as for
+ * export_session_text
+ */
+export declare function exportConversationGraph(filePath: string, format: string, options?: ExportOptions | undefined | null): string
+/** One point on the timeline */
+export interface HandoffEvent {
+  /** "model_switch" or "agent_launch" */
+  kind: string
+  /** uuid of the entry where it happened */
+  messageId?: string
+  timestamp?: string
+  /** Previous model, None for the first model of the session */
+  fromModel?: string
+  /** New model, or the model that launched the agent */
+  toModel?: string
+  subagentType?: string
+  /** Excerpt of the Task description or prompt */
+  description?: string
+}
+/** Model changes and subagent launches of a session, in file order */
+export declare function getModelSwitches(filePath: string): Array<HandoffEvent>
+/** One bar of a histogram */
+export interface HistogramBucket {
+  /** "00".."23" for hours, "Mon".."Sun" for weekdays */
+  label: string
+  messages: number
+  /** Input + output tokens */
+  tokens: number
+}
+/** Result of get_usage_histograms */
+export interface UsageHistograms {
+  timezone: string
+  /** 24 buckets, midnight first */
+  byHour: Array<HistogramBucket>
+  /** 7 buckets, Monday first */
+  byWeekday: Array<HistogramBucket>
+}
+/** Message and token counts under root_dir by hour of day and weekday in timezone (default UTC) */
+export declare function getUsageHistograms(rootDir: string, timezone?: string | undefined | null): UsageHistograms
+/**
+ * parse_claude_session, passing every message through hook; return the
+ * message (changed or not) to keep it, null or undefined to drop it.
+ * If the hook throws, the Promise is rejected with HOOK_FAILED
+ */
+export declare function parseClaudeSessionWithHook(filePath: string, options: ParseOptions | undefined | null, hook: (message: ClaudeMessage) => ClaudeMessage | null | undefined): Promise<Array<ClaudeMessage>>
+/** One image with its data */
+export interface ImageData {
+  mediaType: string
+  /** Base64, as stored in the session file */
+  data: string
+  /** Decoded size */
+  byteSize: number
+  /** Hex SHA-256 of the base64 data */
+  sha256: string
+}
+/** A thumbnail written by generate_thumbnails */
+export interface Thumbnail {
+  messageId: string
+  /** Image number within the message, as in get_image_data */
+  index: number
+  mediaType: string
+  sha256: string
+  /** PNG file, named after sha256 and max_dim so identical images share one */
+  path: string
+  width: number
+  height: number
+}
+/**
+ * PNG thumbnails at most max_dim pixels (default 256) on either side for
+ * every image of a session, written to output_dir. Images that cannot be
+ * decoded are skipped
+ */
+export declare function generateThumbnails(filePath: string, outputDir: string, maxDim?: number | undefined | null): Array<Thumbnail>
+/**
+ * Image number index (as in the image_mode "summary" blocks) of one message
+ * (by message_id or canonical_id), None if the message or the image does not exist
+ */
+export declare function getImageData(filePath: string, messageId: string, index: number): ImageData | null
+/** One line of a session file next to what the parser made of it */
+export interface RawEntry {
+  /** 0-based, as in read_lines_range */
+  lineNumber: number
+  byteOffset: number
+  /** The line as stored, without its line break */
+  rawLine: string
+  /** The line as generic JSON, None when it is not valid JSON */
+  json?: any
+  /** The parsed message, None for lines that are not user or assistant messages */
+  message?: ClaudeMessage
+  /** Why the line does not parse as a log entry, with the column */
+  parseError?: string
+}
+/** Result of read_lines_range */
+export interface LineRange {
+  startLine: number
+  /** Raw lines without their line break, empty ones included */
+  lines: Array<string>
+  totalLines: number
+  hasMore: boolean
+}
+/** Size, mtime and line count of one session file */
+export interface SessionStat {
+  filePath: string
+  exists: boolean
+  bytes: number
+  /** RFC 3339 */
+  modifiedAt?: string
+  /** Changes whenever the size or mtime does; pass it back to detect changes */
+  fingerprint?: string
+  /**
+   * Exact when the file is indexed and unchanged (see lines_exact),
+   * otherwise extrapolated from a sample or an older index
+   */
+  estimatedLines?: number
+  linesExact: boolean
+  /** Whether the file differs from the fingerprint given for it, None when none was */
+  changed?: boolean
+}
+/**
+ * One message with its complete content (e.g. after parsing with
+ * max_text_chars), by message_id or canonical_id
+ */
+export declare function getFullMessage(filePath: string, messageId: string): ClaudeMessage | null
+/**
+ * The original JSON line at a 0-based line number, or of the entry with
+ * that uuid or canonical id, with its generic JSON and parsed message side by side
+ */
+export declare function getRawEntry(filePath: string, lineNumberOrMessageId: number | string): RawEntry | null
+/**
+ * Up to count raw lines of file_path from start_line (0-based), for paging
+ * through files too large to read at once
+ */
+export declare function readLinesRange(filePath: string, startLine: number, count: number): LineRange
+/**
+ * Size, mtime, line count and fingerprint of each file, without parsing.
+ * since maps file paths to fingerprints from an earlier call; those files
+ * get changed set. Missing files are reported with exists false
+ */
+export declare function statSessions(filePaths: Array<string>, since?: Record<string, string> | undefined | null): Array<SessionStat>
+/** A session's link to an issue or pull request */
+export interface IssueLink {
+  sessionId: string
+  /** Canonical form, e.g. "https://github.com/owner/repo/pull/12" */
+  url: string
+  /** "github", "gitlab", "jira", "linear" or "other" */
+  tracker: string
+  /** "issue", "pull_request" or "merge_request" */
+  kind: string
+  /** Short reference: "owner/repo#12", "group/app!3", "PROJ-123" */
+  key: string
+  /** "manual" (link_session_to_issue) or "detected" (mentioned in the session) */
+  source: string
+  /** uuid of the entry that first mentions a detected link */
+  messageId?: string
+  createdAt: number
+}
+/** Link a session to an issue or pull request URL (stored canonical) */
+export declare function linkSessionToIssue(sessionId: string, url: string): IssueLink
+/** Remove a session's link to an issue. Returns false if there was none */
+export declare function unlinkSessionFromIssue(sessionId: string, url: string): boolean
+/** Manual and detected issue links of a session */
+export declare function getSessionIssues(sessionId: string): Array<IssueLink>
+/** Session ids linked to an issue; any URL form of it matches */
+export declare function findSessionsForIssue(url: string): Array<string>
+/**
+ * Find issue and pull request URLs mentioned in a session file and store
+ * them as its detected links
+ */
+export declare function detectSessionIssues(filePath: string): Array<IssueLink>
+/** One language of a role's messages */
+export interface LanguageShare {
+  /** ISO 639-3, e.g. "eng", "vie", "jpn" */
+  code: string
+  /** English name, e.g. "Vietnamese" */
+  name: string
+  messages: number
+  /** Fraction of the role's classified text, by characters */
+  share: number
+}
+/** Result of detect_session_languages, also on full session summaries */
+export interface SessionLanguages {
+  /** Languages of the prompts typed by the user, largest share first */
+  user: Array<LanguageShare>
+  /** Languages of the main-thread replies, largest share first */
+  assistant: Array<LanguageShare>
+  /**
+   * Code of the main prompt language, else of the replies; None when
+   * no message was long enough to tell
+   */
+  primary?: string
+}
+/** Languages of a session's prompts and replies (also on full summaries) */
+export declare function detectSessionLanguages(filePath: string): SessionLanguages
+/** A tool call with no tool_result in the log (still running, interrupted or crashed) */
+export interface PendingToolUse {
+  toolUseId: string
+  name: string
+  /** uuid of the assistant entry that made the call */
+  messageId?: string
+  timestamp?: string
+}
+/** A session that appears to be in progress */
+export interface ActiveSession {
+  sessionId: string
+  filePath: string
+  /** cwd of the session, if logged */
+  project?: string
+  /**
+   * "tool_running" (a tool call has no result yet) or "awaiting_response"
+   * (the last message is the user's)
+   */
+  state: string
+  /** Names of the tools still running */
+  pendingTools: Array<string>
+  /** When the file was last written (RFC 3339) */
+  modifiedAt: string
+  secondsSinceModified: number
+  lastTimestamp?: string
+}
+/**
+ * Sessions under root_dir written in the last within_minutes (default 5)
+ * that stopped mid-turn, most recently written first
+ */
+export declare function getActiveSessions(rootDir: string, withinMinutes?: number | undefined | null): Array<ActiveSession>
+/** One diagnostic record */
+export interface LogRecord {
+  /** "error", "warn", "info" or "debug" */
+  level: string
+  /** Rust module that logged it, e.g. "claude_parser::reader" */
+  target: string
+  message: string
+  /** Structured fields such as line numbers */
+  fields: Record<string, string>
+  timestamp: string
+}
+/** Forward parser log records to a JS callback (null restores stderr) */
+export declare function setLogHandler(handler: ((record: LogRecord) => void) | null): void
+/** Also append log records to a file, rotated past max_bytes (default 5 MiB); null stops */
+export declare function setLogFile(path?: string | undefined | null, maxBytes?: number | undefined | null): void
+/** One session of a duplicate group */
+export interface DuplicateSession {
+  sessionId: string
+  filePath: string
+  bytes: number
+  messageCount: number
+  /** Estimated Jaccard similarity of its messages to the kept session (1 for the kept one) */
+  similarity: number
+}
+/** Sessions that are near copies of each other */
+export interface DuplicateSessionGroup {
+  /** The session with the most messages, which the others mostly repeat */
+  kept: DuplicateSession
+  duplicates: Array<DuplicateSession>
+  /** Bytes of the duplicates */
+  reclaimableBytes: number
+}
+/** Result of find_duplicate_sessions */
+export interface DuplicateSessionReport {
+  filesScanned: number
+  /** Most reclaimable bytes first */
+  groups: Array<DuplicateSessionGroup>
+  reclaimableBytes: number
+}
+/**
+ * Groups of sessions under root_dir whose messages are at least threshold
+ * similar (0..1, default 0.8), with the space deleting the copies would free
+ */
+export declare function findDuplicateSessions(rootDir: string, threshold?: number | undefined | null): DuplicateSessionReport
+/** Messages whose raw_content lives in one shared Buffer */
+export interface RawBufferSession {
+  /** raw_content is empty; use raw_content_offset / raw_content_length */
+  messages: Array<ClaudeMessage>
+  skippedLines: Array<SkippedLine>
+  /** Concatenated raw_content JSON of all messages */
+  rawContent: Buffer
+}
+/** parse_claude_session, returned as a single msgpack or gzipped JSON Buffer */
+export declare function parseClaudeSessionPacked(filePath: string, options?: ParseOptions | undefined | null, format?: string | undefined | null): Buffer
+/** parse_claude_session_with_report with raw_content moved into a single Buffer */
+export declare function parseClaudeSessionWithRawBuffer(filePath: string, options?: ParseOptions | undefined | null): RawBufferSession
+/**
+ * Result of get_payload_stats; content categories count the bytes of the
+ * decoded strings, overhead_bytes is whatever JSON around them is left
+ */
+export interface PayloadStats {
+  fileBytes: number
+  lines: number
+  textBytes: number
+  thinkingBytes: number
+  toolInputBytes: number
+  /** Text of tool results (images inside them count as image_bytes) */
+  toolResultBytes: number
+  /** Base64 image data, in messages and in tool results */
+  imageBytes: number
+  imageCount: number
+  /** Lines that are not messages (file history snapshots, summaries...) or do not parse */
+  nonMessageBytes: number
+  overheadBytes: number
+  largestLineBytes: number
+  /** 1-based, 0 for an empty file */
+  largestLineNumber: number
+}
+/**
+ * How the bytes of one session file split between text, tool traffic,
+ * images and metadata
+ */
+export declare function getPayloadStats(filePath: string): PayloadStats
+/** One blocked tool call */
+export interface PermissionEvent {
+  /** "rejected" (the user said no), "denied" (permission settings) or "hook" */
+  kind: string
+  tool: string
+  /** The tool and its most telling input, e.g. "Bash: rm -rf dist" */
+  call: string
+  /** Start of the tool result text */
+  reason: string
+  /** What the user typed when rejecting, if anything */
+  userFeedback?: string
+  toolUseId: string
+  /** uuid of the assistant entry that made the call */
+  messageId?: string
+  timestamp?: string
+}
+export interface ToolDenials {
+  tool: string
+  count: number
+}
+/** Result of get_permission_events */
+export interface PermissionReport {
+  rejected: number
+  denied: number
+  hookBlocked: number
+  /** Most blocked tool first */
+  byTool: Array<ToolDenials>
+  /** In file order */
+  events: Array<PermissionEvent>
+}
+/** Tool calls of a session that were rejected by the user or blocked by settings or hooks */
+export declare function getPermissionEvents(filePath: string): PermissionReport
+/** USD per million tokens for models whose name contains `pattern` */
+export interface ModelPrice {
+  /** Matched against the model name, e.g. "opus-4-5" or "sonnet" */
+  pattern: string
+  inputPerMtok: number
+  outputPerMtok: number
+  cacheWritePerMtok: number
+  cacheReadPerMtok: number
+}
+/** A distinct user prompt and where it was used */
+export interface ExtractedPrompt {
+  text: string
+  count: number
+  /** Session ids the prompt appeared in (first use first) */
+  sessions: Array<string>
+  firstUsed?: string
+  lastUsed?: string
+}
+/** Distinct user prompts under root_dir, most used first */
+export declare function extractPrompts(rootDir: string, minLength?: number | undefined | null, dedupe?: boolean | undefined | null): Array<ExtractedPrompt>
+/** Serialize extracted prompts for saving: format is "json" or "markdown" */
+export declare function exportPromptLibrary(prompts: Array<ExtractedPrompt>, format: string): string
+/** Activity within one time window */
+export interface RateWindow {
+  start: string
+  end: string
+  /** Input + output tokens */
+  tokens: number
+  messages: number
+  tokensPerHour: number
+  messagesPerHour: number
+}
+/** The 5-hour block that is still open, started on the hour of its first message */
+export interface UsageBlock {
+  start: string
+  end: string
+  tokens: number
+  messages: number
+  remainingMinutes: number
+  /** Block tokens so far plus the current window's rate until the block ends */
+  projectedTokens: number
+}
+/** Result of get_burn_rate */
+export interface BurnRate {
+  windowMinutes: number
+  /** The last window_minutes */
+  current: RateWindow
+  /** The window_minutes before that, for a trend arrow */
+  previous: RateWindow
+  /** None when nothing happened in the last 5 hours */
+  block?: UsageBlock
+}
+/** Recent tokens/hour and messages/hour under root_dir over window_minutes */
+export declare function getBurnRate(rootDir: string, windowMinutes: number): BurnRate
+/** Where a replay stands after a step */
+export interface ReplayState {
+  /** Messages this step added, or every message so far when rewound */
+  messages: Array<ClaudeMessage>
+  /** True when the step went backwards and the state was rebuilt from the start */
+  rewound: boolean
+  /** Messages replayed so far */
+  position: number
+  totalMessages: number
+  /** Distinct files written or edited so far, first modified first */
+  filesModified: Array<string>
+  inputTokens: number
+  outputTokens: number
+  /** True once every message was replayed */
+  done: boolean
+}
+/** Load a session for replay, returns a replay id for step_forward and step_to */
+export declare function openReplay(filePath: string): number
+/** Replay n more messages (negative n steps back) */
+export declare function stepForward(replay: number, n: number): ReplayState
+/** Replay up to and including message_id (or canonical_id), going back if it was already passed */
+export declare function stepTo(replay: number, messageId: string): ReplayState
+/** Free a replay, returns false if it was already closed */
+export declare function closeReplay(replay: number): boolean
+/** One pattern to flag */
+export interface RiskRule {
+  id: string
+  /** "low", "medium", "high" or "critical" */
+  severity: string
+  /** Regular expression matched against the full command */
+  pattern: string
+  description: string
+}
+/** A Bash call that matched a rule */
+export interface RiskFinding {
+  ruleId: string
+  severity: string
+  ruleDescription: string
+  command: string
+  /** The description the agent gave the Bash call */
+  callDescription?: string
+  /** The user prompt the call was made in response to */
+  prompt?: string
+  sessionId: string
+  filePath: string
+  toolUseId: string
+  messageId?: string
+  timestamp?: string
+}
+/** Result of scan_risky_commands */
+export interface RiskReport {
+  filesScanned: number
+  commandsScanned: number
+  /** Most severe first, then in file order */
+  findings: Array<RiskFinding>
+}
+/** The built-in rules, for the UI to show or extend */
+export declare function getDefaultRiskRules(): Array<RiskRule>
+/**
+ * Flag Bash calls in a session file (or every session under a directory)
+ * matching ruleset; the built-in rules when ruleset is not given
+ */
+export declare function scanRiskyCommands(filePathOrRoot: string, ruleset?: Array<RiskRule> | undefined | null): RiskReport
+/** A named query, e.g. "failing tests" = "role:user tests failed" */
+export interface SavedSearch {
+  name: string
+  /** search_history query, filters included */
+  query: string
+  createdAt: number
+  updatedAt: number
+}
+/** Result of run_saved_search */
+export interface SavedSearchResult {
+  name: string
+  page: SearchPage
+  /** Session files read this run; unchanged files reuse the previous hits */
+  filesScanned: number
+  /**
+   * Sessions matching now that did not on the previous run (every match on
+   * the first, and after the root, hit shape or thinking policy changed)
+   */
+  newSessionIds: Array<string>
+}
+/** Save query under name, replacing the query of an existing search */
+export declare function saveSearch(name: string, query: string): SavedSearch
+/** Every saved search, in creation order */
+export declare function listSavedSearches(): Array<SavedSearch>
+/** Delete a saved search. Returns false if it did not exist */
+export declare function deleteSavedSearch(name: string): boolean
+/**
+ * Run a saved search over root_dir like search_history. Repeated runs only
+ * rescan sessions added or changed since the previous one
+ */
+export declare function runSavedSearch(name: string, rootDir: string, options?: SearchOptions | undefined | null): SavedSearchResult
+/** Options for scan_session_files; missing fields use the configure() settings */
+export interface ScanOptions {
+  /** Descend into symlinked folders and include symlinked files (default true) */
+  followSymlinks?: boolean
+  /**
+   * Milliseconds one listing or stat may take before the path is skipped
+   * (default 10000, 0 = wait forever)
+   */
+  timeoutMs?: number
+}
+/** A path the scan could not look into */
+export interface SkippedPath {
+  path: string
+  /**
+   * "unreadable", "timeout", "cycle" (a link to a folder already scanned),
+   * "non_unicode" (a name JS could not pass back) or, for import_ci_artifacts,
+   * "exists" (imported before)
+   */
+  reason: string
+  /** The operating system error, for unreadable paths */
+  message?: string
+}
+/** Result of scan_session_files */
+export interface SessionScan {
+  /** Sorted */
+  files: Array<string>
+  skipped: Array<SkippedPath>
+  dirsScanned: number
+}
+/**
+ * Session files under root_dir with the paths skipped on the way
+ * (unreadable, too slow, symlink loops), as every root-wide function sees them
+ */
+export declare function scanSessionFiles(rootDir: string, options?: ScanOptions | undefined | null): SessionScan
+/** JSON Schema of every output type, keyed by type name */
+export declare function getOutputSchemas(): Record<string, any>
+/** Options for search_history */
+export interface SearchOptions {
+  /** Hits (or sessions when grouped) per page (default 50) */
+  limit?: number
+  /** Hits (or sessions) to skip (default 0) */
+  offset?: number
+  /** "relevance" (default) or "recency" */
+  sort?: string
+  /** Page over sessions, each with its best hits (default false) */
+  groupBySession?: boolean
+  /** Hits kept per session when grouped (default 3) */
+  hitsPerSession?: number
+  /** Messages before and after each hit returned with it (default 0) */
+  contextMessages?: number
+  /** Characters of snippets and context excerpts (default 160) */
+  snippetChars?: number
+}
+/** A message next to a hit */
+export interface ContextMessage {
+  messageId: string
+  messageIndex: number
+  role: string
+  timestamp: string
+  excerpt: string
+}
+/** One matching message */
+export interface SearchHit {
+  sessionId: string
+  filePath: string
+  messageId: string
+  canonicalId: string
+  /** 0-based position among the session's messages, as parse_claude_session returns them */
+  messageIndex: number
+  role: string
+  timestamp: string
+  /** Text around the first match */
+  snippet: string
+  /** Occurrences of the query words */
+  matchCount: number
+  score: number
+  /** Up to context_messages messages before the hit, oldest first */
+  contextBefore: Array<ContextMessage>
+  contextAfter: Array<ContextMessage>
+}
+/** The hits of one session */
+export interface SessionHits {
+  sessionId: string
+  filePath: string
+  hitCount: number
+  bestScore: number
+  lastTimestamp: string
+  /** Best first, at most hits_per_session */
+  hits: Array<SearchHit>
+}
+/** One page of search_history results */
+export interface SearchPage {
+  /** All matching messages */
+  totalHits: number
+  /** All sessions with a match */
+  totalSessions: number
+  offset: number
+  hasMore: boolean
+  /** Empty when grouped */
+  hits: Array<SearchHit>
+  /** Empty unless grouped */
+  groups: Array<SessionHits>
+}
+/**
+ * Messages under root_dir containing every word of query (case-insensitive),
+ * one page at a time. Filter tokens narrow the scan: has:image, has:thinking,
+ * has:tool, tool:<name>, role:<role>, model:<part>, project:<part>, is:sidechain,
+ * lang:<ISO 639-3 code or English name>, tag:<manual or automatic tag>
+ */
+export declare function searchHistory(rootDir: string, query: string, options?: SearchOptions | undefined | null): SearchPage
+/** One entry of a list_sessions page */
+export interface ListedSession {
+  /** Project folder name, e.g. "-home-me-app" */
+  project: string
+  bytes: number
+  /** File modification time (RFC 3339), i.e. the last activity */
+  modifiedAt?: string
+  /** Fast summary: counts, timestamps, tokens, flags and cwd */
+  summary: ClaudeSession
+  /** "automation" for sessions imported by import_ci_artifacts, else "interactive" */
+  category: string
+  /** The CI run of an automation session */
+  ci?: CiMetadata
+  /**
+   * Manual and automatic tags; the automatic ones are recomputed when
+   * the file or the tag rules changed since they were stored
+   */
+  tags: Array<string>
+}
+/** Result of list_sessions */
+export interface SessionListPage {
+  sortBy: string
+  sessions: Array<ListedSession>
+  /** Sessions under the root, on every page */
+  totalSessions: number
+  /** Pass back as cursor for the next page, None on the last one */
+  nextCursor?: string
+}
+/**
+ * One page of the sessions under root_dir, newest or largest first.
+ * sort_by: "last_activity" (default), "created" or "size"; cursor is the
+ * next_cursor of the previous page; limit defaults to 50
+ */
+export declare function listSessions(rootDir: string, sortBy?: string | undefined | null, cursor?: string | undefined | null, limit?: number | undefined | null): SessionListPage
+/** One subagent run */
+export interface SidechainGroup {
+  /** uuid of the first entry of the run */
+  rootUuid: string
+  /** subagent_type of the Task call that launched it, when it can be matched */
+  subagentType?: string
+  /** Excerpt of the prompt the subagent was given */
+  prompt?: string
+  messageCount: number
   inputTokens: number
   outputTokens: number
-  cacheCreationInputTokens?: number
-  cacheReadInputTokens?: number
+  firstTimestamp?: string
+  lastTimestamp?: string
+}
+/** Subagent runs of a session with their message counts and token totals */
+export declare function getSidechainGroups(filePath: string): Array<SidechainGroup>
+/** A past prompt similar to the query text */
+export interface SimilarPrompt {
+  sessionId: string
+  filePath: string
+  messageId: string
+  canonicalId: string
+  timestamp?: string
+  excerpt: string
+  /** Estimated Jaccard similarity (0..1) */
+  similarity: number
+}
+/** Find past user prompts similar to `text` across all sessions under root_dir */
+export declare function findSimilarPrompts(rootDir: string, text: string, threshold?: number | undefined | null): Array<SimilarPrompt>
+/**
+ * Text statistics of one role's messages. Only messages with text count;
+ * pure tool calls and tool results are left out
+ */
+export interface RoleStats {
+  messageCount: number
+  totalChars: number
+  averageChars: number
+  longestChars: number
+  longestMessageId?: string
+  /** Messages containing at least one ``` fenced block */
+  codeBlockMessages: number
+}
+/** Options for get_storage_report */
+export interface StorageOptions {
+  /** Entries in largest_files (default 10) */
+  largestLimit?: number
+  /** Sessions untouched for this many days count as reclaimable (default 90) */
+  archiveOlderThanDays?: number
+}
+/** Size of one session file */
+export interface SessionUsage {
+  filePath: string
+  /** File name without .jsonl (the session id) */
+  sessionId: string
+  project: string
+  bytes: number
+  /** Last modification time (RFC 3339) */
+  modified?: string
+}
+/** Sessions of one project directory, largest first */
+export interface ProjectUsage {
+  /** Directory name below root_dir */
+  project: string
+  bytes: number
+  sessionCount: number
+  lastModified?: string
+  sessions: Array<SessionUsage>
+}
+/** Sessions last modified in one month */
+export interface MonthlyUsage {
+  /** "2024-01" */
+  month: string
+  bytes: number
+  sessionCount: number
+  /** Total size of sessions modified up to the end of this month */
+  cumulativeBytes: number
 }
+/** Result of get_storage_report */
+export interface StorageReport {
+  totalBytes: number
+  sessionCount: number
+  /** Largest projects first */
+  projects: Array<ProjectUsage>
+  largestFiles: Array<SessionUsage>
+  /** Oldest month first */
+  growth: Array<MonthlyUsage>
+  archiveOlderThanDays: number
+  /** Bytes freed by archiving sessions older than archive_older_than_days */
+  reclaimableBytes: number
+  reclaimableSessions: number
+}
+/** Disk usage of all sessions under root_dir (largest files, growth, reclaimable space) */
+export declare function getStorageReport(rootDir: string, options?: StorageOptions | undefined | null): StorageReport
+/** Bookmark / annotation attached to a single message */
+export interface Bookmark {
+  sessionId: string
+  messageId: string
+  /** ClaudeMessage.canonical_id of the message */
+  canonicalId: string
+  note?: string
+  tags: Array<string>
+  createdAt: number
+  updatedAt: number
+}
+/** Filter for list_bookmarks (all fields optional, combined with AND) */
+export interface BookmarkFilter {
+  sessionId?: string
+  tag?: string
+  /** Case-insensitive match against the note text */
+  query?: string
+}
+/** Entries per kind, written by export_metadata or added and updated by import_metadata */
+export interface MetadataCounts {
+  bookmarks: number
+  sessionTags: number
+  savedSearches: number
+  ciSessions: number
+  issueLinks: number
+  tagRules: number
+  sessionTitles: number
+}
+/** Options for import_metadata */
+export interface ImportMetadataOptions {
+  /** Replace the whole store instead of merging into it (default false) */
+  replace?: boolean
+}
+/** Point the metadata store at a directory (e.g. Electron's app.getPath('userData')) */
+export declare function setStorePath(dir: string): void
+/** Get the metadata store file currently in use */
+export declare function getStorePath(): string
+/**
+ * Write bookmarks, tags, tag rules, session titles, saved searches, issue
+ * links and CI session records to path, to carry them to another machine along with
+ * the raw history. An existing file is only replaced with overwrite
+ */
+export declare function exportMetadata(path: string, overwrite?: boolean | undefined | null): MetadataCounts
+/**
+ * Merge a file written by export_metadata into the store (or replace the
+ * store with it). Counts are the entries added or updated
+ */
+export declare function importMetadata(path: string, options?: ImportMetadataOptions | undefined | null): MetadataCounts
+/**
+ * Bookmark a message by message_id or canonical_id (updates note/tags if
+ * it is already bookmarked). Pass the session file to record the ids of the
+ * parsed message; messages without a uuid need it or their canonical_id
+ */
+export declare function addBookmark(sessionId: string, messageId: string, note?: string | undefined | null, tags?: Array<string> | undefined | null, filePath?: string | undefined | null): Bookmark
+/** Remove a bookmark, returns false if the message was not bookmarked */
+export declare function removeBookmark(sessionId: string, messageId: string): boolean
+/** List bookmarks, newest first */
+export declare function listBookmarks(filter?: BookmarkFilter | undefined | null): Array<Bookmark>
+/** All bookmarks/notes for one session, in the order they were created */
+export declare function getAnnotationsForSession(sessionId: string): Array<Bookmark>
+/** Result of get_streaks; dates are local YYYY-MM-DD */
+export interface StreakStats {
+  timezone: string
+  /** Days in a row up to today, or up to yesterday while today is still empty */
+  currentStreak: number
+  longestStreak: number
+  longestStreakStart?: string
+  longestStreakEnd?: string
+  activeDays: number
+  firstActiveDate?: string
+  lastActiveDate?: string
+}
+/** Current and longest daily usage streaks under root_dir, days in timezone (default UTC) */
+export declare function getStreaks(rootDir: string, timezone?: string | undefined | null): StreakStats
+/**
+ * Adds `tag` to sessions matching every pattern that is set, e.g.
+ * { tag: "db-migration", content: "migrat(e|ion)", tool: "^Bash$" }
+ */
+export interface TagRule {
+  /** Unique; saving a rule with the same name replaces it */
+  name: string
+  tag: string
+  /** Case-insensitive regex over message text, tool inputs and tool output */
+  content?: string
+  /** Case-insensitive regex over the names of the tools called */
+  tool?: string
+  /** Case-insensitive regex over the project folder name or the cwd */
+  project?: string
+  /** Set by save_tag_rule */
+  updatedAt?: number
+}
+/** Sessions given one tag by apply_tag_rules */
+export interface TagCount {
+  tag: string
+  sessions: number
+}
+/** Result of apply_tag_rules */
+export interface TagRuleRun {
+  sessionsScanned: number
+  /** Sessions that got at least one automatic tag */
+  sessionsTagged: number
+  /** Sorted by tag */
+  tags: Array<TagCount>
+}
+/** Add or replace a tag rule; its patterns are checked first */
+export declare function saveTagRule(rule: TagRule): TagRule
+/**
+ * Delete a tag rule. Returns false if it did not exist; tags it added stay
+ * until the sessions are tagged again
+ */
+export declare function deleteTagRule(name: string): boolean
+/** Every tag rule, in creation order */
+export declare function listTagRules(): Array<TagRule>
+/**
+ * Recompute the automatic tags (built-in and from the tag rules) of a
+ * session file or of every session under a directory, and store them
+ */
+export declare function applyTagRules(filePathOrRoot: string): TagRuleRun
+/** Tags attached to one session */
+export interface SessionTags {
+  sessionId: string
+  /** Tags set by the user */
+  tags: Array<string>
+  /** Tags derived from content and the tag rules */
+  autoTags: Array<string>
+  /**
+   * Size, mtime and tag rules auto_tags were computed from; listing
+   * the session recomputes them once any of these changes
+   */
+  autoTagsSource?: string
+  updatedAt: number
+}
+/** Replace the user-defined tags of a session */
+export declare function setSessionTags(sessionId: string, tags: Array<string>): SessionTags
+/** Get manual and automatic tags of a session (empty if never tagged) */
+export declare function getSessionTags(sessionId: string): SessionTags
+/** Find session ids carrying a tag (manual or automatic) */
+export declare function findSessionsByTag(tag: string): Array<string>
+/**
+ * Detect automatic tags (built-in and from tag rules) for a session file
+ * and persist them in the store
+ */
+export declare function detectSessionTags(filePath: string): SessionTags
+/** Options for get_term_frequencies */
+export interface TermFrequencyOptions {
+  /** Only prompts at or after this ISO timestamp or YYYY-MM-DD date */
+  from?: string
+  /** Only prompts before this ISO timestamp or YYYY-MM-DD date */
+  to?: string
+  /** IANA timezone for date-only bounds (default UTC) */
+  timezone?: string
+  /** Words per term, 1-3 (default 1) */
+  ngramSize?: number
+  /** Number of terms returned (default 100) */
+  maxTerms?: number
+  /** Drop terms seen fewer times than this (default 1) */
+  minCount?: number
+}
+/** How often a term (or n-gram) appears in user prompts */
+export interface TermFrequency {
+  term: string
+  count: number
+  /** Number of distinct sessions using the term */
+  sessionCount: number
+}
+/** Top terms/n-grams of user prompts under root_dir (powers the word cloud) */
+export declare function getTermFrequencies(rootDir: string, options?: TermFrequencyOptions | undefined | null): Array<TermFrequency>
+/** A timestamp broken down in a given timezone */
+export interface ParsedTimestamp {
+  epochMillis: number
+  /** Normalized RFC 3339 UTC string */
+  utc: string
+  /** RFC 3339 string with the local offset */
+  local: string
+  timezone: string
+  /** Local calendar day, YYYY-MM-DD */
+  date: string
+  /** Local hour of day, 0-23 */
+  hour: number
+  /** Local weekday, 0 = Monday ... 6 = Sunday */
+  weekday: number
+  /** ISO week, YYYY-Www */
+  week: string
+  /** Local month, YYYY-MM */
+  month: string
+}
+/** Parse an ISO timestamp and break it down in an IANA timezone (default UTC) */
+export declare function parseTimestamp(timestamp: string, timezone?: string | undefined | null): ParsedTimestamp
+/** Title the user gave a session */
+export interface SessionTitle {
+  sessionId: string
+  title: string
+  updatedAt: number
+}
+/** Generate a short title for a session (summary entry, else first user prompt) */
+export declare function generateSessionTitle(filePath: string): string | null
+/** Give a session a title of the user's own; None or a blank title clears it */
+export declare function setSessionTitle(sessionId: string, title?: string | undefined | null): SessionTitle | null
+/** The title the user gave a session, if any */
+export declare function getSessionTitle(sessionId: string): string | null
+export interface ValueCount {
+  value: string
+  count: number
+}
+/** Counts for one input field, e.g. Bash "command" or Read "file_path" */
+export interface InputFieldStats {
+  field: string
+  /** Calls that set the field */
+  calls: number
+  distinct: number
+  /** Most frequent first, at most 20 */
+  top: Array<ValueCount>
+}
+/** Result of get_tool_input_stats */
+export interface ToolInputStats {
+  toolName: string
+  calls: number
+  sessions: number
+  /** Fields in order of how many calls set them */
+  fields: Array<InputFieldStats>
+}
+/** Most common input values of tool_name (e.g. "Bash", "Read", "Grep") under root_dir */
+export declare function getToolInputStats(rootDir: string, toolName: string): ToolInputStats
+/** One ranked item; sessions, files or thinking blocks depending on the metric */
+export interface TopEntry {
+  /** 1 for the highest value */
+  rank: number
+  /** USD, seconds, errors, edits or thinking characters */
+  value: number
+  /** Session title, edited file path or thinking excerpt */
+  label: string
+  sessionId: string
+  filePath: string
+  /** The thinking message, or None for session-level metrics */
+  messageId?: string
+  /** Last activity of the session, or the time of the message */
+  timestamp?: string
+}
+/**
+ * The n highest entries under root_dir for a metric ("cost", "duration",
+ * "tool_errors", "edited_files" or "thinking") over "day", "week", "month" or "all"
+ */
+export declare function getTop(rootDir: string, metric: string, n: number, period: string): Array<TopEntry>
+/** Clustering options */
+export interface ClusterOptions {
+  /** Number of topics (default: sqrt(sessions / 2), at least 1) */
+  numClusters?: number
+  /** Keywords reported per topic (default 5) */
+  maxKeywords?: number
+}
+/** Session belonging to a topic */
+export interface ClusterMember {
+  sessionId: string
+  filePath: string
+  title?: string
+  /** Cosine similarity to the topic centroid */
+  score: number
+}
+/** A group of sessions about the same thing */
+export interface TopicCluster {
+  clusterId: number
+  keywords: Array<string>
+  sessions: Array<ClusterMember>
+}
+/** Group sessions under root_dir into topics with representative keywords */
+export declare function clusterSessions(rootDir: string, options?: ClusterOptions | undefined | null): Array<TopicCluster>
+/** Options for build_conversation_tree */
+export interface TreeOptions {
+  /**
+   * Attach orphans and cycle members to the message logged just before
+   * them (default false: they stay roots)
+   */
+  repair?: boolean
+}
+/** One entry of the tree, in file order */
+export interface TreeNode {
+  uuid: string
+  /** Parent in the returned tree (after repair) */
+  parentUuid?: string
+  /** parentUuid as logged */
+  loggedParentUuid?: string
+  /** "user", "assistant", "system", ... */
+  entryType: string
+  timestamp?: string
+  isSidechain: boolean
+  /** 0 for roots */
+  depth: number
+  childCount: number
+  /** True when repair gave the node a different parent than logged */
+  repaired: boolean
+}
+/** A node whose parentUuid is not in the file */
+export interface OrphanNode {
+  uuid: string
+  missingParentUuid: string
+  /** Where repair attached it, None without repair */
+  attachedTo?: string
+}
+/** What is wrong with the tree as logged */
+export interface TreeDiagnostics {
+  orphans: Array<OrphanNode>
+  /** uuids of each parent cycle, starting with the member whose link was cut */
+  cycles: Array<Array<string>>
+  /** Roots as logged (no parent, missing parent or cut out of a cycle) */
+  rootUuids: Array<string>
+  multipleRoots: boolean
+  /** uuids logged more than once; only the first entry is used */
+  duplicateUuids: Array<string>
+}
+/** Result of build_conversation_tree */
+export interface ConversationTree {
+  nodes: Array<TreeNode>
+  /** Roots of the returned tree (after repair) */
+  rootUuids: Array<string>
+  diagnostics: TreeDiagnostics
+}
+/**
+ * The message tree of a session with diagnostics on orphans, cycles and
+ * multiple roots
+ */
+export declare function buildConversationTree(filePath: string, options?: TreeOptions | undefined | null): ConversationTree
+/** Options for get_usage_stats / generate_usage_report */
+export interface UsageOptions {
+  /** IANA timezone for day buckets (default UTC) */
+  timezone?: string
+  /** Name recorded in the stats for team reports (default $USER / %USERNAME%) */
+  user?: string
+  /** Let write_usage_stats replace an existing output file (default false) */
+  overwrite?: boolean
+  /**
+   * Only count sessions whose primary language is this ISO 639-3 code
+   * or English name (see detect_session_languages)
+   */
+  language?: string
+}
+/** Options for export_stats_only */
+export interface StatsExportOptions {
+  /** "day", "week", "month" or "all" (default "all") */
+  period?: string
+  timezone?: string
+  /**
+   * Name recorded in the stats (default none; the login name is not
+   * filled in, so the file does not identify whoever exported it)
+   */
+  user?: string
+  /** Replace project paths with stable "project-1a2b3c4d" ids (default false) */
+  redactProjects?: boolean
+  /** Replace an existing output file (default false) */
+  overwrite?: boolean
+}
+/** Totals for one model, project, day or the whole period */
+export interface UsageRow {
+  /** Model name, project path, YYYY-MM-DD date or "total" */
+  key: string
+  sessions: number
+  messages: number
+  inputTokens: number
+  outputTokens: number
+  cacheCreationTokens: number
+  cacheReadTokens: number
+}
+/** Usage of one machine over a period; serializable so it can be shared and merged */
+export interface UsageStats {
+  /** Who produced the stats, see UsageOptions.user */
+  user?: string
+  /** "day", "week", "month" or "all" */
+  period: string
+  from?: string
+  to: string
+  timezone: string
+  totals: UsageRow
+  /** Most output tokens first */
+  byModel: Array<UsageRow>
+  byProject: Array<UsageRow>
+  /** Oldest day first */
+  daily: Array<UsageRow>
+}
+/** One member's totals in a team report */
+export interface UserUsage {
+  /** From the stats file, else the file name */
+  user: string
+  sourcePath: string
+  period: string
+  totals: UsageRow
+  byModel: Array<UsageRow>
+}
+/** Several members' stats merged; rows are summed by key */
+export interface TeamUsage {
+  /** Earliest start and latest end of the merged periods */
+  from?: string
+  to?: string
+  totals: UsageRow
+  /** Most output tokens first */
+  users: Array<UserUsage>
+  byModel: Array<UsageRow>
+  byProject: Array<UsageRow>
+  /** Oldest day first */
+  daily: Array<UsageRow>
+}
+/** Usage totals under root_dir for "day", "week", "month" (rolling) or "all" */
+export declare function getUsageStats(rootDir: string, period: string, options?: UsageOptions | undefined | null): UsageStats
+/** Write get_usage_stats as JSON to output_path, for aggregate_team_usage elsewhere */
+export declare function writeUsageStats(rootDir: string, period: string, outputPath: string, options?: UsageOptions | undefined | null): UsageStats
+/**
+ * Write aggregate metrics only - counts, tokens, models, days and (optionally
+ * redacted) project names, never any message text - safe to hand to finance
+ */
+export declare function exportStatsOnly(rootDir: string, output: string, options?: StatsExportOptions | undefined | null): UsageStats
+/** Merge stats files written by write_usage_stats on each member's machine */
+export declare function aggregateTeamUsage(paths: Array<string>): TeamUsage
+/** Shareable usage report; format is "markdown" or "html" */
+export declare function generateUsageReport(rootDir: string, period: string, format: string, options?: UsageOptions | undefined | null): string
+/** A change under the watched directory */
+export interface SessionFileEvent {
+  /** "session_created", "session_modified", "session_deleted" or "session_moved" */
+  kind: string
+  filePath: string
+  /** Where a moved session was before */
+  previousPath?: string
+  sessionId?: string
+}
+/** Options for watch_sessions */
+export interface WatchOptions {
+  /** How often the directory is scanned (default 1000 ms) */
+  pollIntervalMs?: number
+}
+/**
+ * Call callback with every change to the session files under root_dir
+ * until stop_watching is called, returns the watcher id
+ */
+export declare function watchSessions(rootDir: string, callback: (event: SessionFileEvent) => void, options?: WatchOptions | undefined | null): number
+/** Stop a watcher, returns false if it was already stopped */
+export declare function stopWatching(watcher: number): boolean
+/** One WebSearch or WebFetch call */
+export interface WebLookup {
+  /** "search" or "fetch" */
+  kind: string
+  toolUseId: string
+  /** uuid of the assistant entry that made the call */
+  messageId?: string
+  timestamp?: string
+  /** Search terms */
+  query?: string
+  /** Fetched URL */
+  url?: string
+  /** What the agent asked about the fetched page */
+  prompt?: string
+  /** Links found in the result, in order */
+  resultUrls: Array<string>
+  /** Start of the result text, None while no result was written */
+  summary?: string
+  isError: boolean
+}
+/** Result of extract_web_activity */
+export interface WebActivity {
+  /** Distinct search terms, first use first */
+  queries: Array<string>
+  /** Distinct fetched URLs, first use first */
+  visitedUrls: Array<string>
+  lookups: Array<WebLookup>
+}
+/** Searches and fetched pages of a session with their results */
+export declare function extractWebActivity(filePath: string): WebActivity
 /** Enhanced Claude message with full content support */
 export interface ClaudeMessage {
   messageId: string
+  canonicalId: string
   sessionId: string
   role: string
   content: string
   timestamp: string
   rawContent: string
+  rawContentOffset?: number
+  rawContentLength?: number
   hasThinking: boolean
   hasToolUse: boolean
   hasImages: boolean
@@ -30,6 +1854,68 @@ export interface ClaudeMessage {
   cacheReadTokens?: number
   isSidechain?: boolean
   userType?: string
+  timestampInferred: boolean
+  attachments: Array<AttachmentInfo>
+  contentTruncated: boolean
+  contentBytes: number
+  toolResultBytes: number
+  imageBytes: number
+}
+/** Options for parse_claude_session */
+export interface ParseOptions {
+  /** Fill missing or invalid timestamps from neighbouring messages */
+  inferTimestamps?: boolean
+  /** Re-sort messages by timestamp (stable, file order breaks ties) */
+  sortByTimestamp?: boolean
+  /** Files at least this many bytes are memory-mapped (default 64 MiB, negative = never) */
+  mmapThresholdBytes?: number
+  /** Files at least this many bytes are parsed in parallel chunks (default 32 MiB, negative = never) */
+  parallelThresholdBytes?: number
+  /** Longer lines (e.g. huge base64 images) are skipped unread (default 32 MiB, negative = unlimited) */
+  maxLineBytes?: number
+  /**
+   * Stop reading once the kept messages' content adds up to this many bytes;
+   * the message that did not fit is reported as skipped (default unlimited).
+   * Parallel reads parse no more than this many bytes of the file ahead
+   */
+  memoryBudgetBytes?: number
+  /** Build raw_content (default true); false leaves it empty and skips serializing it */
+  includeRawContent?: boolean
+  /** Cut content to this many characters for list views (see get_full_message) */
+  maxTextChars?: number
+  /**
+   * For files still being written: "none" (default), "length" (stop at the
+   * last complete line as of the start) or "copy" (the same on a temporary copy)
+   */
+  snapshot?: string
+  /**
+   * Build content from every block in order, tool calls and results included,
+   * each under a "[#index type]" header (default false: text and thinking only)
+   */
+  orderedContent?: boolean
+  /**
+   * "full" (default) or "summary": image blocks in raw_content become
+   * { type, index, media_type, byte_size, sha256 } (see get_image_data)
+   */
+  imageMode?: string
+}
+/** A line parse_claude_session left out, and why */
+export interface SkippedLine {
+  /** 1-based line number */
+  lineNumber: number
+  bytes: number
+  /** "line_too_long", "parse_error" or "memory_budget" */
+  reason: string
+}
+/** Messages plus the lines that were skipped on the way */
+export interface ParsedSession {
+  messages: Array<ClaudeMessage>
+  skippedLines: Array<SkippedLine>
+  /**
+   * Snapshot strategy used: "none", "length" or "copy" ("copy" falls back
+   * to "length" when no temporary copy can be written)
+   */
+  snapshot: string
 }
 /** Session summary */
 export interface ClaudeSession {
@@ -42,15 +1928,41 @@ export interface ClaudeSession {
   lastTimestamp?: string
   totalInputTokens?: number
   totalOutputTokens?: number
+  mainInputTokens: number
+  mainOutputTokens: number
+  sidechainInputTokens: number
+  sidechainOutputTokens: number
   hasThinking: boolean
   hasToolUse: boolean
   cwd?: string
+  firstUserPromptExcerpt?: string
+  lastAssistantExcerpt?: string
+  health?: SessionHealth
+  userStats?: RoleStats
+  assistantStats?: RoleStats
+  durationSeconds?: number
+  activeSeconds?: number
+  pendingToolUses?: Array<PendingToolUse>
+  languages?: SessionLanguages
+}
+/** Options for get_session_summary */
+export interface SummaryOptions {
+  /** Only read counts, timestamps, tokens, flags and cwd (no excerpts or health) */
+  fast?: boolean
+  /** Gaps between entries longer than this are idle, not active time (default 300) */
+  idleThresholdSeconds?: number
 }
 /** Parse Claude Code session file and return all messages */
-export declare function parseClaudeSession(filePath: string): Array<ClaudeMessage>
+export declare function parseClaudeSession(filePath: string, options?: ParseOptions | undefined | null): Array<ClaudeMessage>
+/** Parse a session file and also report every line that was skipped */
+export declare function parseClaudeSessionWithReport(filePath: string, options?: ParseOptions | undefined | null): ParsedSession
 /** Get session summary with enhanced statistics */
-export declare function getSessionSummary(filePath: string): ClaudeSession
+export declare function getSessionSummary(filePath: string, options?: SummaryOptions | undefined | null): ClaudeSession
 export declare function countLines(filePath: string): number
+/** Every non-empty line; read_lines_range pages through large files instead */
 export declare function readLines(filePath: string): Array<string>
 export declare function readLinesWithPattern(filePath: string, pattern: string): Array<string>
+/** "File: ...
+Size: ... bytes
+Lines: ..."; get_file_details returns the same as an object */
 export declare function getFileInfo(filePath: string): string
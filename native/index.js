@@ -310,9 +310,121 @@ if (!nativeBinding) {
   throw new Error(`Failed to load native binding`)
 }
 
-const { parseClaudeSession, getSessionSummary, countLines, readLines, readLinesWithPattern, getFileInfo } = nativeBinding
+const { listAnalyzers, runAnalyzers, getToolOutputSpans, extractFileReads, checkScopeViolations, getCostByAuthor, listBranches, diffBranches, checkBudget, importCiArtifacts, getAutomationDir, listCiSessions, findStaleArtifacts, configure, getConfig, resolveConflicts, openMessageCursor, nextBatch, closeCursor, deleteSession, findDuplicateAttachments, describeError, getSessionEvents, exportSessionObsidian, exportSessionText, exportWithSubstitutions, exportHighlights, exportBashScript, exportObsidianVault, getErrorReport, getFileDetails, followSession, stopFollowing, exportConversationGraph, getModelSwitches, getUsageHistograms, parseClaudeSessionWithHook, generateThumbnails, getImageData, getFullMessage, getRawEntry, readLinesRange, statSessions, linkSessionToIssue, unlinkSessionFromIssue, getSessionIssues, findSessionsForIssue, detectSessionIssues, detectSessionLanguages, getActiveSessions, setLogHandler, setLogFile, findDuplicateSessions, parseClaudeSessionPacked, parseClaudeSessionWithRawBuffer, getPayloadStats, getPermissionEvents, extractPrompts, exportPromptLibrary, getBurnRate, openReplay, stepForward, stepTo, closeReplay, getDefaultRiskRules, scanRiskyCommands, saveSearch, listSavedSearches, deleteSavedSearch, runSavedSearch, scanSessionFiles, getOutputSchemas, searchHistory, listSessions, getSidechainGroups, findSimilarPrompts, getStorageReport, setStorePath, getStorePath, exportMetadata, importMetadata, addBookmark, removeBookmark, listBookmarks, getAnnotationsForSession, getStreaks, saveTagRule, deleteTagRule, listTagRules, applyTagRules, setSessionTags, getSessionTags, findSessionsByTag, detectSessionTags, getTermFrequencies, parseTimestamp, generateSessionTitle, setSessionTitle, getSessionTitle, getToolInputStats, getTop, clusterSessions, buildConversationTree, getUsageStats, writeUsageStats, exportStatsOnly, aggregateTeamUsage, generateUsageReport, watchSessions, stopWatching, extractWebActivity, parseClaudeSession, parseClaudeSessionWithReport, getSessionSummary, countLines, readLines, readLinesWithPattern, getFileInfo } = nativeBinding
 
+module.exports.listAnalyzers = listAnalyzers
+module.exports.runAnalyzers = runAnalyzers
+module.exports.getToolOutputSpans = getToolOutputSpans
+module.exports.extractFileReads = extractFileReads
+module.exports.checkScopeViolations = checkScopeViolations
+module.exports.getCostByAuthor = getCostByAuthor
+module.exports.listBranches = listBranches
+module.exports.diffBranches = diffBranches
+module.exports.checkBudget = checkBudget
+module.exports.importCiArtifacts = importCiArtifacts
+module.exports.getAutomationDir = getAutomationDir
+module.exports.listCiSessions = listCiSessions
+module.exports.findStaleArtifacts = findStaleArtifacts
+module.exports.configure = configure
+module.exports.getConfig = getConfig
+module.exports.resolveConflicts = resolveConflicts
+module.exports.openMessageCursor = openMessageCursor
+module.exports.nextBatch = nextBatch
+module.exports.closeCursor = closeCursor
+module.exports.deleteSession = deleteSession
+module.exports.findDuplicateAttachments = findDuplicateAttachments
+module.exports.describeError = describeError
+module.exports.getSessionEvents = getSessionEvents
+module.exports.exportSessionObsidian = exportSessionObsidian
+module.exports.exportSessionText = exportSessionText
+module.exports.exportWithSubstitutions = exportWithSubstitutions
+module.exports.exportHighlights = exportHighlights
+module.exports.exportBashScript = exportBashScript
+module.exports.exportObsidianVault = exportObsidianVault
+module.exports.getErrorReport = getErrorReport
+module.exports.getFileDetails = getFileDetails
+module.exports.followSession = followSession
+module.exports.stopFollowing = stopFollowing
+module.exports.exportConversationGraph = exportConversationGraph
+module.exports.getModelSwitches = getModelSwitches
+module.exports.getUsageHistograms = getUsageHistograms
+module.exports.parseClaudeSessionWithHook = parseClaudeSessionWithHook
+module.exports.generateThumbnails = generateThumbnails
+module.exports.getImageData = getImageData
+module.exports.getFullMessage = getFullMessage
+module.exports.getRawEntry = getRawEntry
+module.exports.readLinesRange = readLinesRange
+module.exports.statSessions = statSessions
+module.exports.linkSessionToIssue = linkSessionToIssue
+module.exports.unlinkSessionFromIssue = unlinkSessionFromIssue
+module.exports.getSessionIssues = getSessionIssues
+module.exports.findSessionsForIssue = findSessionsForIssue
+module.exports.detectSessionIssues = detectSessionIssues
+module.exports.detectSessionLanguages = detectSessionLanguages
+module.exports.getActiveSessions = getActiveSessions
+module.exports.setLogHandler = setLogHandler
+module.exports.setLogFile = setLogFile
+module.exports.findDuplicateSessions = findDuplicateSessions
+module.exports.parseClaudeSessionPacked = parseClaudeSessionPacked
+module.exports.parseClaudeSessionWithRawBuffer = parseClaudeSessionWithRawBuffer
+module.exports.getPayloadStats = getPayloadStats
+module.exports.getPermissionEvents = getPermissionEvents
+module.exports.extractPrompts = extractPrompts
+module.exports.exportPromptLibrary = exportPromptLibrary
+module.exports.getBurnRate = getBurnRate
+module.exports.openReplay = openReplay
+module.exports.stepForward = stepForward
+module.exports.stepTo = stepTo
+module.exports.closeReplay = closeReplay
+module.exports.getDefaultRiskRules = getDefaultRiskRules
+module.exports.scanRiskyCommands = scanRiskyCommands
+module.exports.saveSearch = saveSearch
+module.exports.listSavedSearches = listSavedSearches
+module.exports.deleteSavedSearch = deleteSavedSearch
+module.exports.runSavedSearch = runSavedSearch
+module.exports.scanSessionFiles = scanSessionFiles
+module.exports.getOutputSchemas = getOutputSchemas
+module.exports.searchHistory = searchHistory
+module.exports.listSessions = listSessions
+module.exports.getSidechainGroups = getSidechainGroups
+module.exports.findSimilarPrompts = findSimilarPrompts
+module.exports.getStorageReport = getStorageReport
+module.exports.setStorePath = setStorePath
+module.exports.getStorePath = getStorePath
+module.exports.exportMetadata = exportMetadata
+module.exports.importMetadata = importMetadata
+module.exports.addBookmark = addBookmark
+module.exports.removeBookmark = removeBookmark
+module.exports.listBookmarks = listBookmarks
+module.exports.getAnnotationsForSession = getAnnotationsForSession
+module.exports.getStreaks = getStreaks
+module.exports.saveTagRule = saveTagRule
+module.exports.deleteTagRule = deleteTagRule
+module.exports.listTagRules = listTagRules
+module.exports.applyTagRules = applyTagRules
+module.exports.setSessionTags = setSessionTags
+module.exports.getSessionTags = getSessionTags
+module.exports.findSessionsByTag = findSessionsByTag
+module.exports.detectSessionTags = detectSessionTags
+module.exports.getTermFrequencies = getTermFrequencies
+module.exports.parseTimestamp = parseTimestamp
+module.exports.generateSessionTitle = generateSessionTitle
+module.exports.setSessionTitle = setSessionTitle
+module.exports.getSessionTitle = getSessionTitle
+module.exports.getToolInputStats = getToolInputStats
+module.exports.getTop = getTop
+module.exports.clusterSessions = clusterSessions
+module.exports.buildConversationTree = buildConversationTree
+module.exports.getUsageStats = getUsageStats
+module.exports.writeUsageStats = writeUsageStats
+module.exports.exportStatsOnly = exportStatsOnly
+module.exports.aggregateTeamUsage = aggregateTeamUsage
+module.exports.generateUsageReport = generateUsageReport
+module.exports.watchSessions = watchSessions
+module.exports.stopWatching = stopWatching
+module.exports.extractWebActivity = extractWebActivity
 module.exports.parseClaudeSession = parseClaudeSession
+module.exports.parseClaudeSessionWithReport = parseClaudeSessionWithReport
 module.exports.getSessionSummary = getSessionSummary
 module.exports.countLines = countLines
 module.exports.readLines = readLines
@@ -0,0 +1,202 @@
+// ============================================
+// ANALYZERS
+// Pluggable per-session analytics: each analyzer visits every entry
+// once and finishes with a JSON report, all in a single read of the file
+// ============================================
+
+use std::collections::BTreeMap;
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use serde_json::{json, Value};
+
+use crate::errors::invalid_argument;
+use crate::failures::HealthTracker;
+use crate::stats::RoleStatsTracker;
+use crate::{for_each_entry, ContentItem, RawLogEntry};
+
+/// One analysis over the entries of a session
+pub(crate) trait Analyzer {
+    fn visit(&mut self, entry: &RawLogEntry);
+    fn finalize(self: Box<Self>) -> Value;
+}
+
+type Factory = fn() -> Box<dyn Analyzer>;
+
+/// Built-in analyzers by name. Company-specific ones belong in their own
+/// module behind a cargo feature, registered here with #[cfg(feature = "...")]
+const REGISTRY: &[(&str, Factory)] = &[
+    ("health", || Box::new(HealthAnalyzer::default())),
+    ("models", || Box::new(CountAnalyzer::new(model_keys))),
+    ("role_stats", || Box::new(RoleStatsAnalyzer::default())),
+    ("tool_counts", || Box::new(CountAnalyzer::new(tool_keys))),
+];
+
+#[derive(Default)]
+struct HealthAnalyzer(HealthTracker);
+
+impl Analyzer for HealthAnalyzer {
+    fn visit(&mut self, entry: &RawLogEntry) {
+        self.0.observe(entry);
+    }
+
+    fn finalize(self: Box<Self>) -> Value {
+        let health = self.0.finish();
+        json!({
+            "score": health.score,
+            "isTroubled": health.is_troubled,
+            "toolCalls": health.tool_calls,
+            "toolErrors": health.tool_errors,
+            "interruptions": health.interruptions,
+            "retries": health.retries,
+            "apiErrors": health.api_errors,
+            "compactions": health.compactions,
+        })
+    }
+}
+
+#[derive(Default)]
+struct RoleStatsAnalyzer(RoleStatsTracker);
+
+impl Analyzer for RoleStatsAnalyzer {
+    fn visit(&mut self, entry: &RawLogEntry) {
+        self.0.observe(entry);
+    }
+
+    fn finalize(self: Box<Self>) -> Value {
+        let (user, assistant) = self.0.finish();
+        let role = |stats: crate::stats::RoleStats| {
+            json!({
+                "messageCount": stats.message_count,
+                "totalChars": stats.total_chars,
+                "averageChars": stats.average_chars,
+                "longestChars": stats.longest_chars,
+                "longestMessageId": stats.longest_message_id,
+                "codeBlockMessages": stats.code_block_messages,
+            })
+        };
+        json!({ "user": role(user), "assistant": role(assistant) })
+    }
+}
+
+/// Occurrences of whatever keys an entry yields, e.g. tool names
+struct CountAnalyzer {
+    keys: fn(&RawLogEntry) -> Vec<String>,
+    counts: BTreeMap<String, u64>,
+}
+
+impl CountAnalyzer {
+    fn new(keys: fn(&RawLogEntry) -> Vec<String>) -> CountAnalyzer {
+        CountAnalyzer { keys, counts: BTreeMap::new() }
+    }
+}
+
+impl Analyzer for CountAnalyzer {
+    fn visit(&mut self, entry: &RawLogEntry) {
+        for key in (self.keys)(entry) {
+            *self.counts.entry(key).or_insert(0) += 1;
+        }
+    }
+
+    fn finalize(self: Box<Self>) -> Value {
+        json!(self.counts)
+    }
+}
+
+fn tool_keys(entry: &RawLogEntry) -> Vec<String> {
+    let Some(message) = &entry.message else { return Vec::new() };
+    message
+        .content
+        .iter()
+        .filter_map(|item| match item {
+            ContentItem::ToolUse { name, .. } => Some(name.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn model_keys(entry: &RawLogEntry) -> Vec<String> {
+    match &entry.message {
+        Some(message) if entry.entry_type == "assistant" => message.model.iter().cloned().collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Report of one analyzer
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnalyzerReport {
+    pub name: String,
+    pub report: Value,
+}
+
+pub(crate) fn run(file_path: &str, names: &[String]) -> Result<Vec<AnalyzerReport>> {
+    let mut analyzers: Vec<(&str, Box<dyn Analyzer>)> = Vec::with_capacity(names.len());
+    for name in names {
+        let (name, factory) = REGISTRY
+            .iter()
+            .find(|(registered, _)| registered == name)
+            .ok_or_else(|| invalid_argument(format!("Unknown analyzer: {}", name)))?;
+        analyzers.push((name, factory()));
+    }
+
+    for_each_entry(file_path, |entry| {
+        for (_, analyzer) in analyzers.iter_mut() {
+            analyzer.visit(&entry);
+        }
+    })?;
+
+    Ok(analyzers
+        .into_iter()
+        .map(|(name, analyzer)| AnalyzerReport { name: name.to_string(), report: analyzer.finalize() })
+        .collect())
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Names accepted by run_analyzers
+#[napi(catch_unwind)]
+pub fn list_analyzers() -> Vec<String> {
+    REGISTRY.iter().map(|(name, _)| name.to_string()).collect()
+}
+
+/// Run the named analyzers (all of them when not given) over one read of a session
+#[napi(catch_unwind)]
+pub fn run_analyzers(file_path: String, analyzer_names: Option<Vec<String>>) -> Result<Vec<AnalyzerReport>> {
+    let names = analyzer_names.unwrap_or_else(list_analyzers);
+    run(&file_path, &names)
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_run_analyzers() {
+        let tmp = TempDir::new("analyzers");
+        let path = tmp.join("analyzers.jsonl");
+        std::fs::write(&path, [
+            r#"{"type":"user","uuid":"u1","message":{"role":"user","content":"hi"}}"#,
+            r#"{"type":"assistant","uuid":"a1","message":{"role":"assistant","model":"claude-sonnet-4","content":[{"type":"tool_use","id":"t1","name":"Bash","input":{}},{"type":"tool_use","id":"t2","name":"Read","input":{}}]}}"#,
+            r#"{"type":"assistant","uuid":"a2","message":{"role":"assistant","model":"claude-sonnet-4","content":[{"type":"tool_use","id":"t3","name":"Bash","input":{}}]}}"#,
+        ].join("\n")).unwrap();
+        let file_path = path.display().to_string();
+
+        let reports = run(&file_path, &["tool_counts".to_string(), "health".to_string()]).unwrap();
+        assert_eq!(reports[0].report, json!({ "Bash": 2, "Read": 1 }));
+        assert_eq!(reports[1].report["toolCalls"], json!(3));
+
+        let all = run_analyzers(file_path.clone(), None).unwrap();
+        assert_eq!(all.iter().map(|r| r.name.as_str()).collect::<Vec<_>>(), vec!["health", "models", "role_stats", "tool_counts"]);
+        assert_eq!(all[1].report, json!({ "claude-sonnet-4": 2 }));
+
+        assert!(run(&file_path, &["nope".to_string()]).is_err());
+    }
+}
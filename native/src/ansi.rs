@@ -0,0 +1,216 @@
+// ============================================
+// ANSI TOOL OUTPUT
+// Bash tool results split into styled spans from their SGR color codes,
+// so the UI can render terminal output instead of escape garbage
+// ============================================
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::{for_each_entry, tool_result_text, ContentItem};
+
+const COLOR_NAMES: [&str; 8] = ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+
+/// A run of text in one style
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct OutputSpan {
+    pub text: String,
+    /// "red", "bright_red"... for the 16 theme colors, "#rrggbb" otherwise
+    pub fg: Option<String>,
+    pub bg: Option<String>,
+    pub bold: bool,
+    pub dim: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub inverse: bool,
+}
+
+/// Result of get_tool_output_spans
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolOutputSpans {
+    pub tool_use_id: String,
+    pub tool_name: Option<String>,
+    pub is_error: bool,
+    /// The output without any escape sequences
+    pub plain_text: String,
+    pub spans: Vec<OutputSpan>,
+}
+
+/// Style part of a span: everything but the text
+fn same_style(a: &OutputSpan, b: &OutputSpan) -> bool {
+    (&a.fg, &a.bg, a.bold, a.dim, a.italic, a.underline, a.inverse) == (&b.fg, &b.bg, b.bold, b.dim, b.italic, b.underline, b.inverse)
+}
+
+/// One of the 256 xterm colors
+fn color_256(index: u32) -> String {
+    match index {
+        0..=7 => COLOR_NAMES[index as usize].to_string(),
+        8..=15 => format!("bright_{}", COLOR_NAMES[index as usize - 8]),
+        16..=231 => {
+            let level = |v: u32| if v == 0 { 0 } else { 55 + v * 40 };
+            let i = index - 16;
+            format!("#{:02x}{:02x}{:02x}", level(i / 36), level(i / 6 % 6), level(i % 6))
+        }
+        _ => {
+            let gray = 8 + (index.min(255) - 232) * 10;
+            format!("#{:02x}{:02x}{:02x}", gray, gray, gray)
+        }
+    }
+}
+
+/// Color of a 38/48 extended code ("5;n" or "2;r;g;b"), consuming its parameters
+fn extended_color(params: &mut impl Iterator<Item = u32>) -> Option<String> {
+    match params.next()? {
+        5 => params.next().map(color_256),
+        2 => {
+            let (r, g, b) = (params.next()?, params.next()?, params.next()?);
+            Some(format!("#{:02x}{:02x}{:02x}", r.min(255), g.min(255), b.min(255)))
+        }
+        _ => None,
+    }
+}
+
+/// Apply one SGR sequence ("1;31" etc.) to the current style
+fn apply_sgr(style: &mut OutputSpan, params: &str) {
+    // An empty parameter list ("ESC[m") is a reset, like 0
+    let mut codes = params.split([';', ':']).map(|p| p.parse::<u32>().unwrap_or(0));
+    while let Some(code) = codes.next() {
+        match code {
+            0 => *style = OutputSpan::default(),
+            1 => style.bold = true,
+            2 => style.dim = true,
+            3 => style.italic = true,
+            4 => style.underline = true,
+            7 => style.inverse = true,
+            22 => (style.bold, style.dim) = (false, false),
+            23 => style.italic = false,
+            24 => style.underline = false,
+            27 => style.inverse = false,
+            30..=37 => style.fg = Some(COLOR_NAMES[code as usize - 30].to_string()),
+            38 => style.fg = extended_color(&mut codes),
+            39 => style.fg = None,
+            40..=47 => style.bg = Some(COLOR_NAMES[code as usize - 40].to_string()),
+            48 => style.bg = extended_color(&mut codes),
+            49 => style.bg = None,
+            90..=97 => style.fg = Some(format!("bright_{}", COLOR_NAMES[code as usize - 90])),
+            100..=107 => style.bg = Some(format!("bright_{}", COLOR_NAMES[code as usize - 100])),
+            _ => {}
+        }
+    }
+}
+
+/// Split terminal output into styled spans. Other CSI and OSC sequences and
+/// control characters besides newline and tab are dropped
+fn parse_spans(output: &str) -> Vec<OutputSpan> {
+    let mut spans: Vec<OutputSpan> = Vec::new();
+    let mut style = OutputSpan::default();
+    let mut text = String::new();
+    let mut chars = output.chars();
+
+    let flush = |spans: &mut Vec<OutputSpan>, text: &mut String, style: &OutputSpan| {
+        if text.is_empty() {
+            return;
+        }
+        match spans.last_mut() {
+            Some(last) if same_style(last, style) => last.text.push_str(text),
+            _ => spans.push(OutputSpan { text: text.clone(), ..style.clone() }),
+        }
+        text.clear();
+    };
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => match chars.next() {
+                Some('[') => {
+                    let mut params = String::new();
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            if c == 'm' {
+                                flush(&mut spans, &mut text, &style);
+                                apply_sgr(&mut style, &params);
+                            }
+                            break;
+                        }
+                        params.push(c);
+                    }
+                }
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' {
+                            break;
+                        }
+                        if c == '\x1b' {
+                            chars.next();
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            },
+            '\n' | '\t' => text.push(c),
+            c if c.is_control() => {}
+            c => text.push(c),
+        }
+    }
+    flush(&mut spans, &mut text, &style);
+    spans
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// The result of one tool call as styled spans, None if the session has no
+/// result for tool_use_id
+#[napi(catch_unwind)]
+pub fn get_tool_output_spans(file_path: String, tool_use_id: String) -> Result<Option<ToolOutputSpans>> {
+    let mut tool_name = None;
+    let mut found = None;
+    for_each_entry(&file_path, |entry| {
+        let Some(message) = &entry.message else { return };
+        for item in &message.content {
+            match item {
+                ContentItem::ToolUse { id, name, .. } if *id == tool_use_id => tool_name = Some(name.clone()),
+                ContentItem::ToolResult { tool_use_id: id, content, is_error } if *id == tool_use_id && found.is_none() => {
+                    found = Some((tool_result_text(content), *is_error == Some(true)));
+                }
+                _ => {}
+            }
+        }
+    })?;
+
+    Ok(found.map(|(output, is_error)| {
+        let spans = parse_spans(&output);
+        ToolOutputSpans {
+            tool_use_id,
+            tool_name,
+            is_error,
+            plain_text: spans.iter().map(|s| s.text.as_str()).collect(),
+            spans,
+        }
+    }))
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_spans() {
+        let spans = parse_spans("\x1b[1;31merror\x1b[0m: \x1b[38;5;208mwarn\x1b[39m\x1b[2K ok\r\n\x1b[48;2;0;0;255mx\x1b[m");
+        let text: Vec<&str> = spans.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(text, vec!["error", ": ", "warn", " ok\n", "x"]);
+        assert_eq!((spans[0].fg.as_deref(), spans[0].bold), (Some("red"), true));
+        assert_eq!(spans[1], OutputSpan { text: ": ".into(), ..Default::default() });
+        assert_eq!(spans[2].fg.as_deref(), Some("#ff8700"));
+        assert_eq!(spans[4].bg.as_deref(), Some("#0000ff"));
+        assert_eq!(color_256(9), "bright_red");
+        assert_eq!(color_256(244), "#808080");
+    }
+}
@@ -0,0 +1,120 @@
+// ============================================
+// ATTACHMENTS
+// Pasted text, images and @-mentioned files in user prompts, so the
+// viewer can show "pasted 300 lines" chips instead of walls of text
+// ============================================
+
+use napi_derive::napi;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::ContentItem;
+
+const PASTED_PREFIX: &str = "[Pasted text #";
+const IMAGE_PREFIX: &str = "[Image #";
+
+/// Something the user attached to a prompt
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AttachmentInfo {
+    /// "pasted_text", "image" or "file"
+    pub kind: String,
+    /// "Pasted text #1", "Image #2", the media type or the mentioned path
+    pub name: Option<String>,
+    /// Lines for pasted text, approximate bytes for images, None for files
+    pub size: Option<i64>,
+}
+
+fn attachment(kind: &str, name: Option<String>, size: Option<i64>) -> AttachmentInfo {
+    AttachmentInfo { kind: kind.to_string(), name, size }
+}
+
+/// "[Pasted text #1 +300 lines]" markers Claude Code leaves in place of long pastes
+fn pasted_markers(text: &str, out: &mut Vec<AttachmentInfo>) {
+    for (start, _) in text.match_indices(PASTED_PREFIX) {
+        let rest = &text[start + 1..];
+        let Some(end) = rest.find(']') else { continue };
+        let marker = &rest[..end];
+
+        let (name, lines) = match marker.split_once(" +") {
+            Some((name, lines)) => (name, lines.trim_end_matches(" lines").parse::<i64>().ok()),
+            None => (marker, None),
+        };
+        out.push(attachment("pasted_text", Some(name.to_string()), lines));
+    }
+}
+
+/// "@src/main.rs" style file mentions
+fn file_mentions(text: &str, out: &mut Vec<AttachmentInfo>) {
+    for word in text.split_whitespace() {
+        let Some(path) = word.strip_prefix('@') else { continue };
+        let path = path.trim_end_matches([',', '.', ';', ':', ')', '?', '!']);
+        // Needs a separator or extension so "@someone" does not count
+        if !path.is_empty() && (path.contains('/') || path.contains('.')) && !path.contains('@') {
+            out.push(attachment("file", Some(path.to_string()), None));
+        }
+    }
+}
+
+/// Attachments of one user message, in content order
+pub(crate) fn detect(content: &[ContentItem]) -> Vec<AttachmentInfo> {
+    let mut out = Vec::new();
+    let mut image_blocks = 0;
+
+    for item in content {
+        match item {
+            ContentItem::Text { text } => {
+                pasted_markers(text, &mut out);
+                file_mentions(text, &mut out);
+            }
+            ContentItem::Image { source } => {
+                image_blocks += 1;
+                // Base64 carries 3 bytes per 4 characters
+                let bytes = (source.data.len() as i64 * 3) / 4;
+                out.push(attachment("image", Some(source.media_type.clone()), Some(bytes)));
+            }
+            _ => {}
+        }
+    }
+
+    // "[Image #1]" markers without an inline image block (e.g. stripped on export)
+    if image_blocks == 0 {
+        for item in content {
+            if let ContentItem::Text { text } = item {
+                for (start, _) in text.match_indices(IMAGE_PREFIX) {
+                    if let Some(end) = text[start..].find(']') {
+                        out.push(attachment("image", Some(text[start + 1..start + end].to_string()), None));
+                    }
+                }
+            }
+        }
+    }
+
+    out
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_attachments() {
+        let line = r#"{"type":"user","message":{"role":"user","content":[
+            {"type":"text","text":"Look at @src/lib.rs and [Pasted text #1 +300 lines], thanks @bob"},
+            {"type":"image","source":{"type":"base64","media_type":"image/png","data":"AAAABBBB"}}
+        ]}}"#;
+        let entry = crate::parse_jsonl_line(line).unwrap();
+        let found = detect(&entry.message.unwrap().content);
+
+        assert_eq!(found, vec![
+            attachment("pasted_text", Some("Pasted text #1".into()), Some(300)),
+            attachment("file", Some("src/lib.rs".into()), None),
+            attachment("image", Some("image/png".into()), Some(6)),
+        ]);
+    }
+}
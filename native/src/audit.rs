@@ -0,0 +1,229 @@
+// ============================================
+// FILE ACCESS AUDIT
+// Which paths the agent's tool calls touched, resolved against the
+// session cwd, and which of them fell outside the allowed roots
+// ============================================
+
+use std::path::{Component, Path, PathBuf};
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::{for_each_entry, ContentItem, RawLogEntry};
+
+/// Tools that only look at files, with the input field naming the path
+const READ_TOOLS: &[(&str, &str)] = &[
+    ("Read", "file_path"),
+    ("NotebookRead", "notebook_path"),
+    ("Glob", "path"),
+    ("Grep", "path"),
+    ("LS", "path"),
+];
+
+/// Tools that change files
+const WRITE_TOOLS: &[(&str, &str)] = &[
+    ("Edit", "file_path"),
+    ("MultiEdit", "file_path"),
+    ("Write", "file_path"),
+    ("NotebookEdit", "notebook_path"),
+];
+
+/// Device paths commands use all the time; never a scope violation
+const IGNORED_PATHS: &[&str] = &["/dev/null", "/dev/stdin", "/dev/stdout", "/dev/stderr", "/dev/tty"];
+
+/// One path a tool call read, wrote or passed to a command
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileAccess {
+    /// "Read", "Glob", "Grep", ...
+    pub tool: String,
+    /// "read", "write" or "command" (a path argument of a Bash command)
+    pub access: String,
+    /// Path as passed to the tool, None when it defaulted to the cwd
+    pub path: Option<String>,
+    /// Absolute path after resolving ~, the cwd and ".."
+    pub resolved_path: String,
+    /// Glob or Grep pattern
+    pub pattern: Option<String>,
+    /// The Bash command the path appeared in
+    pub command: Option<String>,
+    pub tool_use_id: String,
+    /// uuid of the assistant entry that made the call
+    pub message_id: Option<String>,
+    pub timestamp: Option<String>,
+}
+
+/// Lexical absolute path: ~ is $HOME, relative paths are joined to cwd
+pub(crate) fn resolve_path(raw: &str, cwd: Option<&str>) -> String {
+    let home = std::env::var("HOME").or_else(|_| std::env::var("USERPROFILE")).unwrap_or_default();
+    let expanded = match raw.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => format!("{}{}", home, rest),
+        _ => raw.to_string(),
+    };
+    let joined = match cwd {
+        Some(cwd) if Path::new(&expanded).is_relative() => Path::new(cwd).join(&expanded),
+        _ => PathBuf::from(&expanded),
+    };
+
+    let mut normalized = PathBuf::new();
+    for component in joined.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other.as_os_str()),
+        }
+    }
+    normalized.display().to_string()
+}
+
+/// Absolute and ~ paths among the words of a shell command
+fn command_paths(command: &str) -> Vec<String> {
+    let mut paths: Vec<String> = Vec::new();
+    for word in command.split(|c: char| c.is_whitespace() || matches!(c, ';' | '|' | '&' | '<' | '>' | '(' | ')' | '\'' | '"' | '=' | '`')) {
+        if (word.starts_with('/') || word == "~" || word.starts_with("~/")) && !paths.iter().any(|p| p == word) {
+            paths.push(word.to_string());
+        }
+    }
+    paths
+}
+
+/// Every path touched by a tool call, in file order
+pub(crate) fn touched_paths(entries: &[RawLogEntry]) -> Vec<FileAccess> {
+    let mut cwd: Option<String> = None;
+    let mut touched = Vec::new();
+
+    for entry in entries {
+        if entry.cwd.is_some() {
+            cwd = entry.cwd.clone();
+        }
+        let Some(message) = &entry.message else { continue };
+        for item in &message.content {
+            let ContentItem::ToolUse { id, name, input } = item else { continue };
+            let text = |key: &str| input.get(key).and_then(|v| v.as_str()).map(str::to_string);
+            let mut push = |access: &str, path: Option<String>, command: Option<String>| {
+                let Some(raw) = path.clone().or_else(|| cwd.clone()) else { return };
+                touched.push(FileAccess {
+                    tool: name.clone(),
+                    access: access.to_string(),
+                    resolved_path: resolve_path(&raw, cwd.as_deref()),
+                    path,
+                    pattern: text("pattern"),
+                    command,
+                    tool_use_id: id.clone(),
+                    message_id: entry.uuid.clone(),
+                    timestamp: entry.timestamp.clone(),
+                });
+            };
+
+            if let Some((_, field)) = READ_TOOLS.iter().find(|(tool, _)| tool == name) {
+                push("read", text(field), None);
+            } else if let Some((_, field)) = WRITE_TOOLS.iter().find(|(tool, _)| tool == name) {
+                if let Some(path) = text(field) {
+                    push("write", Some(path), None);
+                }
+            } else if let (true, Some(command)) = (name == "Bash", text("command")) {
+                for path in command_paths(&command) {
+                    push("command", Some(path), Some(command.clone()));
+                }
+            }
+        }
+    }
+    touched
+}
+
+fn within(path: &str, roots: &[String]) -> bool {
+    roots.iter().any(|root| Path::new(path).starts_with(root))
+}
+
+/// Result of check_scope_violations
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScopeReport {
+    /// The allowed roots after resolving ~ and ".."
+    pub allowed_roots: Vec<String>,
+    /// Paths checked across all tool calls
+    pub checked: u32,
+    pub violations: Vec<FileAccess>,
+    /// Distinct resolved paths of the violations, first seen first
+    pub outside_paths: Vec<String>,
+}
+
+pub(crate) fn scope_report(entries: &[RawLogEntry], allowed_roots: &[String]) -> ScopeReport {
+    let roots: Vec<String> = allowed_roots.iter().map(|root| resolve_path(root, None)).collect();
+    let touched = touched_paths(entries);
+    let checked = touched.len() as u32;
+
+    let violations: Vec<FileAccess> = touched
+        .into_iter()
+        .filter(|access| !IGNORED_PATHS.contains(&access.resolved_path.as_str()) && !within(&access.resolved_path, &roots))
+        .collect();
+    let mut outside_paths: Vec<String> = Vec::new();
+    for violation in &violations {
+        if !outside_paths.contains(&violation.resolved_path) {
+            outside_paths.push(violation.resolved_path.clone());
+        }
+    }
+    ScopeReport { allowed_roots: roots, checked, violations, outside_paths }
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Every path the agent read, globbed or grepped in a session, in order
+#[napi(catch_unwind)]
+pub fn extract_file_reads(file_path: String) -> Result<Vec<FileAccess>> {
+    let mut entries = Vec::new();
+    for_each_entry(&file_path, |entry| entries.push(entry))?;
+    Ok(touched_paths(&entries).into_iter().filter(|access| access.access == "read").collect())
+}
+
+/// Tool calls of a session that touched paths outside allowed_roots (reads,
+/// writes and absolute paths in Bash commands)
+#[napi(catch_unwind)]
+pub fn check_scope_violations(file_path: String, allowed_roots: Vec<String>) -> Result<ScopeReport> {
+    let mut entries = Vec::new();
+    for_each_entry(&file_path, |entry| entries.push(entry))?;
+    Ok(scope_report(&entries, &allowed_roots))
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_jsonl_line;
+
+    #[test]
+    fn test_file_reads_resolved_against_cwd() {
+        let lines = [
+            r#"{"type":"user","cwd":"/work/app","message":{"role":"user","content":"look around"}}"#,
+            r#"{"type":"assistant","uuid":"a1","message":{"role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Read","input":{"file_path":"src/../Cargo.toml"}},{"type":"tool_use","id":"t2","name":"Grep","input":{"pattern":"TODO"}},{"type":"tool_use","id":"t3","name":"Edit","input":{"file_path":"/work/app/a.rs"}},{"type":"tool_use","id":"t4","name":"Glob","input":{"pattern":"*.rs","path":"/etc"}}]}}"#,
+        ];
+        let entries: Vec<RawLogEntry> = lines.iter().map(|l| parse_jsonl_line(l).unwrap()).collect();
+        let reads: Vec<FileAccess> = touched_paths(&entries).into_iter().filter(|a| a.access == "read").collect();
+
+        let summary: Vec<(&str, &str)> = reads.iter().map(|r| (r.tool.as_str(), r.resolved_path.as_str())).collect();
+        assert_eq!(summary, vec![("Read", "/work/app/Cargo.toml"), ("Grep", "/work/app"), ("Glob", "/etc")]);
+        assert_eq!((reads[1].path.as_ref(), reads[1].pattern.as_deref()), (None, Some("TODO")));
+    }
+
+    #[test]
+    fn test_scope_violations() {
+        let lines = [
+            r#"{"type":"user","cwd":"/work/app","message":{"role":"user","content":"go"}}"#,
+            r#"{"type":"assistant","uuid":"a1","message":{"role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Edit","input":{"file_path":"src/main.rs"}},{"type":"tool_use","id":"t2","name":"Bash","input":{"command":"cat /etc/hosts > /dev/null && ls /work/app/../secrets"}},{"type":"tool_use","id":"t3","name":"Write","input":{"file_path":"/tmp/out.txt"}}]}}"#,
+        ];
+        let entries: Vec<RawLogEntry> = lines.iter().map(|l| parse_jsonl_line(l).unwrap()).collect();
+        let report = scope_report(&entries, &["/work/app/".to_string(), "/tmp".to_string()]);
+
+        assert_eq!(report.checked, 5);
+        assert_eq!(report.outside_paths, vec!["/etc/hosts", "/work/secrets"]);
+        assert_eq!(report.violations[0].access, "command");
+        assert!(report.violations[0].command.as_deref().unwrap().starts_with("cat /etc/hosts"));
+    }
+}
@@ -0,0 +1,281 @@
+// ============================================
+// COST BY AUTHOR
+// Usage and estimated cost split by the git identity (user.email) of
+// the repository each session ran in, so a shared machine or CI runner
+// can bill people separately. Sessions outside a repo stay unattributed
+// ============================================
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::paths::read_to_string;
+use crate::pricing::{cost_usd, default_prices, price_for, ModelPrice};
+use crate::usage::{for_each_usage, period_start};
+
+/// Options for get_cost_by_author
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct AuthorCostOptions {
+    /// "day", "week", "month" (rolling) or "all" (default "all")
+    pub period: Option<String>,
+    /// Price overrides, checked before the built-in list
+    pub prices: Option<Vec<ModelPrice>>,
+}
+
+/// Usage attributed to one git identity
+#[napi(object)]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AuthorCost {
+    /// user.email in effect for the repos (repo config, else the global
+    /// one); None for sessions outside a repo or with no email set
+    pub email: Option<String>,
+    /// user.name next to the email, as first seen
+    pub name: Option<String>,
+    /// Work trees of the repositories, sorted
+    pub repos: Vec<String>,
+    pub sessions: u32,
+    pub messages: u32,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_creation_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cost_usd: f64,
+}
+
+/// Result of get_cost_by_author
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuthorCostReport {
+    /// Highest cost first, the unattributed row among them
+    pub authors: Vec<AuthorCost>,
+    pub total_cost_usd: f64,
+    /// Models with usage but no price, counted at $0
+    pub unpriced_models: Vec<String>,
+}
+
+/// user.name and user.email of a git config file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Identity {
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
+
+/// The [user] values of git config text; includes are not followed
+fn parse_identity(config: &str) -> Identity {
+    let mut identity = Identity::default();
+    let mut in_user = false;
+    for line in config.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            let section = line.trim_start_matches('[').split([']', ' ', '"']).next().unwrap_or("");
+            in_user = section.eq_ignore_ascii_case("user") && !line.contains('"');
+            continue;
+        }
+        if !in_user || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else { continue };
+        let value = value.split(['#', ';']).next().unwrap_or("").trim().trim_matches('"').to_string();
+        if value.is_empty() {
+            continue;
+        }
+        match key.trim().to_lowercase().as_str() {
+            "email" => identity.email = Some(value),
+            "name" => identity.name = Some(value),
+            _ => {}
+        }
+    }
+    identity
+}
+
+/// The user's global git config: $GIT_CONFIG_GLOBAL, ~/.gitconfig, then
+/// $XDG_CONFIG_HOME/git/config
+fn global_identity() -> Identity {
+    let home = std::env::var("HOME").or_else(|_| std::env::var("USERPROFILE")).map(PathBuf::from).unwrap_or_default();
+    let xdg = std::env::var("XDG_CONFIG_HOME").map(PathBuf::from).unwrap_or_else(|_| home.join(".config"));
+    let candidates = [std::env::var("GIT_CONFIG_GLOBAL").ok().map(PathBuf::from), Some(home.join(".gitconfig")), Some(xdg.join("git/config"))];
+    candidates.into_iter().flatten().find_map(|path| read_to_string(path).ok()).map(|text| parse_identity(&text)).unwrap_or_default()
+}
+
+/// Work tree and config file of the repository containing dir. A .git file
+/// (worktree or submodule) points at the git dir, whose commondir holds the config
+fn find_repo(dir: &Path) -> Option<(PathBuf, PathBuf)> {
+    for root in dir.ancestors() {
+        let dot_git = root.join(".git");
+        let git_dir = if dot_git.is_dir() {
+            dot_git
+        } else if let Ok(text) = read_to_string(&dot_git) {
+            match text.trim().strip_prefix("gitdir:") {
+                Some(target) => root.join(target.trim()),
+                None => continue,
+            }
+        } else {
+            continue;
+        };
+        let common = match read_to_string(git_dir.join("commondir")) {
+            Ok(common) => git_dir.join(common.trim()),
+            Err(_) => git_dir,
+        };
+        return Some((root.to_path_buf(), common.join("config")));
+    }
+    None
+}
+
+/// Repository and identity per session folder, looked up once each
+struct Identities {
+    global: Identity,
+    repos: HashMap<String, Option<(String, Identity)>>,
+}
+
+impl Identities {
+    fn resolve(&mut self, project: &str) -> Option<&(String, Identity)> {
+        let global = &self.global;
+        self.repos
+            .entry(project.to_string())
+            .or_insert_with(|| {
+                let (work_tree, config) = find_repo(Path::new(project))?;
+                let local = read_to_string(config).map(|text| parse_identity(&text)).unwrap_or_default();
+                // Like git, a repo value wins over the global one
+                let identity = Identity { name: local.name.or(global.name.clone()), email: local.email.or(global.email.clone()) };
+                Some((work_tree.display().to_string(), identity))
+            })
+            .as_ref()
+    }
+}
+
+#[derive(Default)]
+struct AuthorBuilder {
+    row: AuthorCost,
+    repos: HashSet<String>,
+    sessions: HashSet<String>,
+}
+
+pub(crate) fn cost_by_author(
+    root_dir: &str,
+    from: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+    overrides: &[ModelPrice],
+    global: Identity,
+) -> Result<AuthorCostReport> {
+    let defaults = default_prices();
+    let mut identities = Identities { global, repos: HashMap::new() };
+    // Keyed by lowercased email, "" for unattributed usage
+    let mut authors: HashMap<String, AuthorBuilder> = HashMap::new();
+    let mut unpriced: HashSet<String> = HashSet::new();
+
+    for_each_usage(root_dir, from, now, |event| {
+        // Without a cwd the project is the session's own folder, not a checkout
+        let has_cwd = Path::new(event.file_path).parent() != Some(Path::new(event.project));
+        let repo = if has_cwd { identities.resolve(event.project) } else { None };
+        let email = repo.and_then(|(_, identity)| identity.email.as_deref());
+        let author = authors.entry(email.map(str::to_lowercase).unwrap_or_default()).or_default();
+        if let (Some((work_tree, identity)), Some(email)) = (repo, email) {
+            author.row.email.get_or_insert_with(|| email.to_string());
+            if author.row.name.is_none() {
+                author.row.name = identity.name.clone();
+            }
+            author.repos.insert(work_tree.clone());
+        }
+        author.sessions.insert(event.session_id.to_string());
+        author.row.messages += 1;
+
+        let Some(usage) = event.usage else { return };
+        author.row.input_tokens += usage.input_tokens as i64;
+        author.row.output_tokens += usage.output_tokens as i64;
+        author.row.cache_creation_tokens += usage.cache_creation_input_tokens.unwrap_or(0) as i64;
+        author.row.cache_read_tokens += usage.cache_read_input_tokens.unwrap_or(0) as i64;
+        if let Some(model) = event.model {
+            match price_for(model, overrides, &defaults) {
+                Some(price) => author.row.cost_usd += cost_usd(price, usage),
+                None => {
+                    unpriced.insert(model.to_string());
+                }
+            }
+        }
+    })?;
+
+    let mut authors: Vec<AuthorCost> = authors
+        .into_values()
+        .map(|builder| {
+            let mut repos: Vec<String> = builder.repos.into_iter().collect();
+            repos.sort();
+            AuthorCost { repos, sessions: builder.sessions.len() as u32, ..builder.row }
+        })
+        .collect();
+    authors.sort_by(|a, b| b.cost_usd.total_cmp(&a.cost_usd).then_with(|| a.email.cmp(&b.email)));
+    let mut unpriced_models: Vec<String> = unpriced.into_iter().collect();
+    unpriced_models.sort();
+
+    Ok(AuthorCostReport { total_cost_usd: authors.iter().map(|a| a.cost_usd).sum(), authors, unpriced_models })
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Usage and estimated cost under root_dir per git user.email of the repo
+/// each session's cwd belongs to
+#[napi(catch_unwind)]
+pub fn get_cost_by_author(root_dir: String, options: Option<AuthorCostOptions>) -> Result<AuthorCostReport> {
+    let options = options.unwrap_or_default();
+    let now = Utc::now();
+    let from = period_start(options.period.as_deref().unwrap_or("all"), now)?;
+    cost_by_author(&root_dir, from, now, &options.prices.unwrap_or_default(), global_identity())
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+    use crate::time::parse_utc;
+
+    #[test]
+    fn test_cost_by_author() {
+        let root = TempDir::new("authors");
+        let repo = root.join("work/app");
+        let worktree = root.join("work/app-wt");
+        std::fs::create_dir_all(repo.join(".git/worktrees/wt")).unwrap();
+        std::fs::create_dir_all(worktree.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("projects/p")).unwrap();
+        std::fs::create_dir_all(root.join("scratch")).unwrap();
+        std::fs::write(repo.join(".git/config"), "[core]\n\tbare = false\n[user]\n\tname = Alice\n\temail = Alice@Example.com ; work\n").unwrap();
+        std::fs::write(repo.join(".git/worktrees/wt/commondir"), "../..\n").unwrap();
+        std::fs::write(worktree.join(".git"), format!("gitdir: {}\n", repo.join(".git/worktrees/wt").display())).unwrap();
+
+        let session = |id: &str, cwd: &Path, output: i32| {
+            format!(
+                r#"{{"type":"assistant","sessionId":"{}","cwd":"{}","timestamp":"2024-01-01T10:00:00Z","message":{{"id":"{}","role":"assistant","model":"claude-sonnet-4","content":"x","usage":{{"input_tokens":0,"output_tokens":{}}}}}}}"#,
+                id,
+                cwd.display(),
+                id,
+                output
+            )
+        };
+        std::fs::write(root.join("projects/p/a.jsonl"), session("a", &repo, 1_000_000)).unwrap();
+        std::fs::write(root.join("projects/p/b.jsonl"), session("b", &worktree.join("src"), 1_000_000)).unwrap();
+        std::fs::write(root.join("projects/p/c.jsonl"), session("c", &root.join("scratch"), 2_000_000)).unwrap();
+
+        let now = parse_utc("2024-01-02T00:00:00Z").unwrap();
+        let global = Identity { name: Some("CI".into()), email: None };
+        let report = cost_by_author(&root.join("projects").display().to_string(), None, now, &[], global).unwrap();
+        assert_eq!(report.authors.len(), 2);
+        let unattributed = &report.authors[0];
+        assert_eq!((unattributed.email.as_deref(), unattributed.sessions), (None, 1));
+        let alice = &report.authors[1];
+        assert_eq!((alice.email.as_deref(), alice.name.as_deref()), (Some("Alice@Example.com"), Some("Alice")));
+        assert_eq!((alice.sessions, alice.output_tokens), (2, 2_000_000));
+        assert_eq!(alice.repos, vec![repo.display().to_string(), worktree.display().to_string()]);
+        assert!((alice.cost_usd - 30.0).abs() < 1e-9);
+        assert!((report.total_cost_usd - 60.0).abs() < 1e-9);
+
+        assert_eq!(parse_identity("[user \"x\"]\nemail = no\n[User]\nEmail = \"me@x\"\n"), Identity { name: None, email: Some("me@x".into()) });
+    }
+}
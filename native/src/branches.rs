@@ -0,0 +1,259 @@
+// ============================================
+// BRANCHES
+// Every root-to-leaf path of a session's message tree, labelled by where
+// it diverged, and a side-by-side summary of two of them
+// ============================================
+
+use std::collections::{HashMap, HashSet};
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::audit::touched_paths;
+use crate::errors::not_found;
+use crate::title::{prompt_excerpt, reply_excerpt};
+use crate::tree::build_tree;
+use crate::{extract_text_content, for_each_entry, ContentItem, RawLogEntry};
+
+/// One continuation of the conversation
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BranchInfo {
+    /// uuid of the branch's last entry; pass it to diff_branches
+    pub branch_id: String,
+    /// 1-based, in the order the branches end in the file
+    pub index: u32,
+    /// Excerpt of the first message after the divergence point
+    pub label: String,
+    /// Last entry shared with an earlier branch, None for the first branch
+    pub fork_uuid: Option<String>,
+    /// User and assistant messages from the root to the leaf
+    pub message_count: u32,
+    /// The messages after fork_uuid
+    pub unique_message_count: u32,
+    pub last_timestamp: Option<String>,
+}
+
+/// One side of diff_branches, from the fork to the leaf
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BranchSide {
+    pub branch_id: String,
+    pub message_count: u32,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub tool_calls: u32,
+    /// Distinct tool names, first used first
+    pub tools: Vec<String>,
+    pub files_modified: Vec<String>,
+    pub first_excerpt: Option<String>,
+    pub last_excerpt: Option<String>,
+    pub last_timestamp: Option<String>,
+}
+
+/// How two branches differ after their common history
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BranchDiff {
+    /// Last entry both share, None when they have different roots
+    pub fork_uuid: Option<String>,
+    pub shared_message_count: u32,
+    pub a: BranchSide,
+    pub b: BranchSide,
+    pub tools_only_in_a: Vec<String>,
+    pub tools_only_in_b: Vec<String>,
+    pub files_only_in_a: Vec<String>,
+    pub files_only_in_b: Vec<String>,
+}
+
+fn is_message(entry: &RawLogEntry) -> bool {
+    (entry.entry_type == "user" || entry.entry_type == "assistant") && entry.message.is_some()
+}
+
+fn excerpt(entry: &RawLogEntry) -> Option<String> {
+    let message = entry.message.as_ref()?;
+    if message.role == "assistant" {
+        reply_excerpt(&message.content)
+    } else {
+        prompt_excerpt(&extract_text_content(&message.content))
+    }
+}
+
+/// Main-thread entries with a uuid (first occurrence, as in the tree) and
+/// the root-to-leaf path of every leaf as indexes into them, leaves in file order
+fn branch_paths(entries: &[RawLogEntry]) -> (Vec<&RawLogEntry>, Vec<Vec<usize>>) {
+    let mut seen = HashSet::new();
+    let nodes: Vec<&RawLogEntry> = entries
+        .iter()
+        .filter(|e| e.is_sidechain != Some(true) && e.uuid.as_deref().is_some_and(|u| seen.insert(u)))
+        .collect();
+    let owned: Vec<RawLogEntry> = nodes.iter().map(|e| (*e).clone()).collect();
+    let tree = build_tree(&owned, false);
+    let by_uuid: HashMap<&str, usize> = tree.nodes.iter().enumerate().map(|(i, n)| (n.uuid.as_str(), i)).collect();
+
+    let paths = (0..tree.nodes.len())
+        .filter(|i| tree.nodes[*i].child_count == 0)
+        .map(|leaf| {
+            let mut path = vec![leaf];
+            let mut node = leaf;
+            while let Some(&parent) = tree.nodes[node].parent_uuid.as_deref().and_then(|p| by_uuid.get(p)) {
+                path.push(parent);
+                node = parent;
+            }
+            path.reverse();
+            path
+        })
+        .collect();
+    (nodes, paths)
+}
+
+pub(crate) fn list(entries: &[RawLogEntry]) -> Vec<BranchInfo> {
+    let (nodes, paths) = branch_paths(entries);
+    let mut listed: HashSet<usize> = HashSet::new();
+
+    paths
+        .iter()
+        .enumerate()
+        .map(|(i, path)| {
+            let split = path.iter().position(|n| !listed.contains(n)).unwrap_or(path.len());
+            listed.extend(path.iter().copied());
+            let unique = &path[split..];
+            let count = |part: &[usize]| part.iter().filter(|n| is_message(nodes[**n])).count() as u32;
+
+            BranchInfo {
+                branch_id: nodes[path[path.len() - 1]].uuid.clone().unwrap_or_default(),
+                index: i as u32 + 1,
+                label: unique
+                    .iter()
+                    .find_map(|n| excerpt(nodes[*n]))
+                    .unwrap_or_else(|| format!("Branch {}", i + 1)),
+                fork_uuid: split.checked_sub(1).and_then(|f| nodes[path[f]].uuid.clone()),
+                message_count: count(path),
+                unique_message_count: count(unique),
+                last_timestamp: path.iter().rev().find_map(|n| nodes[*n].timestamp.clone()),
+            }
+        })
+        .collect()
+}
+
+fn side(branch_id: &str, path: &[&RawLogEntry], split: usize) -> BranchSide {
+    let unique = &path[split..];
+    let ids: HashSet<&str> = unique.iter().filter_map(|e| e.uuid.as_deref()).collect();
+    let owned: Vec<RawLogEntry> = path.iter().map(|e| (*e).clone()).collect();
+    let mut files_modified: Vec<String> = Vec::new();
+    for access in touched_paths(&owned) {
+        if access.access == "write" && access.message_id.as_deref().is_some_and(|id| ids.contains(id)) && !files_modified.contains(&access.resolved_path) {
+            files_modified.push(access.resolved_path);
+        }
+    }
+
+    let mut side = BranchSide {
+        branch_id: branch_id.to_string(),
+        message_count: unique.iter().filter(|e| is_message(e)).count() as u32,
+        input_tokens: 0,
+        output_tokens: 0,
+        tool_calls: 0,
+        tools: Vec::new(),
+        files_modified,
+        first_excerpt: unique.iter().find_map(|e| excerpt(e)),
+        last_excerpt: unique.iter().rev().find_map(|e| excerpt(e)),
+        last_timestamp: unique.iter().rev().find_map(|e| e.timestamp.clone()),
+    };
+    for message in unique.iter().filter_map(|e| e.message.as_ref()) {
+        if let Some(usage) = &message.usage {
+            side.input_tokens += usage.input_tokens as i64;
+            side.output_tokens += usage.output_tokens as i64;
+        }
+        for item in &message.content {
+            if let ContentItem::ToolUse { name, .. } = item {
+                side.tool_calls += 1;
+                if !side.tools.contains(name) {
+                    side.tools.push(name.clone());
+                }
+            }
+        }
+    }
+    side
+}
+
+pub(crate) fn diff(entries: &[RawLogEntry], branch_a: &str, branch_b: &str) -> Result<BranchDiff> {
+    let (nodes, paths) = branch_paths(entries);
+    let find = |id: &str| -> Result<Vec<&RawLogEntry>> {
+        paths
+            .iter()
+            .find(|path| nodes[path[path.len() - 1]].uuid.as_deref() == Some(id))
+            .map(|path| path.iter().map(|n| nodes[*n]).collect())
+            .ok_or_else(|| not_found(format!("Branch not found: {}", id)))
+    };
+    let (path_a, path_b) = (find(branch_a)?, find(branch_b)?);
+
+    let split = path_a.iter().zip(&path_b).take_while(|(x, y)| x.uuid == y.uuid).count();
+    let (a, b) = (side(branch_a, &path_a, split), side(branch_b, &path_b, split));
+    let only = |x: &[String], y: &[String]| x.iter().filter(|v| !y.contains(v)).cloned().collect::<Vec<String>>();
+
+    Ok(BranchDiff {
+        fork_uuid: split.checked_sub(1).and_then(|f| path_a[f].uuid.clone()),
+        shared_message_count: path_a[..split].iter().filter(|e| is_message(e)).count() as u32,
+        tools_only_in_a: only(&a.tools, &b.tools),
+        tools_only_in_b: only(&b.tools, &a.tools),
+        files_only_in_a: only(&a.files_modified, &b.files_modified),
+        files_only_in_b: only(&b.files_modified, &a.files_modified),
+        a,
+        b,
+    })
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Every branch of a session (one per leaf of the message tree, subagent
+/// threads excluded), labelled by where it diverged
+#[napi(catch_unwind)]
+pub fn list_branches(file_path: String) -> Result<Vec<BranchInfo>> {
+    let mut entries = Vec::new();
+    for_each_entry(&file_path, |entry| entries.push(entry))?;
+    Ok(list(&entries))
+}
+
+/// Compare two branches (branch ids from list_branches) after their shared history
+#[napi(catch_unwind)]
+pub fn diff_branches(file_path: String, branch_a: String, branch_b: String) -> Result<BranchDiff> {
+    let mut entries = Vec::new();
+    for_each_entry(&file_path, |entry| entries.push(entry))?;
+    diff(&entries, &branch_a, &branch_b)
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_jsonl_line;
+
+    #[test]
+    fn test_list_and_diff_branches() {
+        let lines = [
+            r#"{"type":"user","uuid":"u1","cwd":"/w","timestamp":"2024-01-01T10:00:00Z","message":{"role":"user","content":"Fix the parser"}}"#,
+            r#"{"type":"assistant","uuid":"a1","parentUuid":"u1","message":{"role":"assistant","content":[{"type":"text","text":"Editing lib.rs"},{"type":"tool_use","id":"t1","name":"Edit","input":{"file_path":"lib.rs"}}],"usage":{"input_tokens":10,"output_tokens":5}}}"#,
+            r#"{"type":"assistant","uuid":"a2","parentUuid":"u1","timestamp":"2024-01-01T10:05:00Z","message":{"role":"assistant","content":[{"type":"text","text":"Let me run the tests first"},{"type":"tool_use","id":"t2","name":"Bash","input":{"command":"cargo test"}}]}}"#,
+            r#"{"type":"user","uuid":"u2","parentUuid":"a2","isSidechain":true,"message":{"role":"user","content":"subtask"}}"#,
+        ];
+        let entries: Vec<RawLogEntry> = lines.iter().map(|l| parse_jsonl_line(l).unwrap()).collect();
+
+        let branches = list(&entries);
+        assert_eq!(branches.len(), 2);
+        assert_eq!((branches[0].branch_id.as_str(), branches[0].fork_uuid.as_ref()), ("a1", None));
+        assert_eq!(branches[1].label, "Let me run the tests first");
+        assert_eq!((branches[1].fork_uuid.as_deref(), branches[1].message_count, branches[1].unique_message_count), (Some("u1"), 2, 1));
+
+        let diff = diff(&entries, "a1", "a2").unwrap();
+        assert_eq!((diff.fork_uuid.as_deref(), diff.shared_message_count), (Some("u1"), 1));
+        assert_eq!(diff.a.files_modified, vec!["/w/lib.rs"]);
+        assert_eq!((diff.tools_only_in_a.clone(), diff.tools_only_in_b.clone()), (vec!["Edit".to_string()], vec!["Bash".to_string()]));
+        assert_eq!(diff.a.output_tokens, 5);
+        assert!(super::diff(&entries, "a1", "u1").is_err());
+    }
+}
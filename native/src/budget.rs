@@ -0,0 +1,231 @@
+// ============================================
+// BUDGET ALERTS
+// Daily token, monthly cost and per-project thresholds evaluated
+// against current usage, so the app can warn before limits are hit
+// ============================================
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::pricing::{cost_usd, default_prices, price_for, ModelPrice};
+use crate::time::resolve_timezone;
+use crate::usage::for_each_usage;
+
+/// Alerts turn into warnings at this share of a limit
+const DEFAULT_WARN_RATIO: f64 = 0.8;
+
+/// Monthly limits for one project (cwd path)
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct ProjectCap {
+    pub project: String,
+    pub monthly_tokens: Option<i64>,
+    pub monthly_cost_usd: Option<f64>,
+}
+
+/// Thresholds for check_budget; unset limits are not checked
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct BudgetConfig {
+    /// Input + output tokens since local midnight
+    pub daily_tokens: Option<i64>,
+    /// Estimated cost since the first of the month
+    pub monthly_cost_usd: Option<f64>,
+    pub project_caps: Option<Vec<ProjectCap>>,
+    /// Share of a limit that raises a warning (default 0.8)
+    pub warn_ratio: Option<f64>,
+    /// IANA timezone for "today" and "this month" (default UTC)
+    pub timezone: Option<String>,
+    /// Price overrides, checked before the built-in list
+    pub prices: Option<Vec<ModelPrice>>,
+}
+
+/// A threshold that was reached
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct BudgetAlert {
+    /// "daily_tokens", "monthly_cost", "project_tokens" or "project_cost"
+    pub kind: String,
+    /// "warning" or "exceeded"
+    pub level: String,
+    /// Project path for per-project alerts
+    pub project: Option<String>,
+    pub used: f64,
+    pub limit: f64,
+    pub message: String,
+}
+
+/// Current usage and the alerts it triggers
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct BudgetStatus {
+    pub today_tokens: i64,
+    pub month_tokens: i64,
+    pub month_cost_usd: f64,
+    /// Month-to-date cost extrapolated to the whole month
+    pub projected_month_cost_usd: f64,
+    pub alerts: Vec<BudgetAlert>,
+}
+
+#[derive(Default)]
+struct ProjectSpend {
+    tokens: i64,
+    cost: f64,
+}
+
+fn local_midnight(date: NaiveDate, tz: Tz) -> DateTime<Utc> {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+    tz.from_local_datetime(&midnight)
+        .earliest()
+        .map_or_else(|| Utc.from_utc_datetime(&midnight), |dt| dt.with_timezone(&Utc))
+}
+
+fn days_in_month(date: NaiveDate) -> u32 {
+    let (year, month) = if date.month() == 12 { (date.year() + 1, 1) } else { (date.year(), date.month() + 1) };
+    NaiveDate::from_ymd_opt(year, month, 1)
+        .and_then(|next| next.pred_opt())
+        .map_or(30, |last| last.day())
+}
+
+struct Thresholds {
+    warn_ratio: f64,
+    alerts: Vec<BudgetAlert>,
+}
+
+impl Thresholds {
+    fn check(&mut self, kind: &str, project: Option<&str>, used: f64, limit: f64, unit: &str) {
+        if limit <= 0.0 || used < limit * self.warn_ratio {
+            return;
+        }
+        let level = if used >= limit { "exceeded" } else { "warning" };
+        let scope = project.map(|p| format!(" for {}", p)).unwrap_or_default();
+        let (used_text, limit_text) = if unit == "$" {
+            (format!("${:.2}", used), format!("${:.2}", limit))
+        } else {
+            (format!("{} {}", used as i64, unit), format!("{} {}", limit as i64, unit))
+        };
+        self.alerts.push(BudgetAlert {
+            kind: kind.to_string(),
+            level: level.to_string(),
+            project: project.map(str::to_string),
+            used,
+            limit,
+            message: format!("{}{}: {} of {} ({:.0}%)", kind.replace('_', " "), scope, used_text, limit_text, used / limit * 100.0),
+        });
+    }
+}
+
+fn evaluate(root_dir: &str, config: &BudgetConfig, now: DateTime<Utc>) -> Result<BudgetStatus> {
+    let tz = resolve_timezone(config.timezone.as_deref())?;
+    let today = now.with_timezone(&tz).date_naive();
+    let today_start = local_midnight(today, tz);
+    let month_start = local_midnight(today.with_day(1).unwrap_or(today), tz);
+
+    let defaults = default_prices();
+    let overrides = config.prices.clone().unwrap_or_default();
+
+    let mut today_tokens = 0i64;
+    let mut month_tokens = 0i64;
+    let mut month_cost = 0.0;
+    let mut projects: HashMap<String, ProjectSpend> = HashMap::new();
+
+    for_each_usage(root_dir, Some(month_start), now, |event| {
+        let Some(usage) = event.usage else { return };
+        let tokens = usage.input_tokens as i64 + usage.output_tokens as i64;
+        let cost = event
+            .model
+            .and_then(|model| price_for(model, &overrides, &defaults))
+            .map_or(0.0, |price| cost_usd(price, usage));
+
+        if event.timestamp >= today_start {
+            today_tokens += tokens;
+        }
+        month_tokens += tokens;
+        month_cost += cost;
+        let spend = projects.entry(event.project.to_string()).or_default();
+        spend.tokens += tokens;
+        spend.cost += cost;
+    })?;
+
+    let elapsed_days = (now - month_start).num_seconds() as f64 / 86_400.0;
+    let projected = if elapsed_days > 0.0 { month_cost / elapsed_days * days_in_month(today) as f64 } else { month_cost };
+
+    let mut thresholds = Thresholds { warn_ratio: config.warn_ratio.unwrap_or(DEFAULT_WARN_RATIO), alerts: Vec::new() };
+    if let Some(limit) = config.daily_tokens {
+        thresholds.check("daily_tokens", None, today_tokens as f64, limit as f64, "tokens");
+    }
+    if let Some(limit) = config.monthly_cost_usd {
+        thresholds.check("monthly_cost", None, month_cost, limit, "$");
+    }
+    for cap in config.project_caps.iter().flatten() {
+        let spend = projects.get(&cap.project);
+        if let Some(limit) = cap.monthly_tokens {
+            thresholds.check("project_tokens", Some(&cap.project), spend.map_or(0, |s| s.tokens) as f64, limit as f64, "tokens");
+        }
+        if let Some(limit) = cap.monthly_cost_usd {
+            thresholds.check("project_cost", Some(&cap.project), spend.map_or(0.0, |s| s.cost), limit, "$");
+        }
+    }
+
+    Ok(BudgetStatus {
+        today_tokens,
+        month_tokens,
+        month_cost_usd: month_cost,
+        projected_month_cost_usd: projected,
+        alerts: thresholds.alerts,
+    })
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Evaluate budget thresholds against usage under root_dir
+#[napi(catch_unwind)]
+pub fn check_budget(root_dir: String, budget_config: BudgetConfig) -> Result<BudgetStatus> {
+    evaluate(&root_dir, &budget_config, Utc::now())
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+    use crate::time::parse_utc;
+
+    #[test]
+    fn test_budget_alerts() {
+        let root = TempDir::new("budget-test");
+        std::fs::create_dir_all(root.join("proj")).unwrap();
+        std::fs::write(
+            root.join("proj/s1.jsonl"),
+            r#"{"type":"assistant","sessionId":"s1","cwd":"/work/app","timestamp":"2024-03-01T10:00:00Z","message":{"id":"r1","role":"assistant","model":"claude-sonnet-4","content":"a","usage":{"input_tokens":1000000,"output_tokens":100000}}}
+{"type":"assistant","sessionId":"s1","cwd":"/work/app","timestamp":"2024-03-10T10:00:00Z","message":{"id":"r2","role":"assistant","model":"claude-sonnet-4","content":"b","usage":{"input_tokens":8000,"output_tokens":1000}}}
+{"type":"assistant","sessionId":"s1","cwd":"/work/app","timestamp":"2024-02-28T10:00:00Z","message":{"id":"r0","role":"assistant","model":"claude-sonnet-4","content":"old","usage":{"input_tokens":9000000,"output_tokens":0}}}"#,
+        )
+        .unwrap();
+
+        let config = BudgetConfig {
+            daily_tokens: Some(10_000),
+            monthly_cost_usd: Some(4.0),
+            project_caps: Some(vec![ProjectCap { project: "/work/app".into(), monthly_tokens: Some(5_000_000), monthly_cost_usd: None }]),
+            ..Default::default()
+        };
+        let now = parse_utc("2024-03-10T12:00:00Z").unwrap();
+        let status = evaluate(&root.to_string_lossy(), &config, now).unwrap();
+
+        assert_eq!((status.today_tokens, status.month_tokens), (9_000, 1_109_000));
+        // $3 input + $1.50 output on the 1st, a few cents on the 10th; February is ignored
+        assert!((status.month_cost_usd - 4.539).abs() < 1e-6);
+        let alerts: Vec<(&str, &str)> = status.alerts.iter().map(|a| (a.kind.as_str(), a.level.as_str())).collect();
+        assert_eq!(alerts, vec![("daily_tokens", "warning"), ("monthly_cost", "exceeded")]);
+        assert!(status.alerts[1].message.starts_with("monthly cost: $4.54 of $4.00"));
+    }
+}
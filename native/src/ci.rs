@@ -0,0 +1,231 @@
+// ============================================
+// CI SESSIONS
+// Session logs produced by headless runs (GitHub Actions artifacts) are
+// copied into an "automation" folder of the data dir, apart from the
+// user's own history, and recorded in the sidecar store with the run
+// they came from
+// ============================================
+
+use std::collections::HashMap;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+use crate::errors::{invalid_argument, io_error};
+use crate::paths::{long_path, open_file, read_file};
+use crate::reader::lossy_lines;
+use crate::safe_write::write_file;
+use crate::scan::{scan, SkippedPath};
+use crate::store::{now_millis, read_store, store_dir, update_store, MetadataStore};
+
+/// Folder of the data dir holding imported CI sessions
+const AUTOMATION_DIR_NAME: &str = "automation";
+
+/// Lines read looking for the session id
+const SESSION_ID_SCAN_LINES: usize = 50;
+
+/// The CI run a set of session logs came from
+#[napi(object)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+pub struct CiMetadata {
+    /// e.g. "github-actions" (the default), "gitlab-ci"
+    pub provider: Option<String>,
+    /// GITHUB_RUN_ID or the provider's equivalent
+    pub run_id: String,
+    pub workflow: Option<String>,
+    /// "owner/name"
+    pub repository: Option<String>,
+    pub branch: Option<String>,
+    /// Pull request number, for runs triggered by one
+    pub pull_request: Option<u32>,
+    pub commit_sha: Option<String>,
+    /// Link to the run page
+    pub run_url: Option<String>,
+}
+
+/// An imported CI session, as kept in the store
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+pub struct CiSession {
+    pub session_id: String,
+    /// The copy under the automation folder
+    pub file_path: String,
+    pub metadata: CiMetadata,
+    pub imported_at: i64,
+}
+
+/// Result of import_ci_artifacts
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CiImport {
+    /// Root of every imported CI session; use it as root_dir for
+    /// automation-only listings and analytics
+    pub automation_dir: String,
+    pub sessions: Vec<CiSession>,
+    pub skipped: Vec<SkippedPath>,
+}
+
+#[derive(Deserialize)]
+struct SessionStamp {
+    #[serde(rename = "sessionId", default)]
+    session_id: Option<String>,
+}
+
+impl MetadataStore {
+    /// Add CI sessions, replacing earlier imports of the same sessions
+    pub fn record_ci_sessions(&mut self, sessions: &[CiSession]) {
+        for session in sessions {
+            match self.ci_sessions.iter_mut().find(|c| c.session_id == session.session_id) {
+                Some(existing) => *existing = session.clone(),
+                None => self.ci_sessions.push(session.clone()),
+            }
+        }
+    }
+
+    /// CI metadata by session id
+    pub(crate) fn ci_metadata(&self) -> HashMap<String, CiMetadata> {
+        self.ci_sessions.iter().map(|c| (c.session_id.clone(), c.metadata.clone())).collect()
+    }
+}
+
+pub(crate) fn automation_dir() -> PathBuf {
+    store_dir().join(AUTOMATION_DIR_NAME)
+}
+
+/// A value usable as a single folder name
+fn folder_name(value: &str) -> String {
+    value.chars().map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '_' { c } else { '-' }).collect()
+}
+
+/// sessionId of the first entry that has one, else the file stem
+fn session_id_of(path: &Path) -> String {
+    let from_entries = open_file(path).ok().and_then(|file| {
+        lossy_lines(BufReader::new(file))
+            .take(SESSION_ID_SCAN_LINES)
+            .find_map(|line| serde_json::from_str::<SessionStamp>(&line).ok()?.session_id)
+    });
+    from_entries.unwrap_or_else(|| path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default())
+}
+
+/// Copy the session logs under artifact_dir into automation, laid out like
+/// ~/.claude/projects: loose files go into a folder named after the repository.
+/// Without overwrite, files imported before are skipped as "exists"
+pub(crate) fn copy_artifacts(
+    artifact_dir: &Path,
+    metadata: &CiMetadata,
+    automation: &Path,
+    overwrite: bool,
+    now: i64,
+) -> Result<(Vec<CiSession>, Vec<SkippedPath>)> {
+    if metadata.run_id.trim().is_empty() {
+        return Err(invalid_argument("CI metadata needs a run_id"));
+    }
+    let metadata = CiMetadata { provider: Some(metadata.provider.clone().unwrap_or_else(|| "github-actions".to_string())), ..metadata.clone() };
+    let scanned = scan(artifact_dir, config::follow_symlinks(), config::scan_timeout_ms())?;
+    let loose_folder = folder_name(metadata.repository.as_deref().unwrap_or("ci"));
+
+    let mut sessions = Vec::new();
+    let mut skipped = scanned.skipped;
+    for source in scanned.files {
+        let relative = source.strip_prefix(artifact_dir).unwrap_or(&source);
+        let target = if relative.parent().is_some_and(|p| !p.as_os_str().is_empty()) {
+            automation.join(relative)
+        } else {
+            automation.join(&loose_folder).join(relative)
+        };
+        if !overwrite && long_path(&target).exists() {
+            skipped.push(SkippedPath { path: source.display().to_string(), reason: "exists".to_string(), message: None });
+            continue;
+        }
+        let contents = read_file(&source).map_err(|e| io_error("Cannot read CI artifact", &source, e))?;
+        write_file(&target, &contents, overwrite)?;
+        sessions.push(CiSession {
+            session_id: session_id_of(&target),
+            file_path: target.display().to_string(),
+            metadata: metadata.clone(),
+            imported_at: now,
+        });
+    }
+    Ok((sessions, skipped))
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Import the session logs of a CI run (e.g. a downloaded GitHub Actions
+/// artifact) into the automation folder, tagged with the run's metadata.
+/// Sessions imported before are skipped unless overwrite is set (default false)
+#[napi(catch_unwind)]
+pub fn import_ci_artifacts(dir: String, metadata: CiMetadata, overwrite: Option<bool>) -> Result<CiImport> {
+    let automation = automation_dir();
+    let (sessions, skipped) = copy_artifacts(Path::new(&dir), &metadata, &automation, overwrite.unwrap_or(false), now_millis())?;
+    update_store(|store| store.record_ci_sessions(&sessions))?;
+    Ok(CiImport { automation_dir: automation.display().to_string(), sessions, skipped })
+}
+
+/// Folder the imported CI sessions live in, kept out of the user's history
+#[napi(catch_unwind)]
+pub fn get_automation_dir() -> String {
+    automation_dir().display().to_string()
+}
+
+/// Imported CI sessions, optionally of one run, most recent import first
+#[napi(catch_unwind)]
+pub fn list_ci_sessions(run_id: Option<String>) -> Result<Vec<CiSession>> {
+    let mut sessions = read_store(|store| store.ci_sessions.clone())?;
+    sessions.retain(|s| run_id.as_ref().is_none_or(|id| s.metadata.run_id == *id));
+    sessions.sort_by(|a, b| b.imported_at.cmp(&a.imported_at).then_with(|| a.file_path.cmp(&b.file_path)));
+    Ok(sessions)
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_copy_and_record_ci_sessions() {
+        let base = TempDir::new("ci");
+        let artifact = base.join("artifact");
+        let automation = base.join("automation");
+        std::fs::create_dir_all(artifact.join("-home-runner-work-app")).unwrap();
+        std::fs::write(artifact.join("-home-runner-work-app/s1.jsonl"), "{\"type\":\"user\",\"sessionId\":\"run-s1\"}\n").unwrap();
+        std::fs::write(artifact.join("loose.jsonl"), "{}\n").unwrap();
+
+        let metadata = CiMetadata { run_id: "123".into(), repository: Some("me/app".into()), pull_request: Some(7), ..Default::default() };
+        let (sessions, skipped) = copy_artifacts(&artifact, &metadata, &automation, false, 5).unwrap();
+        assert!(skipped.is_empty());
+        let ids: Vec<&str> = sessions.iter().map(|s| s.session_id.as_str()).collect();
+        assert_eq!(ids, vec!["run-s1", "loose"]);
+        assert!(automation.join("-home-runner-work-app/s1.jsonl").is_file());
+        assert_eq!(sessions[1].file_path, automation.join("me-app/loose.jsonl").display().to_string());
+        assert_eq!(sessions[0].metadata.provider.as_deref(), Some("github-actions"));
+
+        let mut store = MetadataStore::default();
+        store.record_ci_sessions(&sessions);
+        // Importing the run again skips the copies, unless overwriting them
+        let (again, skipped) = copy_artifacts(&artifact, &metadata, &automation, false, 7).unwrap();
+        assert!(again.is_empty());
+        assert_eq!(skipped.iter().map(|s| s.reason.as_str()).collect::<Vec<_>>(), vec!["exists", "exists"]);
+        let (again, _) = copy_artifacts(&artifact, &metadata, &automation, true, 9).unwrap();
+        store.record_ci_sessions(&again);
+        assert_eq!(store.ci_sessions.len(), 2);
+        assert_eq!(store.ci_sessions[0].imported_at, 9);
+        assert_eq!(store.ci_metadata()["run-s1"].pull_request, Some(7));
+
+        let no_run = CiMetadata { run_id: " ".into(), ..Default::default() };
+        assert!(copy_artifacts(&artifact, &no_run, &automation, false, 1).is_err());
+    }
+}
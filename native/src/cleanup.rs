@@ -0,0 +1,202 @@
+// ============================================
+// STALE ARTIFACTS
+// Sessions without a single message, empty project dirs and temp files
+// left behind by crashes, with optional removal
+// ============================================
+
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use schemars::JsonSchema;
+
+use crate::errors::{ErrorCode, HubError};
+use crate::fast;
+use crate::paths::{metadata, open_file, read_dir, remove_dir, remove_file};
+
+/// Younger artifacts may belong to a session that is still being written
+const DEFAULT_MIN_AGE_MINUTES: u32 = 60;
+
+const TEMP_EXTENSIONS: &[&str] = &["tmp", "temp", "partial", "swp"];
+
+/// Options for find_stale_artifacts
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct StaleArtifactOptions {
+    /// Delete what was found (default false, report only)
+    pub cleanup: Option<bool>,
+    /// Ignore anything modified more recently than this (default 60 minutes)
+    pub min_age_minutes: Option<u32>,
+}
+
+/// A leftover file or directory under the projects root
+#[napi(object)]
+#[derive(Debug, Clone, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+pub struct StaleArtifact {
+    pub path: String,
+    /// "empty_session", "empty_dir" or "temp_file"
+    pub kind: String,
+    pub bytes: i64,
+    /// True when cleanup removed it
+    pub removed: bool,
+}
+
+/// Editor and atomic-write leftovers ("x.tmp", "x.jsonl.tmp.123", "x~", ".#x")
+fn is_temp_file(name: &str) -> bool {
+    let extension = Path::new(name).extension().map(|e| e.to_string_lossy().to_lowercase());
+    extension.is_some_and(|e| TEMP_EXTENSIONS.contains(&e.as_str()))
+        || name.contains(".tmp.")
+        || name.ends_with('~')
+        || name.starts_with(".#")
+}
+
+/// True when no line of the file is a user or assistant entry (stops at the first one)
+fn has_no_messages(path: &Path) -> bool {
+    let Ok(file) = open_file(path) else { return false };
+    let mut reader = BufReader::new(file);
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) => return true,
+            Ok(_) => {
+                if fast::peek_entry(&line).is_some_and(|peek| fast::is_message_type(&peek.entry_type)) {
+                    return false;
+                }
+            }
+            Err(_) => return false,
+        }
+    }
+}
+
+fn old_enough(path: &Path, cutoff: SystemTime) -> bool {
+    metadata(path)
+        .and_then(|m| m.modified())
+        .is_ok_and(|modified| modified <= cutoff)
+}
+
+/// Collect artifacts below dir (children first). Returns true when dir holds
+/// nothing but artifacts, so the caller can report it as empty
+fn scan_dir(dir: &Path, depth: usize, cutoff: SystemTime, out: &mut Vec<StaleArtifact>) -> bool {
+    let Ok(entries) = read_dir(dir) else { return false };
+    let mut only_artifacts = true;
+
+    for entry in entries.flatten() {
+        let path = dir.join(entry.file_name());
+        let Ok(file_type) = entry.file_type() else {
+            only_artifacts = false;
+            continue;
+        };
+
+        if file_type.is_dir() {
+            if depth < 3 && scan_dir(&path, depth + 1, cutoff, out) && old_enough(&path, cutoff) {
+                out.push(StaleArtifact { path: path.display().to_string(), kind: "empty_dir".into(), bytes: 0, removed: false });
+            } else {
+                only_artifacts = false;
+            }
+            continue;
+        }
+
+        let name = entry.file_name().to_string_lossy().to_string();
+        let kind = if is_temp_file(&name) {
+            "temp_file"
+        } else if path.extension().is_some_and(|ext| ext == "jsonl") && has_no_messages(&path) {
+            "empty_session"
+        } else {
+            only_artifacts = false;
+            continue;
+        };
+
+        if !old_enough(&path, cutoff) {
+            only_artifacts = false;
+            continue;
+        }
+        let bytes = entry.metadata().map(|m| m.len() as i64).unwrap_or(0);
+        out.push(StaleArtifact { path: path.display().to_string(), kind: kind.into(), bytes, removed: false });
+    }
+
+    only_artifacts
+}
+
+fn find_artifacts(root_dir: &str, options: &StaleArtifactOptions, now: SystemTime) -> Result<Vec<StaleArtifact>> {
+    let root = Path::new(root_dir);
+    if !root.is_dir() {
+        return Err(HubError::new(ErrorCode::FileNotFound, format!("Not a directory: {}", root_dir)).path(root_dir).into());
+    }
+
+    let min_age = options.min_age_minutes.unwrap_or(DEFAULT_MIN_AGE_MINUTES);
+    let cutoff = now - Duration::from_secs(min_age as u64 * 60);
+
+    // The root itself is never reported, even when empty
+    let mut artifacts = Vec::new();
+    scan_dir(root, 0, cutoff, &mut artifacts);
+
+    if options.cleanup.unwrap_or(false) {
+        // Children were pushed before their directory, so dirs are empty by the time we get there
+        for artifact in &mut artifacts {
+            artifact.removed = if artifact.kind == "empty_dir" {
+                remove_dir(&artifact.path).is_ok()
+            } else {
+                remove_file(&artifact.path).is_ok()
+            };
+        }
+    }
+
+    Ok(artifacts)
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Zero-message sessions, empty project dirs and crash leftovers under root_dir
+#[napi(catch_unwind)]
+pub fn find_stale_artifacts(root_dir: String, options: Option<StaleArtifactOptions>) -> Result<Vec<StaleArtifact>> {
+    find_artifacts(&root_dir, &options.unwrap_or_default(), SystemTime::now())
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_find_and_clean_stale_artifacts() {
+        let root = TempDir::new("cleanup-test");
+        std::fs::create_dir_all(root.join("live")).unwrap();
+        std::fs::create_dir_all(root.join("dead")).unwrap();
+        std::fs::create_dir_all(root.join("empty")).unwrap();
+        std::fs::write(
+            root.join("live/s1.jsonl"),
+            r#"{"type":"summary","summary":"x"}
+{"type":"user","uuid":"1","message":{"role":"user","content":"hi"}}"#,
+        )
+        .unwrap();
+        std::fs::write(root.join("live/s1.jsonl.tmp.42"), "partial").unwrap();
+        std::fs::write(root.join("dead/s2.jsonl"), r#"{"type":"summary","summary":"x"}"#).unwrap();
+
+        let root_dir = root.to_string_lossy().to_string();
+        let later = SystemTime::now() + Duration::from_secs(2 * 3600);
+
+        // Nothing is old enough yet
+        assert!(find_artifacts(&root_dir, &StaleArtifactOptions::default(), SystemTime::now()).unwrap().is_empty());
+
+        let mut found = find_artifacts(&root_dir, &StaleArtifactOptions::default(), later).unwrap();
+        found.sort_by(|a, b| a.path.cmp(&b.path));
+        let kinds: Vec<&str> = found.iter().map(|a| a.kind.as_str()).collect();
+        assert_eq!(kinds, vec!["empty_dir", "empty_session", "empty_dir", "temp_file"]);
+        assert!(root.join("dead/s2.jsonl").exists());
+
+        let options = StaleArtifactOptions { cleanup: Some(true), ..Default::default() };
+        let removed = find_artifacts(&root_dir, &options, later).unwrap();
+        assert!(removed.iter().all(|a| a.removed));
+        assert!(!root.join("dead").exists() && root.join("live/s1.jsonl").exists());
+    }
+}
@@ -0,0 +1,244 @@
+// ============================================
+// RUNTIME CONFIGURATION
+// Thread count, cursor cache size, log level, thinking policy and directory
+// scanning, set once per process by the embedding app (e.g. fewer threads
+// on low-end machines, longer timeouts for projects on network drives)
+// ============================================
+
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU8, Ordering};
+use std::sync::{Arc, RwLock};
+
+use napi::bindgen_prelude::*;
+use napi::Env;
+use napi_derive::napi;
+
+use history_core::{set_thinking_policy, thinking_policy, ThinkingPolicy};
+
+use crate::errors::{internal, invalid_argument};
+
+/// Open cursors kept before the oldest is closed
+pub const DEFAULT_MAX_OPEN_CURSORS: u32 = 64;
+
+/// Wait for one directory listing or stat before skipping the path (10 s)
+pub const DEFAULT_SCAN_TIMEOUT_MS: u32 = 10_000;
+
+static POOL: RwLock<Option<Arc<rayon::ThreadPool>>> = RwLock::new(None);
+static THREADS: AtomicU32 = AtomicU32::new(0);
+static MAX_OPEN_CURSORS: AtomicU32 = AtomicU32::new(DEFAULT_MAX_OPEN_CURSORS);
+static LOG_LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Warn as u8);
+static FOLLOW_SYMLINKS: AtomicBool = AtomicBool::new(true);
+static SCAN_TIMEOUT_MS: AtomicU32 = AtomicU32::new(DEFAULT_SCAN_TIMEOUT_MS);
+
+/// Diagnostics verbosity, most severe first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum LogLevel {
+    Off = 0,
+    Error = 1,
+    Warn = 2,
+    Info = 3,
+    Debug = 4,
+}
+
+impl LogLevel {
+    pub fn parse(level: &str) -> Result<LogLevel> {
+        match level.trim().to_lowercase().as_str() {
+            "off" => Ok(LogLevel::Off),
+            "error" => Ok(LogLevel::Error),
+            "warn" => Ok(LogLevel::Warn),
+            "info" => Ok(LogLevel::Info),
+            "debug" => Ok(LogLevel::Debug),
+            _ => Err(invalid_argument(format!("Unknown log level: {}", level))),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LogLevel::Off => "off",
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+        }
+    }
+
+    fn from_u8(value: u8) -> LogLevel {
+        match value {
+            0 => LogLevel::Off,
+            1 => LogLevel::Error,
+            2 => LogLevel::Warn,
+            3 => LogLevel::Info,
+            _ => LogLevel::Debug,
+        }
+    }
+}
+
+/// Settings passed to configure; missing fields keep their current value
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct ConfigureOptions {
+    /// Worker threads for parallel parsing (0 = one per core)
+    pub threads: Option<u32>,
+    /// Open message cursors (and replays) kept before the oldest is closed (default 64)
+    pub max_open_cursors: Option<u32>,
+    /// "off", "error", "warn" (default), "info" or "debug"
+    pub log_level: Option<String>,
+    /// How thinking blocks appear in message content, exports and search:
+    /// "include" (default, prefixed "[Thinking]"), "exclude" or "placeholder"
+    pub thinking_policy: Option<String>,
+    /// Descend into symlinked project folders when scanning a root (default true)
+    pub follow_symlinks: Option<bool>,
+    /// Milliseconds one directory listing or stat may take while scanning a
+    /// root before the path is skipped (default 10000, 0 = wait forever)
+    pub scan_timeout_ms: Option<u32>,
+}
+
+/// Effective configuration, also kept as napi instance data
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct RuntimeConfig {
+    /// Configured thread count, 0 = one per core
+    pub threads: u32,
+    pub max_open_cursors: u32,
+    pub log_level: String,
+    pub thinking_policy: String,
+    pub follow_symlinks: bool,
+    pub scan_timeout_ms: u32,
+}
+
+/// Run `op` on the configured pool, or rayon's global pool if none was set
+pub(crate) fn install<R: Send>(op: impl FnOnce() -> R + Send) -> R {
+    let pool = POOL.read().unwrap_or_else(|e| e.into_inner()).clone();
+    match pool {
+        Some(pool) => pool.install(op),
+        None => op(),
+    }
+}
+
+pub(crate) fn max_open_cursors() -> usize {
+    MAX_OPEN_CURSORS.load(Ordering::Relaxed).max(1) as usize
+}
+
+pub(crate) fn follow_symlinks() -> bool {
+    FOLLOW_SYMLINKS.load(Ordering::Relaxed)
+}
+
+pub(crate) fn scan_timeout_ms() -> u32 {
+    SCAN_TIMEOUT_MS.load(Ordering::Relaxed)
+}
+
+pub(crate) fn log_enabled(level: LogLevel) -> bool {
+    level != LogLevel::Off && level as u8 <= LOG_LEVEL.load(Ordering::Relaxed)
+}
+
+fn set_threads(threads: u32) -> Result<()> {
+    let pool = if threads == 0 {
+        None
+    } else {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads as usize)
+            .thread_name(|i| format!("history-hub-parse-{}", i))
+            .build()
+            .map_err(|e| internal(format!("Cannot build thread pool: {}", e)))?;
+        Some(Arc::new(pool))
+    };
+
+    *POOL.write().unwrap_or_else(|e| e.into_inner()) = pool;
+    THREADS.store(threads, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Apply options to the process-wide settings and return the result
+pub(crate) fn apply(options: &ConfigureOptions) -> Result<RuntimeConfig> {
+    // Validate everything before changing anything
+    let log_level = options.log_level.as_deref().map(LogLevel::parse).transpose()?;
+    let thinking = options
+        .thinking_policy
+        .as_deref()
+        .map(|policy| ThinkingPolicy::parse(policy).ok_or_else(|| invalid_argument(format!("Unknown thinking policy: {}", policy))))
+        .transpose()?;
+
+    if let Some(threads) = options.threads {
+        if threads != THREADS.load(Ordering::Relaxed) {
+            set_threads(threads)?;
+        }
+    }
+    if let Some(max) = options.max_open_cursors {
+        MAX_OPEN_CURSORS.store(max.max(1), Ordering::Relaxed);
+    }
+    if let Some(level) = log_level {
+        LOG_LEVEL.store(level as u8, Ordering::Relaxed);
+    }
+    if let Some(policy) = thinking {
+        set_thinking_policy(policy);
+    }
+    if let Some(follow) = options.follow_symlinks {
+        FOLLOW_SYMLINKS.store(follow, Ordering::Relaxed);
+    }
+    if let Some(timeout) = options.scan_timeout_ms {
+        SCAN_TIMEOUT_MS.store(timeout, Ordering::Relaxed);
+    }
+
+    Ok(current())
+}
+
+pub(crate) fn current() -> RuntimeConfig {
+    RuntimeConfig {
+        threads: THREADS.load(Ordering::Relaxed),
+        max_open_cursors: MAX_OPEN_CURSORS.load(Ordering::Relaxed),
+        log_level: LogLevel::from_u8(LOG_LEVEL.load(Ordering::Relaxed)).as_str().to_string(),
+        thinking_policy: thinking_policy().as_str().to_string(),
+        follow_symlinks: follow_symlinks(),
+        scan_timeout_ms: scan_timeout_ms(),
+    }
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Tune resource usage once per process (thread count, cursor cache, log level,
+/// thinking policy, directory scanning)
+#[napi(catch_unwind)]
+pub fn configure(env: Env, options: ConfigureOptions) -> Result<RuntimeConfig> {
+    let config = apply(&options)?;
+
+    match env.get_instance_data::<RuntimeConfig>()? {
+        Some(stored) => *stored = config.clone(),
+        None => env.set_instance_data(config.clone(), (), |_| {})?,
+    }
+    Ok(config)
+}
+
+/// The configuration set by configure, or the defaults
+#[napi(catch_unwind)]
+pub fn get_config(env: Env) -> Result<RuntimeConfig> {
+    Ok(env
+        .get_instance_data::<RuntimeConfig>()?
+        .map(|config| config.clone())
+        .unwrap_or_else(current))
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_configuration() {
+        let config = apply(&ConfigureOptions { threads: Some(2), ..Default::default() }).unwrap();
+        assert_eq!(config.threads, 2);
+        assert_eq!(install(rayon::current_num_threads), 2);
+
+        // A bad field rejects the whole call
+        assert!(apply(&ConfigureOptions { threads: Some(3), log_level: Some("loud".into()), ..Default::default() }).is_err());
+        assert!(apply(&ConfigureOptions { threads: Some(3), thinking_policy: Some("hide".into()), ..Default::default() }).is_err());
+        assert_eq!(current().threads, 2);
+
+        assert!(log_enabled(LogLevel::Warn) && !log_enabled(LogLevel::Debug));
+        apply(&ConfigureOptions { threads: Some(0), ..Default::default() }).unwrap();
+        assert!(POOL.read().unwrap().is_none());
+    }
+}
@@ -0,0 +1,295 @@
+// ============================================
+// SYNC CONFLICTS
+// The same session file in several roots (synced or copied between
+// machines) with diverging content, reported and optionally resolved
+// by keeping one copy or merging their entries by uuid
+// ============================================
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::errors::unsupported_format;
+use crate::paths::read_to_string;
+use crate::safe_write::write_file;
+use crate::scan::SkippedPath;
+use crate::similarity::fnv1a;
+use crate::time::parse_utc;
+use crate::{collect_session_files, parse_jsonl_line};
+
+const STRATEGIES: &[&str] = &["keep-longest", "keep-newest", "merge-by-uuid"];
+
+/// Options for resolve_conflicts
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct ConflictOptions {
+    /// Rewrite the copies with the resolved content (default false, report only)
+    pub apply: Option<bool>,
+    /// Let apply replace the copies in place (default true); false fails with
+    /// FILE_EXISTS rather than write over an existing copy
+    pub overwrite: Option<bool>,
+}
+
+/// One copy of a conflicting session
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConflictCopy {
+    pub file_path: String,
+    pub bytes: i64,
+    pub lines: u32,
+    /// Latest entry timestamp
+    pub last_timestamp: Option<String>,
+    /// Entry uuids no other copy has
+    pub unique_ids: Vec<String>,
+    /// True when the resolution differs from this copy, so applying rewrites it
+    pub changed: bool,
+}
+
+/// A session whose copies disagree
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionConflict {
+    pub session_id: String,
+    /// In root order
+    pub copies: Vec<ConflictCopy>,
+    /// Entries present in every copy
+    pub shared_lines: u32,
+    /// Copy whose content wins, None when merged
+    pub winner: Option<String>,
+    pub resolved_lines: u32,
+    /// Files rewritten with the resolution (empty unless applied)
+    pub written: Vec<String>,
+}
+
+/// Result of resolve_conflicts
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConflictReport {
+    pub strategy: String,
+    pub applied: bool,
+    pub files_scanned: u32,
+    pub conflicts: Vec<SessionConflict>,
+    /// Copies that could not be read, left out of the resolutions
+    pub skipped: Vec<SkippedPath>,
+}
+
+/// A line of a copy with what ordering and deduplication need
+struct Line {
+    text: String,
+    key: String,
+    /// The entry's timestamp, or the latest one before it in the same copy
+    timestamp: Option<DateTime<Utc>>,
+}
+
+struct Copy {
+    path: PathBuf,
+    content: String,
+    lines: Vec<Line>,
+    /// Latest entry timestamp, parsed and as written
+    last: Option<(DateTime<Utc>, String)>,
+}
+
+fn read_copy(path: &Path) -> std::io::Result<Copy> {
+    let content = read_to_string(path)?;
+    let mut last: Option<(DateTime<Utc>, String)> = None;
+    let lines = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let entry = parse_jsonl_line(line).ok();
+            // Compared as instants: offsets and fractional seconds vary between writers
+            let own = entry.as_ref().and_then(|e| e.timestamp.as_deref()).and_then(|ts| Some((parse_utc(ts)?, ts.to_string())));
+            if let Some((time, ts)) = &own {
+                if last.as_ref().is_none_or(|(l, _)| time > l) {
+                    last = Some((*time, ts.clone()));
+                }
+            }
+            Line {
+                // Lines without a uuid (summaries, snapshots) are matched on their content
+                key: entry.as_ref().and_then(|e| e.uuid.clone()).unwrap_or_else(|| format!("line:{:016x}", fnv1a(line.trim().as_bytes()))),
+                timestamp: own.map(|(time, _)| time).or(last.as_ref().map(|(time, _)| *time)),
+                text: line.to_string(),
+            }
+        })
+        .collect();
+    Ok(Copy { path: path.to_path_buf(), content, lines, last })
+}
+
+/// Every line of every copy, once per key, in timestamp order
+fn merge(copies: &[Copy]) -> Vec<&Line> {
+    let mut seen: HashSet<&str> = HashSet::new();
+    let mut lines: Vec<&Line> = copies.iter().flat_map(|c| &c.lines).filter(|l| seen.insert(&l.key)).collect();
+    // Stable, so lines sharing a timestamp keep their file order
+    lines.sort_by_key(|l| l.timestamp);
+    lines
+}
+
+/// Index of the winning copy for the keep-* strategies (earlier roots win ties)
+fn pick(copies: &[Copy], strategy: &str) -> usize {
+    let mut best = 0;
+    for (i, copy) in copies.iter().enumerate().skip(1) {
+        let better = match strategy {
+            "keep-newest" => copy.last.as_ref().map(|(time, _)| time) > copies[best].last.as_ref().map(|(time, _)| time),
+            _ => (copy.lines.len(), copy.content.len()) > (copies[best].lines.len(), copies[best].content.len()),
+        };
+        if better {
+            best = i;
+        }
+    }
+    best
+}
+
+fn resolve(session_id: String, copies: Vec<Copy>, strategy: &str, apply: bool, overwrite: bool) -> Result<SessionConflict> {
+    let (winner, resolved) = if strategy == "merge-by-uuid" {
+        (None, merge(&copies).iter().map(|l| format!("{}\n", l.text)).collect())
+    } else {
+        let best = pick(&copies, strategy);
+        (Some(best), copies[best].content.clone())
+    };
+    let resolved_lines = resolved.lines().filter(|l| !l.trim().is_empty()).count() as u32;
+
+    let keys: Vec<HashSet<&str>> = copies.iter().map(|c| c.lines.iter().map(|l| l.key.as_str()).collect()).collect();
+    let shared_lines = keys[0].iter().filter(|k| keys[1..].iter().all(|other| other.contains(*k))).count() as u32;
+    let mut written = Vec::new();
+    let mut report = Vec::new();
+    for (i, copy) in copies.iter().enumerate() {
+        let changed = copy.content != resolved;
+        let unique_ids = copy
+            .lines
+            .iter()
+            .filter(|l| !l.key.starts_with("line:") && keys.iter().enumerate().all(|(j, other)| j == i || !other.contains(l.key.as_str())))
+            .map(|l| l.key.clone())
+            .collect();
+        let file_path = copy.path.display().to_string();
+        if apply && changed {
+            write_file(&copy.path, resolved.as_bytes(), overwrite)?;
+            written.push(file_path.clone());
+        }
+        report.push(ConflictCopy {
+            file_path,
+            bytes: copy.content.len() as i64,
+            lines: copy.lines.len() as u32,
+            last_timestamp: copy.last.as_ref().map(|(_, ts)| ts.clone()),
+            unique_ids,
+            changed,
+        });
+    }
+
+    Ok(SessionConflict {
+        session_id,
+        winner: winner.map(|i| report[i].file_path.clone()),
+        copies: report,
+        shared_lines,
+        resolved_lines,
+        written,
+    })
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Sessions found under more than one of roots (same file name, i.e.
+/// sessionId) whose copies differ, resolved with strategy: "keep-longest",
+/// "keep-newest" or "merge-by-uuid". Only reports unless options.apply is set
+#[napi(catch_unwind)]
+pub fn resolve_conflicts(roots: Vec<String>, strategy: String, options: Option<ConflictOptions>) -> Result<ConflictReport> {
+    if !STRATEGIES.contains(&strategy.as_str()) {
+        return Err(unsupported_format(format!("Unknown conflict strategy: {}", strategy)));
+    }
+    let options = options.unwrap_or_default();
+    let apply = options.apply.unwrap_or(false);
+    let overwrite = options.overwrite.unwrap_or(true);
+
+    let mut by_session: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    let mut files_scanned = 0;
+    for root in &roots {
+        for path in collect_session_files(root)? {
+            files_scanned += 1;
+            let session_id = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+            by_session.entry(session_id).or_default().push(path);
+        }
+    }
+
+    let mut conflicts = Vec::new();
+    let mut skipped = Vec::new();
+    for (session_id, paths) in by_session.into_iter().filter(|(_, paths)| paths.len() > 1) {
+        // An unreadable copy cannot take part in the resolution
+        let mut copies: Vec<Copy> = Vec::new();
+        for path in &paths {
+            match read_copy(path) {
+                Ok(copy) => copies.push(copy),
+                Err(e) => skipped.push(SkippedPath { path: path.display().to_string(), reason: "unreadable".to_string(), message: Some(e.to_string()) }),
+            }
+        }
+        if copies.len() < 2 || copies.iter().all(|c| c.content == copies[0].content) {
+            continue;
+        }
+        conflicts.push(resolve(session_id, copies, &strategy, apply, overwrite)?);
+    }
+
+    Ok(ConflictReport { strategy, applied: apply, files_scanned, conflicts, skipped })
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_resolve_conflicts() {
+        let base = TempDir::new("conflicts");
+        let (laptop, desktop) = (base.join("laptop"), base.join("desktop"));
+        std::fs::create_dir_all(laptop.join("-home-me-app")).unwrap();
+        std::fs::create_dir_all(desktop.join("-Users-me-app")).unwrap();
+        let line = |uuid: &str, minute: u32| {
+            format!(r#"{{"type":"user","uuid":"{}","timestamp":"2024-01-01T10:{:02}:00Z","message":{{"role":"user","content":"x"}}}}"#, uuid, minute)
+        };
+        let (a, b) = (laptop.join("-home-me-app/s1.jsonl"), desktop.join("-Users-me-app/s1.jsonl"));
+        std::fs::write(&a, [line("u1", 0), line("u2", 1), line("u4", 3)].join("\n")).unwrap();
+        std::fs::write(&b, [line("u1", 0), line("u3", 2)].join("\n")).unwrap();
+        // Identical copies are not a conflict
+        std::fs::write(laptop.join("-home-me-app/s2.jsonl"), line("v1", 0)).unwrap();
+        std::fs::write(desktop.join("-Users-me-app/s2.jsonl"), line("v1", 0)).unwrap();
+        // A copy that is not UTF-8 cannot be read
+        std::fs::write(laptop.join("-home-me-app/s3.jsonl"), [0xff, 0xfe]).unwrap();
+        std::fs::write(desktop.join("-Users-me-app/s3.jsonl"), line("w1", 0)).unwrap();
+        let roots = vec![laptop.display().to_string(), desktop.display().to_string()];
+
+        let report = resolve_conflicts(roots.clone(), "keep-longest".into(), None).unwrap();
+        assert_eq!((report.files_scanned, report.conflicts.len(), report.applied), (6, 1, false));
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!((report.skipped[0].path.as_str(), report.skipped[0].reason.as_str()), (laptop.join("-home-me-app/s3.jsonl").display().to_string().as_str(), "unreadable"));
+        let conflict = &report.conflicts[0];
+        assert_eq!((conflict.session_id.as_str(), conflict.shared_lines), ("s1", 1));
+        assert_eq!(conflict.winner.as_deref(), Some(a.display().to_string().as_str()));
+        assert_eq!(conflict.copies[0].unique_ids, vec!["u2", "u4"]);
+        assert_eq!(conflict.copies.iter().map(|c| c.changed).collect::<Vec<_>>(), vec![false, true]);
+
+        let newest = resolve_conflicts(roots.clone(), "keep-newest".into(), None).unwrap();
+        assert_eq!(newest.conflicts[0].copies[0].last_timestamp.as_deref(), Some("2024-01-01T10:03:00Z"));
+        // 12:00+03:00 sorts after 10:30Z as text but is earlier
+        let (early, late) = (base.join("early.jsonl"), base.join("late.jsonl"));
+        std::fs::write(&early, r#"{"type":"user","uuid":"e1","timestamp":"2024-01-01T12:00:00+03:00"}"#).unwrap();
+        std::fs::write(&late, r#"{"type":"user","uuid":"l1","timestamp":"2024-01-01T10:30:00Z"}"#).unwrap();
+        let copies = vec![read_copy(&early).unwrap(), read_copy(&late).unwrap()];
+        assert_eq!(pick(&copies, "keep-newest"), 1);
+        assert_eq!(merge(&copies).iter().map(|l| l.key.as_str()).collect::<Vec<_>>(), vec!["e1", "l1"]);
+
+        let options = ConflictOptions { apply: Some(true), overwrite: None };
+        let merged = resolve_conflicts(roots.clone(), "merge-by-uuid".into(), Some(options)).unwrap();
+        assert_eq!((merged.conflicts[0].resolved_lines, merged.conflicts[0].written.len()), (4, 2));
+        let expected = [line("u1", 0), line("u2", 1), line("u3", 2), line("u4", 3)].join("\n") + "\n";
+        assert_eq!(std::fs::read_to_string(&b).unwrap(), expected);
+        assert!(resolve_conflicts(roots.clone(), "keep-longest".into(), None).unwrap().conflicts.is_empty());
+
+        assert!(resolve_conflicts(roots, "keep-all".into(), None).is_err());
+    }
+}
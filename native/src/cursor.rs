@@ -0,0 +1,195 @@
+// ============================================
+// MESSAGE CURSORS
+// Read huge sessions in batches so neither side holds the whole
+// file as one giant Vec
+// ============================================
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::errors::{io_error, not_found};
+use crate::paths::open_file;
+use crate::{config, entry_to_message, parse_jsonl_line, reader, ClaudeMessage};
+
+static NEXT_CURSOR_ID: AtomicU32 = AtomicU32::new(1);
+static CURSORS: Mutex<Option<HashMap<u32, MessageCursor>>> = Mutex::new(None);
+
+/// Which messages a cursor yields
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct MessageFilter {
+    /// "user" or "assistant"
+    pub role: Option<String>,
+    /// Include subagent (sidechain) messages (default true)
+    pub include_sidechain: Option<bool>,
+    /// Case-insensitive substring of the merged content
+    pub query: Option<String>,
+}
+
+impl MessageFilter {
+    pub fn matches(&self, message: &ClaudeMessage) -> bool {
+        if self.role.as_ref().is_some_and(|role| &message.role != role) {
+            return false;
+        }
+        if !self.include_sidechain.unwrap_or(true) && message.is_sidechain == Some(true) {
+            return false;
+        }
+        self.query
+            .as_ref()
+            .is_none_or(|q| message.content.to_lowercase().contains(&q.to_lowercase()))
+    }
+}
+
+/// One batch read from a cursor
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct MessageBatch {
+    pub messages: Vec<ClaudeMessage>,
+    /// True once the end of the file was reached (the cursor is closed)
+    pub done: bool,
+    /// Lines consumed so far, for progress reporting
+    pub lines_read: u32,
+}
+
+struct MessageCursor {
+    file_path: String,
+    lines: std::io::Lines<BufReader<File>>,
+    filter: MessageFilter,
+    lines_read: u32,
+}
+
+impl MessageCursor {
+    fn next_batch(&mut self, n: usize) -> (Vec<ClaudeMessage>, bool) {
+        let mut messages = Vec::with_capacity(n.min(1024));
+
+        while messages.len() < n {
+            let Some(line) = self.lines.next() else {
+                return (messages, true);
+            };
+            self.lines_read += 1;
+
+            // Read errors end the cursor the same way EOF does
+            let Ok(line) = line else {
+                return (messages, true);
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let index = self.lines_read as usize - 1;
+            let parsed = reader::with_line_context(&self.file_path, index, || {
+                parse_jsonl_line(&line).ok().and_then(entry_to_message)
+            });
+            if let Some(message) = parsed {
+                if self.filter.matches(&message) {
+                    messages.push(message);
+                }
+            }
+        }
+
+        (messages, false)
+    }
+}
+
+fn with_cursors<T>(f: impl FnOnce(&mut HashMap<u32, MessageCursor>) -> T) -> T {
+    let mut guard = CURSORS.lock().unwrap_or_else(|e| e.into_inner());
+    f(guard.get_or_insert_with(HashMap::new))
+}
+
+/// Close every cursor reading file_path, returns how many were open
+pub(crate) fn close_cursors_for(file_path: &str) -> usize {
+    with_cursors(|cursors| {
+        let before = cursors.len();
+        cursors.retain(|_, cursor| cursor.file_path != file_path);
+        before - cursors.len()
+    })
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Open a cursor over a session file, returns a cursor id for next_batch
+#[napi(catch_unwind)]
+pub fn open_message_cursor(file_path: String, filter: Option<MessageFilter>) -> Result<u32> {
+    let file = open_file(&file_path)
+        .map_err(|e| io_error("Cannot open file", &file_path, e))?;
+
+    let cursor = MessageCursor {
+        file_path,
+        lines: BufReader::new(file).lines(),
+        filter: filter.unwrap_or_default(),
+        lines_read: 0,
+    };
+
+    let id = NEXT_CURSOR_ID.fetch_add(1, Ordering::Relaxed);
+    with_cursors(|cursors| {
+        cursors.insert(id, cursor);
+        // Forgotten cursors hold file handles; close the oldest past the limit
+        while cursors.len() > config::max_open_cursors() {
+            let Some(oldest) = cursors.keys().min().copied() else { break };
+            cursors.remove(&oldest);
+        }
+    });
+    Ok(id)
+}
+
+/// Read up to n more messages; the cursor closes itself at end of file
+#[napi(catch_unwind)]
+pub fn next_batch(cursor: u32, n: u32) -> Result<MessageBatch> {
+    with_cursors(|cursors| {
+        let state = cursors
+            .get_mut(&cursor)
+            .ok_or_else(|| not_found(format!("Unknown or closed cursor: {}", cursor)))?;
+
+        let (messages, done) = state.next_batch(n.max(1) as usize);
+        let lines_read = state.lines_read;
+        if done {
+            cursors.remove(&cursor);
+        }
+
+        Ok(MessageBatch { messages, done, lines_read })
+    })
+}
+
+/// Close a cursor early, returns false if it was already closed
+#[napi(catch_unwind)]
+pub fn close_cursor(cursor: u32) -> bool {
+    with_cursors(|cursors| cursors.remove(&cursor).is_some())
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_cursor_batches_until_done() {
+        let tmp = TempDir::new("cursor-test");
+        let path = tmp.join("cursor-test.jsonl");
+        let lines: Vec<String> = (0..5)
+            .map(|i| format!(r#"{{"type":"user","uuid":"u{}","message":{{"role":"user","content":"hi {}"}}}}"#, i, i))
+            .collect();
+        std::fs::write(&path, lines.join("\n")).unwrap();
+
+        let id = open_message_cursor(path.display().to_string(), None).unwrap();
+        let first = next_batch(id, 3).unwrap();
+        assert_eq!(first.messages.len(), 3);
+        assert!(!first.done);
+
+        let second = next_batch(id, 3).unwrap();
+        assert_eq!(second.messages.len(), 2);
+        assert!(second.done);
+        assert!(next_batch(id, 1).is_err());
+    }
+}
@@ -0,0 +1,131 @@
+// ============================================
+// SESSION DELETION
+// Remove a session file after checking it really is a Claude Code log,
+// optionally via the OS trash, and drop everything cached for it
+// ============================================
+
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::errors::{io_error, ErrorCode, HubError};
+use crate::paths::{long_path, metadata, open_file, remove_file};
+use crate::{cursor, fast, index};
+
+/// Non-empty lines checked before a file is accepted as a session
+const VALIDATE_LINES: usize = 5;
+
+/// Options for delete_session
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct DeleteOptions {
+    /// Move to the OS trash / recycle bin instead of deleting for good (default true)
+    pub to_trash: Option<bool>,
+}
+
+/// What delete_session did
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct DeleteResult {
+    pub file_path: String,
+    pub bytes: i64,
+    /// True when the file went to the trash, false when deleted permanently
+    pub trashed: bool,
+    /// Open cursors on the file that were closed
+    pub closed_cursors: u32,
+}
+
+/// A .jsonl file whose first lines are JSON entries with a "type" field
+fn validate_session_file(path: &Path) -> Result<()> {
+    if !long_path(path).is_file() {
+        return Err(HubError::new(ErrorCode::FileNotFound, "Not a file").path(path).into());
+    }
+    if path.extension().is_none_or(|ext| ext != "jsonl") {
+        return Err(HubError::new(ErrorCode::UnsupportedFormat, "Not a session file (expected .jsonl)").path(path).into());
+    }
+
+    let file = open_file(path).map_err(|e| io_error("Cannot open file", path, e))?;
+    let mut reader = BufReader::new(file);
+    let mut line = Vec::new();
+    let mut checked = 0;
+
+    while checked < VALIDATE_LINES {
+        line.clear();
+        let read = reader
+            .read_until(b'\n', &mut line)
+            .map_err(|e| io_error("Cannot read file", path, e))?;
+        if read == 0 {
+            break;
+        }
+        if line.iter().all(u8::is_ascii_whitespace) {
+            continue;
+        }
+        if fast::peek_entry(&line).is_none() {
+            return Err(HubError::new(ErrorCode::UnsupportedFormat, "Not a Claude session log").path(path).line(checked as u32 + 1).into());
+        }
+        checked += 1;
+    }
+    Ok(())
+}
+
+fn delete_file(file_path: &str, options: &DeleteOptions) -> Result<DeleteResult> {
+    let path = Path::new(file_path);
+    validate_session_file(path)?;
+
+    let bytes = metadata(path).map(|m| m.len() as i64).unwrap_or(0);
+    let to_trash = options.to_trash.unwrap_or(true);
+
+    // Release our own handles first; Windows cannot delete open files
+    let closed_cursors = cursor::close_cursors_for(file_path) as u32;
+    index::forget(file_path);
+
+    if to_trash {
+        trash::delete(path).map_err(|e| Error::from(HubError::new(ErrorCode::IoError, format!("Cannot move to trash: {}", e)).path(path)))?;
+    } else {
+        remove_file(path).map_err(|e| io_error("Cannot delete file", path, e))?;
+    }
+
+    tracing::info!(file = file_path, bytes, trashed = to_trash, "Deleted session");
+    Ok(DeleteResult { file_path: file_path.to_string(), bytes, trashed: to_trash, closed_cursors })
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Delete a session file (to the OS trash by default) and drop cached state for it
+#[napi(catch_unwind)]
+pub fn delete_session(file_path: String, options: Option<DeleteOptions>) -> Result<DeleteResult> {
+    delete_file(&file_path, &options.unwrap_or_default())
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_delete_session_validates_and_closes_cursors() {
+        let dir = TempDir::new("delete-test");
+        let session = dir.join("s1.jsonl");
+        let other = dir.join("notes.jsonl");
+        std::fs::write(&session, r#"{"type":"user","uuid":"1","message":{"role":"user","content":"hi"}}"#).unwrap();
+        std::fs::write(&other, "just some text\n").unwrap();
+
+        let permanent = DeleteOptions { to_trash: Some(false) };
+        assert!(delete_file(&other.to_string_lossy(), &permanent).is_err());
+        assert!(other.exists());
+
+        let file_path = session.to_string_lossy().to_string();
+        cursor::open_message_cursor(file_path.clone(), None).unwrap();
+        let result = delete_file(&file_path, &permanent).unwrap();
+        assert_eq!((result.trashed, result.closed_cursors), (false, 1));
+        assert!(!session.exists());
+    }
+}
@@ -0,0 +1,192 @@
+// ============================================
+// DUPLICATE ATTACHMENTS
+// Images and tool results stored more than once across sessions (the
+// same screenshot pasted again, the same file read ten times), by hash
+// ============================================
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use serde_json::Value;
+
+use crate::images::{for_each_image, image_field, sha256_hex};
+use crate::{collect_session_files, for_each_entry, tool_result_text, ContentItem};
+
+/// Tool results smaller than this are too common ("ok", "") to be worth reporting
+const DEFAULT_MIN_BYTES: u32 = 1024;
+
+/// Options for find_duplicate_attachments
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct DuplicateOptions {
+    /// Payloads smaller than this many bytes are ignored (default 1024)
+    pub min_bytes: Option<u32>,
+}
+
+/// Where one copy is stored
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttachmentOccurrence {
+    pub file_path: String,
+    pub session_id: String,
+    pub message_id: String,
+    /// Image number within the message, as in get_image_data
+    pub image_index: Option<u32>,
+    /// Set for tool results
+    pub tool_use_id: Option<String>,
+}
+
+/// One payload stored more than once
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateAttachment {
+    /// "image" or "tool_result"
+    pub kind: String,
+    pub sha256: String,
+    pub media_type: Option<String>,
+    /// Size of one copy as stored (base64 for images)
+    pub bytes: i64,
+    pub copies: u32,
+    /// bytes times every copy but the first
+    pub wasted_bytes: i64,
+    /// In file order
+    pub occurrences: Vec<AttachmentOccurrence>,
+}
+
+/// Result of find_duplicate_attachments
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateReport {
+    pub files_scanned: u32,
+    pub payloads_scanned: u32,
+    pub wasted_bytes: i64,
+    /// Most wasted bytes first
+    pub duplicates: Vec<DuplicateAttachment>,
+}
+
+/// Payloads seen so far, by kind and hash
+#[derive(Default)]
+struct PayloadIndex {
+    groups: HashMap<(&'static str, String), DuplicateAttachment>,
+    scanned: u32,
+}
+
+impl PayloadIndex {
+    fn add(&mut self, kind: &'static str, payload: &str, media_type: Option<&str>, occurrence: AttachmentOccurrence) {
+        self.scanned += 1;
+        let sha256 = sha256_hex(payload);
+        let group = self.groups.entry((kind, sha256.clone())).or_insert_with(|| DuplicateAttachment {
+            kind: kind.to_string(),
+            sha256,
+            media_type: media_type.map(str::to_string),
+            bytes: payload.len() as i64,
+            copies: 0,
+            wasted_bytes: 0,
+            occurrences: Vec::new(),
+        });
+        group.copies += 1;
+        group.wasted_bytes = group.bytes * (group.copies as i64 - 1);
+        group.occurrences.push(occurrence);
+    }
+
+    fn scan_file(&mut self, path: &Path, min_bytes: usize) -> Result<()> {
+        let file_path = path.display().to_string();
+        let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+
+        for_each_entry(&file_path, |entry| {
+            let Some(message) = &entry.message else { return };
+            let occurrence = |image_index, tool_use_id| AttachmentOccurrence {
+                file_path: file_path.clone(),
+                session_id: entry.session_id.clone().unwrap_or_else(|| stem.clone()),
+                message_id: entry.uuid.clone().unwrap_or_else(|| "unknown".to_string()),
+                image_index,
+                tool_use_id,
+            };
+
+            for item in &message.content {
+                let ContentItem::ToolResult { tool_use_id, content, .. } = item else { continue };
+                let text = tool_result_text(content);
+                if text.len() >= min_bytes {
+                    self.add("tool_result", &text, None, occurrence(None, Some(tool_use_id.clone())));
+                }
+            }
+            if !message.content.iter().any(|item| matches!(item, ContentItem::Image { .. } | ContentItem::ToolResult { .. })) {
+                return;
+            }
+            let Ok(Value::Array(mut blocks)) = serde_json::to_value(&message.content) else { return };
+            for_each_image(&mut blocks, &mut 0, &mut |index, block| {
+                let data = image_field(block, "data");
+                if data.len() >= min_bytes {
+                    self.add("image", data, Some(image_field(block, "media_type")), occurrence(Some(index), None));
+                }
+            });
+        })
+    }
+
+    fn finish(self, files_scanned: u32) -> DuplicateReport {
+        let mut duplicates: Vec<DuplicateAttachment> = self.groups.into_values().filter(|g| g.copies > 1).collect();
+        duplicates.sort_by(|a, b| b.wasted_bytes.cmp(&a.wasted_bytes).then_with(|| a.sha256.cmp(&b.sha256)));
+        DuplicateReport {
+            files_scanned,
+            payloads_scanned: self.scanned,
+            wasted_bytes: duplicates.iter().map(|d| d.wasted_bytes).sum(),
+            duplicates,
+        }
+    }
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Images and tool results stored more than once in the sessions under
+/// root_dir, with every place they appear
+#[napi(catch_unwind)]
+pub fn find_duplicate_attachments(root_dir: String, options: Option<DuplicateOptions>) -> Result<DuplicateReport> {
+    let min_bytes = options.unwrap_or_default().min_bytes.unwrap_or(DEFAULT_MIN_BYTES) as usize;
+    let files = collect_session_files(&root_dir)?;
+    let mut index = PayloadIndex::default();
+    for path in &files {
+        // An unreadable file only leaves its payloads out
+        let _ = index.scan_file(path, min_bytes);
+    }
+    Ok(index.finish(files.len() as u32))
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_find_duplicates() {
+        let root = TempDir::new("duplicates");
+        std::fs::create_dir_all(root.join("p")).unwrap();
+        let image = |uuid: &str| {
+            format!(r#"{{"type":"user","uuid":"{}","message":{{"role":"user","content":[{{"type":"image","source":{{"type":"base64","media_type":"image/png","data":"QUJDRA=="}}}}]}}}}"#, uuid)
+        };
+        let result = |uuid: &str, text: &str| {
+            format!(r#"{{"type":"user","uuid":"{}","message":{{"role":"user","content":[{{"type":"tool_result","tool_use_id":"t-{}","content":"{}"}}]}}}}"#, uuid, uuid, text)
+        };
+        std::fs::write(root.join("p/a.jsonl"), [image("u1"), result("u2", "same file"), result("u3", "other")].join("\n")).unwrap();
+        std::fs::write(root.join("p/b.jsonl"), [image("u4"), image("u5"), result("u6", "same file")].join("\n")).unwrap();
+
+        let report = find_duplicate_attachments(root.display().to_string(), Some(DuplicateOptions { min_bytes: Some(4) })).unwrap();
+        assert_eq!((report.files_scanned, report.payloads_scanned), (2, 6));
+        assert_eq!(report.duplicates.len(), 2);
+        let image_group = &report.duplicates[0];
+        assert_eq!((image_group.kind.as_str(), image_group.copies, image_group.wasted_bytes), ("image", 3, 16));
+        assert_eq!(image_group.occurrences[2].message_id, "u5");
+        assert_eq!(report.duplicates[1].occurrences[1].tool_use_id.as_deref(), Some("t-u6"));
+        assert_eq!(report.wasted_bytes, 16 + 9);
+
+        // The default threshold leaves these small payloads out
+        assert!(find_duplicate_attachments(root.display().to_string(), None).unwrap().duplicates.is_empty());
+    }
+}
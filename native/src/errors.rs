@@ -0,0 +1,251 @@
+// ============================================
+// ERROR CODES
+// Errors leave Rust as "FILE_NOT_FOUND: Cannot open file: ..." plus
+// optional "path: ..." and "line: ..." lines; a wrapper installed over
+// the exports at load turns that into err.code, err.message, err.path
+// and err.line. describe_error splits the raw form for anything else
+// ============================================
+
+use std::path::Path;
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+/// Kind of failure, shown to JS as err.code in SCREAMING_CASE
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    FileNotFound,
+    PermissionDenied,
+    /// A file exists where a new one was to be written
+    FileExists,
+    /// Other I/O failures
+    IoError,
+    /// Content that does not parse: JSON, dates, patterns
+    ParseError,
+    /// A format, mode or strategy name the function does not know
+    UnsupportedFormat,
+    InvalidArgument,
+    /// A session, message, cursor or other id that does not exist
+    NotFound,
+    /// A JS callback passed in threw
+    HookFailed,
+    Internal,
+}
+
+const CODES: &[(ErrorCode, &str)] = &[
+    (ErrorCode::FileNotFound, "FILE_NOT_FOUND"),
+    (ErrorCode::PermissionDenied, "PERMISSION_DENIED"),
+    (ErrorCode::FileExists, "FILE_EXISTS"),
+    (ErrorCode::IoError, "IO_ERROR"),
+    (ErrorCode::ParseError, "PARSE_ERROR"),
+    (ErrorCode::UnsupportedFormat, "UNSUPPORTED_FORMAT"),
+    (ErrorCode::InvalidArgument, "INVALID_ARGUMENT"),
+    (ErrorCode::NotFound, "NOT_FOUND"),
+    (ErrorCode::HookFailed, "HOOK_FAILED"),
+    (ErrorCode::Internal, "INTERNAL"),
+];
+
+impl ErrorCode {
+    pub fn as_str(self) -> &'static str {
+        CODES.iter().find(|(code, _)| *code == self).map(|(_, name)| *name).unwrap_or("INTERNAL")
+    }
+
+    pub fn parse(name: &str) -> Option<ErrorCode> {
+        CODES.iter().find(|(_, n)| *n == name).map(|(code, _)| *code)
+    }
+
+    fn from_io(kind: std::io::ErrorKind) -> ErrorCode {
+        match kind {
+            std::io::ErrorKind::NotFound => ErrorCode::FileNotFound,
+            std::io::ErrorKind::PermissionDenied => ErrorCode::PermissionDenied,
+            std::io::ErrorKind::AlreadyExists => ErrorCode::FileExists,
+            _ => ErrorCode::IoError,
+        }
+    }
+}
+
+/// An error with its code and context, converted into napi::Error at the boundary
+#[derive(Debug, Clone, PartialEq)]
+pub struct HubError {
+    pub code: ErrorCode,
+    pub message: String,
+    pub path: Option<String>,
+    /// 1-based line of the file the error is about
+    pub line: Option<u32>,
+}
+
+impl HubError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> HubError {
+        HubError { code, message: message.into(), path: None, line: None }
+    }
+
+    pub fn path(mut self, path: impl AsRef<Path>) -> HubError {
+        self.path = Some(path.as_ref().display().to_string());
+        self
+    }
+
+    pub fn line(mut self, line: u32) -> HubError {
+        self.line = Some(line);
+        self
+    }
+}
+
+impl From<HubError> for Error {
+    fn from(error: HubError) -> Error {
+        let mut reason = format!("{}: {}", error.code.as_str(), error.message);
+        if let Some(path) = &error.path {
+            reason.push_str(&format!("\npath: {}", path));
+        }
+        if let Some(line) = error.line {
+            reason.push_str(&format!("\nline: {}", line));
+        }
+        Error::from_reason(reason)
+    }
+}
+
+pub(crate) fn error(code: ErrorCode, message: impl Into<String>) -> Error {
+    HubError::new(code, message).into()
+}
+
+pub(crate) fn invalid_argument(message: impl Into<String>) -> Error {
+    error(ErrorCode::InvalidArgument, message)
+}
+
+pub(crate) fn unsupported_format(message: impl Into<String>) -> Error {
+    error(ErrorCode::UnsupportedFormat, message)
+}
+
+pub(crate) fn not_found(message: impl Into<String>) -> Error {
+    error(ErrorCode::NotFound, message)
+}
+
+pub(crate) fn parse_error(message: impl Into<String>) -> Error {
+    error(ErrorCode::ParseError, message)
+}
+
+pub(crate) fn internal(message: impl Into<String>) -> Error {
+    error(ErrorCode::Internal, message)
+}
+
+/// An I/O failure on path, coded by its kind ("Cannot open file: <os error>")
+pub(crate) fn io_error(context: &str, path: impl AsRef<Path>, err: std::io::Error) -> Error {
+    HubError::new(ErrorCode::from_io(err.kind()), format!("{}: {}", context, err)).path(path).into()
+}
+
+/// An I/O failure where the path is not at hand
+pub(crate) fn io_failure(context: &str, err: std::io::Error) -> Error {
+    error(ErrorCode::from_io(err.kind()), format!("{}: {}", context, err))
+}
+
+/// Result of describe_error
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorInfo {
+    /// "FILE_NOT_FOUND", "PARSE_ERROR"... or "UNKNOWN" for messages without a code
+    pub code: String,
+    pub message: String,
+    pub path: Option<String>,
+    pub line: Option<u32>,
+}
+
+fn describe(reason: &str) -> ErrorInfo {
+    let mut lines = reason.lines();
+    let first = lines.next().unwrap_or_default();
+    let (code, message) = match first.split_once(": ") {
+        Some((code, message)) if ErrorCode::parse(code).is_some() => (code.to_string(), message.to_string()),
+        _ => return ErrorInfo { code: "UNKNOWN".to_string(), message: reason.to_string(), path: None, line: None },
+    };
+    let mut info = ErrorInfo { code, message, path: None, line: None };
+    for line in lines {
+        match line.split_once(": ") {
+            Some(("path", path)) => info.path = Some(path.to_string()),
+            Some(("line", n)) => info.line = n.parse().ok(),
+            _ => {}
+        }
+    }
+    info
+}
+
+/// Wraps every exported function so what it throws, or the Promise it
+/// returns rejects with, has its code, path and line as properties
+#[cfg(not(test))]
+const ERROR_WRAPPER: &str = r#"(exports, codes) => {
+  const convert = (error) => {
+    if (!(error instanceof Error)) return error
+    const [first, ...context] = error.message.split('\n')
+    const code = first.slice(0, first.indexOf(': '))
+    if (!codes.includes(code)) return error
+    const raw = error.message
+    error.code = code
+    error.message = first.slice(code.length + 2)
+    for (const line of context) {
+      if (line.startsWith('path: ')) error.path = line.slice(6)
+      if (line.startsWith('line: ')) error.line = Number(line.slice(6))
+    }
+    if (typeof error.stack === 'string') error.stack = error.stack.replace(raw, error.message)
+    return error
+  }
+  for (const [name, native] of Object.entries(exports)) {
+    if (typeof native !== 'function') continue
+    const wrapped = function (...args) {
+      let result
+      try {
+        result = native.apply(this, args)
+      } catch (error) {
+        throw convert(error)
+      }
+      return result instanceof Promise ? result.catch((error) => { throw convert(error) }) : result
+    }
+    Object.defineProperty(wrapped, 'name', { value: name })
+    exports[name] = wrapped
+  }
+}"#;
+
+/// Runs once the exports are registered: install ERROR_WRAPPER over them.
+/// Left out of test builds, which do not link against Node
+#[cfg(not(test))]
+#[napi_derive::module_exports]
+fn wrap_exports(exports: napi::JsObject, env: Env) -> Result<()> {
+    let codes: Vec<&str> = CODES.iter().map(|(_, name)| *name).collect();
+    let script = format!("({})", ERROR_WRAPPER);
+    let wrap: napi::JsFunction = env.run_script(script)?;
+    wrap.call(None, &[exports.into_unknown(), env.to_js_value(&codes)?])?;
+    Ok(())
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Code, message and context of an error in its raw form (e.g. a logged
+/// message); errors thrown to JS already carry them as properties
+#[napi(catch_unwind)]
+pub fn describe_error(message: String) -> ErrorInfo {
+    describe(&message)
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_round_trip() {
+        let missing = std::io::Error::from(std::io::ErrorKind::NotFound);
+        let error = io_error("Cannot open file", "/x/a.jsonl", missing);
+        let info = describe(&error.reason);
+        assert_eq!((info.code.as_str(), info.path.as_deref()), ("FILE_NOT_FOUND", Some("/x/a.jsonl")));
+        assert!(info.message.starts_with("Cannot open file: "));
+
+        let error: Error = HubError::new(ErrorCode::ParseError, "Invalid JSON").path("/x/b.jsonl").line(7).into();
+        assert_eq!(error.reason, "PARSE_ERROR: Invalid JSON\npath: /x/b.jsonl\nline: 7");
+        assert_eq!(describe(&error.reason).line, Some(7));
+
+        let info = describe("something: else");
+        assert_eq!((info.code.as_str(), info.message.as_str()), ("UNKNOWN", "something: else"));
+        assert!(CODES.iter().all(|(code, name)| ErrorCode::parse(name) == Some(*code)));
+    }
+}
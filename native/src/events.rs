@@ -0,0 +1,144 @@
+// ============================================
+// SESSION EVENTS
+// Raw entries normalized into a stable event model (messages, tool
+// calls and results, summaries, compactions) for timelines and replays
+// ============================================
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use serde_json::Value;
+
+use history_core::{EventNormalizer, SessionEvent as CoreEvent};
+
+use crate::for_each_entry;
+
+/// One normalized event; only the fields of its kind are set
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SessionEvent {
+    /// "message_added", "tool_invoked", "tool_completed", "summary_written" or "compaction"
+    pub kind: String,
+    /// 0-based position in the session
+    pub sequence: u32,
+    /// uuid of the message, compaction boundary, or message carrying the tool block
+    pub uuid: Option<String>,
+    pub parent_uuid: Option<String>,
+    pub timestamp: Option<String>,
+    pub role: Option<String>,
+    /// Message text, tool output or compaction summary
+    pub text: Option<String>,
+    pub model: Option<String>,
+    pub is_sidechain: Option<bool>,
+    pub input_tokens: Option<i32>,
+    pub output_tokens: Option<i32>,
+    pub tool_use_id: Option<String>,
+    pub tool_name: Option<String>,
+    pub tool_input: Option<Value>,
+    pub is_error: Option<bool>,
+    pub summary: Option<String>,
+    pub leaf_uuid: Option<String>,
+}
+
+impl SessionEvent {
+    fn from_core(sequence: u32, event: CoreEvent) -> SessionEvent {
+        let base = SessionEvent { sequence, ..Default::default() };
+        match event {
+            CoreEvent::MessageAdded { uuid, parent_uuid, role, timestamp, text, model, is_sidechain, input_tokens, output_tokens } => {
+                SessionEvent {
+                    kind: "message_added".to_string(),
+                    uuid,
+                    parent_uuid,
+                    timestamp,
+                    role: Some(role),
+                    text: Some(text),
+                    model,
+                    is_sidechain: Some(is_sidechain),
+                    input_tokens,
+                    output_tokens,
+                    ..base
+                }
+            }
+            CoreEvent::ToolInvoked { tool_use_id, name, input, message_uuid, timestamp } => SessionEvent {
+                kind: "tool_invoked".to_string(),
+                uuid: message_uuid,
+                timestamp,
+                tool_use_id: Some(tool_use_id),
+                tool_name: Some(name),
+                tool_input: Some(input),
+                ..base
+            },
+            CoreEvent::ToolCompleted { tool_use_id, name, is_error, output, message_uuid, timestamp } => SessionEvent {
+                kind: "tool_completed".to_string(),
+                uuid: message_uuid,
+                timestamp,
+                text: Some(output),
+                tool_use_id: Some(tool_use_id),
+                tool_name: name,
+                is_error: Some(is_error),
+                ..base
+            },
+            CoreEvent::SummaryWritten { summary, leaf_uuid } => SessionEvent {
+                kind: "summary_written".to_string(),
+                summary: Some(summary),
+                leaf_uuid,
+                ..base
+            },
+            CoreEvent::Compaction { uuid, timestamp, summary } => SessionEvent {
+                kind: "compaction".to_string(),
+                uuid,
+                timestamp,
+                text: summary,
+                ..base
+            },
+        }
+    }
+}
+
+/// Normalized events of a session file, in log order
+pub(crate) fn session_events(file_path: &str) -> Result<Vec<CoreEvent>> {
+    let mut normalizer = EventNormalizer::new();
+    for_each_entry(file_path, |entry| normalizer.push(&entry))?;
+    Ok(normalizer.finish())
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Every message, tool call, tool result, summary and compaction of a session, in order
+#[napi(catch_unwind)]
+pub fn get_session_events(file_path: String) -> Result<Vec<SessionEvent>> {
+    Ok(session_events(&file_path)?
+        .into_iter()
+        .enumerate()
+        .map(|(i, event)| SessionEvent::from_core(i as u32, event))
+        .collect())
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_get_session_events() {
+        let tmp = TempDir::new("events");
+        let path = tmp.join("events.jsonl");
+        std::fs::write(&path, [
+            r#"{"type":"user","uuid":"u1","message":{"role":"user","content":"list files"}}"#,
+            r#"{"type":"assistant","uuid":"a1","parentUuid":"u1","message":{"role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Bash","input":{"command":"ls"}}],"usage":{"input_tokens":5,"output_tokens":2}}}"#,
+            r#"{"type":"user","uuid":"u2","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","is_error":true,"content":"ls: denied"}]}}"#,
+        ].join("\n")).unwrap();
+
+        let events = get_session_events(path.display().to_string()).unwrap();
+        let kinds: Vec<&str> = events.iter().map(|e| e.kind.as_str()).collect();
+        assert_eq!(kinds, vec!["message_added", "message_added", "tool_invoked", "message_added", "tool_completed"]);
+        assert_eq!(events[1].input_tokens, Some(5));
+        assert_eq!((events[2].tool_name.as_deref(), events[2].uuid.as_deref()), (Some("Bash"), Some("a1")));
+        assert_eq!((events[4].is_error, events[4].text.as_deref(), events[4].sequence), (Some(true), Some("ls: denied"), 4));
+    }
+}
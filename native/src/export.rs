@@ -0,0 +1,893 @@
+// ============================================
+// SESSION EXPORT
+// Transcripts for sharing and archiving: Obsidian notes with YAML
+// frontmatter and wiki-links between resumed sessions, plain text for
+// pasting into issue trackers and chat, and Bash replay scripts
+// ============================================
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use regex::{NoExpand, Regex, RegexBuilder};
+
+use crate::errors::{invalid_argument, io_error, not_found, parse_error, unsupported_format};
+use crate::images::AssetStore;
+use crate::safe_write::write_file;
+use crate::store::{normalize_tags, read_store, Bookmark, BookmarkFilter};
+use crate::title::{derive_title, is_substantive_prompt, truncate_words};
+use crate::watch::read_session_id;
+use crate::{
+    collect_session_files, entry_canonical_id, entry_to_message, for_each_entry, resolve_session_files, tool_result_text, ClaudeMessage,
+    ContentItem, RawLogEntry,
+};
+use history_core::{select_range, thinking_policy, ExportRange};
+
+/// Characters Obsidian does not allow in note names or links
+const FORBIDDEN_NAME_CHARS: &[char] = &['/', '\\', ':', '*', '?', '"', '<', '>', '|', '#', '^', '[', ']'];
+
+const TOOL_SUMMARY_CHARS: usize = 100;
+
+/// Tool input fields that say the most about a call, best first
+const TOOL_SUMMARY_FIELDS: &[&str] = &["command", "file_path", "path", "pattern", "url", "query", "description", "prompt"];
+
+/// Vault directory holding images, shared by every note
+const ASSETS_DIR: &str = "assets";
+
+/// Context messages around a highlight are cut to this many characters
+const CONTEXT_CHARS: usize = 240;
+
+/// Part of a session to export (uuids or canonical ids; ids win over indexes, bounds inclusive)
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    pub from_message_id: Option<String>,
+    pub to_message_id: Option<String>,
+    /// 0-based message index
+    pub from_index: Option<u32>,
+    pub to_index: Option<u32>,
+    /// One line per tool call in text transcripts (default true)
+    pub include_tool_calls: Option<bool>,
+    /// Text transcripts keep only the prompts and each turn's final answer,
+    /// without intermediate replies, thinking or tool calls (default false)
+    pub final_answers_only: Option<bool>,
+    /// Remove ANSI escape sequences and other terminal control characters,
+    /// e.g. colors in tool output (default false)
+    pub strip_control_chars: Option<bool>,
+    /// "lf" or "crlf" to normalize line endings (default: left as they are)
+    pub line_endings: Option<String>,
+    /// Replacements applied in order to the finished export (default none)
+    pub substitutions: Option<Vec<Substitution>>,
+}
+
+/// One find-and-replace, e.g. an internal hostname or project codename
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Substitution {
+    pub pattern: String,
+    pub replacement: String,
+    /// pattern is a regular expression and replacement may use $1 or ${name} (default false)
+    pub regex: Option<bool>,
+    /// Match regardless of case (default false)
+    pub ignore_case: Option<bool>,
+}
+
+fn compile_substitutions(substitutions: &[Substitution]) -> Result<Vec<(Regex, &Substitution)>> {
+    substitutions
+        .iter()
+        .map(|sub| {
+            if sub.pattern.is_empty() {
+                return Err(invalid_argument("Empty substitution pattern"));
+            }
+            let pattern = if sub.regex == Some(true) { sub.pattern.clone() } else { regex::escape(&sub.pattern) };
+            let regex = RegexBuilder::new(&pattern)
+                .case_insensitive(sub.ignore_case == Some(true))
+                .build()
+                .map_err(|e| parse_error(format!("Invalid substitution pattern {}: {}", sub.pattern, e)))?;
+            Ok((regex, sub))
+        })
+        .collect()
+}
+
+fn apply_substitutions(text: String, substitutions: &[Substitution]) -> Result<String> {
+    let mut text = text;
+    for (regex, sub) in compile_substitutions(substitutions)? {
+        text = if sub.regex == Some(true) {
+            regex.replace_all(&text, sub.replacement.as_str()).into_owned()
+        } else {
+            regex.replace_all(&text, NoExpand(&sub.replacement)).into_owned()
+        };
+    }
+    Ok(text)
+}
+
+impl ExportOptions {
+    fn range(&self) -> ExportRange {
+        ExportRange {
+            from_message_id: self.from_message_id.clone(),
+            to_message_id: self.to_message_id.clone(),
+            from_index: self.from_index.map(|i| i as usize),
+            to_index: self.to_index.map(|i| i as usize),
+        }
+    }
+
+    /// The finished export with the requested cleanup and substitutions applied
+    pub(crate) fn sanitize(&self, text: String) -> Result<String> {
+        let text = if self.strip_control_chars == Some(true) { strip_terminal_codes(&text) } else { text };
+        let text = apply_substitutions(text, self.substitutions.as_deref().unwrap_or_default())?;
+        match self.line_endings.as_deref() {
+            None => Ok(text),
+            Some("lf") => Ok(text.replace("\r\n", "\n").replace('\r', "\n")),
+            Some("crlf") => Ok(text.replace("\r\n", "\n").replace('\r', "\n").replace('\n', "\r\n")),
+            Some(other) => Err(unsupported_format(format!("Unknown line endings: {}", other))),
+        }
+    }
+}
+
+/// text without ANSI escape sequences and control characters other than
+/// newlines, carriage returns and tabs
+fn strip_terminal_codes(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => match chars.next() {
+                // CSI (colors, cursor moves): up to a final byte in @..~
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                // OSC (titles, hyperlinks): up to BEL or ESC \
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' {
+                            break;
+                        }
+                        if c == '\x1b' {
+                            chars.next();
+                            break;
+                        }
+                    }
+                }
+                // Any other escape is two characters long
+                _ => {}
+            },
+            '\n' | '\r' | '\t' => out.push(c),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// A note written by export_obsidian_vault
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct ObsidianNote {
+    pub session_id: String,
+    pub source_path: String,
+    pub note_path: String,
+    /// Note name without .md, as used in [[wiki-links]]
+    pub note_name: String,
+    /// Note name of the session this one resumed
+    pub resumed_from: Option<String>,
+    /// Files in the vault's assets/ directory the note embeds
+    pub assets: Vec<String>,
+}
+
+/// Everything a note needs, read from one session file
+struct SessionNote {
+    session_id: String,
+    title: Option<String>,
+    cwd: Option<String>,
+    /// Other session ids found in the file (copied history of a resumed session)
+    earlier_session_ids: Vec<String>,
+    /// leafUuids of summaries pointing outside this file
+    foreign_leaf_uuids: Vec<String>,
+    uuids: Vec<String>,
+    messages: Vec<ClaudeMessage>,
+}
+
+fn read_session_note(file_path: &str) -> Result<SessionNote> {
+    let mut entries: Vec<RawLogEntry> = Vec::new();
+    for_each_entry(file_path, |entry| entries.push(entry))?;
+
+    let file_stem = Path::new(file_path).file_stem().map(|s| s.to_string_lossy().to_string());
+    // The newest id is the session itself; older ids were carried over on resume
+    let session_id = entries
+        .iter()
+        .rev()
+        .find_map(|e| e.session_id.clone())
+        .or(file_stem)
+        .unwrap_or_else(|| "unknown".to_string());
+
+    let mut earlier_session_ids: Vec<String> = Vec::new();
+    for id in entries.iter().filter_map(|e| e.session_id.as_ref()) {
+        if *id != session_id && !earlier_session_ids.contains(id) {
+            earlier_session_ids.push(id.clone());
+        }
+    }
+
+    let uuids: Vec<String> = entries.iter().filter_map(|e| e.uuid.clone()).collect();
+    let foreign_leaf_uuids = entries
+        .iter()
+        .filter_map(|e| e.leaf_uuid.clone())
+        .filter(|leaf| !uuids.contains(leaf))
+        .collect();
+
+    Ok(SessionNote {
+        session_id,
+        title: derive_title(&entries),
+        cwd: entries.iter().find_map(|e| e.cwd.clone()),
+        earlier_session_ids,
+        foreign_leaf_uuids,
+        uuids,
+        messages: entries.into_iter().filter_map(entry_to_message).collect(),
+    })
+}
+
+/// Double-quoted YAML scalar
+fn yaml_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', " "))
+}
+
+/// Obsidian tags cannot contain spaces
+fn obsidian_tag(tag: &str) -> String {
+    tag.split_whitespace().collect::<Vec<&str>>().join("-")
+}
+
+/// "2024-01-31 Fix lifetime errors", safe as a file name and link target
+fn note_name(note: &SessionNote) -> String {
+    let date = note.messages.iter().find_map(|m| m.timestamp.get(..10)).filter(|d| d.as_bytes()[4] == b'-');
+    let title = note.title.clone().unwrap_or_else(|| note.session_id.clone());
+    let title: String = title.chars().filter(|c| !FORBIDDEN_NAME_CHARS.contains(c)).collect();
+    match date {
+        Some(date) => format!("{} {}", date, title.trim()),
+        None => title.trim().to_string(),
+    }
+}
+
+fn session_tags(session_id: &str) -> Vec<String> {
+    // A missing or unreadable store just means no tags
+    read_store(|store| {
+        store
+            .session_tags
+            .iter()
+            .find(|t| t.session_id == session_id)
+            .map(|t| t.tags.iter().chain(&t.auto_tags).cloned().collect())
+            .unwrap_or_default()
+    })
+    .unwrap_or_default()
+}
+
+fn render_note(note: &SessionNote, messages: &[ClaudeMessage], resumed_from: Option<&str>, assets: &HashMap<&str, Vec<String>>) -> String {
+    let mut models: Vec<&str> = Vec::new();
+    for model in messages.iter().filter_map(|m| m.model.as_deref()) {
+        if !models.contains(&model) {
+            models.push(model);
+        }
+    }
+    let input_tokens: i64 = messages.iter().filter_map(|m| m.input_tokens).map(i64::from).sum();
+    let output_tokens: i64 = messages.iter().filter_map(|m| m.output_tokens).map(i64::from).sum();
+    let tags = normalize_tags(session_tags(&note.session_id));
+
+    let mut out = String::from("---\n");
+    out.push_str(&format!("session_id: {}\n", yaml_string(&note.session_id)));
+    if let Some(first) = messages.first().filter(|m| m.timestamp != "unknown") {
+        out.push_str(&format!("date: {}\n", yaml_string(&first.timestamp)));
+    }
+    if let Some(cwd) = &note.cwd {
+        out.push_str(&format!("project: {}\n", yaml_string(cwd)));
+    }
+    if let Some(model) = models.first() {
+        out.push_str(&format!("model: {}\n", yaml_string(model)));
+    }
+    if models.len() > 1 {
+        let all: Vec<String> = models.iter().map(|m| yaml_string(m)).collect();
+        out.push_str(&format!("models: [{}]\n", all.join(", ")));
+    }
+    let tags: Vec<String> = tags.iter().map(|t| yaml_string(&obsidian_tag(t))).collect();
+    out.push_str(&format!("tags: [{}]\n", tags.join(", ")));
+    out.push_str(&format!("messages: {}\n", messages.len()));
+    out.push_str(&format!("input_tokens: {}\noutput_tokens: {}\n", input_tokens, output_tokens));
+    if let Some(previous) = resumed_from {
+        out.push_str(&format!("resumed_from: {}\n", yaml_string(&format!("[[{}]]", previous))));
+    }
+    out.push_str("---\n\n");
+
+    out.push_str(&format!("# {}\n\n", note.title.as_deref().unwrap_or(&note.session_id)));
+    if let Some(previous) = resumed_from {
+        out.push_str(&format!("> Resumed from [[{}]]\n\n", previous));
+    }
+
+    for message in messages {
+        let who = if message.role == "user" { "User" } else { "Assistant" };
+        out.push_str(&format!("## {} · {}\n\n{}\n\n", who, message.timestamp, message.content.trim()));
+        for asset in assets.get(message.message_id.as_str()).into_iter().flatten() {
+            out.push_str(&format!("![[{}/{}]]\n\n", ASSETS_DIR, asset));
+        }
+    }
+    out
+}
+
+/// Bookmarked messages with up to `context` messages before and after each,
+/// as a markdown digest; overlapping context is printed once
+fn render_highlights(note: &SessionNote, bookmarks: &[Bookmark], context: usize) -> String {
+    let messages = &note.messages;
+    let mut marked: Vec<(usize, &Bookmark)> = bookmarks
+        .iter()
+        .filter_map(|b| {
+            let index = messages.iter().position(|m| m.message_id == b.message_id || m.canonical_id == b.canonical_id)?;
+            Some((index, b))
+        })
+        .collect();
+    marked.sort_by_key(|(index, _)| *index);
+
+    let mut out = format!("# Highlights: {}\n\n", note.title.as_deref().unwrap_or(&note.session_id));
+    out.push_str(&format!("> {} highlights from session `{}`\n\n", marked.len(), note.session_id));
+    let who = |m: &ClaudeMessage| if m.role == "user" { "User" } else { "Assistant" };
+    let mut printed_until = 0;
+
+    for (n, (index, bookmark)) in marked.iter().enumerate() {
+        let message = &messages[*index];
+        let heading = bookmark.note.clone().unwrap_or_else(|| truncate_words(&message.content, 60));
+        out.push_str(&format!("## {}. {}\n\n", n + 1, heading));
+        if !bookmark.tags.is_empty() {
+            let tags: Vec<String> = bookmark.tags.iter().map(|t| format!("#{}", obsidian_tag(t))).collect();
+            out.push_str(&format!("{}\n\n", tags.join(" ")));
+        }
+
+        let start = index.saturating_sub(context).max(printed_until);
+        let end = (index + context + 1).min(messages.len());
+        let next_highlight = marked.get(n + 1).map_or(usize::MAX, |(next, _)| *next);
+        for (i, m) in messages.iter().enumerate().take(end.min(next_highlight)).skip(start) {
+            if i == *index {
+                out.push_str(&format!("**{} · {}**\n\n{}\n\n", who(m), m.timestamp, m.content.trim()));
+            } else {
+                out.push_str(&format!("> *{}:* {}\n\n", who(m), truncate_words(&m.content, CONTEXT_CHARS)));
+            }
+        }
+        printed_until = end.min(next_highlight);
+    }
+    out
+}
+
+/// The session file for session_id: file_path_or_root itself, or the file
+/// under it named after the session or logging its sessionId
+fn find_session_file(session_id: &str, file_path_or_root: &str) -> Result<String> {
+    let files = resolve_session_files(file_path_or_root)?;
+    let named = files.iter().find(|p| p.file_stem().is_some_and(|s| s == session_id));
+    named
+        .or_else(|| files.iter().find(|p| read_session_id(&p.display().to_string()).as_deref() == Some(session_id)))
+        .map(|p| p.display().to_string())
+        .ok_or_else(|| not_found(format!("Session not found: {}", session_id)))
+}
+
+/// "Bash: npm test" - the tool name and its most telling input
+pub(crate) fn tool_call_summary(name: &str, input: &serde_json::Value) -> String {
+    let detail = TOOL_SUMMARY_FIELDS
+        .iter()
+        .find_map(|field| input.get(*field).and_then(|v| v.as_str()))
+        .map(|value| truncate_words(value, TOOL_SUMMARY_CHARS));
+    match detail {
+        Some(detail) if !detail.is_empty() => format!("{}: {}", name, detail),
+        _ => name.to_string(),
+    }
+}
+
+/// Lines of one message in a text transcript (empty for tool-result-only turns)
+fn text_lines(content: &[ContentItem], include_tool_calls: bool) -> Vec<String> {
+    let mut lines = Vec::new();
+    for item in content {
+        match item {
+            ContentItem::Text { text } if !text.trim().is_empty() => lines.push(text.trim().to_string()),
+            ContentItem::Thinking { thinking, .. } => lines.extend(thinking_policy().render(thinking.trim())),
+            ContentItem::ToolUse { name, input, .. } if include_tool_calls => {
+                lines.push(format!("[{}]", tool_call_summary(name, input)));
+            }
+            ContentItem::ToolResult { content, is_error: Some(true), .. } if include_tool_calls => {
+                let first_line = tool_result_text(content).lines().next().unwrap_or("").to_string();
+                lines.push(format!("[tool error: {}]", truncate_words(&first_line, TOOL_SUMMARY_CHARS)));
+            }
+            _ => {}
+        }
+    }
+    lines
+}
+
+fn render_text(entries: &[RawLogEntry], include_tool_calls: bool) -> String {
+    let mut out = String::new();
+    let mut last_role: Option<&str> = None;
+
+    for entry in entries {
+        if entry.is_meta == Some(true) {
+            continue;
+        }
+        let Some(message) = &entry.message else { continue };
+        let lines = text_lines(&message.content, include_tool_calls);
+        if lines.is_empty() {
+            continue;
+        }
+
+        // Claude Code splits one reply into several entries; label each turn once
+        let role = message.role.as_str();
+        if last_role != Some(role) {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            out.push_str(if role == "user" { "User: " } else { "Assistant: " });
+            last_role = Some(role);
+        }
+        out.push_str(&lines.join("\n"));
+        out.push('\n');
+    }
+    out
+}
+
+/// The prompts and, for each turn, the assistant text written after its last
+/// tool call
+fn final_answers(entries: &[RawLogEntry]) -> Vec<RawLogEntry> {
+    let mut kept = Vec::new();
+    let mut answer: Vec<RawLogEntry> = Vec::new();
+
+    for entry in entries.iter().filter(|e| e.is_sidechain != Some(true)) {
+        if is_substantive_prompt(entry) {
+            kept.append(&mut answer);
+            kept.push(entry.clone());
+            continue;
+        }
+        let Some(message) = &entry.message else { continue };
+        if entry.entry_type != "assistant" {
+            continue;
+        }
+        if message.content.iter().any(|item| matches!(item, ContentItem::ToolUse { .. })) {
+            answer.clear();
+        } else if message.content.iter().any(|item| matches!(item, ContentItem::Text { text } if !text.trim().is_empty())) {
+            // Thinking is never part of the answer, whatever the policy
+            let mut text_only = entry.clone();
+            if let Some(message) = &mut text_only.message {
+                message.content.retain(|item| matches!(item, ContentItem::Text { .. }));
+            }
+            answer.push(text_only);
+        }
+    }
+    kept.append(&mut answer);
+    kept
+}
+
+/// Single-quoted for the shell
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+fn render_bash_script(file_path: &str, entries: &[RawLogEntry]) -> String {
+    // Commands whose result came back as an error
+    let failed: HashSet<&str> = entries
+        .iter()
+        .filter_map(|e| e.message.as_ref())
+        .flat_map(|m| &m.content)
+        .filter_map(|item| match item {
+            ContentItem::ToolResult { tool_use_id, is_error: Some(true), .. } => Some(tool_use_id.as_str()),
+            _ => None,
+        })
+        .collect();
+
+    let session_id = entries.iter().find_map(|e| e.session_id.as_deref()).unwrap_or("unknown");
+    let mut out = String::from("#!/usr/bin/env bash\n");
+    out.push_str(&format!("# Bash commands of Claude Code session {}\n", session_id));
+    out.push_str(&format!("# Source: {}\n", file_path));
+    out.push_str("# Commands that failed in the session are commented out. Review before running.\n");
+
+    let mut cwd: Option<&str> = None;
+    for entry in entries {
+        let Some(message) = &entry.message else { continue };
+        for item in &message.content {
+            let ContentItem::ToolUse { id, name, input } = item else { continue };
+            if name != "Bash" {
+                continue;
+            }
+            let Some(command) = input.get("command").and_then(|c| c.as_str()) else { continue };
+
+            if let Some(dir) = entry.cwd.as_deref().filter(|dir| cwd != Some(*dir)) {
+                out.push_str(&format!("\ncd {}\n", shell_quote(dir)));
+                cwd = Some(dir);
+            }
+
+            out.push('\n');
+            let mut header = entry.timestamp.clone().unwrap_or_default();
+            if let Some(description) = input.get("description").and_then(|d| d.as_str()) {
+                header = if header.is_empty() { description.to_string() } else { format!("{} - {}", header, description) };
+            }
+            if !header.is_empty() {
+                out.push_str(&format!("# {}\n", header.replace('\n', " ")));
+            }
+            if input.get("run_in_background").and_then(|b| b.as_bool()) == Some(true) {
+                out.push_str("# (ran in the background)\n");
+            }
+
+            if failed.contains(id.as_str()) {
+                out.push_str("# FAILED in the session:\n");
+                for line in command.lines() {
+                    out.push_str(&format!("# {}\n", line));
+                }
+            } else {
+                out.push_str(command.trim_end());
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
+
+/// Range bounds may be canonical ids; swap those for the message ids select_range matches
+fn resolve_canonical_ids<'a, T>(range: &mut ExportRange, items: &'a [T], ids: impl Fn(&'a T) -> (&'a str, String)) {
+    for bound in [&mut range.from_message_id, &mut range.to_message_id].into_iter().flatten() {
+        if let Some((message_id, _)) = items.iter().map(&ids).find(|(_, canonical)| canonical == bound) {
+            *bound = message_id.to_string();
+        }
+    }
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// One session as an Obsidian note (YAML frontmatter + transcript)
+#[napi(catch_unwind)]
+pub fn export_session_obsidian(file_path: String, options: Option<ExportOptions>) -> Result<String> {
+    let note = read_session_note(&file_path)?;
+    let options = options.unwrap_or_default();
+    let mut range = options.range();
+    resolve_canonical_ids(&mut range, &note.messages, |m| (m.message_id.as_str(), m.canonical_id.clone()));
+    let messages = select_range(&note.messages, |m| &m.message_id, &range).map_err(invalid_argument)?;
+
+    // Without the rest of the vault the link can only name the session id
+    let resumed_from = note.earlier_session_ids.last().cloned();
+    options.sanitize(render_note(&note, messages, resumed_from.as_deref(), &HashMap::new()))
+}
+
+/// Same entries parse_claude_session turns into messages, so indexes line up
+fn is_message_entry(entry: &RawLogEntry) -> bool {
+    (entry.entry_type == "user" || entry.entry_type == "assistant") && entry.message.is_some()
+}
+
+/// The entries from the first to the last message the options' range selects,
+/// with whatever lies between them (system entries, snapshots)
+pub(crate) fn selected_entries<'a>(entries: &'a [RawLogEntry], options: &ExportOptions) -> Result<&'a [RawLogEntry]> {
+    let mut range = options.range();
+    if range == ExportRange::default() {
+        return Ok(entries);
+    }
+    // Position and uuid of every message
+    let messages: Vec<(usize, &str)> = (0..entries.len())
+        .filter(|&i| is_message_entry(&entries[i]))
+        .map(|i| (i, entries[i].uuid.as_deref().unwrap_or("unknown")))
+        .collect();
+    resolve_canonical_ids(&mut range, &messages, |&(i, uuid)| (uuid, entry_canonical_id(&entries[i])));
+    let selected = select_range(&messages, |(_, uuid)| uuid, &range).map_err(invalid_argument)?;
+    match (selected.first(), selected.last()) {
+        (Some(&(first, _)), Some(&(last, _))) => Ok(&entries[first..=last]),
+        _ => Ok(&[]),
+    }
+}
+
+/// Minimal "User:" / "Assistant:" transcript with one line per tool call
+#[napi(catch_unwind)]
+pub fn export_session_text(file_path: String, options: Option<ExportOptions>) -> Result<String> {
+    let options = options.unwrap_or_default();
+    let mut entries: Vec<RawLogEntry> = Vec::new();
+    for_each_entry(&file_path, |entry| {
+        if is_message_entry(&entry) {
+            entries.push(entry);
+        }
+    })?;
+
+    let selected = selected_entries(&entries, &options)?;
+    let text = if options.final_answers_only == Some(true) {
+        render_text(&final_answers(selected), false)
+    } else {
+        render_text(selected, options.include_tool_calls.unwrap_or(true))
+    };
+    options.sanitize(text)
+}
+
+/// Text transcript with substitutions applied after any in options, for
+/// scrubbing hostnames or codenames before sharing outside the team
+#[napi(catch_unwind)]
+pub fn export_with_substitutions(file_path: String, substitutions: Vec<Substitution>, options: Option<ExportOptions>) -> Result<String> {
+    let mut options = options.unwrap_or_default();
+    options.substitutions.get_or_insert_with(Vec::new).extend(substitutions);
+    export_session_text(file_path, Some(options))
+}
+
+/// Options for export_highlights
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct HighlightOptions {
+    /// Messages shown before and after each highlight (default 1)
+    pub context_messages: Option<u32>,
+    /// Cleanup and substitutions, as for export_session_text; the range is ignored
+    pub export: Option<ExportOptions>,
+}
+
+/// The bookmarked messages of a session with a little context around each,
+/// as a markdown digest. file_path_or_root is the session file or a directory
+/// to look for it in
+#[napi(catch_unwind)]
+pub fn export_highlights(session_id: String, file_path_or_root: String, options: Option<HighlightOptions>) -> Result<String> {
+    let options = options.unwrap_or_default();
+    let context = options.context_messages.unwrap_or(1) as usize;
+    let filter = BookmarkFilter { session_id: Some(session_id.clone()), ..Default::default() };
+    let bookmarks = read_store(|store| store.list_bookmarks(&filter))?;
+    let note = read_session_note(&find_session_file(&session_id, &file_path_or_root)?)?;
+    options.export.unwrap_or_default().sanitize(render_highlights(&note, &bookmarks, context))
+}
+
+/// Every Bash tool command of a session as an annotated shell script; options
+/// pick the range and the cleanup and substitutions, as for export_session_text
+#[napi(catch_unwind)]
+pub fn export_bash_script(file_path: String, options: Option<ExportOptions>) -> Result<String> {
+    let options = options.unwrap_or_default();
+    let mut entries: Vec<RawLogEntry> = Vec::new();
+    for_each_entry(&file_path, |entry| entries.push(entry))?;
+    options.sanitize(render_bash_script(&file_path, selected_entries(&entries, &options)?))
+}
+
+/// Write one note per session under root_dir into output_dir, linking resumed
+/// sessions; images go once into output_dir/assets/, named by hash. Notes
+/// already in the vault are only replaced with overwrite. options clean up
+/// the notes as for export_session_text; the range is ignored
+#[napi(catch_unwind)]
+pub fn export_obsidian_vault(root_dir: String, output_dir: String, overwrite: Option<bool>, options: Option<ExportOptions>) -> Result<Vec<ObsidianNote>> {
+    let options = options.unwrap_or_default();
+    let mut notes: Vec<(String, SessionNote)> = Vec::new();
+    for path in collect_session_files(&root_dir)? {
+        let file_path = path.display().to_string();
+        if let Ok(note) = read_session_note(&file_path) {
+            notes.push((file_path, note));
+        }
+    }
+
+    // Unique note names, then the lookups needed to resolve links
+    let mut names: Vec<String> = Vec::new();
+    for (_, note) in &notes {
+        let mut name = note_name(note);
+        if names.contains(&name) {
+            name = format!("{} ({})", name, note.session_id.chars().take(8).collect::<String>());
+        }
+        names.push(name);
+    }
+    let mut by_session: HashMap<&str, usize> = HashMap::new();
+    let mut by_uuid: HashMap<&str, usize> = HashMap::new();
+    for (i, (_, note)) in notes.iter().enumerate() {
+        by_session.entry(note.session_id.as_str()).or_insert(i);
+        for uuid in &note.uuids {
+            by_uuid.entry(uuid.as_str()).or_insert(i);
+        }
+    }
+
+    let output = Path::new(&output_dir);
+    std::fs::create_dir_all(output).map_err(|e| io_error("Cannot create directory", output, e))?;
+
+    let store = AssetStore::new(output.join(ASSETS_DIR));
+    let mut written = Vec::new();
+    for (i, (source_path, note)) in notes.iter().enumerate() {
+        let mut assets: HashMap<&str, Vec<String>> = HashMap::new();
+        // Cheap pre-check; images can also sit inside tool results
+        for message in note.messages.iter().filter(|m| m.raw_content.contains("\"image\"")) {
+            let names = store.store_images(&message.raw_content)?;
+            if !names.is_empty() {
+                assets.insert(&message.message_id, names);
+            }
+        }
+
+        let previous = note
+            .earlier_session_ids
+            .iter()
+            .rev()
+            .find_map(|id| by_session.get(id.as_str()))
+            .or_else(|| note.foreign_leaf_uuids.iter().find_map(|uuid| by_uuid.get(uuid.as_str())))
+            .filter(|&&j| j != i)
+            .map(|&j| names[j].clone());
+
+        let note_path = output.join(format!("{}.md", names[i]));
+        let text = options.sanitize(render_note(note, &note.messages, previous.as_deref(), &assets))?;
+        write_file(&note_path, text.as_bytes(), overwrite.unwrap_or(false))?;
+
+        written.push(ObsidianNote {
+            session_id: note.session_id.clone(),
+            source_path: source_path.clone(),
+            note_path: note_path.display().to_string(),
+            note_name: names[i].clone(),
+            resumed_from: previous,
+            assets: {
+                let mut names: Vec<String> = assets.into_values().flatten().collect();
+                names.sort();
+                names.dedup();
+                names
+            },
+        });
+    }
+    Ok(written)
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{StoreDir, TempDir};
+
+    #[test]
+    fn test_obsidian_vault_links_resumed_sessions() {
+        let root = TempDir::new("obsidian-test");
+        let vault = root.join("vault");
+        std::fs::create_dir_all(root.join("proj")).unwrap();
+        std::fs::write(
+            root.join("proj/a.jsonl"),
+            r#"{"type":"user","uuid":"1","sessionId":"a","cwd":"/work/app","timestamp":"2024-01-01T10:00:00Z","message":{"role":"user","content":"Fix the \"login\" bug please"}}
+{"type":"assistant","uuid":"2","sessionId":"a","timestamp":"2024-01-01T10:00:05Z","message":{"role":"assistant","model":"claude-sonnet","content":"Done","usage":{"input_tokens":10,"output_tokens":3}}}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("proj/b.jsonl"),
+            r#"{"type":"user","uuid":"1","sessionId":"a","timestamp":"2024-01-01T10:00:00Z","message":{"role":"user","content":"Fix the \"login\" bug please"}}
+{"type":"user","uuid":"3","sessionId":"b","timestamp":"2024-01-02T09:00:00Z","message":{"role":"user","content":"Now add tests"}}
+{"type":"user","uuid":"4","sessionId":"b","timestamp":"2024-01-02T09:01:00Z","message":{"role":"user","content":[{"type":"text","text":"Like this"},{"type":"image","source":{"type":"base64","media_type":"image/png","data":"aGVsbG8="}}]}}
+{"type":"user","uuid":"5","sessionId":"b","timestamp":"2024-01-02T09:02:00Z","message":{"role":"user","content":[{"type":"image","source":{"type":"base64","media_type":"image/png","data":"aGVsbG8="}}]}}"#,
+        )
+        .unwrap();
+
+        let notes = export_obsidian_vault(root.to_string_lossy().to_string(), vault.to_string_lossy().to_string(), None, None).unwrap();
+        assert_eq!(notes.len(), 2);
+        assert_eq!(notes[0].note_name, "2024-01-01 Fix the login bug please");
+        assert!(notes[1].note_name.ends_with("(b)"));
+        assert_eq!(notes[1].resumed_from.as_deref(), Some(notes[0].note_name.as_str()));
+
+        let first = std::fs::read_to_string(&notes[0].note_path).unwrap();
+        assert!(first.starts_with("---\nsession_id: \"a\"\ndate: \"2024-01-01T10:00:00Z\"\nproject: \"/work/app\"\nmodel: \"claude-sonnet\"\n"));
+        assert!(first.contains("input_tokens: 10\noutput_tokens: 3\n---\n\n# Fix the \"login\" bug please"));
+        let second = std::fs::read_to_string(&notes[1].note_path).unwrap();
+        assert!(second.contains("> Resumed from [[2024-01-01 Fix the login bug please]]"));
+
+        // The same image twice is stored once and embedded twice
+        assert!(notes[0].assets.is_empty());
+        assert_eq!(notes[1].assets.len(), 1);
+        let asset = &notes[1].assets[0];
+        assert!(asset.ends_with(".png"));
+        assert_eq!(std::fs::read(vault.join("assets").join(asset)).unwrap(), b"hello");
+        assert_eq!(second.matches(&format!("![[assets/{}]]", asset)).count(), 2);
+
+        // A second export does not clobber the notes unless asked to
+        let again = export_obsidian_vault(root.to_string_lossy().to_string(), vault.to_string_lossy().to_string(), None, None);
+        assert!(again.unwrap_err().reason.starts_with("FILE_EXISTS: "));
+        let crlf = ExportOptions { line_endings: Some("crlf".into()), ..Default::default() };
+        let rewritten = export_obsidian_vault(root.to_string_lossy().to_string(), vault.to_string_lossy().to_string(), Some(true), Some(crlf)).unwrap();
+        assert!(std::fs::read_to_string(&rewritten[0].note_path).unwrap().starts_with("---\r\nsession_id: \"a\"\r\n"));
+    }
+
+    #[test]
+    fn test_text_transcript() {
+        let lines = [
+            r#"{"type":"user","uuid":"1","message":{"role":"user","content":"Run the tests"}}"#,
+            r#"{"type":"assistant","uuid":"2","message":{"role":"assistant","content":[{"type":"text","text":"Running them."},{"type":"tool_use","id":"t1","name":"Bash","input":{"command":"cargo   test"}}]}}"#,
+            r#"{"type":"user","uuid":"3","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"error: 2 failed\nmore","is_error":true}]}}"#,
+            r#"{"type":"assistant","uuid":"4","message":{"role":"assistant","content":[{"type":"tool_use","id":"t2","name":"TodoWrite","input":{}}]}}"#,
+            r#"{"type":"assistant","uuid":"5","message":{"role":"assistant","content":"Two tests fail."}}"#,
+        ];
+        let entries: Vec<RawLogEntry> = lines.iter().map(|l| crate::parse_jsonl_line(l).unwrap()).collect();
+
+        assert_eq!(
+            render_text(&entries, true),
+            "User: Run the tests\n\nAssistant: Running them.\n[Bash: cargo test]\n\nUser: [tool error: error: 2 failed]\n\nAssistant: [TodoWrite]\nTwo tests fail.\n"
+        );
+        assert_eq!(render_text(&entries, false), "User: Run the tests\n\nAssistant: Running them.\nTwo tests fail.\n");
+        assert_eq!(render_text(&final_answers(&entries), false), "User: Run the tests\n\nAssistant: Two tests fail.\n");
+
+        // Canonical ids select the same range as uuids
+        let mut range = ExportRange { from_message_id: Some(entry_canonical_id(&entries[4])), ..Default::default() };
+        resolve_canonical_ids(&mut range, &entries, |e| (e.uuid.as_deref().unwrap_or("unknown"), entry_canonical_id(e)));
+        assert_eq!(range.from_message_id.as_deref(), Some("5"));
+    }
+
+    #[test]
+    fn test_sanitize_export() {
+        let raw = "\x1b[31merror\x1b[0m: \x1b]8;;http://x\x07link\x1b]8;;\x07 done\x08\r\nnext\rlast\n".to_string();
+        let strip = ExportOptions { strip_control_chars: Some(true), ..Default::default() };
+        assert_eq!(strip.sanitize(raw.clone()).unwrap(), "error: link done\r\nnext\rlast\n");
+
+        let lf = ExportOptions { line_endings: Some("lf".into()), ..strip.clone() };
+        assert_eq!(lf.sanitize(raw.clone()).unwrap(), "error: link done\nnext\nlast\n");
+        let crlf = ExportOptions { line_endings: Some("crlf".into()), ..Default::default() };
+        assert_eq!(crlf.sanitize("a\r\nb\n".into()).unwrap(), "a\r\nb\r\n");
+        assert!(ExportOptions { line_endings: Some("cr".into()), ..Default::default() }.sanitize(raw).is_err());
+    }
+
+    #[test]
+    fn test_substitutions() {
+        let sub = |pattern: &str, replacement: &str, regex: bool| Substitution {
+            pattern: pattern.into(),
+            replacement: replacement.into(),
+            regex: Some(regex),
+            ignore_case: Some(true),
+        };
+        let options = ExportOptions {
+            substitutions: Some(vec![sub("Project Falcon", "the project", false), sub(r"(\w+)\.corp\.example\.com", "$1.internal", true), sub("$1", "$2", false)]),
+            ..Default::default()
+        };
+        let text = "project falcon runs on build7.corp.example.com for $1".to_string();
+        assert_eq!(options.sanitize(text).unwrap(), "the project runs on build7.internal for $2");
+
+        let invalid = ExportOptions { substitutions: Some(vec![sub("(", "", true)]), ..Default::default() };
+        assert!(invalid.sanitize("x".into()).is_err());
+    }
+
+    #[test]
+    fn test_highlights_digest() {
+        let tmp = TempDir::new("highlights");
+        let path = tmp.join("highlights.jsonl");
+        let lines: Vec<String> = (1..=6)
+            .map(|i| {
+                let role = if i % 2 == 1 { "user" } else { "assistant" };
+                format!(r#"{{"type":"{}","uuid":"m{}","sessionId":"s1","timestamp":"t{}","message":{{"role":"{}","content":"message {}"}}}}"#, role, i, i, role, i)
+            })
+            .collect();
+        std::fs::write(&path, lines.join("\n")).unwrap();
+        let note = read_session_note(&path.display().to_string()).unwrap();
+        let bookmark = |id: &str, note: Option<&str>| Bookmark {
+            session_id: "s1".into(),
+            message_id: id.into(),
+            canonical_id: String::new(),
+            note: note.map(str::to_string),
+            tags: vec!["key idea".into()],
+            created_at: 0,
+            updated_at: 0,
+        };
+
+        let digest = render_highlights(&note, &[bookmark("m5", None), bookmark("m2", Some("The fix"))], 1);
+        assert!(digest.starts_with("# Highlights: message 1\n\n> 2 highlights from session `s1`\n\n## 1."));
+        assert!(digest.contains("## 1. The fix\n\n#key-idea\n\n> *User:* message 1\n\n**Assistant · t2**\n\nmessage 2\n\n> *User:* message 3\n\n## 2."));
+        // m4 is context of the second highlight only, m3 is not printed twice
+        assert_eq!(digest.matches("message 3").count(), 1);
+        assert!(digest.ends_with("> *Assistant:* message 4\n\n**User · t5**\n\nmessage 5\n\n> *Assistant:* message 6\n\n"));
+
+        assert_eq!(find_session_file("s1", &path.display().to_string()).unwrap(), path.display().to_string());
+
+        let _store = StoreDir::set(&tmp.join("store"));
+        crate::store::add_bookmark("s1".into(), "m2".into(), Some("The \x1b[1mfix\x1b[0m".into()), None, None).unwrap();
+        let export = ExportOptions { strip_control_chars: Some(true), line_endings: Some("crlf".into()), ..Default::default() };
+        let options = HighlightOptions { context_messages: Some(0), export: Some(export) };
+        let digest = export_highlights("s1".into(), path.display().to_string(), Some(options)).unwrap();
+        assert!(digest.contains("## 1. The fix\r\n") && !digest.contains('\x1b'));
+    }
+
+    #[test]
+    fn test_bash_script() {
+        let lines = [
+            r#"{"type":"assistant","sessionId":"s1","cwd":"/work/it's","timestamp":"2024-01-01T10:00:00Z","message":{"role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Bash","input":{"command":"cargo build","description":"Build"}},{"type":"tool_use","id":"t2","name":"Read","input":{"file_path":"x"}}]}}"#,
+            r#"{"type":"assistant","sessionId":"s1","cwd":"/work/it's","timestamp":"2024-01-01T10:01:00Z","message":{"role":"assistant","content":[{"type":"tool_use","id":"t3","name":"Bash","input":{"command":"cargo test"}}]}}"#,
+            r#"{"type":"user","sessionId":"s1","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t3","content":"1 failed","is_error":true}]}}"#,
+        ];
+        let entries: Vec<RawLogEntry> = lines.iter().map(|l| crate::parse_jsonl_line(l).unwrap()).collect();
+
+        let script = render_bash_script("s1.jsonl", &entries);
+        assert!(script.starts_with("#!/usr/bin/env bash\n# Bash commands of Claude Code session s1\n"));
+        assert!(script.contains("\ncd '/work/it'\\''s'\n\n# 2024-01-01T10:00:00Z - Build\ncargo build\n"));
+        assert!(script.ends_with("# 2024-01-01T10:01:00Z\n# FAILED in the session:\n# cargo test\n"));
+        assert_eq!(script.matches("cd ").count(), 1);
+
+        // Export options pick the range and scrub the script
+        let substitution = Substitution { pattern: "cargo".into(), replacement: "make".into(), regex: None, ignore_case: None };
+        let options = ExportOptions { from_index: Some(1), substitutions: Some(vec![substitution]), ..Default::default() };
+        let selected = selected_entries(&entries, &options).unwrap();
+        assert_eq!(selected.len(), 2);
+        let script = options.sanitize(render_bash_script("s1.jsonl", selected)).unwrap();
+        assert!(script.ends_with("# make test\n") && !script.contains("cargo"));
+    }
+}
@@ -0,0 +1,358 @@
+// ============================================
+// ERROR AND FAILURE ANALYSIS
+// Tool errors, API error entries and assistant retries grouped by
+// tool and normalized error signature, plus a per-session health score
+// ============================================
+
+use std::collections::HashMap;
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use schemars::JsonSchema;
+
+use crate::{extract_text_content, for_each_entry, resolve_session_files, tool_result_text, ContentItem, RawLogEntry};
+
+const MAX_SIGNATURE_CHARS: usize = 120;
+const MAX_EXAMPLES: usize = 5;
+
+/// Phrases the assistant uses when it retries after a failure
+const RETRY_PHRASES: &[&str] = &[
+    "i apologize",
+    "sorry about that",
+    "my apologies",
+    "let me try again",
+    "let me try a different approach",
+    "let me retry",
+    "that didn't work",
+];
+
+const INTERRUPT_MARKER: &str = "[Request interrupted";
+
+/// Sessions scoring below this are flagged for review
+const TROUBLED_THRESHOLD: f64 = 60.0;
+
+/// Where a failure was seen
+#[napi(object)]
+#[derive(Debug, Clone, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+pub struct ErrorOccurrence {
+    pub session_id: String,
+    pub file_path: String,
+    pub message_id: Option<String>,
+    pub timestamp: Option<String>,
+}
+
+/// Failures sharing the same kind, tool and signature
+#[napi(object)]
+#[derive(Debug, Clone, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+pub struct ErrorGroup {
+    /// "tool_error", "api_error" or "retry"
+    pub kind: String,
+    pub tool: Option<String>,
+    pub signature: String,
+    pub count: u32,
+    /// First few occurrences
+    pub examples: Vec<ErrorOccurrence>,
+}
+
+/// Error rate of one tool
+#[napi(object)]
+#[derive(Debug, Clone, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+pub struct ToolErrorStats {
+    pub tool: String,
+    pub calls: u32,
+    pub errors: u32,
+    pub error_rate: f64,
+}
+
+/// Aggregated failure report
+#[napi(object)]
+#[derive(Debug, Clone, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+pub struct ErrorReport {
+    pub files_scanned: u32,
+    pub total_errors: u32,
+    pub groups: Vec<ErrorGroup>,
+    pub tools: Vec<ToolErrorStats>,
+}
+
+/// Normalize an error message so recurring failures group together
+pub(crate) fn error_signature(text: &str) -> String {
+    let line = text.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or("");
+
+    let normalized = line
+        .split_whitespace()
+        .map(|word| {
+            if word.starts_with('/') || word.starts_with("~/") || word.contains(":\\") {
+                "<path>".to_string()
+            } else {
+                // Collapse numbers (line numbers, ids, ports) into N
+                let mut out = String::with_capacity(word.len());
+                let mut in_digits = false;
+                for c in word.chars() {
+                    if c.is_ascii_digit() {
+                        if !in_digits {
+                            out.push('N');
+                        }
+                        in_digits = true;
+                    } else {
+                        out.push(c);
+                        in_digits = false;
+                    }
+                }
+                out
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ");
+
+    normalized.chars().take(MAX_SIGNATURE_CHARS).collect()
+}
+
+/// Assistant text that apologises for / retries a failed attempt
+pub(crate) fn is_retry_text(text: &str) -> bool {
+    let lower = text.to_lowercase();
+    RETRY_PHRASES.iter().any(|p| lower.contains(p))
+}
+
+/// User entry recording that the user interrupted a turn
+pub(crate) fn is_interruption(entry: &RawLogEntry) -> bool {
+    entry.entry_type == "user"
+        && entry.message.as_ref().is_some_and(|m| {
+            m.content.iter().any(|item| match item {
+                ContentItem::Text { text } => text.starts_with(INTERRUPT_MARKER),
+                ContentItem::ToolResult { content, .. } => tool_result_text(content).starts_with(INTERRUPT_MARKER),
+                _ => false,
+            })
+        })
+}
+
+// ============================================
+// SESSION HEALTH
+// ============================================
+
+/// Heuristic health of a session, 100 = smooth run
+#[napi(object)]
+#[derive(Debug, Clone, Default, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+pub struct SessionHealth {
+    pub score: f64,
+    pub is_troubled: bool,
+    pub tool_calls: u32,
+    pub tool_errors: u32,
+    pub interruptions: u32,
+    pub retries: u32,
+    pub api_errors: u32,
+    pub compactions: u32,
+}
+
+/// Accumulates health signals entry by entry (used by get_session_summary)
+#[derive(Debug, Default)]
+pub(crate) struct HealthTracker {
+    health: SessionHealth,
+    compact_boundaries: u32,
+    compact_summaries: u32,
+}
+
+impl HealthTracker {
+    pub fn observe(&mut self, entry: &RawLogEntry) {
+        if entry.subtype.as_deref() == Some("compact_boundary") {
+            self.compact_boundaries += 1;
+        }
+        if entry.is_compact_summary == Some(true) {
+            self.compact_summaries += 1;
+        }
+        if entry.is_api_error_message == Some(true) {
+            self.health.api_errors += 1;
+        }
+        if is_interruption(entry) {
+            self.health.interruptions += 1;
+        }
+
+        let Some(message) = &entry.message else { return };
+        for item in &message.content {
+            match item {
+                ContentItem::ToolUse { .. } => self.health.tool_calls += 1,
+                ContentItem::ToolResult { is_error: Some(true), .. } => self.health.tool_errors += 1,
+                ContentItem::Text { text } if entry.entry_type == "assistant" && is_retry_text(text) => {
+                    self.health.retries += 1
+                }
+                _ => {}
+            }
+        }
+    }
+
+    pub fn finish(self) -> SessionHealth {
+        let mut health = self.health;
+        // Newer files write both a boundary and a summary per compaction
+        health.compactions = self.compact_boundaries.max(self.compact_summaries);
+
+        let error_ratio = health.tool_errors as f64 / health.tool_calls.max(1) as f64;
+        let penalty = (error_ratio * 50.0).min(50.0)
+            + (health.interruptions as f64 * 5.0).min(20.0)
+            + (health.retries as f64 * 3.0).min(15.0)
+            + (health.api_errors as f64 * 5.0).min(15.0)
+            + (health.compactions as f64 * 5.0).min(15.0);
+
+        health.score = (100.0 - penalty).max(0.0);
+        health.is_troubled = health.score < TROUBLED_THRESHOLD;
+        health
+    }
+}
+
+#[derive(Default)]
+struct ReportBuilder {
+    groups: Vec<ErrorGroup>,
+    index: HashMap<(String, Option<String>, String), usize>,
+    tools: HashMap<String, (u32, u32)>,
+    total_errors: u32,
+}
+
+impl ReportBuilder {
+    fn record(&mut self, kind: &str, tool: Option<String>, text: &str, occurrence: ErrorOccurrence) {
+        let signature = error_signature(text);
+        let key = (kind.to_string(), tool.clone(), signature.clone());
+        let i = *self.index.entry(key).or_insert_with(|| {
+            self.groups.push(ErrorGroup {
+                kind: kind.to_string(),
+                tool,
+                signature,
+                count: 0,
+                examples: Vec::new(),
+            });
+            self.groups.len() - 1
+        });
+
+        let group = &mut self.groups[i];
+        group.count += 1;
+        if group.examples.len() < MAX_EXAMPLES {
+            group.examples.push(occurrence);
+        }
+        self.total_errors += 1;
+    }
+
+    fn scan_file(&mut self, file_path: &str) -> Result<()> {
+        let mut tool_names: HashMap<String, String> = HashMap::new();
+
+        for_each_entry(file_path, |entry| {
+            let Some(message) = &entry.message else { return };
+            let occurrence = || ErrorOccurrence {
+                session_id: entry.session_id.clone().unwrap_or_else(|| "unknown".to_string()),
+                file_path: file_path.to_string(),
+                message_id: entry.uuid.clone(),
+                timestamp: entry.timestamp.clone(),
+            };
+
+            if entry.is_api_error_message == Some(true) {
+                self.record("api_error", None, &extract_text_content(&message.content), occurrence());
+                return;
+            }
+
+            for item in &message.content {
+                match item {
+                    ContentItem::ToolUse { id, name, .. } => {
+                        tool_names.insert(id.clone(), name.clone());
+                        self.tools.entry(name.clone()).or_default().0 += 1;
+                    }
+                    ContentItem::ToolResult { tool_use_id, content, is_error: Some(true) } => {
+                        let tool = tool_names.get(tool_use_id).cloned();
+                        if let Some(name) = &tool {
+                            self.tools.entry(name.clone()).or_default().1 += 1;
+                        }
+                        self.record("tool_error", tool, &tool_result_text(content), occurrence());
+                    }
+                    ContentItem::Text { text } if entry.entry_type == "assistant" && is_retry_text(text) => {
+                        self.record("retry", None, text, occurrence());
+                    }
+                    _ => {}
+                }
+            }
+        })
+    }
+
+    fn finish(mut self, files_scanned: u32) -> ErrorReport {
+        self.groups.sort_by_key(|g| std::cmp::Reverse(g.count));
+
+        let mut tools: Vec<ToolErrorStats> = self
+            .tools
+            .into_iter()
+            .map(|(tool, (calls, errors))| ToolErrorStats {
+                tool,
+                calls,
+                errors,
+                error_rate: if calls > 0 { errors as f64 / calls as f64 } else { 0.0 },
+            })
+            .collect();
+        tools.sort_by(|a, b| b.errors.cmp(&a.errors).then_with(|| a.tool.cmp(&b.tool)));
+
+        ErrorReport {
+            files_scanned,
+            total_errors: self.total_errors,
+            groups: self.groups,
+            tools,
+        }
+    }
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Aggregate failures in one session file or every session under a directory
+#[napi(catch_unwind)]
+pub fn get_error_report(file_path_or_root: String) -> Result<ErrorReport> {
+    let files = resolve_session_files(&file_path_or_root)?;
+    let mut builder = ReportBuilder::default();
+    let mut scanned = 0;
+
+    for path in &files {
+        if builder.scan_file(&path.display().to_string()).is_ok() {
+            scanned += 1;
+        }
+    }
+
+    Ok(builder.finish(scanned))
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_signature_normalization() {
+        assert_eq!(
+            error_signature("\nError: file /home/me/a.rs not found at line 42\nstack..."),
+            "Error: file <path> not found at line N"
+        );
+        assert_eq!(error_signature("exit code 127"), error_signature("exit code 1"));
+        assert!(is_retry_text("I apologize, let me try again."));
+        assert!(!is_retry_text("Done, all tests pass."));
+    }
+
+    #[test]
+    fn test_session_health_score() {
+        let lines = [
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Bash","input":{}},{"type":"tool_use","id":"t2","name":"Bash","input":{}}]}}"#,
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"fail","is_error":true},{"type":"tool_result","tool_use_id":"t2","content":"[Request interrupted by user for tool use]","is_error":true}]}}"#,
+            r#"{"type":"system","subtype":"compact_boundary"}"#,
+            r#"{"type":"user","isCompactSummary":true,"message":{"role":"user","content":"summary"}}"#,
+        ];
+        let mut tracker = HealthTracker::default();
+        for line in lines {
+            tracker.observe(&crate::parse_jsonl_line(line).unwrap());
+        }
+        let health = tracker.finish();
+
+        assert_eq!(health.tool_errors, 2);
+        assert_eq!(health.interruptions, 1);
+        assert_eq!(health.compactions, 1);
+        assert_eq!(health.score, 100.0 - 50.0 - 5.0 - 5.0);
+        assert!(health.is_troubled);
+    }
+}
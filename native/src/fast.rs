@@ -0,0 +1,275 @@
+// ============================================
+// FAST JSON PATH
+// Borrowed peek at hot fields before the full parse, an optional
+// simd-json backend (feature "simd") with serde_json fallback, and the
+// partial-deserialization scan behind get_session_summary's fast mode
+// ============================================
+
+use std::borrow::Cow;
+use std::fmt;
+
+use napi::bindgen_prelude::*;
+use serde::de::{SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+
+use crate::time::ActivityTracker;
+use crate::{reader, ClaudeSession, RawLogEntry, TokenUsage};
+
+/// Only the fields needed to decide whether a line is worth a full parse.
+/// Strings are borrowed straight from the line when they contain no escapes
+#[derive(Debug, Deserialize)]
+pub(crate) struct EntryPeek<'a> {
+    #[serde(rename = "type", borrow)]
+    pub entry_type: Cow<'a, str>,
+    #[serde(borrow, default)]
+    pub uuid: Option<Cow<'a, str>>,
+    #[serde(rename = "sessionId", borrow, default)]
+    pub session_id: Option<Cow<'a, str>>,
+}
+
+/// Type, uuid and sessionId of an entry, None for unparseable lines
+pub(crate) fn peek_entry(line: &[u8]) -> Option<EntryPeek<'_>> {
+    serde_json::from_slice(line).ok()
+}
+
+/// Cheap look at an entry's type without building the message content
+pub(crate) fn peek_entry_type(line: &str) -> Option<Cow<'_, str>> {
+    peek_entry(line.as_bytes()).map(|peek| peek.entry_type)
+}
+
+/// True for entry types that become ClaudeMessages
+pub(crate) fn is_message_type(entry_type: &str) -> bool {
+    entry_type == "user" || entry_type == "assistant"
+}
+
+#[cfg(feature = "simd")]
+thread_local! {
+    /// simd-json parses in place, so each thread keeps a reusable copy buffer
+    static SCRATCH: std::cell::RefCell<Vec<u8>> = const { std::cell::RefCell::new(Vec::new()) };
+}
+
+/// Parse a full entry through the fastest available backend.
+/// simd-json is tried first when enabled; anything it rejects goes through
+/// serde_json so results never differ from the plain path
+pub(crate) fn parse_entry(line: &str) -> std::result::Result<RawLogEntry, serde_json::Error> {
+    #[cfg(feature = "simd")]
+    {
+        let parsed = SCRATCH.with(|scratch| {
+            let mut buf = scratch.borrow_mut();
+            buf.clear();
+            buf.extend_from_slice(line.as_bytes());
+            simd_json::serde::from_slice::<RawLogEntry>(&mut buf).ok()
+        });
+        if let Some(entry) = parsed {
+            return Ok(entry);
+        }
+    }
+
+    serde_json::from_slice(line.as_bytes())
+}
+
+// ============================================
+// FAST SUMMARY SCAN
+// ============================================
+
+/// Which block kinds a content array holds - reads only each block's "type"
+#[derive(Debug, Default)]
+struct ContentKinds {
+    thinking: bool,
+    tool_use: bool,
+}
+
+#[derive(Deserialize)]
+struct BlockType<'a> {
+    #[serde(rename = "type", borrow)]
+    block_type: Cow<'a, str>,
+}
+
+impl<'de> Deserialize<'de> for ContentKinds {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        struct KindsVisitor;
+
+        impl<'de> Visitor<'de> for KindsVisitor {
+            type Value = ContentKinds;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("string or array of content blocks")
+            }
+
+            fn visit_str<E>(self, _: &str) -> std::result::Result<ContentKinds, E> {
+                Ok(ContentKinds::default())
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<ContentKinds, A::Error> {
+                let mut kinds = ContentKinds::default();
+                while let Some(block) = seq.next_element::<BlockType>()? {
+                    match block.block_type.as_ref() {
+                        "thinking" => kinds.thinking = true,
+                        "tool_use" => kinds.tool_use = true,
+                        _ => {}
+                    }
+                }
+                Ok(kinds)
+            }
+        }
+
+        deserializer.deserialize_any(KindsVisitor)
+    }
+}
+
+#[derive(Deserialize)]
+struct SummaryMessage {
+    #[serde(default)]
+    content: Option<ContentKinds>,
+    #[serde(default)]
+    usage: Option<TokenUsage>,
+}
+
+/// The handful of fields a session list row needs; everything else is skipped
+#[derive(Deserialize)]
+struct SummaryEntry<'a> {
+    #[serde(rename = "type", borrow)]
+    entry_type: Cow<'a, str>,
+    #[serde(rename = "sessionId", borrow, default)]
+    session_id: Option<Cow<'a, str>>,
+    #[serde(borrow, default)]
+    timestamp: Option<Cow<'a, str>>,
+    #[serde(borrow, default)]
+    cwd: Option<Cow<'a, str>>,
+    #[serde(rename = "isSidechain", default)]
+    is_sidechain: Option<bool>,
+    #[serde(default)]
+    message: Option<SummaryMessage>,
+}
+
+/// get_session_summary without full deserialization: no excerpts, health or role stats
+pub(crate) fn scan_summary(file_path: &str, idle_threshold_secs: u32) -> Result<ClaudeSession> {
+    let mut session = ClaudeSession {
+        session_id: String::from("unknown"),
+        file_path: file_path.to_string(),
+        message_count: 0,
+        user_message_count: 0,
+        assistant_message_count: 0,
+        first_timestamp: None,
+        last_timestamp: None,
+        total_input_tokens: None,
+        total_output_tokens: None,
+        main_input_tokens: 0,
+        main_output_tokens: 0,
+        sidechain_input_tokens: 0,
+        sidechain_output_tokens: 0,
+        has_thinking: false,
+        has_tool_use: false,
+        cwd: None,
+        first_user_prompt_excerpt: None,
+        last_assistant_excerpt: None,
+        health: None,
+        user_stats: None,
+        assistant_stats: None,
+        duration_seconds: None,
+        active_seconds: None,
+        pending_tool_uses: None,
+        languages: None,
+    };
+    let mut activity = ActivityTracker::default();
+    let mut total_input_tokens = 0;
+    let mut total_output_tokens = 0;
+
+    reader::for_each_line(file_path, reader::DEFAULT_MMAP_THRESHOLD, |_, line| {
+        let Ok(entry) = serde_json::from_slice::<SummaryEntry>(line.as_bytes()) else { return };
+
+        if let Some(sid) = entry.session_id {
+            session.session_id = sid.into_owned();
+        }
+        if session.cwd.is_none() {
+            session.cwd = entry.cwd.map(Cow::into_owned);
+        }
+
+        match entry.entry_type.as_ref() {
+            "user" => {
+                session.user_message_count += 1;
+                session.message_count += 1;
+            }
+            "assistant" => {
+                session.assistant_message_count += 1;
+                session.message_count += 1;
+
+                if let Some(message) = entry.message {
+                    if let Some(usage) = message.usage {
+                        total_input_tokens += usage.input_tokens;
+                        total_output_tokens += usage.output_tokens;
+                        if entry.is_sidechain == Some(true) {
+                            session.sidechain_input_tokens += usage.input_tokens;
+                            session.sidechain_output_tokens += usage.output_tokens;
+                        }
+                    }
+                    if let Some(kinds) = message.content {
+                        session.has_thinking |= kinds.thinking;
+                        session.has_tool_use |= kinds.tool_use;
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        if let Some(ts) = entry.timestamp {
+            activity.observe(&ts);
+            if session.first_timestamp.is_none() {
+                session.first_timestamp = Some(ts.to_string());
+            }
+            session.last_timestamp = Some(ts.into_owned());
+        }
+    })?;
+
+    session.total_input_tokens = if total_input_tokens > 0 { Some(total_input_tokens) } else { None };
+    session.total_output_tokens = if total_output_tokens > 0 { Some(total_output_tokens) } else { None };
+    session.main_input_tokens = total_input_tokens - session.sidechain_input_tokens;
+    session.main_output_tokens = total_output_tokens - session.sidechain_output_tokens;
+    (session.duration_seconds, session.active_seconds) = activity.finish(idle_threshold_secs);
+    Ok(session)
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_peek_and_parse_agree() {
+        let line = r#"{"type":"assistant","uuid":"1","message":{"role":"assistant","content":[{"type":"text","text":"hi"}]}}"#;
+        assert_eq!(peek_entry_type(line).as_deref(), Some("assistant"));
+        assert!(matches!(peek_entry_type(line), Some(Cow::Borrowed(_))));
+        assert!(peek_entry_type("not json").is_none());
+
+        let entry = parse_entry(line).unwrap();
+        assert_eq!(entry.uuid.as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn test_fast_summary_matches_full_summary() {
+        let tmp = TempDir::new("fast-summary");
+        let path = tmp.join("fast-summary.jsonl");
+        std::fs::write(&path, [
+            r#"{"type":"summary","summary":"x","leafUuid":"a"}"#,
+            r#"{"type":"user","sessionId":"s1","cwd":"/repo","timestamp":"2024-01-01T10:00:00Z","message":{"role":"user","content":"hi"}}"#,
+            r#"{"type":"assistant","sessionId":"s1","timestamp":"2024-01-01T10:00:05Z","message":{"role":"assistant","content":[{"type":"thinking","thinking":"t"},{"type":"tool_use","id":"1","name":"Bash","input":{"command":"ls"}}],"usage":{"input_tokens":10,"output_tokens":4}}}"#,
+        ].join("\n")).unwrap();
+        let file_path = path.display().to_string();
+
+        let fast = scan_summary(&file_path, crate::time::DEFAULT_IDLE_THRESHOLD_SECS).unwrap();
+        let full = crate::get_session_summary(file_path, None).unwrap();
+        assert_eq!(fast.session_id, full.session_id);
+        assert_eq!(fast.message_count, full.message_count);
+        assert_eq!(fast.last_timestamp, full.last_timestamp);
+        assert_eq!((fast.duration_seconds, fast.active_seconds), (full.duration_seconds, Some(5)));
+        assert_eq!(fast.total_input_tokens, Some(10));
+        assert_eq!(fast.cwd.as_deref(), Some("/repo"));
+        assert!(fast.has_thinking && fast.has_tool_use);
+        assert!(fast.pending_tool_uses.is_none());
+        assert_eq!(full.pending_tool_uses.unwrap()[0].name, "Bash");
+    }
+}
@@ -0,0 +1,194 @@
+// ============================================
+// FILE INFO
+// Size, line count, timestamps and a sniffed format for any file the
+// user points the app at, as an object instead of get_file_info's text
+// ============================================
+
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::time::SystemTime;
+
+use chrono::{DateTime, Utc};
+use flate2::read::MultiGzDecoder;
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::errors::{io_error, ErrorCode, HubError};
+use crate::paths::{metadata, open_file};
+
+/// Bytes looked at to detect the format
+const SAMPLE_BYTES: u64 = 64 * 1024;
+
+/// Magic numbers of compressed files this addon may be pointed at
+const COMPRESSIONS: &[(&[u8], &str)] = &[
+    (&[0x1f, 0x8b], "gzip"),
+    (&[0x28, 0xb5, 0x2f, 0xfd], "zstd"),
+    (b"BZh", "bzip2"),
+    (&[0xfd, b'7', b'z', b'X', b'Z', 0x00], "xz"),
+    (b"PK\x03\x04", "zip"),
+];
+
+/// Result of get_file_details
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileInfo {
+    pub file_path: String,
+    pub size: i64,
+    /// Lines of the (decompressed) content, None when it cannot be decoded
+    pub line_count: Option<u32>,
+    /// RFC 3339
+    pub modified_at: Option<String>,
+    /// RFC 3339, None where the file system does not record it
+    pub created_at: Option<String>,
+    pub is_compressed: bool,
+    /// "gzip", "zstd", "bzip2", "xz" or "zip"
+    pub compression: Option<String>,
+    /// Of the decompressed content: "claude-jsonl" (session entries), "jsonl",
+    /// "json", "text", "binary", "empty", or "unknown" for undecodable archives
+    pub detected_format: String,
+}
+
+fn compression_of(head: &[u8]) -> Option<&'static str> {
+    COMPRESSIONS.iter().find(|(magic, _)| head.starts_with(magic)).map(|(_, name)| *name)
+}
+
+/// Format of content from its first bytes; truncated says more follows
+fn detect_format(sample: &[u8], truncated: bool) -> &'static str {
+    let Some(first) = sample.split(|&b| b == b'\n').map(|l| l.trim_ascii()).find(|l| !l.is_empty()) else {
+        return "empty";
+    };
+    if let Ok(serde_json::Value::Object(entry)) = serde_json::from_slice(first) {
+        return if entry.get("type").is_some_and(|t| t.is_string()) { "claude-jsonl" } else { "jsonl" };
+    }
+    // A first line cut off by the sample (e.g. a pasted image) still shows its opening
+    let line_cut = truncated && !sample.contains(&b'\n');
+    match first[0] {
+        b'{' if line_cut && first.windows(7).any(|w| w == b"\"type\":") => "claude-jsonl",
+        b'{' if line_cut => "jsonl",
+        b'{' | b'[' => "json",
+        _ if !sample.contains(&0) && is_text(sample) => "text",
+        _ => "binary",
+    }
+}
+
+/// Valid UTF-8, allowing a character cut off at the end of the sample
+fn is_text(sample: &[u8]) -> bool {
+    std::str::from_utf8(sample).map_or_else(|e| e.error_len().is_none(), |_| true)
+}
+
+fn count_lines(reader: impl Read) -> Option<u32> {
+    let mut reader = BufReader::new(reader);
+    let mut lines = 0u32;
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) => return Some(lines),
+            Ok(_) => lines = lines.saturating_add(1),
+            Err(_) => return None,
+        }
+    }
+}
+
+fn rfc3339(time: std::io::Result<SystemTime>) -> Option<String> {
+    time.ok().map(|t| DateTime::<Utc>::from(t).to_rfc3339())
+}
+
+pub(crate) fn file_info(file_path: &str) -> Result<FileInfo> {
+    let path = Path::new(file_path);
+    let metadata = metadata(path).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            HubError::new(ErrorCode::FileNotFound, "File does not exist").path(path).into()
+        } else {
+            io_error("Cannot read metadata", path, e)
+        }
+    })?;
+    if metadata.is_dir() {
+        return Err(HubError::new(ErrorCode::InvalidArgument, "Is a directory").path(path).into());
+    }
+    let open = || open_file(path).map_err(|e| io_error("Cannot open file", path, e));
+
+    let mut head = Vec::new();
+    open()?.take(SAMPLE_BYTES).read_to_end(&mut head).map_err(|e| io_error("Cannot read file", path, e))?;
+    let compression = compression_of(&head);
+    let (line_count, detected_format) = match compression {
+        None => (count_lines(open()?), detect_format(&head, metadata.len() > head.len() as u64)),
+        Some("gzip") => {
+            let mut sample = Vec::new();
+            let decoded = MultiGzDecoder::new(&head[..]).take(SAMPLE_BYTES).read_to_end(&mut sample);
+            // The compressed head may end mid-stream; what decoded is still a sample
+            let truncated = decoded.is_err() || sample.len() as u64 == SAMPLE_BYTES || metadata.len() > head.len() as u64;
+            let lines = count_lines(MultiGzDecoder::new(open()?));
+            let format = if sample.is_empty() && lines.is_none() { "unknown" } else { detect_format(&sample, truncated) };
+            (lines, format)
+        }
+        Some(_) => (None, "unknown"),
+    };
+
+    Ok(FileInfo {
+        file_path: file_path.to_string(),
+        size: metadata.len() as i64,
+        line_count,
+        modified_at: rfc3339(metadata.modified()),
+        created_at: rfc3339(metadata.created()),
+        is_compressed: compression.is_some(),
+        compression: compression.map(str::to_string),
+        detected_format: detected_format.to_string(),
+    })
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Size, line count, timestamps, compression and format of a file
+#[napi(catch_unwind)]
+pub fn get_file_details(file_path: String) -> Result<FileInfo> {
+    file_info(&file_path)
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+    use std::io::Write;
+
+    #[test]
+    fn test_file_details() {
+        let dir = TempDir::new("file-info");
+        let session = "{\"type\":\"user\",\"uuid\":\"u1\"}\n{\"type\":\"assistant\",\"uuid\":\"a1\"}\n";
+        let write = |name: &str, data: &[u8]| {
+            let path = dir.join(name);
+            std::fs::write(&path, data).unwrap();
+            path.display().to_string()
+        };
+
+        let info = file_info(&write("s.jsonl", session.as_bytes())).unwrap();
+        assert_eq!((info.size, info.line_count, info.is_compressed), (session.len() as i64, Some(2), false));
+        assert_eq!(info.detected_format, "claude-jsonl");
+        assert!(info.modified_at.is_some());
+
+        let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        gz.write_all(session.as_bytes()).unwrap();
+        let info = file_info(&write("s.jsonl.gz", &gz.finish().unwrap())).unwrap();
+        assert_eq!((info.compression.as_deref(), info.line_count), (Some("gzip"), Some(2)));
+        assert_eq!(info.detected_format, "claude-jsonl");
+
+        let format = |name: &str, data: &[u8]| file_info(&write(name, data)).unwrap().detected_format;
+        assert_eq!(format("a.jsonl", b"{\"id\":1}\n{\"id\":2}"), "jsonl");
+        assert_eq!(format("a.json", b"[\n  {\"type\": \"user\"}\n]"), "json");
+        assert_eq!(format("a.txt", b"hello\n"), "text");
+        assert_eq!(format("a.bin", &[0, 159, 146, 150]), "binary");
+        assert_eq!(format("e.jsonl", b""), "empty");
+        assert_eq!(format("a.zst", &[0x28, 0xb5, 0x2f, 0xfd, 1]), "unknown");
+        // A first line longer than the sample
+        let long = format!("{{\"type\":\"user\",\"content\":\"{}\"}}", "x".repeat(SAMPLE_BYTES as usize));
+        assert_eq!(format("long.jsonl", long.as_bytes()), "claude-jsonl");
+
+        assert!(file_info(&dir.join("missing").display().to_string()).unwrap_err().reason.starts_with("FILE_NOT_FOUND: "));
+    }
+}
@@ -0,0 +1,177 @@
+// ============================================
+// FOLLOW MODE
+// Poll a session file and push every message appended to it to a JS
+// callback, starting over when the file is truncated or replaced
+// ============================================
+
+use std::collections::HashMap;
+use std::fs::Metadata;
+use std::io::{Read, Seek, SeekFrom};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use napi::bindgen_prelude::*;
+use napi::threadsafe_function::{ErrorStrategy, ThreadSafeCallContext, ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi::JsFunction;
+use napi_derive::napi;
+
+use crate::errors::io_error;
+use crate::paths::{metadata, open_file};
+use crate::{entry_to_message, fast, ClaudeMessage};
+
+const DEFAULT_POLL_INTERVAL_MS: u32 = 500;
+
+static NEXT_FOLLOWER_ID: AtomicU32 = AtomicU32::new(1);
+/// Stop flag of each running follower
+static FOLLOWERS: Mutex<Option<HashMap<u32, Arc<AtomicBool>>>> = Mutex::new(None);
+
+/// Options for follow_session
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct FollowOptions {
+    /// Also push the messages already in the file (default false)
+    pub from_start: Option<bool>,
+    /// How often the file is checked (default 500 ms)
+    pub poll_interval_ms: Option<u32>,
+}
+
+/// (device, inode) of a file, to tell a rotated file from a grown one
+#[cfg(unix)]
+pub(crate) fn file_identity(metadata: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+pub(crate) fn file_identity(_metadata: &Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+/// Read position in a followed file
+struct FileTail {
+    file_path: String,
+    offset: u64,
+    identity: Option<(u64, u64)>,
+    /// Bytes after the last newline, waiting for the rest of their line
+    partial: Vec<u8>,
+}
+
+impl FileTail {
+    fn open(file_path: &str, from_start: bool) -> Result<FileTail> {
+        let metadata = metadata(file_path).map_err(|e| io_error("Cannot open file", file_path, e))?;
+        Ok(FileTail {
+            file_path: file_path.to_string(),
+            offset: if from_start { 0 } else { metadata.len() },
+            identity: file_identity(&metadata),
+            partial: Vec::new(),
+        })
+    }
+
+    /// Messages in the lines completed since the last poll. A missing file
+    /// (mid-rotation) yields nothing until it comes back
+    fn poll(&mut self) -> Vec<ClaudeMessage> {
+        let Ok(mut file) = open_file(&self.file_path) else { return Vec::new() };
+        let Ok(metadata) = file.metadata() else { return Vec::new() };
+
+        let identity = file_identity(&metadata);
+        if identity != self.identity || metadata.len() < self.offset {
+            self.identity = identity;
+            self.offset = 0;
+            self.partial.clear();
+        }
+        if metadata.len() == self.offset || file.seek(SeekFrom::Start(self.offset)).is_err() {
+            return Vec::new();
+        }
+        let Ok(read) = file.read_to_end(&mut self.partial) else { return Vec::new() };
+        self.offset += read as u64;
+
+        let Some(end) = self.partial.iter().rposition(|b| *b == b'\n') else { return Vec::new() };
+        let complete: Vec<u8> = self.partial.drain(..=end).collect();
+        String::from_utf8_lossy(&complete)
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| fast::parse_entry(line).ok().and_then(entry_to_message))
+            .collect()
+    }
+}
+
+fn with_followers<T>(f: impl FnOnce(&mut HashMap<u32, Arc<AtomicBool>>) -> T) -> T {
+    let mut guard = FOLLOWERS.lock().unwrap_or_else(|e| e.into_inner());
+    f(guard.get_or_insert_with(HashMap::new))
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Push each message appended to a session file to callback until
+/// stop_following is called, returns the follower id
+#[napi(ts_args_type = "filePath: string, callback: (message: ClaudeMessage) => void, options?: FollowOptions | undefined | null", catch_unwind)]
+pub fn follow_session(file_path: String, callback: JsFunction, options: Option<FollowOptions>) -> Result<u32> {
+    let options = options.unwrap_or_default();
+    let mut tail = FileTail::open(&file_path, options.from_start.unwrap_or(false))?;
+    let interval = Duration::from_millis(options.poll_interval_ms.unwrap_or(DEFAULT_POLL_INTERVAL_MS).max(10) as u64);
+
+    let tsfn: ThreadsafeFunction<ClaudeMessage, ErrorStrategy::Fatal> =
+        callback.create_threadsafe_function(0, |ctx: ThreadSafeCallContext<ClaudeMessage>| Ok(vec![ctx.value]))?;
+    let stop = Arc::new(AtomicBool::new(false));
+    let id = NEXT_FOLLOWER_ID.fetch_add(1, Ordering::Relaxed);
+    with_followers(|followers| followers.insert(id, stop.clone()));
+
+    std::thread::spawn(move || {
+        while !stop.load(Ordering::Relaxed) {
+            for message in tail.poll() {
+                tsfn.call(message, ThreadsafeFunctionCallMode::Blocking);
+            }
+            std::thread::sleep(interval);
+        }
+    });
+    Ok(id)
+}
+
+/// Stop a follower, returns false if it was already stopped
+#[napi(catch_unwind)]
+pub fn stop_following(follower: u32) -> bool {
+    match with_followers(|followers| followers.remove(&follower)) {
+        Some(stop) => {
+            stop.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+    use std::io::Write;
+
+    #[test]
+    fn test_tail_partial_lines_and_truncation() {
+        let tmp = TempDir::new("follow");
+        let path = tmp.join("follow.jsonl");
+        let line = |id: &str| format!(r#"{{"type":"user","uuid":"{}","message":{{"role":"user","content":"hi"}}}}"#, id);
+        std::fs::write(&path, format!("{}\n", line("old"))).unwrap();
+        let file_path = path.display().to_string();
+        let mut tail = FileTail::open(&file_path, false).unwrap();
+
+        let mut file = std::fs::OpenOptions::new().append(true).open(&path).unwrap();
+        let new = line("u1");
+        let (head, rest) = new.split_at(10);
+        file.write_all(head.as_bytes()).unwrap();
+        assert!(tail.poll().is_empty());
+        writeln!(file, "{}", rest).unwrap();
+        let messages = tail.poll();
+        assert_eq!(messages.iter().map(|m| m.message_id.as_str()).collect::<Vec<_>>(), vec!["u1"]);
+
+        std::fs::write(&path, format!("{}\n", line("u2"))).unwrap();
+        assert_eq!(tail.poll()[0].message_id, "u2");
+        assert!(tail.poll().is_empty());
+    }
+}
@@ -0,0 +1,210 @@
+// ============================================
+// CONVERSATION GRAPH
+// The uuid/parentUuid tree of a session as Graphviz DOT or Mermaid,
+// colored by branch with sidechains drawn dashed
+// ============================================
+
+use std::collections::HashMap;
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::errors::unsupported_format;
+use crate::export::{selected_entries, ExportOptions};
+use crate::title::truncate_words;
+use crate::{extract_text_content, for_each_entry, RawLogEntry};
+
+const LABEL_CHARS: usize = 40;
+
+/// Fill colors cycled per branch
+const BRANCH_COLORS: &[&str] = &["#dbeafe", "#dcfce7", "#fef9c3", "#fce7f3", "#ede9fe", "#ffedd5"];
+const SIDECHAIN_COLOR: &str = "#e5e7eb";
+
+/// One entry of the tree
+struct GraphNode {
+    uuid: String,
+    parent: Option<usize>,
+    label: String,
+    branch: usize,
+    sidechain: bool,
+}
+
+fn node_label(entry: &RawLogEntry) -> String {
+    let role = entry.message.as_ref().map_or(entry.entry_type.as_str(), |m| m.role.as_str());
+    let text = entry
+        .message
+        .as_ref()
+        .map(|m| truncate_words(&extract_text_content(&m.content), LABEL_CHARS))
+        .unwrap_or_default();
+    if text.is_empty() { role.to_string() } else { format!("{}: {}", role, text) }
+}
+
+/// Nodes in file order; the first child continues its parent's branch,
+/// every later sibling starts a new one
+fn build_nodes(entries: &[RawLogEntry]) -> Vec<GraphNode> {
+    let mut nodes: Vec<GraphNode> = Vec::new();
+    let mut by_uuid: HashMap<String, usize> = HashMap::new();
+    let mut child_count: HashMap<usize, usize> = HashMap::new();
+    let mut branches = 0;
+
+    for entry in entries {
+        let Some(uuid) = &entry.uuid else { continue };
+        if by_uuid.contains_key(uuid) {
+            continue;
+        }
+        let parent = entry.parent_uuid.as_ref().and_then(|p| by_uuid.get(p).copied());
+
+        let branch = match parent {
+            Some(p) => {
+                let siblings = child_count.entry(p).or_insert(0);
+                *siblings += 1;
+                if *siblings == 1 {
+                    nodes[p].branch
+                } else {
+                    branches += 1;
+                    branches
+                }
+            }
+            None if nodes.is_empty() => 0,
+            None => {
+                branches += 1;
+                branches
+            }
+        };
+
+        by_uuid.insert(uuid.clone(), nodes.len());
+        nodes.push(GraphNode {
+            uuid: uuid.clone(),
+            parent,
+            label: node_label(entry),
+            branch,
+            sidechain: entry.is_sidechain == Some(true),
+        });
+    }
+    nodes
+}
+
+fn branch_color(branch: usize) -> &'static str {
+    BRANCH_COLORS[branch % BRANCH_COLORS.len()]
+}
+
+fn to_dot(nodes: &[GraphNode]) -> String {
+    let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+    let mut out = String::from("digraph conversation {\n");
+    out.push_str("  rankdir=TB;\n  node [shape=box, style=\"rounded,filled\", fontname=\"Helvetica\"];\n");
+
+    for node in nodes {
+        if node.sidechain {
+            out.push_str(&format!(
+                "  \"{}\" [label=\"{}\", fillcolor=\"{}\", style=\"rounded,filled,dashed\"];\n",
+                escape(&node.uuid),
+                escape(&node.label),
+                SIDECHAIN_COLOR
+            ));
+        } else {
+            out.push_str(&format!(
+                "  \"{}\" [label=\"{}\", fillcolor=\"{}\"];\n",
+                escape(&node.uuid),
+                escape(&node.label),
+                branch_color(node.branch)
+            ));
+        }
+    }
+    for node in nodes {
+        if let Some(parent) = node.parent {
+            out.push_str(&format!("  \"{}\" -> \"{}\";\n", escape(&nodes[parent].uuid), escape(&node.uuid)));
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn to_mermaid(nodes: &[GraphNode]) -> String {
+    // Mermaid has no string escapes, only HTML entities
+    let escape = |s: &str| s.replace('"', "#quot;");
+    let mut out = String::from("flowchart TD\n");
+
+    for (i, node) in nodes.iter().enumerate() {
+        out.push_str(&format!("  n{}[\"{}\"]\n", i, escape(&node.label)));
+    }
+    for (i, node) in nodes.iter().enumerate() {
+        if let Some(parent) = node.parent {
+            out.push_str(&format!("  n{} --> n{}\n", parent, i));
+        }
+    }
+
+    let mut branches: Vec<usize> = nodes.iter().filter(|n| !n.sidechain).map(|n| n.branch % BRANCH_COLORS.len()).collect();
+    branches.sort_unstable();
+    branches.dedup();
+    for branch in branches {
+        out.push_str(&format!("  classDef branch{} fill:{}\n", branch, BRANCH_COLORS[branch]));
+    }
+    if nodes.iter().any(|n| n.sidechain) {
+        out.push_str(&format!("  classDef sidechain fill:{},stroke-dasharray: 5 5\n", SIDECHAIN_COLOR));
+    }
+    for (i, node) in nodes.iter().enumerate() {
+        let class = if node.sidechain {
+            "sidechain".to_string()
+        } else {
+            format!("branch{}", node.branch % BRANCH_COLORS.len())
+        };
+        out.push_str(&format!("  class n{} {}\n", i, class));
+    }
+    out
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Render the message tree of a session; format is "dot" or "mermaid".
+/// options pick the range and the cleanup and substitutions, as for
+/// export_session_text
+#[napi(catch_unwind)]
+pub fn export_conversation_graph(file_path: String, format: String, options: Option<ExportOptions>) -> Result<String> {
+    let options = options.unwrap_or_default();
+    let render = match format.as_str() {
+        "dot" => to_dot,
+        "mermaid" => to_mermaid,
+        other => return Err(unsupported_format(format!("Unknown graph format: {}", other))),
+    };
+
+    let mut entries = Vec::new();
+    for_each_entry(&file_path, |entry| entries.push(entry))?;
+    options.sanitize(render(&build_nodes(selected_entries(&entries, &options)?)))
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_jsonl_line;
+
+    #[test]
+    fn test_branches_and_sidechains() {
+        let lines = [
+            r#"{"type":"user","uuid":"a","message":{"role":"user","content":"Say \"hi\""}}"#,
+            r#"{"type":"assistant","uuid":"b","parentUuid":"a","message":{"role":"assistant","content":"hi"}}"#,
+            r#"{"type":"assistant","uuid":"c","parentUuid":"a","message":{"role":"assistant","content":"hello"}}"#,
+            r#"{"type":"user","uuid":"d","parentUuid":"b","isSidechain":true,"message":{"role":"user","content":"task"}}"#,
+        ];
+        let entries: Vec<RawLogEntry> = lines.iter().map(|l| parse_jsonl_line(l).unwrap()).collect();
+        let nodes = build_nodes(&entries);
+        assert_eq!(nodes.iter().map(|n| n.branch).collect::<Vec<_>>(), vec![0, 0, 1, 0]);
+
+        let dot = to_dot(&nodes);
+        assert!(dot.contains(r##""a" [label="user: Say \"hi\"", fillcolor="#dbeafe"];"##));
+        assert!(dot.contains(r#""a" -> "c";"#) && dot.contains("dashed"));
+
+        let mermaid = to_mermaid(&nodes);
+        assert!(mermaid.contains("n0[\"user: Say #quot;hi#quot;\"]\n"));
+        assert!(mermaid.contains("  n0 --> n2\n") && mermaid.contains("  class n3 sidechain\n"));
+
+        let options = ExportOptions { from_message_id: Some("b".into()), to_index: Some(2), ..Default::default() };
+        let nodes = build_nodes(selected_entries(&entries, &options).unwrap());
+        assert_eq!(nodes.iter().map(|n| n.uuid.as_str()).collect::<Vec<_>>(), vec!["b", "c"]);
+    }
+}
@@ -0,0 +1,120 @@
+// ============================================
+// MODEL AND AGENT HAND-OFFS
+// Where a session changes model (e.g. an opus to sonnet fallback) and
+// launches subagents, in order, for a "who did what" lane view
+// ============================================
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::title::prompt_excerpt;
+use crate::{for_each_entry, ContentItem, RawLogEntry};
+
+/// Written by Claude Code for locally generated replies, not a real model
+const SYNTHETIC_MODEL: &str = "<synthetic>";
+
+/// One point on the timeline
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandoffEvent {
+    /// "model_switch" or "agent_launch"
+    pub kind: String,
+    /// uuid of the entry where it happened
+    pub message_id: Option<String>,
+    pub timestamp: Option<String>,
+    /// Previous model, None for the first model of the session
+    pub from_model: Option<String>,
+    /// New model, or the model that launched the agent
+    pub to_model: Option<String>,
+    pub subagent_type: Option<String>,
+    /// Excerpt of the Task description or prompt
+    pub description: Option<String>,
+}
+
+/// Main-thread events only; sidechain replies belong to the agent that was launched
+pub(crate) fn handoffs(entries: &[RawLogEntry]) -> Vec<HandoffEvent> {
+    let mut events = Vec::new();
+    let mut current_model: Option<String> = None;
+
+    for entry in entries {
+        if entry.entry_type != "assistant" || entry.is_sidechain == Some(true) {
+            continue;
+        }
+        let Some(message) = &entry.message else { continue };
+
+        let model = message.model.as_deref().filter(|m| *m != SYNTHETIC_MODEL);
+        if let Some(model) = model {
+            if current_model.as_deref() != Some(model) {
+                events.push(HandoffEvent {
+                    kind: "model_switch".to_string(),
+                    message_id: entry.uuid.clone(),
+                    timestamp: entry.timestamp.clone(),
+                    from_model: current_model.replace(model.to_string()),
+                    to_model: Some(model.to_string()),
+                    subagent_type: None,
+                    description: None,
+                });
+            }
+        }
+
+        for item in &message.content {
+            let ContentItem::ToolUse { name, input, .. } = item else { continue };
+            if name != "Task" {
+                continue;
+            }
+            let text = |key: &str| input.get(key).and_then(|v| v.as_str());
+            events.push(HandoffEvent {
+                kind: "agent_launch".to_string(),
+                message_id: entry.uuid.clone(),
+                timestamp: entry.timestamp.clone(),
+                from_model: None,
+                to_model: current_model.clone(),
+                subagent_type: text("subagent_type").map(str::to_string),
+                description: text("description").or_else(|| text("prompt")).and_then(prompt_excerpt),
+            });
+        }
+    }
+    events
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Model changes and subagent launches of a session, in file order
+#[napi(catch_unwind)]
+pub fn get_model_switches(file_path: String) -> Result<Vec<HandoffEvent>> {
+    let mut entries = Vec::new();
+    for_each_entry(&file_path, |entry| entries.push(entry))?;
+    Ok(handoffs(&entries))
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_jsonl_line;
+
+    #[test]
+    fn test_model_switch_timeline() {
+        let lines = [
+            r#"{"type":"assistant","uuid":"a1","message":{"role":"assistant","model":"claude-opus-4-1","content":"hi"}}"#,
+            r#"{"type":"assistant","uuid":"a2","message":{"role":"assistant","model":"claude-opus-4-1","content":[{"type":"tool_use","id":"t1","name":"Task","input":{"subagent_type":"Explore","description":"Find the parser","prompt":"..."}}]}}"#,
+            r#"{"type":"assistant","uuid":"s1","isSidechain":true,"message":{"role":"assistant","model":"claude-haiku-4-5","content":"found"}}"#,
+            r#"{"type":"assistant","uuid":"a3","message":{"role":"assistant","model":"<synthetic>","content":"No response requested."}}"#,
+            r#"{"type":"assistant","uuid":"a4","message":{"role":"assistant","model":"claude-sonnet-4-5","content":"fallback"}}"#,
+        ];
+        let entries: Vec<RawLogEntry> = lines.iter().map(|l| parse_jsonl_line(l).unwrap()).collect();
+        let events = handoffs(&entries);
+
+        let kinds: Vec<(&str, Option<&str>)> = events.iter().map(|e| (e.kind.as_str(), e.message_id.as_deref())).collect();
+        assert_eq!(kinds, vec![("model_switch", Some("a1")), ("agent_launch", Some("a2")), ("model_switch", Some("a4"))]);
+        assert_eq!(events[1].subagent_type.as_deref(), Some("Explore"));
+        assert_eq!(events[1].description.as_deref(), Some("Find the parser"));
+        assert_eq!(events[2].from_model.as_deref(), Some("claude-opus-4-1"));
+        assert_eq!(events[2].to_model.as_deref(), Some("claude-sonnet-4-5"));
+    }
+}
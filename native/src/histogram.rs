@@ -0,0 +1,97 @@
+// ============================================
+// ACTIVITY HISTOGRAMS
+// Messages and tokens by local hour of day and by weekday,
+// showing when agentic work actually happens
+// ============================================
+
+use chrono::{Datelike, Timelike};
+use chrono_tz::Tz;
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::time::resolve_timezone;
+use crate::usage::for_each_usage;
+
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// One bar of a histogram
+#[napi(object)]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HistogramBucket {
+    /// "00".."23" for hours, "Mon".."Sun" for weekdays
+    pub label: String,
+    pub messages: u32,
+    /// Input + output tokens
+    pub tokens: i64,
+}
+
+/// Result of get_usage_histograms
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsageHistograms {
+    pub timezone: String,
+    /// 24 buckets, midnight first
+    pub by_hour: Vec<HistogramBucket>,
+    /// 7 buckets, Monday first
+    pub by_weekday: Vec<HistogramBucket>,
+}
+
+fn histograms(root_dir: &str, tz: Tz) -> Result<UsageHistograms> {
+    let mut by_hour: Vec<HistogramBucket> = (0..24)
+        .map(|hour| HistogramBucket { label: format!("{:02}", hour), ..Default::default() })
+        .collect();
+    let mut by_weekday: Vec<HistogramBucket> = WEEKDAYS
+        .iter()
+        .map(|day| HistogramBucket { label: day.to_string(), ..Default::default() })
+        .collect();
+
+    for_each_usage(root_dir, None, chrono::Utc::now(), |event| {
+        let local = event.timestamp.with_timezone(&tz);
+        let tokens = event.usage.map_or(0, |u| u.input_tokens as i64 + u.output_tokens as i64);
+        for bucket in [&mut by_hour[local.hour() as usize], &mut by_weekday[local.weekday().num_days_from_monday() as usize]] {
+            bucket.messages += 1;
+            bucket.tokens += tokens;
+        }
+    })?;
+
+    Ok(UsageHistograms { timezone: tz.name().to_string(), by_hour, by_weekday })
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Message and token counts under root_dir by hour of day and weekday in timezone (default UTC)
+#[napi(catch_unwind)]
+pub fn get_usage_histograms(root_dir: String, timezone: Option<String>) -> Result<UsageHistograms> {
+    histograms(&root_dir, resolve_timezone(timezone.as_deref())?)
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_hour_and_weekday_buckets() {
+        let root = TempDir::new("histogram-test");
+        std::fs::create_dir_all(root.join("proj")).unwrap();
+        std::fs::write(
+            root.join("proj/s1.jsonl"),
+            r#"{"type":"user","sessionId":"s1","timestamp":"2024-03-10T23:30:00Z","message":{"role":"user","content":"late"}}
+{"type":"assistant","sessionId":"s1","timestamp":"2024-03-10T23:31:00Z","message":{"id":"r1","role":"assistant","content":"ok","usage":{"input_tokens":10,"output_tokens":5}}}"#,
+        )
+        .unwrap();
+
+        // Sunday 23:30 UTC is Monday 08:30 in Tokyo
+        let result = histograms(&root.to_string_lossy(), resolve_timezone(Some("Asia/Tokyo")).unwrap()).unwrap();
+        assert_eq!(result.by_hour.len(), 24);
+        assert_eq!((result.by_hour[8].messages, result.by_hour[8].tokens), (2, 15));
+        assert_eq!(result.by_weekday[0], HistogramBucket { label: "Mon".into(), messages: 2, tokens: 15 });
+        assert_eq!(result.by_weekday[6].messages, 0);
+    }
+}
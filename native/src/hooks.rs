@@ -0,0 +1,160 @@
+// ============================================
+// MESSAGE HOOKS
+// parse_claude_session with a JS callback run on every message, so apps
+// can enrich, rewrite or drop messages without forking the parser
+// ============================================
+
+use std::sync::mpsc;
+
+use napi::bindgen_prelude::*;
+use napi::threadsafe_function::{ErrorStrategy, ThreadSafeCallContext, ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi::{JsFunction, JsObject, JsUnknown};
+use napi_derive::napi;
+
+use crate::errors::{error, internal, invalid_argument, ErrorCode};
+use crate::reader::catch_panic;
+use crate::{parse_claude_session, ClaudeMessage, ParseOptions};
+
+/// Runs the hook inside try/catch: an exception reaching the threadsafe
+/// function would abort the process, so it comes back as { error } instead
+const CATCHING_WRAPPER: &str = "(hook) => (message) => { try { return { message: hook(message) } } catch (e) { return { error: String(e && e.message !== undefined ? e.message : e) } } }";
+
+/// What the Promise is rejected with when the hook throws
+fn hook_threw(thrown: &str) -> Error {
+    error(ErrorCode::HookFailed, format!("Message hook threw: {}", thrown))
+}
+
+/// The kept message of a wrapped hook's reply, or the error it threw
+fn read_reply(reply: JsObject) -> Result<Option<ClaudeMessage>> {
+    if let Some(thrown) = reply.get::<_, String>("error")? {
+        return Err(hook_threw(&thrown));
+    }
+    // Anything but a message, null or undefined is reported too
+    let Some(value) = reply.get::<_, JsUnknown>("message")? else { return Ok(None) };
+    Option::<ClaudeMessage>::from_unknown(value)
+        .map_err(|e| invalid_argument(format!("Invalid message returned by hook: {}", e.reason)))
+}
+
+/// Feed each message to the hook: Some keeps its (possibly changed) message, None drops it
+fn apply_hook(
+    messages: Vec<ClaudeMessage>,
+    mut hook: impl FnMut(ClaudeMessage) -> Result<Option<ClaudeMessage>>,
+) -> Result<Vec<ClaudeMessage>> {
+    let mut kept = Vec::with_capacity(messages.len());
+    for message in messages {
+        if let Some(message) = hook(message)? {
+            kept.push(message);
+        }
+    }
+    Ok(kept)
+}
+
+/// Parses on the libuv pool and waits there for the hook's answer to each
+/// message, leaving the JS thread free to run it
+pub struct HookedParse {
+    file_path: String,
+    options: Option<ParseOptions>,
+    hook: ThreadsafeFunction<ClaudeMessage, ErrorStrategy::Fatal>,
+}
+
+impl Task for HookedParse {
+    type Output = Vec<ClaudeMessage>;
+    type JsValue = Vec<ClaudeMessage>;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        // napi runs compute on the libuv pool without catch_unwind
+        let file_path = self.file_path.clone();
+        catch_panic(&file_path, || self.parse_and_hook())
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(output)
+    }
+}
+
+impl HookedParse {
+    fn parse_and_hook(&mut self) -> Result<Vec<ClaudeMessage>> {
+        let messages = parse_claude_session(self.file_path.clone(), self.options.take())?;
+        apply_hook(messages, |message| {
+            let (sender, receiver) = mpsc::sync_channel(1);
+            let status = self.hook.call_with_return_value(
+                message,
+                ThreadsafeFunctionCallMode::Blocking,
+                move |reply: JsObject| {
+                    // Returning an error here would be fatal, so it goes to the task
+                    let _ = sender.send(read_reply(reply));
+                    Ok(())
+                },
+            );
+            if status != Status::Ok {
+                return Err(internal(format!("Cannot call message hook: {}", status)));
+            }
+            receiver.recv().map_err(|_| internal("Message hook did not return"))?
+        })
+    }
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// parse_claude_session, passing every message through hook; return the
+/// message (changed or not) to keep it, null or undefined to drop it.
+/// If the hook throws, the Promise is rejected with HOOK_FAILED
+#[napi(
+    ts_args_type = "filePath: string, options: ParseOptions | undefined | null, hook: (message: ClaudeMessage) => ClaudeMessage | null | undefined",
+    ts_return_type = "Promise<Array<ClaudeMessage>>",
+    catch_unwind
+)]
+pub fn parse_claude_session_with_hook(
+    env: Env,
+    file_path: String,
+    options: Option<ParseOptions>,
+    hook: JsFunction,
+) -> Result<AsyncTask<HookedParse>> {
+    let wrap: JsFunction = env.run_script(CATCHING_WRAPPER)?;
+    let hook: JsFunction = wrap.call(None, &[hook])?.try_into()?;
+    let hook = hook.create_threadsafe_function(0, |ctx: ThreadSafeCallContext<ClaudeMessage>| Ok(vec![ctx.value]))?;
+    Ok(AsyncTask::new(HookedParse { file_path, options, hook }))
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_hook_rewrites_and_drops() {
+        let tmp = TempDir::new("hooks");
+        let path = tmp.join("hooks.jsonl");
+        std::fs::write(&path, [
+            r#"{"type":"user","uuid":"u1","message":{"role":"user","content":"keep me"}}"#,
+            r#"{"type":"assistant","uuid":"a1","message":{"role":"assistant","content":"drop me"}}"#,
+        ].join("\n")).unwrap();
+
+        let messages = parse_claude_session(path.display().to_string(), None).unwrap();
+        let kept = apply_hook(messages, |mut message| {
+            if message.role == "assistant" {
+                return Ok(None);
+            }
+            message.content = message.content.to_uppercase();
+            Ok(Some(message))
+        })
+        .unwrap();
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].content, "KEEP ME");
+
+        // A throwing hook stops the parse with its message
+        let mut calls = 0;
+        let failed = apply_hook([kept.clone(), kept].concat(), |_| {
+            calls += 1;
+            Err(hook_threw("boom"))
+        });
+        assert_eq!(failed.unwrap_err().reason, "HOOK_FAILED: Message hook threw: boom");
+        assert_eq!(calls, 1);
+    }
+}
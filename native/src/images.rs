@@ -0,0 +1,301 @@
+// ============================================
+// IMAGE SUMMARIES
+// Image blocks reduced to media type, size and hash when parsing, so
+// base64 data only crosses NAPI when one image is actually shown, small
+// PNG thumbnails for previews, and a content-addressed store for exports
+// ============================================
+
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::errors::{io_error, parse_error};
+use crate::index::get_full_message;
+use crate::safe_write::write_file;
+use crate::{for_each_entry, ContentItem};
+
+/// Values accepted by ParseOptions.image_mode
+pub(crate) const IMAGE_MODES: &[&str] = &["full", "summary"];
+
+const DEFAULT_THUMBNAIL_DIM: u32 = 256;
+
+/// One image with its data
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageData {
+    pub media_type: String,
+    /// Base64, as stored in the session file
+    pub data: String,
+    /// Decoded size
+    pub byte_size: i64,
+    /// Hex SHA-256 of the base64 data
+    pub sha256: String,
+}
+
+/// A thumbnail written by generate_thumbnails
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Thumbnail {
+    pub message_id: String,
+    /// Image number within the message, as in get_image_data
+    pub index: u32,
+    pub media_type: String,
+    pub sha256: String,
+    /// PNG file, named after sha256 and max_dim so identical images share one
+    pub path: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Bytes the base64 text decodes to
+fn decoded_len(data: &str) -> i64 {
+    let padding = data.bytes().rev().take_while(|b| *b == b'=').count();
+    (data.len() * 3 / 4).saturating_sub(padding) as i64
+}
+
+pub(crate) fn sha256_hex(data: &str) -> String {
+    Sha256::digest(data.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Every image block of a content array in order, images in tool results
+/// included; index counts them across the whole message
+pub(crate) fn for_each_image(blocks: &mut [Value], index: &mut u32, f: &mut impl FnMut(u32, &mut Value)) {
+    for block in blocks {
+        match block.get("type").and_then(|t| t.as_str()) {
+            Some("image") => {
+                f(*index, block);
+                *index += 1;
+            }
+            Some("tool_result") => {
+                if let Some(Value::Array(items)) = block.get_mut("content") {
+                    for_each_image(items, index, f);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+pub(crate) fn image_field<'a>(block: &'a Value, field: &str) -> &'a str {
+    block.get("source").and_then(|s| s.get(field)).and_then(|v| v.as_str()).unwrap_or("")
+}
+
+/// raw_content JSON with each image replaced by
+/// { type: "image", index, media_type, byte_size, sha256 }
+pub(crate) fn summarized_raw_content(content: &[ContentItem]) -> String {
+    let mut value = serde_json::to_value(content).unwrap_or(Value::Array(Vec::new()));
+    if let Value::Array(blocks) = &mut value {
+        for_each_image(blocks, &mut 0, &mut |index, block| {
+            let data = image_field(block, "data");
+            *block = json!({
+                "type": "image",
+                "index": index,
+                "media_type": image_field(block, "media_type"),
+                "byte_size": decoded_len(data),
+                "sha256": sha256_hex(data),
+            });
+        });
+    }
+    value.to_string()
+}
+
+fn extension(media_type: &str) -> &str {
+    match media_type {
+        "image/png" => "png",
+        "image/jpeg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        _ => "bin",
+    }
+}
+
+/// Decoded images under a directory as "<sha256>.<ext>", each written once
+/// however many sessions contain it
+pub(crate) struct AssetStore {
+    dir: PathBuf,
+}
+
+impl AssetStore {
+    pub fn new(dir: PathBuf) -> AssetStore {
+        AssetStore { dir }
+    }
+
+    /// File names of the images of one message (raw_content JSON), writing new ones
+    pub fn store_images(&self, raw_content: &str) -> Result<Vec<String>> {
+        let Ok(Value::Array(mut blocks)) = serde_json::from_str::<Value>(raw_content) else { return Ok(Vec::new()) };
+        let mut names = Vec::new();
+        let mut failed = None;
+        for_each_image(&mut blocks, &mut 0, &mut |_, block| {
+            let data = image_field(block, "data");
+            let name = format!("{}.{}", sha256_hex(data), extension(image_field(block, "media_type")));
+            match self.write(&name, data) {
+                Ok(()) => names.push(name),
+                Err(e) => failed = Some(e),
+            }
+        });
+        failed.map_or(Ok(names), Err)
+    }
+
+    fn write(&self, name: &str, data: &str) -> Result<()> {
+        let path = self.dir.join(name);
+        if path.is_file() {
+            return Ok(());
+        }
+        let bytes = base64::engine::general_purpose::STANDARD.decode(data.trim()).map_err(|e| parse_error(format!("Invalid image data: {}", e)))?;
+        // Same name, same content, so losing a race to another writer is fine
+        write_file(&path, &bytes, true)
+    }
+}
+
+/// Decode one base64 image and write it scaled to fit max_dim, unless a
+/// thumbnail of the same image already exists; returns its size
+fn write_thumbnail(data: &str, path: &Path, max_dim: u32) -> std::result::Result<(u32, u32), String> {
+    if path.is_file() {
+        return image::image_dimensions(path).map_err(|e| e.to_string());
+    }
+    let bytes = base64::engine::general_purpose::STANDARD.decode(data.trim()).map_err(|e| e.to_string())?;
+    let decoded = image::load_from_memory(&bytes).map_err(|e| e.to_string())?;
+    let thumbnail = if decoded.width() > max_dim || decoded.height() > max_dim { decoded.thumbnail(max_dim, max_dim) } else { decoded };
+    let mut png = std::io::Cursor::new(Vec::new());
+    thumbnail.write_to(&mut png, image::ImageFormat::Png).map_err(|e| e.to_string())?;
+    write_file(path, png.get_ref(), true).map_err(|e| e.reason)?;
+    Ok((thumbnail.width(), thumbnail.height()))
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// PNG thumbnails at most max_dim pixels (default 256) on either side for
+/// every image of a session, written to output_dir. Images that cannot be
+/// decoded are skipped
+#[napi(catch_unwind)]
+pub fn generate_thumbnails(file_path: String, output_dir: String, max_dim: Option<u32>) -> Result<Vec<Thumbnail>> {
+    let max_dim = max_dim.unwrap_or(DEFAULT_THUMBNAIL_DIM).max(1);
+    std::fs::create_dir_all(&output_dir).map_err(|e| io_error("Cannot create directory", &output_dir, e))?;
+
+    let mut thumbnails = Vec::new();
+    for_each_entry(&file_path, |entry| {
+        let Some(message) = &entry.message else { return };
+        if !message.content.iter().any(|item| matches!(item, ContentItem::Image { .. } | ContentItem::ToolResult { .. })) {
+            return;
+        }
+        let message_id = entry.uuid.clone().unwrap_or_else(|| "unknown".to_string());
+        let Ok(Value::Array(mut blocks)) = serde_json::to_value(&message.content) else { return };
+
+        for_each_image(&mut blocks, &mut 0, &mut |index, block| {
+            let data = image_field(block, "data");
+            let sha256 = sha256_hex(data);
+            let path = Path::new(&output_dir).join(format!("{}-{}.png", sha256, max_dim));
+            match write_thumbnail(data, &path, max_dim) {
+                Ok((width, height)) => thumbnails.push(Thumbnail {
+                    message_id: message_id.clone(),
+                    index,
+                    media_type: image_field(block, "media_type").to_string(),
+                    sha256,
+                    path: path.display().to_string(),
+                    width,
+                    height,
+                }),
+                Err(e) => tracing::warn!(message_id = message_id.as_str(), index, "Cannot make thumbnail: {}", e),
+            }
+        });
+    })?;
+    Ok(thumbnails)
+}
+
+/// Image number index (as in the image_mode "summary" blocks) of one message
+/// (by message_id or canonical_id), None if the message or the image does not exist
+#[napi(catch_unwind)]
+pub fn get_image_data(file_path: String, message_id: String, index: u32) -> Result<Option<ImageData>> {
+    let Some(message) = get_full_message(file_path, message_id)? else {
+        return Ok(None);
+    };
+    let Ok(Value::Array(mut blocks)) = serde_json::from_str::<Value>(&message.raw_content) else {
+        return Ok(None);
+    };
+
+    let mut found = None;
+    for_each_image(&mut blocks, &mut 0, &mut |i, block| {
+        if i == index {
+            let data = image_field(block, "data");
+            found = Some(ImageData {
+                media_type: image_field(block, "media_type").to_string(),
+                data: data.to_string(),
+                byte_size: decoded_len(data),
+                sha256: sha256_hex(data),
+            });
+        }
+    });
+    Ok(found)
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_claude_session, ParseOptions};
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_summarize_and_fetch_images() {
+        let tmp = TempDir::new("images");
+        let path = tmp.join("images.jsonl");
+        std::fs::write(&path, [
+            r#"{"type":"user","uuid":"u1","message":{"role":"user","content":[{"type":"text","text":"see"},{"type":"image","source":{"type":"base64","media_type":"image/png","data":"aGVsbG8="}}]}}"#,
+            r#"{"type":"user","uuid":"u2","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":[{"type":"image","source":{"type":"base64","media_type":"image/jpeg","data":"AAAA"}}]}]}}"#,
+        ].join("\n")).unwrap();
+        let file_path = path.display().to_string();
+
+        let options = ParseOptions { image_mode: Some("summary".into()), ..Default::default() };
+        let messages = parse_claude_session(file_path.clone(), Some(options)).unwrap();
+        let blocks: Value = serde_json::from_str(&messages[0].raw_content).unwrap();
+        assert_eq!(blocks[1]["byte_size"], json!(5));
+        assert_eq!(blocks[1]["sha256"].as_str().map(str::len), Some(64));
+        assert!(!messages[0].raw_content.contains("aGVsbG8="));
+        let nested: Value = serde_json::from_str(&messages[1].raw_content).unwrap();
+        assert_eq!(nested[0]["content"][0]["media_type"], json!("image/jpeg"));
+
+        let image = get_image_data(file_path.clone(), "u1".into(), 0).unwrap().unwrap();
+        assert_eq!((image.media_type.as_str(), image.data.as_str()), ("image/png", "aGVsbG8="));
+        assert_eq!(image.sha256, blocks[1]["sha256"].as_str().unwrap());
+        assert_eq!(get_image_data(file_path.clone(), "u2".into(), 0).unwrap().unwrap().byte_size, 3);
+        assert!(get_image_data(file_path.clone(), "u1".into(), 1).unwrap().is_none());
+
+        // Neither test image decodes, so no thumbnails are written
+        let out_dir = TempDir::new("images-thumbs");
+        assert!(generate_thumbnails(file_path.clone(), out_dir.display().to_string(), None).unwrap().is_empty());
+
+        let bad = ParseOptions { image_mode: Some("thumbnails".into()), ..Default::default() };
+        assert!(parse_claude_session(file_path, Some(bad)).is_err());
+    }
+
+    #[test]
+    fn test_generate_thumbnails() {
+        let root = TempDir::new("thumbnails");
+        let mut png = std::io::Cursor::new(Vec::new());
+        image::RgbImage::new(40, 20).write_to(&mut png, image::ImageFormat::Png).unwrap();
+        let data = base64::engine::general_purpose::STANDARD.encode(png.into_inner());
+
+        let session = root.join("s.jsonl");
+        let line = format!(r#"{{"type":"user","uuid":"u1","message":{{"role":"user","content":[{{"type":"image","source":{{"type":"base64","media_type":"image/png","data":"{}"}}}}]}}}}"#, data);
+        std::fs::write(&session, line).unwrap();
+        let out_dir = root.join("thumbs").display().to_string();
+
+        let thumbnails = generate_thumbnails(session.display().to_string(), out_dir.clone(), Some(10)).unwrap();
+        assert_eq!(thumbnails.len(), 1);
+        assert_eq!((thumbnails[0].width, thumbnails[0].height, thumbnails[0].index), (10, 5, 0));
+        assert_eq!(image::image_dimensions(&thumbnails[0].path).unwrap(), (10, 5));
+
+        // A second run reuses the file
+        let again = generate_thumbnails(session.display().to_string(), out_dir, Some(10)).unwrap();
+        assert_eq!(again, thumbnails);
+    }
+}
@@ -0,0 +1,527 @@
+// ============================================
+// BYTE-OFFSET INDEX
+// uuid and canonical id -> line offset per session file, so a single
+// message can be re-read in full without parsing the whole session
+// again. Line checkpoints let read_lines_range page through huge files
+// and get_raw_entry find a line, and the cached line counts back
+// stat_sessions, a cheap call for polling files
+// ============================================
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Utc};
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::errors::{io_error, io_failure};
+use crate::paths::{metadata, open_file};
+use crate::{canonical_message_id, entry_canonical_id, entry_to_message, fast, parse_jsonl_line, ClaudeMessage};
+
+/// Bytes read from the start of an unindexed file to estimate its line count
+const LINE_SAMPLE_BYTES: u64 = 64 * 1024;
+
+/// Lines between two recorded line offsets
+const LINE_STRIDE: u64 = 1024;
+
+/// Files whose index is kept; the least recently used one goes beyond this
+const MAX_CACHED_INDEXES: usize = 64;
+
+static INDEXES: Mutex<Option<IndexCache>> = Mutex::new(None);
+
+/// Offsets of one file, valid while its size and mtime are unchanged
+struct FileIndex {
+    len: u64,
+    modified: Option<SystemTime>,
+    /// By uuid and by canonical id
+    offsets: HashMap<String, u64>,
+    lines: u64,
+    /// Offset of every LINE_STRIDE-th line, starting with line 0
+    checkpoints: Vec<u64>,
+}
+
+/// Indexes by file path, each with the tick it was last used at
+#[derive(Default)]
+struct IndexCache {
+    files: HashMap<String, (FileIndex, u64)>,
+    tick: u64,
+}
+
+impl IndexCache {
+    /// The index of file_path if it is still valid for stamp, marked as used
+    fn current(&mut self, file_path: &str, stamp: (u64, Option<SystemTime>)) -> Option<&FileIndex> {
+        self.tick += 1;
+        let (index, used) = self.files.get_mut(file_path).filter(|(index, _)| (index.len, index.modified) == stamp)?;
+        *used = self.tick;
+        Some(index)
+    }
+
+    fn insert(&mut self, file_path: &str, index: FileIndex) {
+        self.tick += 1;
+        self.files.insert(file_path.to_string(), (index, self.tick));
+        if self.files.len() > MAX_CACHED_INDEXES {
+            let oldest = self.files.iter().min_by_key(|(_, (_, used))| *used).map(|(path, _)| path.clone());
+            if let Some(oldest) = oldest {
+                self.files.remove(&oldest);
+            }
+        }
+    }
+}
+
+fn cache() -> MutexGuard<'static, Option<IndexCache>> {
+    INDEXES.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// One line of a session file next to what the parser made of it
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct RawEntry {
+    /// 0-based, as in read_lines_range
+    pub line_number: u32,
+    pub byte_offset: i64,
+    /// The line as stored, without its line break
+    pub raw_line: String,
+    /// The line as generic JSON, None when it is not valid JSON
+    pub json: Option<serde_json::Value>,
+    /// The parsed message, None for lines that are not user or assistant messages
+    pub message: Option<ClaudeMessage>,
+    /// Why the line does not parse as a log entry, with the column
+    pub parse_error: Option<String>,
+}
+
+/// Result of read_lines_range
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineRange {
+    pub start_line: u32,
+    /// Raw lines without their line break, empty ones included
+    pub lines: Vec<String>,
+    pub total_lines: u32,
+    pub has_more: bool,
+}
+
+/// Size, mtime and line count of one session file
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionStat {
+    pub file_path: String,
+    pub exists: bool,
+    pub bytes: i64,
+    /// RFC 3339
+    pub modified_at: Option<String>,
+    /// Changes whenever the size or mtime does; pass it back to detect changes
+    pub fingerprint: Option<String>,
+    /// Exact when the file is indexed and unchanged (see lines_exact),
+    /// otherwise extrapolated from a sample or an older index
+    pub estimated_lines: Option<u32>,
+    pub lines_exact: bool,
+    /// Whether the file differs from the fingerprint given for it, None when none was
+    pub changed: Option<bool>,
+}
+
+fn file_stamp(file: &File) -> Result<(u64, Option<SystemTime>)> {
+    let metadata = file.metadata().map_err(|e| io_failure("Cannot open file", e))?;
+    Ok((metadata.len(), metadata.modified().ok()))
+}
+
+/// Canonical id of an entry line; only entries without a uuid need a full parse
+fn line_canonical_id(line: &[u8], peek: &fast::EntryPeek) -> Option<String> {
+    match &peek.uuid {
+        Some(uuid) => Some(canonical_message_id(peek.session_id.as_deref(), Some(uuid), "", None)),
+        None if fast::is_message_type(&peek.entry_type) => {
+            let entry = parse_jsonl_line(std::str::from_utf8(line).ok()?.trim_end()).ok()?;
+            Some(entry_canonical_id(&entry))
+        }
+        None => None,
+    }
+}
+
+/// Scan the file once, recording where each entry with a uuid or a canonical id starts
+fn build_index(file: &File) -> Result<FileIndex> {
+    let (len, modified) = file_stamp(file)?;
+    let mut reader = BufReader::new(file);
+    reader.seek(SeekFrom::Start(0)).map_err(|e| io_failure("Cannot read file", e))?;
+
+    let mut offsets = HashMap::new();
+    let mut offset = 0u64;
+    let mut lines = 0u64;
+    let mut checkpoints = Vec::new();
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        let read = reader
+            .read_until(b'\n', &mut line)
+            .map_err(|e| io_failure("Cannot read file", e))?;
+        if read == 0 {
+            break;
+        }
+        if lines.is_multiple_of(LINE_STRIDE) {
+            checkpoints.push(offset);
+        }
+        if let Some(peek) = fast::peek_entry(&line) {
+            if let Some(canonical_id) = line_canonical_id(&line, &peek) {
+                offsets.entry(canonical_id).or_insert(offset);
+            }
+            if let Some(uuid) = peek.uuid {
+                offsets.entry(uuid.into_owned()).or_insert(offset);
+            }
+        }
+        offset += read as u64;
+        lines += 1;
+    }
+
+    Ok(FileIndex { len, modified, offsets, lines, checkpoints })
+}
+
+/// Offset of message_id (a uuid or a canonical id). An index built at the file's current size and
+/// mtime holds every uuid, so a miss on it is final and needs no rebuild
+fn lookup(file_path: &str, file: &File, message_id: &str) -> Result<Option<u64>> {
+    with_index(file_path, file, |index| index.offsets.get(message_id).copied())
+}
+
+/// Run f on the index of file, (re)building it when stale. The file is
+/// scanned without holding the lock, so other files stay available
+fn with_index<R>(file_path: &str, file: &File, f: impl FnOnce(&FileIndex) -> R) -> Result<R> {
+    let stamp = file_stamp(file)?;
+    if let Some(index) = cache().get_or_insert_with(IndexCache::default).current(file_path, stamp) {
+        return Ok(f(index));
+    }
+    let index = build_index(file)?;
+    let result = f(&index);
+    cache().get_or_insert_with(IndexCache::default).insert(file_path, index);
+    Ok(result)
+}
+
+/// Drop the cached index of a file (after it was deleted or rewritten)
+pub(crate) fn forget(file_path: &str) {
+    if let Some(indexes) = cache().as_mut() {
+        indexes.files.remove(file_path);
+    }
+}
+
+fn fingerprint(len: u64, modified: Option<SystemTime>) -> String {
+    let nanos = modified.and_then(|t| t.duration_since(UNIX_EPOCH).ok()).map(|d| d.as_nanos()).unwrap_or(0);
+    format!("{:x}-{:x}", len, nanos)
+}
+
+/// Line count of a file of len bytes: exact from a current index or a
+/// sample covering the whole file, else scaled from bytes per line
+fn estimate_lines(file_path: &str, len: u64, modified: Option<SystemTime>) -> Option<(u64, bool)> {
+    if len == 0 {
+        return Some((0, true));
+    }
+    let cached = cache().as_ref().and_then(|indexes| {
+        indexes.files.get(file_path).map(|(index, _)| (index.len, index.modified, index.lines))
+    });
+    match cached {
+        Some((indexed_len, indexed_modified, lines)) if (indexed_len, indexed_modified) == (len, modified) => return Some((lines, true)),
+        // Sessions are appended to, so an older index still knows the line length
+        Some((indexed_len, _, lines)) if indexed_len > 0 && lines > 0 => return Some(((len as u128 * lines as u128 / indexed_len as u128) as u64, false)),
+        _ => {}
+    }
+
+    let mut sample = Vec::new();
+    open_file(file_path).ok()?.take(LINE_SAMPLE_BYTES).read_to_end(&mut sample).ok()?;
+    let newlines = sample.iter().filter(|&&b| b == b'\n').count() as u64;
+    if sample.len() as u64 >= len {
+        let unterminated = sample.last().is_some_and(|&b| b != b'\n');
+        return Some((newlines + unterminated as u64, true));
+    }
+    Some(((len as u128 * newlines.max(1) as u128 / sample.len() as u128) as u64, false))
+}
+
+fn stat(file_path: String, since: Option<&String>) -> SessionStat {
+    let Ok(metadata) = metadata(&file_path) else {
+        return SessionStat {
+            changed: since.map(|_| true),
+            file_path,
+            exists: false,
+            bytes: 0,
+            modified_at: None,
+            fingerprint: None,
+            estimated_lines: None,
+            lines_exact: false,
+        };
+    };
+    let (len, modified) = (metadata.len(), metadata.modified().ok());
+    let fingerprint = fingerprint(len, modified);
+    let lines = estimate_lines(&file_path, len, modified);
+    SessionStat {
+        changed: since.map(|since| *since != fingerprint),
+        exists: true,
+        bytes: len as i64,
+        modified_at: modified.map(|t| DateTime::<Utc>::from(t).to_rfc3339()),
+        fingerprint: Some(fingerprint),
+        estimated_lines: lines.map(|(n, _)| n.min(u32::MAX as u64) as u32),
+        lines_exact: lines.is_some_and(|(_, exact)| exact),
+        file_path,
+    }
+}
+
+/// Read forward from a line start until done(line number, offset) holds.
+/// None when the file ends first
+fn scan_to(file: &File, mut offset: u64, mut line_no: u64, done: impl Fn(u64, u64) -> bool) -> Result<Option<(u64, u64)>> {
+    let mut reader = BufReader::new(file);
+    reader.seek(SeekFrom::Start(offset)).map_err(|e| io_failure("Cannot read file", e))?;
+    let mut line = Vec::new();
+    while !done(line_no, offset) {
+        line.clear();
+        let read = reader.read_until(b'\n', &mut line).map_err(|e| io_failure("Cannot read file", e))?;
+        if read == 0 {
+            return Ok(None);
+        }
+        offset += read as u64;
+        line_no += 1;
+    }
+    Ok(Some((line_no, offset)))
+}
+
+/// Line number and offset of a line, by number or by the uuid or canonical id of its entry
+fn locate(file_path: &str, file: &File, locator: &Either<u32, String>) -> Result<Option<(u64, u64)>> {
+    let target = match locator {
+        Either::A(line_number) => {
+            let line_number = *line_number as u64;
+            let start = with_index(file_path, file, |index| {
+                let nearest = (line_number / LINE_STRIDE) as usize;
+                (line_number < index.lines).then(|| (index.checkpoints[nearest], nearest as u64 * LINE_STRIDE))
+            })?;
+            let Some((offset, line_no)) = start else { return Ok(None) };
+            return scan_to(file, offset, line_no, |n, _| n == line_number);
+        }
+        Either::B(message_id) => match lookup(file_path, file, message_id)? {
+            Some(offset) => offset,
+            None => return Ok(None),
+        },
+    };
+    let (offset, line_no) = with_index(file_path, file, |index| {
+        let nearest = index.checkpoints.partition_point(|&o| o <= target).saturating_sub(1);
+        (index.checkpoints.get(nearest).copied().unwrap_or(0), nearest as u64 * LINE_STRIDE)
+    })?;
+    scan_to(file, offset, line_no, |_, o| o >= target)
+}
+
+fn read_line_at(file: &File, offset: u64) -> Result<String> {
+    let mut reader = BufReader::new(file);
+    reader
+        .seek(SeekFrom::Start(offset))
+        .map_err(|e| io_failure("Cannot read file", e))?;
+    let mut line = Vec::new();
+    reader
+        .read_until(b'\n', &mut line)
+        .map_err(|e| io_failure("Cannot read file", e))?;
+    Ok(String::from_utf8_lossy(&line).into_owned())
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// One message with its complete content (e.g. after parsing with
+/// max_text_chars), by message_id or canonical_id
+#[napi(catch_unwind)]
+pub fn get_full_message(file_path: String, message_id: String) -> Result<Option<ClaudeMessage>> {
+    let file = open_file(&file_path).map_err(|e| io_error("Cannot open file", &file_path, e))?;
+
+    let Some(offset) = lookup(&file_path, &file, &message_id)? else {
+        return Ok(None);
+    };
+    let line = read_line_at(&file, offset)?;
+    Ok(parse_jsonl_line(line.trim_end()).ok().and_then(entry_to_message))
+}
+
+/// The original JSON line at a 0-based line number, or of the entry with
+/// that uuid or canonical id, with its generic JSON and parsed message side by side
+#[napi(catch_unwind)]
+pub fn get_raw_entry(file_path: String, line_number_or_message_id: Either<u32, String>) -> Result<Option<RawEntry>> {
+    let file = open_file(&file_path).map_err(|e| io_error("Cannot open file", &file_path, e))?;
+    let Some((line_number, offset)) = locate(&file_path, &file, &line_number_or_message_id)? else {
+        return Ok(None);
+    };
+    let line = read_line_at(&file, offset)?;
+    let raw_line = line.strip_suffix('\n').map(|l| l.strip_suffix('\r').unwrap_or(l)).unwrap_or(&line).to_string();
+    let (message, parse_error) = match parse_jsonl_line(&raw_line) {
+        Ok(entry) => (entry_to_message(entry), None),
+        Err(e) => (None, Some(e.to_string())),
+    };
+    Ok(Some(RawEntry {
+        line_number: line_number.min(u32::MAX as u64) as u32,
+        byte_offset: offset as i64,
+        json: serde_json::from_str(&raw_line).ok(),
+        raw_line,
+        message,
+        parse_error,
+    }))
+}
+
+/// Up to count raw lines of file_path from start_line (0-based), for paging
+/// through files too large to read at once
+#[napi(catch_unwind)]
+pub fn read_lines_range(file_path: String, start_line: u32, count: u32) -> Result<LineRange> {
+    let file = open_file(&file_path).map_err(|e| io_error("Cannot open file", &file_path, e))?;
+    let start = start_line as u64;
+    let (checkpoint, total) = with_index(&file_path, &file, |index| {
+        let nearest = (start / LINE_STRIDE) as usize;
+        (index.checkpoints.get(nearest).map(|offset| (*offset, nearest as u64 * LINE_STRIDE)), index.lines)
+    })?;
+
+    let mut lines = Vec::new();
+    if let Some((offset, mut line_no)) = checkpoint {
+        let mut reader = BufReader::new(&file);
+        reader.seek(SeekFrom::Start(offset)).map_err(|e| io_failure("Cannot read file", e))?;
+        let mut line = Vec::new();
+        while (lines.len() as u32) < count {
+            line.clear();
+            if reader.read_until(b'\n', &mut line).map_err(|e| io_failure("Cannot read file", e))? == 0 {
+                break;
+            }
+            if line_no >= start {
+                let text = line.strip_suffix(b"\n").unwrap_or(&line);
+                lines.push(String::from_utf8_lossy(text.strip_suffix(b"\r").unwrap_or(text)).into_owned());
+            }
+            line_no += 1;
+        }
+    }
+    let end = start + lines.len() as u64;
+    Ok(LineRange { start_line, lines, total_lines: total.min(u32::MAX as u64) as u32, has_more: end < total })
+}
+
+/// Size, mtime, line count and fingerprint of each file, without parsing.
+/// since maps file paths to fingerprints from an earlier call; those files
+/// get changed set. Missing files are reported with exists false
+#[napi(catch_unwind)]
+pub fn stat_sessions(file_paths: Vec<String>, since: Option<HashMap<String, String>>) -> Vec<SessionStat> {
+    let since = since.unwrap_or_default();
+    file_paths
+        .into_iter()
+        .map(|file_path| {
+            let previous = since.get(&file_path);
+            stat(file_path, previous)
+        })
+        .collect()
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_claude_session, ParseOptions};
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_truncate_and_hydrate() {
+        let tmp = TempDir::new("index-test");
+        let path = tmp.join("index-test.jsonl");
+        let long = "é".repeat(50);
+        let lines = [
+            r#"{"type":"summary","summary":"x"}"#.to_string(),
+            format!(r#"{{"type":"user","uuid":"u1","message":{{"role":"user","content":"{}"}}}}"#, long),
+            r#"{"type":"assistant","uuid":"a1","message":{"role":"assistant","content":"short"}}"#.to_string(),
+        ];
+        std::fs::write(&path, lines.join("\n")).unwrap();
+        let file_path = path.to_string_lossy().to_string();
+
+        let options = ParseOptions { max_text_chars: Some(10), ..Default::default() };
+        let messages = parse_claude_session(file_path.clone(), Some(options)).unwrap();
+        assert_eq!(messages[0].content.chars().count(), 10);
+        assert!(messages[0].content_truncated && !messages[1].content_truncated);
+
+        let full = get_full_message(file_path.clone(), "u1".into()).unwrap().unwrap();
+        assert_eq!(full.content, long);
+        assert_eq!(get_full_message(file_path.clone(), "a1".into()).unwrap().unwrap().content, "short");
+        assert!(get_full_message(file_path, "missing".into()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_read_lines_range() {
+        let tmp = TempDir::new("index-range");
+        let path = tmp.join("index-range.jsonl");
+        let mut content: String = (0..2500).map(|i| format!("{{\"n\":{}}}\n", i)).collect();
+        content.push_str("\r\nlast");
+        std::fs::write(&path, content).unwrap();
+        let file_path = path.to_string_lossy().to_string();
+
+        let range = read_lines_range(file_path.clone(), 1020, 10).unwrap();
+        assert_eq!((range.lines.len(), range.total_lines, range.has_more), (10, 2502, true));
+        assert_eq!((range.lines[0].as_str(), range.lines[9].as_str()), ("{\"n\":1020}", "{\"n\":1029}"));
+
+        let tail = read_lines_range(file_path.clone(), 2499, 50).unwrap();
+        assert_eq!((tail.lines, tail.has_more), (vec!["{\"n\":2499}".to_string(), String::new(), "last".to_string()], false));
+        assert!(read_lines_range(file_path, 9000, 5).unwrap().lines.is_empty());
+    }
+
+    #[test]
+    fn test_get_raw_entry() {
+        let tmp = TempDir::new("index-raw");
+        let path = tmp.join("index-raw.jsonl");
+        let mut lines: Vec<String> = (0..1500).map(|i| format!(r#"{{"type":"summary","summary":"s{}"}}"#, i)).collect();
+        lines.push(r#"{"type":"user","uuid":"u1","message":{"role":"user","content":"hello"}}"#.to_string());
+        lines.push(r#"{"type":"user","uuid":"u2","message":{"role":"user","content":5}}"#.to_string());
+        lines.push(r#"{"type":"user", broken"#.to_string());
+        std::fs::write(&path, lines.join("\r\n")).unwrap();
+        let file_path = path.to_string_lossy().to_string();
+
+        let by_id = get_raw_entry(file_path.clone(), Either::B("u1".into())).unwrap().unwrap();
+        assert_eq!((by_id.line_number, by_id.raw_line.as_str()), (1500, lines[1500].as_str()));
+        let message = by_id.message.unwrap();
+        assert_eq!(message.content, "hello");
+        let by_canonical = get_raw_entry(file_path.clone(), Either::B(message.canonical_id.clone())).unwrap().unwrap();
+        assert_eq!(by_canonical.line_number, 1500);
+        assert_eq!(get_full_message(file_path.clone(), message.canonical_id).unwrap().unwrap().message_id, "u1");
+        let by_line = get_raw_entry(file_path.clone(), Either::A(1500)).unwrap().unwrap();
+        assert_eq!(by_line.byte_offset, by_id.byte_offset);
+        assert!(get_raw_entry(file_path.clone(), Either::A(3)).unwrap().unwrap().message.is_none());
+
+        // Valid JSON the entry schema rejects, and a line that is not JSON at all
+        let mismatch = get_raw_entry(file_path.clone(), Either::A(1501)).unwrap().unwrap();
+        assert!(mismatch.json.is_some() && mismatch.parse_error.is_some());
+        let broken = get_raw_entry(file_path.clone(), Either::A(1502)).unwrap().unwrap();
+        assert!(broken.json.is_none() && broken.parse_error.unwrap().contains("column"));
+
+        assert!(get_raw_entry(file_path.clone(), Either::A(1503)).unwrap().is_none());
+        assert!(get_raw_entry(file_path, Either::B("nope".into())).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_stat_sessions() {
+        let tmp = TempDir::new("index-stat");
+        let path = tmp.join("index-stat.jsonl");
+        let line = |uuid: &str| format!(r#"{{"type":"user","uuid":"{}","message":{{"role":"user","content":"hello"}}}}"#, uuid);
+        std::fs::write(&path, [line("u1"), line("u2"), line("u3")].join("\n")).unwrap();
+        let file_path = path.to_string_lossy().to_string();
+        let missing = format!("{}.gone", file_path);
+
+        let stats = stat_sessions(vec![file_path.clone(), missing.clone()], None);
+        assert_eq!((stats[0].estimated_lines, stats[0].lines_exact, stats[0].changed), (Some(3), true, None));
+        assert!(!stats[1].exists);
+        let fingerprint = stats[0].fingerprint.clone().unwrap();
+
+        // Indexed, then appended to: the old index scales to an estimate
+        get_full_message(file_path.clone(), "u1".into()).unwrap();
+        let since = HashMap::from([(file_path.clone(), fingerprint.clone()), (missing.clone(), "x".to_string())]);
+        assert_eq!(stat_sessions(vec![file_path.clone()], Some(since.clone()))[0].changed, Some(false));
+        std::fs::write(&path, [line("u1"), line("u2"), line("u3"), line("u4")].join("\n")).unwrap();
+        let stats = stat_sessions(vec![file_path.clone(), missing], Some(since));
+        assert_eq!((stats[0].changed, stats[1].changed), (Some(true), Some(true)));
+        assert!(!stats[0].lines_exact && (3..=5).contains(&stats[0].estimated_lines.unwrap()));
+    }
+
+    #[test]
+    fn test_index_cache_evicts_least_recently_used() {
+        let index = || FileIndex { len: 1, modified: None, offsets: HashMap::new(), lines: 1, checkpoints: vec![0] };
+        let mut cache = IndexCache::default();
+        for i in 0..MAX_CACHED_INDEXES {
+            cache.insert(&format!("f{}", i), index());
+        }
+        assert!(cache.current("f0", (1, None)).is_some());
+        assert!(cache.current("f1", (2, None)).is_none());
+        cache.insert("new", index());
+        assert_eq!(cache.files.len(), MAX_CACHED_INDEXES);
+        assert!(cache.files.contains_key("f0") && !cache.files.contains_key("f1"));
+    }
+}
@@ -0,0 +1,305 @@
+// ============================================
+// ISSUE LINKS
+// Sessions connected to tracker issues and pull requests: links set by
+// hand plus URLs found in the conversation (prompts, replies and tool
+// output such as `gh pr create`), kept in the sidecar store
+// ============================================
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use regex::{Captures, Regex};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{internal, invalid_argument, ErrorCode, HubError};
+use crate::store::{now_millis, read_store, update_store, MetadataStore};
+use crate::{for_each_entry, tool_result_text, ContentItem};
+
+/// Issue and pull request URLs of the supported trackers (hosts in any case)
+const TRACKERS: &[(&str, &str)] = &[
+    ("github", r"(?i:https?://(?:www\.)?github\.com)/([\w.-]+)/([\w.-]+)/(issues|pull)/(\d+)"),
+    ("gitlab", r"(?i:https?://)((?i:(?:[\w-]+\.)*gitlab(?:\.[\w-]+)+))/([\w.-]+(?:/[\w.-]+)+)/-/(issues|merge_requests)/(\d+)"),
+    ("jira", r"(?i:https?://)((?i:[\w-]+\.atlassian\.net))/browse/([A-Z][A-Z0-9_]+-\d+)"),
+    ("linear", r"(?i:https?://linear\.app)/([\w-]+)/issue/([A-Z][A-Z0-9]+-\d+)"),
+];
+
+/// A session's link to an issue or pull request
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+pub struct IssueLink {
+    pub session_id: String,
+    /// Canonical form, e.g. "https://github.com/owner/repo/pull/12"
+    pub url: String,
+    /// "github", "gitlab", "jira", "linear" or "other"
+    pub tracker: String,
+    /// "issue", "pull_request" or "merge_request"
+    pub kind: String,
+    /// Short reference: "owner/repo#12", "group/app!3", "PROJ-123"
+    pub key: String,
+    /// "manual" (link_session_to_issue) or "detected" (mentioned in the session)
+    pub source: String,
+    /// uuid of the entry that first mentions a detected link
+    #[serde(default)]
+    pub message_id: Option<String>,
+    pub created_at: i64,
+}
+
+/// An issue URL in canonical form, before it is linked to a session
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct IssueRef {
+    pub url: String,
+    pub tracker: &'static str,
+    pub kind: &'static str,
+    pub key: String,
+}
+
+impl IssueRef {
+    fn link(self, session_id: &str, source: &str, message_id: Option<String>, now: i64) -> IssueLink {
+        IssueLink {
+            session_id: session_id.to_string(),
+            url: self.url,
+            tracker: self.tracker.to_string(),
+            kind: self.kind.to_string(),
+            key: self.key,
+            source: source.to_string(),
+            message_id,
+            created_at: now,
+        }
+    }
+}
+
+pub(crate) struct IssuePatterns {
+    trackers: Vec<(&'static str, Regex)>,
+}
+
+impl IssuePatterns {
+    pub(crate) fn new() -> Result<IssuePatterns> {
+        let trackers = TRACKERS
+            .iter()
+            .map(|&(tracker, pattern)| Regex::new(pattern).map(|regex| (tracker, regex)))
+            .collect::<std::result::Result<_, _>>()
+            .map_err(|e| internal(format!("Invalid issue pattern: {}", e)))?;
+        Ok(IssuePatterns { trackers })
+    }
+
+    fn canonical(tracker: &'static str, caps: &Captures) -> IssueRef {
+        let cap = |i: usize| caps.get(i).map_or("", |m| m.as_str());
+        match tracker {
+            "github" => {
+                let kind = if cap(3) == "pull" { "pull_request" } else { "issue" };
+                let (owner, repo) = (cap(1).to_lowercase(), cap(2).to_lowercase());
+                let url = format!("https://github.com/{}/{}/{}/{}", owner, repo, cap(3), cap(4));
+                IssueRef { url, tracker, kind, key: format!("{}/{}#{}", owner, repo, cap(4)) }
+            }
+            "gitlab" => {
+                let (kind, sigil) = if cap(3) == "issues" { ("issue", '#') } else { ("merge_request", '!') };
+                let url = format!("https://{}/{}/-/{}/{}", cap(1).to_lowercase(), cap(2), cap(3), cap(4));
+                IssueRef { url, tracker, kind, key: format!("{}{}{}", cap(2), sigil, cap(4)) }
+            }
+            "jira" => IssueRef { url: format!("https://{}/browse/{}", cap(1).to_lowercase(), cap(2)), tracker, kind: "issue", key: cap(2).to_string() },
+            _ => IssueRef { url: format!("https://linear.app/{}/issue/{}", cap(1), cap(2)), tracker, kind: "issue", key: cap(2).to_string() },
+        }
+    }
+
+    /// Canonical form of any http(s) URL; ones of unknown trackers lose
+    /// only their fragment and trailing slash
+    pub(crate) fn parse(&self, url: &str) -> Option<IssueRef> {
+        let url = url.trim();
+        for (tracker, regex) in &self.trackers {
+            if let Some(caps) = regex.captures(url).filter(|c| c.get(0).is_some_and(|m| m.start() == 0)) {
+                return Some(Self::canonical(tracker, &caps));
+            }
+        }
+        let lower = url.to_lowercase();
+        if !(lower.starts_with("https://") || lower.starts_with("http://")) || url.contains(char::is_whitespace) {
+            return None;
+        }
+        let url = url.split('#').next().unwrap_or(url).trim_end_matches('/').to_string();
+        Some(IssueRef { key: url.clone(), url, tracker: "other", kind: "issue" })
+    }
+
+    /// Issue URLs of the known trackers in text, in order
+    pub(crate) fn find_all(&self, text: &str) -> Vec<IssueRef> {
+        let mut found: Vec<(usize, IssueRef)> = self
+            .trackers
+            .iter()
+            .flat_map(|(tracker, regex)| regex.captures_iter(text).map(|caps| (caps.get(0).map_or(0, |m| m.start()), Self::canonical(tracker, &caps))))
+            .collect();
+        found.sort_by_key(|(start, _)| *start);
+        found.into_iter().map(|(_, issue)| issue).collect()
+    }
+}
+
+impl MetadataStore {
+    /// Link a session to an issue by hand; a detected link becomes manual
+    pub(crate) fn link_issue(&mut self, session_id: &str, issue: IssueRef, now: i64) -> IssueLink {
+        match self.issue_links.iter_mut().find(|l| l.session_id == session_id && l.url == issue.url) {
+            Some(existing) => {
+                existing.source = "manual".to_string();
+                existing.clone()
+            }
+            None => {
+                let link = issue.link(session_id, "manual", None, now);
+                self.issue_links.push(link.clone());
+                link
+            }
+        }
+    }
+
+    /// Returns false if the session was not linked to the issue
+    pub fn unlink_issue(&mut self, session_id: &str, url: &str) -> bool {
+        let before = self.issue_links.len();
+        self.issue_links.retain(|l| !(l.session_id == session_id && l.url == url));
+        self.issue_links.len() != before
+    }
+
+    /// Replace the detected links of a session; manual ones are kept
+    pub fn set_detected_issues(&mut self, session_id: &str, links: Vec<IssueLink>) {
+        self.issue_links.retain(|l| l.session_id != session_id || l.source == "manual");
+        for link in links {
+            if !self.issue_links.iter().any(|l| l.session_id == session_id && l.url == link.url) {
+                self.issue_links.push(link);
+            }
+        }
+    }
+
+    /// Links of one session, in the order they were added
+    pub fn session_issues(&self, session_id: &str) -> Vec<IssueLink> {
+        self.issue_links.iter().filter(|l| l.session_id == session_id).cloned().collect()
+    }
+
+    /// Session ids linked to the issue, manually or by mention
+    pub fn find_sessions_for_issue(&self, url: &str) -> Vec<String> {
+        let mut sessions: Vec<String> = Vec::new();
+        for link in self.issue_links.iter().filter(|l| l.url == url) {
+            if !sessions.contains(&link.session_id) {
+                sessions.push(link.session_id.clone());
+            }
+        }
+        sessions
+    }
+}
+
+fn parse_url(patterns: &IssuePatterns, url: &str) -> Result<IssueRef> {
+    patterns.parse(url).ok_or_else(|| invalid_argument(format!("Not an http(s) URL: {}", url)))
+}
+
+/// Session id and the issue links mentioned in a session file
+pub(crate) fn detect_links(file_path: &str, patterns: &IssuePatterns, now: i64) -> Result<(Option<String>, Vec<IssueLink>)> {
+    let mut session_id: Option<String> = None;
+    let mut found: Vec<(IssueRef, Option<String>)> = Vec::new();
+    for_each_entry(file_path, |entry| {
+        if session_id.is_none() {
+            session_id = entry.session_id.clone();
+        }
+        let Some(message) = &entry.message else { return };
+        for item in &message.content {
+            let text = match item {
+                ContentItem::Text { text } => text.clone(),
+                ContentItem::ToolUse { input, .. } => input.to_string(),
+                ContentItem::ToolResult { content, .. } => tool_result_text(content),
+                _ => continue,
+            };
+            for issue in patterns.find_all(&text) {
+                if !found.iter().any(|(seen, _)| seen.url == issue.url) {
+                    found.push((issue, entry.uuid.clone()));
+                }
+            }
+        }
+    })?;
+    let sid = session_id.clone().unwrap_or_default();
+    Ok((session_id, found.into_iter().map(|(issue, uuid)| issue.link(&sid, "detected", uuid, now)).collect()))
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Link a session to an issue or pull request URL (stored canonical)
+#[napi(catch_unwind)]
+pub fn link_session_to_issue(session_id: String, url: String) -> Result<IssueLink> {
+    let issue = parse_url(&IssuePatterns::new()?, &url)?;
+    let now = now_millis();
+    update_store(|store| store.link_issue(&session_id, issue, now))
+}
+
+/// Remove a session's link to an issue. Returns false if there was none
+#[napi(catch_unwind)]
+pub fn unlink_session_from_issue(session_id: String, url: String) -> Result<bool> {
+    let issue = parse_url(&IssuePatterns::new()?, &url)?;
+    update_store(|store| store.unlink_issue(&session_id, &issue.url))
+}
+
+/// Manual and detected issue links of a session
+#[napi(catch_unwind)]
+pub fn get_session_issues(session_id: String) -> Result<Vec<IssueLink>> {
+    read_store(|store| store.session_issues(&session_id))
+}
+
+/// Session ids linked to an issue; any URL form of it matches
+#[napi(catch_unwind)]
+pub fn find_sessions_for_issue(url: String) -> Result<Vec<String>> {
+    let issue = parse_url(&IssuePatterns::new()?, &url)?;
+    read_store(|store| store.find_sessions_for_issue(&issue.url))
+}
+
+/// Find issue and pull request URLs mentioned in a session file and store
+/// them as its detected links
+#[napi(catch_unwind)]
+pub fn detect_session_issues(file_path: String) -> Result<Vec<IssueLink>> {
+    let (session_id, links) = detect_links(&file_path, &IssuePatterns::new()?, now_millis())?;
+    let session_id = session_id
+        .ok_or_else(|| Error::from(HubError::new(ErrorCode::InvalidArgument, "Session file has no sessionId").path(&file_path)))?;
+    update_store(|store| {
+        store.set_detected_issues(&session_id, links);
+        store.session_issues(&session_id)
+    })
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_issue_links() {
+        let patterns = IssuePatterns::new().unwrap();
+        let pr = patterns.parse("https://GitHub.com/Me/App/pull/12/files#diff").unwrap();
+        assert_eq!((pr.url.as_str(), pr.kind, pr.key.as_str()), ("https://github.com/me/app/pull/12", "pull_request", "me/app#12"));
+        let mr = patterns.parse("https://gitlab.com/group/sub/app/-/merge_requests/3").unwrap();
+        assert_eq!((mr.tracker, mr.key.as_str()), ("gitlab", "group/sub/app!3"));
+        assert_eq!(patterns.parse("https://acme.atlassian.net/browse/PROJ-42?focus=1").unwrap().key, "PROJ-42");
+        assert_eq!(patterns.parse("https://tracker.example/t/9/#top").unwrap().url, "https://tracker.example/t/9");
+        assert!(patterns.parse("not a url").is_none());
+
+        let dir = TempDir::new("issues");
+        let path = dir.join("s.jsonl");
+        let lines = [
+            r#"{"type":"user","uuid":"u1","sessionId":"s","message":{"role":"user","content":"Fix https://github.com/me/app/issues/7 and linear.app is https://linear.app/acme/issue/ENG-5/slug"}}"#,
+            r#"{"type":"user","uuid":"u2","sessionId":"s","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"https://github.com/me/app/pull/8\n"}]}}"#,
+            r#"{"type":"assistant","uuid":"a1","sessionId":"s","message":{"role":"assistant","content":"Done with https://github.com/me/app/issues/7."}}"#,
+        ];
+        std::fs::write(&path, lines.join("\n")).unwrap();
+        let (session_id, links) = detect_links(&path.display().to_string(), &patterns, 1).unwrap();
+        assert_eq!(session_id.as_deref(), Some("s"));
+        let keys: Vec<(&str, Option<&str>)> = links.iter().map(|l| (l.key.as_str(), l.message_id.as_deref())).collect();
+        assert_eq!(keys, vec![("me/app#7", Some("u1")), ("ENG-5", Some("u1")), ("me/app#8", Some("u2"))]);
+
+        let mut store = MetadataStore::default();
+        store.link_issue("s", patterns.parse("https://github.com/me/app/pull/8").unwrap(), 0);
+        store.link_issue("other", patterns.parse("https://github.com/me/app/issues/7").unwrap(), 0);
+        store.set_detected_issues("s", links.clone());
+        // Detecting again replaces the detected links only
+        store.set_detected_issues("s", links[..1].to_vec());
+        let sources: Vec<String> = store.session_issues("s").into_iter().map(|l| l.source).collect();
+        assert_eq!(sources, vec!["manual", "detected"]);
+        assert_eq!(store.find_sessions_for_issue("https://github.com/me/app/issues/7"), vec!["other", "s"]);
+        assert!(store.unlink_issue("s", "https://github.com/me/app/pull/8"));
+        assert!(!store.unlink_issue("s", "https://github.com/me/app/pull/8"));
+    }
+}
@@ -0,0 +1,182 @@
+// ============================================
+// LANGUAGE DETECTION
+// Natural language of a session's prompts and of its replies, guessed
+// per message with whatlang from the prose (code and injected tags
+// removed) and weighted by length. Backs the lang: search filter too
+// ============================================
+
+use std::collections::HashMap;
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use schemars::JsonSchema;
+use whatlang::Lang;
+
+use crate::errors::invalid_argument;
+use crate::title::{is_substantive_prompt, strip_code_blocks, strip_tag_blocks};
+use crate::{for_each_entry, ContentItem, RawLogEntry};
+
+/// Prose shorter than this ("ok", "yes", a file name) is not classified
+const MIN_DETECT_CHARS: usize = 20;
+
+/// Characters of a message given to the detector; more does not help it
+const MAX_DETECT_CHARS: usize = 2000;
+
+/// One language of a role's messages
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+pub struct LanguageShare {
+    /// ISO 639-3, e.g. "eng", "vie", "jpn"
+    pub code: String,
+    /// English name, e.g. "Vietnamese"
+    pub name: String,
+    pub messages: u32,
+    /// Fraction of the role's classified text, by characters
+    pub share: f64,
+}
+
+/// Result of detect_session_languages, also on full session summaries
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+pub struct SessionLanguages {
+    /// Languages of the prompts typed by the user, largest share first
+    pub user: Vec<LanguageShare>,
+    /// Languages of the main-thread replies, largest share first
+    pub assistant: Vec<LanguageShare>,
+    /// Code of the main prompt language, else of the replies; None when
+    /// no message was long enough to tell
+    pub primary: Option<String>,
+}
+
+/// A language given by ISO 639-3 code ("vie") or English name ("vietnamese")
+pub(crate) fn parse_language(value: &str) -> Result<Lang> {
+    let value = value.trim().to_lowercase();
+    Lang::from_code(value.as_str())
+        .or_else(|| Lang::all().iter().copied().find(|lang| lang.eng_name().to_lowercase() == value))
+        .ok_or_else(|| invalid_argument(format!("Unknown language: {}", value)))
+}
+
+/// Language of a text and the characters it was judged on, None when the
+/// text is too short or too mixed to tell
+pub(crate) fn detect(text: &str) -> Option<(Lang, usize)> {
+    let prose = strip_tag_blocks(&strip_code_blocks(text));
+    let prose: String = prose.trim().chars().take(MAX_DETECT_CHARS).collect();
+    let chars = prose.chars().filter(|c| c.is_alphabetic()).count();
+    if chars < MIN_DETECT_CHARS {
+        return None;
+    }
+    whatlang::detect(&prose).filter(|info| info.is_reliable()).map(|info| (info.lang(), chars))
+}
+
+/// Text blocks only; thinking is left out as it often differs from the reply
+fn message_text(content: &[ContentItem]) -> String {
+    content
+        .iter()
+        .filter_map(|item| match item {
+            ContentItem::Text { text } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<&str>>()
+        .join("\n\n")
+}
+
+fn shares(tally: HashMap<Lang, (u32, usize)>) -> Vec<LanguageShare> {
+    let total: usize = tally.values().map(|(_, chars)| chars).sum();
+    let mut shares: Vec<(Lang, u32, usize)> = tally.into_iter().map(|(lang, (messages, chars))| (lang, messages, chars)).collect();
+    shares.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.code().cmp(b.0.code())));
+    shares
+        .into_iter()
+        .map(|(lang, messages, chars)| LanguageShare {
+            code: lang.code().to_string(),
+            name: lang.eng_name().to_string(),
+            messages,
+            share: chars as f64 / total as f64,
+        })
+        .collect()
+}
+
+/// Languages of a session, message by message
+#[derive(Default)]
+pub(crate) struct LanguageTally {
+    /// Messages and characters per language
+    user: HashMap<Lang, (u32, usize)>,
+    assistant: HashMap<Lang, (u32, usize)>,
+}
+
+impl LanguageTally {
+    pub(crate) fn observe(&mut self, entry: &RawLogEntry) {
+        let tally = match entry.entry_type.as_str() {
+            "user" if is_substantive_prompt(entry) => &mut self.user,
+            "assistant" if entry.is_sidechain != Some(true) => &mut self.assistant,
+            _ => return,
+        };
+        let Some(message) = &entry.message else { return };
+        if let Some((lang, chars)) = detect(&message_text(&message.content)) {
+            let counts = tally.entry(lang).or_default();
+            counts.0 += 1;
+            counts.1 += chars;
+        }
+    }
+
+    pub(crate) fn finish(self) -> SessionLanguages {
+        let user = shares(self.user);
+        let assistant = shares(self.assistant);
+        let primary = user.first().or(assistant.first()).map(|share| share.code.clone());
+        SessionLanguages { user, assistant, primary }
+    }
+}
+
+pub(crate) fn session_languages(file_path: &str) -> Result<SessionLanguages> {
+    let mut tally = LanguageTally::default();
+    for_each_entry(file_path, |entry| tally.observe(&entry))?;
+    Ok(tally.finish())
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Languages of a session's prompts and replies (also on full summaries)
+#[napi(catch_unwind)]
+pub fn detect_session_languages(file_path: String) -> Result<SessionLanguages> {
+    session_languages(&file_path)
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_session_languages() {
+        let dir = TempDir::new("language");
+        let path = dir.join("s.jsonl");
+        let line = |kind: &str, text: &str| {
+            format!(r#"{{"type":"{}","sessionId":"s","message":{{"role":"{}","content":[{{"type":"text","text":"{}"}}]}}}}"#, kind, kind, text)
+        };
+        let lines = [
+            line("user", "Làm thế nào để sửa lỗi đăng nhập trong ứng dụng của tôi? Người dùng không thể vào được."),
+            line("user", "ok"),
+            line("assistant", "The login fails because the session cookie is never set after the redirect, so every request looks anonymous."),
+            line("user", "Cảm ơn bạn rất nhiều, bây giờ mọi thứ đã hoạt động tốt rồi.\\n```\\nfn main() {}\\n```"),
+        ];
+        std::fs::write(&path, lines.join("\n")).unwrap();
+
+        let languages = session_languages(&path.display().to_string()).unwrap();
+        assert_eq!(languages.user.len(), 1);
+        assert_eq!((languages.user[0].code.as_str(), languages.user[0].messages, languages.user[0].share), ("vie", 2, 1.0));
+        assert_eq!(languages.assistant[0].name, "English");
+        assert_eq!(languages.primary.as_deref(), Some("vie"));
+
+        assert_eq!(parse_language("VIE").unwrap(), Lang::Vie);
+        assert_eq!(parse_language("japanese").unwrap(), Lang::Jpn);
+        assert!(parse_language("klingon").is_err());
+        assert_eq!(detect("too short"), None);
+    }
+}
@@ -3,175 +3,110 @@
 // Based on claude-code-history-viewer research
 // ============================================
 
-use std::fs::File;
 use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
 
 use napi::bindgen_prelude::*;
 use napi_derive::napi;
-use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::errors::{io_error, unsupported_format, ErrorCode, HubError};
+use crate::paths::{long_path, open_file};
+use crate::reader::lossy_lines;
+
+pub mod analyzers;
+pub mod ansi;
+pub mod attachments;
+pub mod audit;
+pub mod authors;
+pub mod branches;
+pub mod budget;
+pub mod ci;
+pub mod cleanup;
+pub mod config;
+pub mod conflicts;
+pub mod cursor;
+pub mod delete;
+pub mod duplicates;
+pub mod errors;
+pub mod events;
+pub mod export;
+pub mod failures;
+pub mod fast;
+pub mod file_info;
+pub mod follow;
+pub mod graph;
+pub mod handoffs;
+pub mod histogram;
+pub mod hooks;
+pub mod images;
+pub mod index;
+pub mod issues;
+pub mod language;
+pub mod live;
+pub mod logging;
+pub mod near_duplicates;
+pub mod packed;
+pub mod paths;
+pub mod payload;
+pub mod permissions;
+pub mod pricing;
+pub mod prompts;
+pub mod rate;
+pub mod reader;
+pub mod replay;
+pub mod risk;
+pub mod safe_write;
+pub mod saved_search;
+pub mod scan;
+pub mod schema;
+pub mod search;
+pub mod sessions;
+pub mod sidechains;
+pub mod similarity;
+pub mod snapshot;
+pub mod stats;
+pub mod storage;
+pub mod store;
+pub mod streaks;
+pub mod tag_rules;
+pub mod tags;
+pub mod terms;
+#[cfg(test)]
+mod test_util;
+pub mod time;
+pub mod title;
+pub mod tools;
+pub mod top;
+pub mod topics;
+pub mod tree;
+pub mod usage;
+pub mod watch;
+pub mod web;
 
 // ============================================
 // ENHANCED DATA STRUCTURES
 // ============================================
 
-/// Content item variants - supports all Claude content types
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type")]
-pub enum ContentItem {
-    #[serde(rename = "text")]
-    Text {
-        text: String
-    },
-
-    #[serde(rename = "thinking")]
-    Thinking {
-        thinking: String,
-        #[serde(skip_serializing_if = "Option::is_none")]
-        signature: Option<String>,
-    },
-
-    #[serde(rename = "tool_use")]
-    ToolUse {
-        id: String,
-        name: String,
-        input: serde_json::Value,
-    },
-
-    #[serde(rename = "tool_result")]
-    ToolResult {
-        tool_use_id: String,
-        content: serde_json::Value,
-        #[serde(skip_serializing_if = "Option::is_none")]
-        is_error: Option<bool>,
-    },
-
-    #[serde(rename = "image")]
-    Image {
-        source: ImageSource,
-    },
-}
-
-/// Image source data
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ImageSource {
-    #[serde(rename = "type")]
-    pub source_type: String,  // "base64"
-    pub media_type: String,   // "image/png", "image/jpeg", etc.
-    pub data: String,         // base64 encoded data
-}
-
-/// Token usage with cache support
-#[napi(object)]
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TokenUsage {
-    pub input_tokens: i32,
-    pub output_tokens: i32,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub cache_creation_input_tokens: Option<i32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub cache_read_input_tokens: Option<i32>,
-}
-
-/// Message object - supports both string and array content
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MessageObject {
-    pub role: String,
-
-    // Content can be string (user) or array (assistant)
-    #[serde(deserialize_with = "deserialize_content")]
-    pub content: Vec<ContentItem>,
-
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub model: Option<String>,
-
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub id: Option<String>,
-
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub stop_reason: Option<String>,
-
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub usage: Option<TokenUsage>,
-}
-
-/// Custom deserializer for content field (handles both string and array)
-fn deserialize_content<'de, D>(deserializer: D) -> std::result::Result<Vec<ContentItem>, D::Error>
-where
-    D: serde::Deserializer<'de>,
-{
-    use serde::de::Error;
-
-    let value: serde_json::Value = serde::Deserialize::deserialize(deserializer)?;
-
-    match value {
-        // String content (user messages)
-        serde_json::Value::String(s) => Ok(vec![ContentItem::Text { text: s }]),
-
-        // Array content (assistant messages)
-        serde_json::Value::Array(arr) => {
-            let items: std::result::Result<Vec<ContentItem>, _> = arr
-                .into_iter()
-                .map(|v| serde_json::from_value(v).map_err(Error::custom))
-                .collect();
-            items
-        }
-
-        _ => Err(Error::custom("Content must be string or array")),
-    }
-}
-
-/// Raw log entry from JSONL
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct RawLogEntry {
-    #[serde(rename = "type")]
-    pub entry_type: String,
-
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub uuid: Option<String>,
-
-    #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(rename = "parentUuid")]
-    pub parent_uuid: Option<String>,
-
-    #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(rename = "sessionId")]
-    pub session_id: Option<String>,
-
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub timestamp: Option<String>,
-
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub message: Option<MessageObject>,
-
-    // Additional fields for completeness
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub summary: Option<String>,
-
-    #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(rename = "leafUuid")]
-    pub leaf_uuid: Option<String>,
-
-    #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(rename = "isSidechain")]
-    pub is_sidechain: Option<bool>,
-
-    #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(rename = "userType")]
-    pub user_type: Option<String>,
-
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub cwd: Option<String>,
-
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub version: Option<String>,
-}
+// Log entry types and content helpers live in history-core so the WASM
+// build and the CLI parse exactly the same way
+pub use history_core::{ContentItem, ImageSource, MessageObject, RawLogEntry, TokenUsage};
+pub(crate) use history_core::{
+    canonical_message_id, entry_canonical_id, extract_text_content, has_thinking, has_tool_use, parse_jsonl_line, tool_result_text,
+};
 
 /// Enhanced Claude message with full content support
 #[napi(object)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(rename_all = "camelCase")]
 pub struct ClaudeMessage {
     pub message_id: String,
+    // Hash of sessionId + uuid (text + timestamp without a uuid), stable across
+    // re-parses and merged files; accepted wherever a message id is
+    pub canonical_id: String,
     pub session_id: String,
     pub role: String,
     pub content: String,  // Main text content (merged)
@@ -179,6 +114,9 @@ pub struct ClaudeMessage {
 
     // Content details (serialized as JSON)
     pub raw_content: String,  // Full content array as JSON
+    // Where raw_content sits in the shared Buffer of parse_claude_session_with_raw_buffer
+    pub raw_content_offset: Option<u32>,
+    pub raw_content_length: Option<u32>,
     pub has_thinking: bool,
     pub has_tool_use: bool,
     pub has_images: bool,
@@ -197,11 +135,125 @@ pub struct ClaudeMessage {
     // Additional metadata
     pub is_sidechain: Option<bool>,
     pub user_type: Option<String>,
+
+    // True when the timestamp was missing/invalid and inferred from neighbors
+    pub timestamp_inferred: bool,
+
+    // Pasted text, images and @-mentioned files (user messages only)
+    pub attachments: Vec<attachments::AttachmentInfo>,
+
+    // True when content was cut by ParseOptions.max_text_chars
+    pub content_truncated: bool,
+
+    // Size metrics (bytes as stored in the log, images base64-encoded)
+    pub content_bytes: i64,
+    pub tool_result_bytes: i64,
+    pub image_bytes: i64,
+}
+
+impl From<history_core::Message> for ClaudeMessage {
+    fn from(message: history_core::Message) -> Self {
+        ClaudeMessage {
+            message_id: message.message_id,
+            canonical_id: message.canonical_id,
+            session_id: message.session_id,
+            role: message.role,
+            content: message.content,
+            timestamp: message.timestamp,
+            raw_content: message.raw_content,
+            raw_content_offset: None,
+            raw_content_length: None,
+            has_thinking: message.has_thinking,
+            has_tool_use: message.has_tool_use,
+            has_images: message.has_images,
+            parent_id: message.parent_id,
+            model: message.model,
+            stop_reason: message.stop_reason,
+            input_tokens: message.input_tokens,
+            output_tokens: message.output_tokens,
+            cache_creation_tokens: message.cache_creation_tokens,
+            cache_read_tokens: message.cache_read_tokens,
+            is_sidechain: message.is_sidechain,
+            user_type: message.user_type,
+            timestamp_inferred: message.timestamp_inferred,
+            attachments: Vec::new(),
+            content_truncated: false,
+            content_bytes: message.content_bytes,
+            tool_result_bytes: message.tool_result_bytes,
+            image_bytes: message.image_bytes,
+        }
+    }
+}
+
+/// Options for parse_claude_session
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    /// Fill missing or invalid timestamps from neighbouring messages
+    pub infer_timestamps: Option<bool>,
+    /// Re-sort messages by timestamp (stable, file order breaks ties)
+    pub sort_by_timestamp: Option<bool>,
+    /// Files at least this many bytes are memory-mapped (default 64 MiB, negative = never)
+    pub mmap_threshold_bytes: Option<i64>,
+    /// Files at least this many bytes are parsed in parallel chunks (default 32 MiB, negative = never)
+    pub parallel_threshold_bytes: Option<i64>,
+    /// Longer lines (e.g. huge base64 images) are skipped unread (default 32 MiB, negative = unlimited)
+    pub max_line_bytes: Option<i64>,
+    /// Stop reading once the kept messages' content adds up to this many bytes;
+    /// the message that did not fit is reported as skipped (default unlimited).
+    /// Parallel reads parse no more than this many bytes of the file ahead
+    pub memory_budget_bytes: Option<i64>,
+    /// Build raw_content (default true); false leaves it empty and skips serializing it
+    pub include_raw_content: Option<bool>,
+    /// Cut content to this many characters for list views (see get_full_message)
+    pub max_text_chars: Option<u32>,
+    /// For files still being written: "none" (default), "length" (stop at the
+    /// last complete line as of the start) or "copy" (the same on a temporary copy)
+    pub snapshot: Option<String>,
+    /// Build content from every block in order, tool calls and results included,
+    /// each under a "[#index type]" header (default false: text and thinking only)
+    pub ordered_content: Option<bool>,
+    /// "full" (default) or "summary": image blocks in raw_content become
+    /// { type, index, media_type, byte_size, sha256 } (see get_image_data)
+    pub image_mode: Option<String>,
+}
+
+/// A line parse_claude_session left out, and why
+#[napi(object)]
+#[derive(Debug, Clone, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+pub struct SkippedLine {
+    /// 1-based line number
+    pub line_number: u32,
+    pub bytes: i64,
+    /// "line_too_long", "parse_error" or "memory_budget"
+    pub reason: String,
+}
+
+/// Messages plus the lines that were skipped on the way
+#[napi(object)]
+#[derive(Debug, Clone, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+pub struct ParsedSession {
+    pub messages: Vec<ClaudeMessage>,
+    pub skipped_lines: Vec<SkippedLine>,
+    /// Snapshot strategy used: "none", "length" or "copy" ("copy" falls back
+    /// to "length" when no temporary copy can be written)
+    pub snapshot: String,
+}
+
+const DEFAULT_MAX_LINE_BYTES: i64 = 32 * 1024 * 1024;
+
+enum LineOutcome {
+    /// Line index and the message parsed from it
+    Message(usize, Box<ClaudeMessage>),
+    Skipped(SkippedLine),
 }
 
 /// Session summary
 #[napi(object)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
 pub struct ClaudeSession {
     pub session_id: String,
     pub file_path: String,
@@ -214,151 +266,260 @@ pub struct ClaudeSession {
     // Enhanced stats
     pub total_input_tokens: Option<i32>,
     pub total_output_tokens: Option<i32>,
+    // The totals split into the main thread and subagent (sidechain) messages
+    pub main_input_tokens: i32,
+    pub main_output_tokens: i32,
+    pub sidechain_input_tokens: i32,
+    pub sidechain_output_tokens: i32,
     pub has_thinking: bool,
     pub has_tool_use: bool,
 
     // Project info
     pub cwd: Option<String>,
+
+    // Previews for the session list
+    pub first_user_prompt_excerpt: Option<String>,
+    pub last_assistant_excerpt: Option<String>,
+
+    // Heuristic health (errors, interruptions, retries, compactions).
+    // None when the summary came from the fast scan
+    pub health: Option<failures::SessionHealth>,
+
+    // Per-role text sizes, None when the summary came from the fast scan
+    pub user_stats: Option<stats::RoleStats>,
+    pub assistant_stats: Option<stats::RoleStats>,
+
+    // First to last timestamp, and the same without idle gaps
+    pub duration_seconds: Option<i64>,
+    pub active_seconds: Option<i64>,
+
+    // Tool calls with no result: a live, interrupted or truncated run.
+    // None when the summary came from the fast scan
+    pub pending_tool_uses: Option<Vec<live::PendingToolUse>>,
+
+    // Languages of the prompts and replies. None when the summary came
+    // from the fast scan
+    pub languages: Option<language::SessionLanguages>,
+}
+
+/// Options for get_session_summary
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct SummaryOptions {
+    /// Only read counts, timestamps, tokens, flags and cwd (no excerpts or health)
+    pub fast: Option<bool>,
+    /// Gaps between entries longer than this are idle, not active time (default 300)
+    pub idle_threshold_seconds: Option<u32>,
 }
 
 // ============================================
 // PARSING FUNCTIONS
 // ============================================
 
-/// Parse a JSONL line with better error handling
-fn parse_jsonl_line(line: &str) -> std::result::Result<RawLogEntry, serde_json::Error> {
-    serde_json::from_str(line)
+/// Convert RawLogEntry to ClaudeMessage with full content support
+pub(crate) fn entry_to_message(entry: RawLogEntry) -> Option<ClaudeMessage> {
+    entry_to_message_with(entry, true)
 }
 
-/// Extract all text content from content array
-fn extract_text_content(content_items: &[ContentItem]) -> String {
-    content_items
-        .iter()
-        .filter_map(|item| match item {
-            ContentItem::Text { text } => Some(text.clone()),
-            ContentItem::Thinking { thinking, .. } => Some(format!("[Thinking]\n{}", thinking)),
-            _ => None,
-        })
-        .collect::<Vec<String>>()
-        .join("\n\n")
-}
+/// entry_to_message, optionally without the raw_content JSON
+pub(crate) fn entry_to_message_with(entry: RawLogEntry, include_raw_content: bool) -> Option<ClaudeMessage> {
+    let attachments = match &entry.message {
+        Some(message) if entry.entry_type == "user" => attachments::detect(&message.content),
+        _ => Vec::new(),
+    };
 
-/// Check if content has thinking
-fn has_thinking(content_items: &[ContentItem]) -> bool {
-    content_items.iter().any(|item| matches!(item, ContentItem::Thinking { .. }))
+    history_core::entry_to_message_with(entry, include_raw_content)
+        .map(|message| ClaudeMessage { attachments, ..ClaudeMessage::from(message) })
 }
 
-/// Check if content has tool use
-fn has_tool_use(content_items: &[ContentItem]) -> bool {
-    content_items.iter().any(|item| matches!(item, ContentItem::ToolUse { .. }))
+/// Cut content to max_chars characters, flagging the message as truncated
+pub(crate) fn truncate_content(message: &mut ClaudeMessage, max_chars: usize) {
+    if let Some((cut, _)) = message.content.char_indices().nth(max_chars) {
+        message.content.truncate(cut);
+        message.content_truncated = true;
+    }
 }
 
-/// Check if content has images
-fn has_images(content_items: &[ContentItem]) -> bool {
-    content_items.iter().any(|item| matches!(item, ContentItem::Image { .. }))
+/// Open a session file and feed every parseable entry to `f` (bad lines are skipped)
+pub(crate) fn for_each_entry(file_path: &str, mut f: impl FnMut(RawLogEntry)) -> Result<()> {
+    reader::for_each_line(file_path, reader::DEFAULT_MMAP_THRESHOLD, |_, line| {
+        if line.trim().is_empty() {
+            return;
+        }
+
+        if let Ok(entry) = fast::parse_entry(line) {
+            f(entry);
+        }
+    })
 }
 
-/// Convert RawLogEntry to ClaudeMessage with full content support
-fn entry_to_message(entry: RawLogEntry) -> Option<ClaudeMessage> {
-    // Only process user and assistant messages
-    if entry.entry_type != "user" && entry.entry_type != "assistant" {
-        return None;
+/// Collect all .jsonl session files under a root dir (e.g. ~/.claude/projects)
+/// with the configure() scan settings. Skipped paths are logged
+pub(crate) fn collect_session_files(root_dir: &str) -> Result<Vec<PathBuf>> {
+    let scanned = scan::scan(Path::new(root_dir), config::follow_symlinks(), config::scan_timeout_ms())?;
+    for skipped in &scanned.skipped {
+        tracing::warn!(path = skipped.path.as_str(), reason = skipped.reason.as_str(), "Skipped path while scanning: {}", skipped.message.as_deref().unwrap_or(""));
     }
+    Ok(scanned.files)
+}
 
-    let message = entry.message?;
-
-    // Extract text content
-    let content = extract_text_content(&message.content);
-
-    // Serialize full content as JSON for frontend
-    let raw_content = serde_json::to_string(&message.content).unwrap_or_default();
-
-    // Detect content features
-    let has_thinking_flag = has_thinking(&message.content);
-    let has_tool_use_flag = has_tool_use(&message.content);
-    let has_images_flag = has_images(&message.content);
-
-    // Get token usage
-    let (input_tokens, output_tokens, cache_creation, cache_read) = if let Some(usage) = message.usage {
-        (
-            Some(usage.input_tokens),
-            Some(usage.output_tokens),
-            usage.cache_creation_input_tokens,
-            usage.cache_read_input_tokens,
-        )
+/// A single session file, or every session file under a directory
+pub(crate) fn resolve_session_files(file_path_or_root: &str) -> Result<Vec<PathBuf>> {
+    let path = Path::new(file_path_or_root);
+    if long_path(path).is_file() {
+        Ok(vec![path.to_path_buf()])
     } else {
-        (None, None, None, None)
-    };
-
-    Some(ClaudeMessage {
-        message_id: entry.uuid.unwrap_or_else(|| "unknown".to_string()),
-        session_id: entry.session_id.unwrap_or_else(|| "unknown".to_string()),
-        role: message.role,
-        content,
-        timestamp: entry.timestamp.unwrap_or_else(|| "unknown".to_string()),
-        raw_content,
-        has_thinking: has_thinking_flag,
-        has_tool_use: has_tool_use_flag,
-        has_images: has_images_flag,
-        parent_id: entry.parent_uuid,
-        model: message.model,
-        stop_reason: message.stop_reason,
-        input_tokens,
-        output_tokens,
-        cache_creation_tokens: cache_creation,
-        cache_read_tokens: cache_read,
-        is_sidechain: entry.is_sidechain,
-        user_type: entry.user_type,
-    })
+        collect_session_files(file_path_or_root)
+    }
 }
 
 // ============================================
 // EXPORTED FUNCTIONS
 // ============================================
 
+/// Runs when the addon is loaded: route diagnostics through the log sinks
+#[napi::module_init]
+fn init() {
+    logging::init();
+}
+
 /// Parse Claude Code session file and return all messages
-#[napi]
-pub fn parse_claude_session(file_path: String) -> Result<Vec<ClaudeMessage>> {
-    let file = File::open(&file_path)
-        .map_err(|e| Error::from_reason(format!("Cannot open file: {}", e)))?;
+#[napi(catch_unwind)]
+pub fn parse_claude_session(file_path: String, options: Option<ParseOptions>) -> Result<Vec<ClaudeMessage>> {
+    parse_claude_session_with_report(file_path, options).map(|parsed| parsed.messages)
+}
 
-    let reader = BufReader::new(file);
-    let mut messages = Vec::new();
+/// Parse a session file and also report every line that was skipped
+#[napi(catch_unwind)]
+pub fn parse_claude_session_with_report(file_path: String, options: Option<ParseOptions>) -> Result<ParsedSession> {
+    let options = options.unwrap_or_default();
+    let include_raw_content = options.include_raw_content.unwrap_or(true);
+    let ordered_content = options.ordered_content.unwrap_or(false);
+    let image_mode = options.image_mode.as_deref().unwrap_or("full");
+    if !images::IMAGE_MODES.contains(&image_mode) {
+        return Err(unsupported_format(format!("Unknown image mode: {}", image_mode)));
+    }
+    let summarize_images = include_raw_content && image_mode == "summary";
+    let snapshot = snapshot::take(&file_path, options.snapshot.as_deref())?;
 
-    for (line_num, line) in reader.lines().enumerate() {
-        let line = line.map_err(|e| {
-            Error::from_reason(format!("Error reading line {}: {}", line_num + 1, e))
-        })?;
+    let mmap_threshold = reader::mmap_threshold(options.mmap_threshold_bytes);
+    let max_line_bytes = match options.max_line_bytes.unwrap_or(DEFAULT_MAX_LINE_BYTES) {
+        n if n < 0 => u64::MAX,
+        n => n as u64,
+    };
 
+    let parse_line = |line_num: usize, line: reader::LineRef| -> Option<LineOutcome> {
+        let skipped = |bytes: usize, reason: &str| {
+            LineOutcome::Skipped(SkippedLine {
+                line_number: line_num as u32 + 1,
+                bytes: bytes as i64,
+                reason: reason.to_string(),
+            })
+        };
+
+        let line = match line {
+            Ok(line) => line,
+            Err(len) => {
+                tracing::warn!(line = line_num + 1, bytes = len, "Skipped line exceeding max_line_bytes");
+                return Some(skipped(len as usize, "line_too_long"));
+            }
+        };
         if line.trim().is_empty() {
-            continue;
+            return None;
+        }
+
+        // Skip summaries, snapshots etc. without deserializing their content
+        if fast::peek_entry_type(line).is_some_and(|t| !fast::is_message_type(&t)) {
+            return None;
         }
 
         // Parse JSONL line with graceful error handling
-        match parse_jsonl_line(&line) {
+        match fast::parse_entry(line) {
             Ok(entry) => {
-                if let Some(msg) = entry_to_message(entry) {
-                    messages.push(msg);
-                }
+                let ordered = entry.message.as_ref().filter(|_| ordered_content).map(|m| history_core::ordered_content(&m.content));
+                let raw_content = entry.message.as_ref().filter(|_| summarize_images).map(|m| images::summarized_raw_content(&m.content));
+                entry_to_message_with(entry, include_raw_content && !summarize_images).map(|mut m| {
+                    if let Some(content) = ordered {
+                        m.content = content;
+                    }
+                    if let Some(raw_content) = raw_content {
+                        m.raw_content = raw_content;
+                    }
+                    if let Some(max) = options.max_text_chars {
+                        truncate_content(&mut m, max as usize);
+                    }
+                    LineOutcome::Message(line_num, Box::new(m))
+                })
             }
             Err(e) => {
                 // Log error but continue parsing
-                eprintln!("⚠️  Parse error at line {}: {}", line_num + 1, e);
-                eprintln!("   Line content: {}", &line[..line.len().min(100)]);
+                tracing::warn!(
+                    line = line_num + 1,
+                    content = &line[..line.len().min(100)],
+                    "Parse error: {}",
+                    e
+                );
+                Some(skipped(line.len(), "parse_error"))
             }
         }
+    };
+
+    // Content and raw JSON dominate a message's footprint. The first message
+    // over budget is reported and reading stops there
+    let budget = options.memory_budget_bytes.filter(|b| *b >= 0).map(|b| b as usize);
+    let mut used = 0;
+    let mut messages = Vec::new();
+    let mut skipped_lines = Vec::new();
+    let mut keep = |outcome: LineOutcome| {
+        match outcome {
+            LineOutcome::Message(line_num, message) => {
+                let size = message.content.len() + message.raw_content.len();
+                if budget.is_some_and(|b| used + size > b) {
+                    skipped_lines.push(SkippedLine {
+                        line_number: line_num as u32 + 1,
+                        bytes: size as i64,
+                        reason: "memory_budget".to_string(),
+                    });
+                    return ControlFlow::Break(());
+                }
+                used += size;
+                messages.push(*message);
+            }
+            LineOutcome::Skipped(line) => skipped_lines.push(line),
+        }
+        ControlFlow::Continue(())
+    };
+
+    // Very large files are split at newline boundaries and parsed on all cores
+    let parallel_threshold = reader::parallel_threshold(options.parallel_threshold_bytes);
+    if reader::file_len(&snapshot.path).min(snapshot.read_len) >= parallel_threshold {
+        // At most one budget's worth of lines is parsed ahead of keep
+        let window = budget.map_or(u64::MAX, |b| b as u64);
+        reader::par_filter_map_lines(&snapshot.path, mmap_threshold, max_line_bytes, snapshot.read_len, window, parse_line, keep)?;
+    } else {
+        reader::for_each_line_bounded(&snapshot.path, mmap_threshold, max_line_bytes, snapshot.read_len, |line_num, line| {
+            parse_line(line_num, line).map_or(ControlFlow::Continue(()), &mut keep)
+        })?;
+    }
+
+    if options.infer_timestamps.unwrap_or(false) {
+        time::infer_missing_timestamps(&mut messages);
+    }
+    if options.sort_by_timestamp.unwrap_or(false) {
+        time::sort_by_timestamp(&mut messages);
     }
 
-    Ok(messages)
+    Ok(ParsedSession { messages, skipped_lines, snapshot: snapshot.strategy.to_string() })
 }
 
 /// Get session summary with enhanced statistics
-#[napi]
-pub fn get_session_summary(file_path: String) -> Result<ClaudeSession> {
-    let file = File::open(&file_path)
-        .map_err(|e| Error::from_reason(format!("Cannot open file: {}", e)))?;
-
-    let reader = BufReader::new(file);
+#[napi(catch_unwind)]
+pub fn get_session_summary(file_path: String, options: Option<SummaryOptions>) -> Result<ClaudeSession> {
+    let options = options.unwrap_or_default();
+    let idle_threshold = options.idle_threshold_seconds.unwrap_or(time::DEFAULT_IDLE_THRESHOLD_SECS);
+    if options.fast.unwrap_or(false) {
+        return fast::scan_summary(&file_path, idle_threshold);
+    }
 
     let mut session_id = String::from("unknown");
     let mut message_count = 0;
@@ -368,68 +529,102 @@ pub fn get_session_summary(file_path: String) -> Result<ClaudeSession> {
     let mut last_timestamp: Option<String> = None;
     let mut total_input_tokens = 0;
     let mut total_output_tokens = 0;
+    let mut sidechain_input_tokens = 0;
+    let mut sidechain_output_tokens = 0;
     let mut has_thinking_flag = false;
     let mut has_tool_use_flag = false;
     let mut cwd: Option<String> = None;
+    let mut first_prompt: Option<String> = None;
+    let mut last_reply: Option<String> = None;
+    let mut health = failures::HealthTracker::default();
+    let mut role_stats = stats::RoleStatsTracker::default();
+    let mut activity = time::ActivityTracker::default();
+    let mut pending = live::PendingToolUses::default();
+    let mut languages = language::LanguageTally::default();
+
+    reader::for_each_line(&file_path, reader::DEFAULT_MMAP_THRESHOLD, |_, line| {
+        if line.trim().is_empty() {
+            return;
+        }
 
-    for line in reader.lines() {
-        if let Ok(line) = line {
-            if line.trim().is_empty() {
-                continue;
+        if let Ok(entry) = parse_jsonl_line(line) {
+            health.observe(&entry);
+            role_stats.observe(&entry);
+            pending.observe(&entry);
+            languages.observe(&entry);
+
+            // Update session ID
+            if let Some(sid) = &entry.session_id {
+                session_id = sid.clone();
             }
 
-            if let Ok(entry) = parse_jsonl_line(&line) {
-                // Update session ID
-                if let Some(sid) = &entry.session_id {
-                    session_id = sid.clone();
+            // Capture cwd if available (only need to do this once)
+            if cwd.is_none() {
+                if let Some(ref cwd_value) = entry.cwd {
+                    cwd = Some(cwd_value.clone());
                 }
+            }
 
-                // Capture cwd if available (only need to do this once)
-                if cwd.is_none() {
-                    if let Some(ref cwd_value) = entry.cwd {
-                        cwd = Some(cwd_value.clone());
-                    }
-                }
+            // Count messages
+            match entry.entry_type.as_str() {
+                "user" => {
+                    user_count += 1;
+                    message_count += 1;
 
-                // Count messages
-                match entry.entry_type.as_str() {
-                    "user" => {
-                        user_count += 1;
-                        message_count += 1;
-                    }
-                    "assistant" => {
-                        assistant_count += 1;
-                        message_count += 1;
-
-                        // Track token usage
+                    // First real prompt (skips meta entries and command wrappers)
+                    if first_prompt.is_none() && title::is_substantive_prompt(&entry) {
                         if let Some(message) = &entry.message {
-                            if let Some(usage) = &message.usage {
-                                total_input_tokens += usage.input_tokens;
-                                total_output_tokens += usage.output_tokens;
+                            first_prompt = title::prompt_excerpt(&extract_text_content(&message.content));
+                        }
+                    }
+                }
+                "assistant" => {
+                    assistant_count += 1;
+                    message_count += 1;
+
+                    // Track token usage
+                    if let Some(message) = &entry.message {
+                        if let Some(usage) = &message.usage {
+                            total_input_tokens += usage.input_tokens;
+                            total_output_tokens += usage.output_tokens;
+                            if entry.is_sidechain == Some(true) {
+                                sidechain_input_tokens += usage.input_tokens;
+                                sidechain_output_tokens += usage.output_tokens;
                             }
+                        }
 
-                            // Check for thinking and tool use
-                            if has_thinking(&message.content) {
-                                has_thinking_flag = true;
-                            }
-                            if has_tool_use(&message.content) {
-                                has_tool_use_flag = true;
+                        // Check for thinking and tool use
+                        if has_thinking(&message.content) {
+                            has_thinking_flag = true;
+                        }
+                        if has_tool_use(&message.content) {
+                            has_tool_use_flag = true;
+                        }
+
+                        // Keep the latest reply that actually contains text
+                        if entry.is_sidechain != Some(true) {
+                            if let Some(excerpt) = title::reply_excerpt(&message.content) {
+                                last_reply = Some(excerpt);
                             }
                         }
                     }
-                    _ => {}
                 }
+                _ => {}
+            }
 
-                // Track timestamps
-                if let Some(ts) = entry.timestamp {
-                    if first_timestamp.is_none() {
-                        first_timestamp = Some(ts.clone());
-                    }
-                    last_timestamp = Some(ts);
+            // Track timestamps
+            if let Some(ts) = entry.timestamp {
+                activity.observe(&ts);
+                if first_timestamp.is_none() {
+                    first_timestamp = Some(ts.clone());
                 }
+                last_timestamp = Some(ts);
             }
         }
-    }
+    })?;
+
+    let (user_stats, assistant_stats) = role_stats.finish();
+    let (duration_seconds, active_seconds) = activity.finish(idle_threshold);
 
     Ok(ClaudeSession {
         session_id,
@@ -441,9 +636,22 @@ pub fn get_session_summary(file_path: String) -> Result<ClaudeSession> {
         last_timestamp,
         total_input_tokens: if total_input_tokens > 0 { Some(total_input_tokens) } else { None },
         total_output_tokens: if total_output_tokens > 0 { Some(total_output_tokens) } else { None },
+        main_input_tokens: total_input_tokens - sidechain_input_tokens,
+        main_output_tokens: total_output_tokens - sidechain_output_tokens,
+        sidechain_input_tokens,
+        sidechain_output_tokens,
         has_thinking: has_thinking_flag,
         has_tool_use: has_tool_use_flag,
         cwd,
+        first_user_prompt_excerpt: first_prompt,
+        last_assistant_excerpt: last_reply,
+        health: Some(health.finish()),
+        user_stats: Some(user_stats),
+        assistant_stats: Some(assistant_stats),
+        duration_seconds,
+        active_seconds,
+        pending_tool_uses: Some(pending.finish()),
+        languages: Some(languages.finish()),
     })
 }
 
@@ -451,10 +659,10 @@ pub fn get_session_summary(file_path: String) -> Result<ClaudeSession> {
 // LEGACY FUNCTIONS (kept for compatibility)
 // ============================================
 
-#[napi]
+#[napi(catch_unwind)]
 pub fn count_lines(file_path: String) -> Result<i32> {
-    let file = File::open(&file_path)
-        .map_err(|e| Error::from_reason(format!("Cannot open file: {}", e)))?;
+    let file = open_file(&file_path)
+        .map_err(|e| io_error("Cannot open file", &file_path, e))?;
 
     let reader = BufReader::new(file);
     let count = reader.lines().count() as i32;
@@ -462,46 +670,42 @@ pub fn count_lines(file_path: String) -> Result<i32> {
     Ok(count)
 }
 
-#[napi]
+/// Every non-empty line; read_lines_range pages through large files instead
+#[napi(catch_unwind)]
 pub fn read_lines(file_path: String) -> Result<Vec<String>> {
-    let file = File::open(&file_path)
-        .map_err(|e| Error::from_reason(format!("Cannot open file: {}", e)))?;
+    let file = open_file(&file_path)
+        .map_err(|e| io_error("Cannot open file", &file_path, e))?;
 
     let reader = BufReader::new(file);
-    let lines: Vec<String> = reader
-        .lines()
-        .filter_map(|line| line.ok())
+    let lines: Vec<String> = lossy_lines(reader)
         .filter(|line| !line.trim().is_empty())
         .collect();
 
     Ok(lines)
 }
 
-#[napi]
+#[napi(catch_unwind)]
 pub fn read_lines_with_pattern(file_path: String, pattern: String) -> Result<Vec<String>> {
-    let file = File::open(&file_path)
-        .map_err(|e| Error::from_reason(format!("Cannot open file: {}", e)))?;
+    let file = open_file(&file_path)
+        .map_err(|e| io_error("Cannot open file", &file_path, e))?;
 
     let reader = BufReader::new(file);
-    let matching_lines: Vec<String> = reader
-        .lines()
-        .filter_map(|line| line.ok())
+    let matching_lines: Vec<String> = lossy_lines(reader)
         .filter(|line| line.contains(&pattern))
         .collect();
 
     Ok(matching_lines)
 }
 
-#[napi]
+/// "File: ...\nSize: ... bytes\nLines: ..."; get_file_details returns the same as an object
+#[napi(catch_unwind)]
 pub fn get_file_info(file_path: String) -> Result<String> {
     let path = PathBuf::from(&file_path);
 
-    if !path.exists() {
-        return Err(Error::from_reason("File does not exist".to_string()));
-    }
-
-    let metadata = std::fs::metadata(&path)
-        .map_err(|e| Error::from_reason(format!("Cannot read metadata: {}", e)))?;
+    let metadata = paths::metadata(&path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => HubError::new(ErrorCode::FileNotFound, "File does not exist").path(&path).into(),
+        _ => io_error("Cannot read metadata", &path, e),
+    })?;
 
     let file_size = metadata.len();
     let line_count = count_lines(file_path)?;
@@ -523,6 +727,7 @@ pub fn get_file_info(file_path: String) -> Result<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_util::TempDir;
 
     #[test]
     fn test_parse_user_message() {
@@ -560,4 +765,45 @@ mod tests {
         assert!(msg.has_thinking);
         assert!(msg.content.contains("Let me think..."));
     }
+
+    #[test]
+    fn test_skipped_lines_are_reported() {
+        let tmp = TempDir::new("skipped-lines");
+        let path = tmp.join("skipped-lines.jsonl");
+        let big = format!(r#"{{"type":"user","uuid":"big","message":{{"role":"user","content":"{}"}}}}"#, "x".repeat(500));
+        std::fs::write(&path, [
+            r#"{"type":"user","uuid":"1","message":{"role":"user","content":"hi"}}"#,
+            &big,
+            "{broken",
+        ].join("\n")).unwrap();
+
+        let options = ParseOptions { max_line_bytes: Some(200), ..Default::default() };
+        let parsed = parse_claude_session_with_report(path.display().to_string(), Some(options)).unwrap();
+        assert_eq!(parsed.messages.len(), 1);
+        let reasons: Vec<(u32, &str)> = parsed.skipped_lines.iter().map(|s| (s.line_number, s.reason.as_str())).collect();
+        assert_eq!(reasons, vec![(2, "line_too_long"), (3, "parse_error")]);
+    }
+
+    #[test]
+    fn test_memory_budget_stops_reading() {
+        let tmp = TempDir::new("memory-budget");
+        let path = tmp.join("memory-budget.jsonl");
+        let line = |uuid: &str, content: &str| format!(r#"{{"type":"user","uuid":"{}","message":{{"role":"user","content":"{}"}}}}"#, uuid, content);
+        std::fs::write(&path, [line("1", &"a".repeat(10)), line("2", &"b".repeat(100)), line("3", "small"), "{broken".to_string()].join("\n")).unwrap();
+
+        // Sequential and parallel reads stop at the same message
+        for parallel_threshold_bytes in [None, Some(0)] {
+            let options = ParseOptions {
+                memory_budget_bytes: Some(50),
+                include_raw_content: Some(false),
+                parallel_threshold_bytes,
+                ..Default::default()
+            };
+            let parsed = parse_claude_session_with_report(path.display().to_string(), Some(options)).unwrap();
+            let ids: Vec<&str> = parsed.messages.iter().map(|m| m.message_id.as_str()).collect();
+            assert_eq!(ids, vec!["1"]);
+            let skipped: Vec<(u32, i64, &str)> = parsed.skipped_lines.iter().map(|s| (s.line_number, s.bytes, s.reason.as_str())).collect();
+            assert_eq!(skipped, vec![(2, 100, "memory_budget")]);
+        }
+    }
 }
@@ -0,0 +1,198 @@
+// ============================================
+// LIVE SESSIONS
+// Sessions that look like they are running right now: written to a
+// moment ago and stopped mid-turn, e.g. on a tool call with no result yet
+// ============================================
+
+use chrono::{DateTime, Utc};
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use schemars::JsonSchema;
+
+use crate::paths::metadata;
+use crate::{collect_session_files, for_each_entry, ContentItem, RawLogEntry};
+
+/// How recently a file must have been written to count as live
+const DEFAULT_WITHIN_MINUTES: u32 = 5;
+
+/// A tool call with no tool_result in the log (still running, interrupted or crashed)
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+pub struct PendingToolUse {
+    pub tool_use_id: String,
+    pub name: String,
+    /// uuid of the assistant entry that made the call
+    pub message_id: Option<String>,
+    pub timestamp: Option<String>,
+}
+
+/// tool_use blocks still waiting for their tool_result, in call order
+#[derive(Debug, Default)]
+pub(crate) struct PendingToolUses {
+    pending: Vec<PendingToolUse>,
+}
+
+impl PendingToolUses {
+    pub fn observe(&mut self, entry: &RawLogEntry) {
+        let Some(message) = &entry.message else { return };
+        for item in &message.content {
+            match item {
+                ContentItem::ToolUse { id, name, .. } => self.pending.push(PendingToolUse {
+                    tool_use_id: id.clone(),
+                    name: name.clone(),
+                    message_id: entry.uuid.clone(),
+                    timestamp: entry.timestamp.clone(),
+                }),
+                ContentItem::ToolResult { tool_use_id, .. } => self.pending.retain(|p| &p.tool_use_id != tool_use_id),
+                _ => {}
+            }
+        }
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.pending.iter().map(|p| p.name.clone()).collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    pub fn finish(self) -> Vec<PendingToolUse> {
+        self.pending
+    }
+}
+
+/// A session that appears to be in progress
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActiveSession {
+    pub session_id: String,
+    pub file_path: String,
+    /// cwd of the session, if logged
+    pub project: Option<String>,
+    /// "tool_running" (a tool call has no result yet) or "awaiting_response"
+    /// (the last message is the user's)
+    pub state: String,
+    /// Names of the tools still running
+    pub pending_tools: Vec<String>,
+    /// When the file was last written (RFC 3339)
+    pub modified_at: String,
+    pub seconds_since_modified: i64,
+    pub last_timestamp: Option<String>,
+}
+
+/// Where a session file left off
+struct FileScan {
+    session_id: Option<String>,
+    project: Option<String>,
+    last_role: String,
+    pending: PendingToolUses,
+    last_timestamp: Option<String>,
+}
+
+fn scan_file(file_path: &str) -> Option<FileScan> {
+    let mut session_id = None;
+    let mut project = None;
+    let mut last_role = None;
+    let mut last_timestamp = None;
+    let mut pending = PendingToolUses::default();
+
+    for_each_entry(file_path, |entry| {
+        session_id = session_id.take().or_else(|| entry.session_id.clone());
+        project = project.take().or_else(|| entry.cwd.clone());
+        if entry.entry_type != "user" && entry.entry_type != "assistant" {
+            return;
+        }
+        let Some(message) = &entry.message else { return };
+        pending.observe(&entry);
+        last_role = Some(message.role.clone());
+        last_timestamp = entry.timestamp.clone().or(last_timestamp.take());
+    })
+    .ok()?;
+    Some(FileScan { session_id, project, last_role: last_role?, pending, last_timestamp })
+}
+
+fn active_sessions(root_dir: &str, within_minutes: u32, now: DateTime<Utc>) -> Result<Vec<ActiveSession>> {
+    let mut active = Vec::new();
+
+    for path in collect_session_files(root_dir)? {
+        let Some(modified) = metadata(&path).and_then(|m| m.modified()).ok() else { continue };
+        let modified: DateTime<Utc> = modified.into();
+        let age = (now - modified).num_seconds().max(0);
+        if age > within_minutes as i64 * 60 {
+            continue;
+        }
+
+        let file_path = path.display().to_string();
+        let Some(scan) = scan_file(&file_path) else { continue };
+        let state = if !scan.pending.is_empty() {
+            "tool_running"
+        } else if scan.last_role == "user" {
+            "awaiting_response"
+        } else {
+            continue;
+        };
+
+        active.push(ActiveSession {
+            session_id: scan.session_id.unwrap_or_else(|| "unknown".to_string()),
+            file_path,
+            project: scan.project,
+            state: state.to_string(),
+            pending_tools: scan.pending.names(),
+            modified_at: modified.to_rfc3339(),
+            seconds_since_modified: age,
+            last_timestamp: scan.last_timestamp,
+        });
+    }
+
+    active.sort_by_key(|session| session.seconds_since_modified);
+    Ok(active)
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Sessions under root_dir written in the last within_minutes (default 5)
+/// that stopped mid-turn, most recently written first
+#[napi(catch_unwind)]
+pub fn get_active_sessions(root_dir: String, within_minutes: Option<u32>) -> Result<Vec<ActiveSession>> {
+    active_sessions(&root_dir, within_minutes.unwrap_or(DEFAULT_WITHIN_MINUTES), Utc::now())
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_active_sessions() {
+        let root = TempDir::new("live");
+        let project = root.join("-work-app");
+        std::fs::create_dir_all(&project).unwrap();
+        std::fs::write(project.join("running.jsonl"), [
+            r#"{"type":"user","sessionId":"s1","cwd":"/work/app","message":{"role":"user","content":"build it"}}"#,
+            r#"{"type":"assistant","sessionId":"s1","message":{"role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Read","input":{}},{"type":"tool_use","id":"t2","name":"Bash","input":{}}]}}"#,
+            r#"{"type":"user","sessionId":"s1","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"ok"}]}}"#,
+        ].join("\n")).unwrap();
+        std::fs::write(project.join("finished.jsonl"), [
+            r#"{"type":"user","sessionId":"s2","message":{"role":"user","content":"hi"}}"#,
+            r#"{"type":"assistant","sessionId":"s2","message":{"role":"assistant","content":"hello"}}"#,
+        ].join("\n")).unwrap();
+        let root_dir = root.display().to_string();
+
+        let active = active_sessions(&root_dir, 5, Utc::now()).unwrap();
+        assert_eq!(active.len(), 1);
+        assert_eq!((active[0].session_id.as_str(), active[0].state.as_str()), ("s1", "tool_running"));
+        assert_eq!(active[0].pending_tools, vec!["Bash"]);
+        assert_eq!(active[0].project.as_deref(), Some("/work/app"));
+
+        let later = Utc::now() + chrono::Duration::minutes(10);
+        assert!(active_sessions(&root_dir, 5, later).unwrap().is_empty());
+    }
+}
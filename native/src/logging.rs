@@ -0,0 +1,262 @@
+// ============================================
+// STRUCTURED LOGGING
+// Parser diagnostics go through `tracing`; records are forwarded to a
+// JS callback, a size-rotated log file, or stderr when neither is set
+// ============================================
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+
+use napi::bindgen_prelude::*;
+use napi::threadsafe_function::{ErrorStrategy, ThreadSafeCallContext, ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi::{Env, JsFunction};
+use napi_derive::napi;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+
+use crate::config::{self, LogLevel};
+use crate::errors::io_error;
+
+/// Log files are rotated past this size (5 MiB)
+pub const DEFAULT_MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Rotated files kept next to the log (app.log.1 ... app.log.3)
+const KEEP_ROTATED: u32 = 3;
+
+type Handler = Box<dyn Fn(LogRecord) + Send + Sync>;
+
+static HANDLER: RwLock<Option<Handler>> = RwLock::new(None);
+static LOG_FILE: Mutex<Option<LogFile>> = Mutex::new(None);
+
+/// One diagnostic record
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct LogRecord {
+    /// "error", "warn", "info" or "debug"
+    pub level: String,
+    /// Rust module that logged it, e.g. "claude_parser::reader"
+    pub target: String,
+    pub message: String,
+    /// Structured fields such as line numbers
+    pub fields: HashMap<String, String>,
+    pub timestamp: String,
+}
+
+impl fmt::Display for LogRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {:5} {}: {}", self.timestamp, self.level.to_uppercase(), self.target, self.message)?;
+        let mut fields: Vec<_> = self.fields.iter().collect();
+        fields.sort();
+        for (key, value) in fields {
+            write!(f, " {}={}", key, value)?;
+        }
+        Ok(())
+    }
+}
+
+fn level_of(level: &Level) -> LogLevel {
+    match *level {
+        Level::ERROR => LogLevel::Error,
+        Level::WARN => LogLevel::Warn,
+        Level::INFO => LogLevel::Info,
+        _ => LogLevel::Debug,
+    }
+}
+
+#[derive(Default)]
+struct RecordVisitor {
+    message: String,
+    fields: HashMap<String, String>,
+}
+
+impl Visit for RecordVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.fields.insert(field.name().to_string(), value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.fields.insert(field.name().to_string(), format!("{:?}", value));
+        }
+    }
+}
+
+// ============================================
+// ROTATING FILE
+// ============================================
+
+struct LogFile {
+    path: PathBuf,
+    max_bytes: u64,
+    file: File,
+    written: u64,
+}
+
+impl LogFile {
+    fn open(path: &Path, max_bytes: u64) -> std::io::Result<LogFile> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let written = file.metadata().map(|m| m.len()).unwrap_or(0);
+        Ok(LogFile { path: path.to_path_buf(), max_bytes, file, written })
+    }
+
+    fn rotated(&self, n: u32) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{}", n));
+        PathBuf::from(name)
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        for n in (1..KEEP_ROTATED).rev() {
+            let from = self.rotated(n);
+            if from.exists() {
+                std::fs::rename(&from, self.rotated(n + 1))?;
+            }
+        }
+        std::fs::rename(&self.path, self.rotated(1))?;
+        *self = LogFile::open(&self.path.clone(), self.max_bytes)?;
+        Ok(())
+    }
+
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        if self.written > 0 && self.written + line.len() as u64 + 1 > self.max_bytes {
+            self.rotate()?;
+        }
+        writeln!(self.file, "{}", line)?;
+        self.written += line.len() as u64 + 1;
+        Ok(())
+    }
+}
+
+// ============================================
+// SUBSCRIBER
+// ============================================
+
+struct SinkLayer;
+
+impl<S: Subscriber> Layer<S> for SinkLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let level = level_of(metadata.level());
+        if !config::log_enabled(level) {
+            return;
+        }
+
+        let mut visitor = RecordVisitor::default();
+        event.record(&mut visitor);
+        dispatch(LogRecord {
+            level: level.as_str().to_string(),
+            target: metadata.target().to_string(),
+            message: visitor.message,
+            fields: visitor.fields,
+            timestamp: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        });
+    }
+}
+
+fn dispatch(record: LogRecord) {
+    let mut delivered = false;
+
+    if let Some(file) = LOG_FILE.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        delivered = file.write_line(&record.to_string()).is_ok();
+    }
+    if let Some(handler) = HANDLER.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        handler(record);
+        return;
+    }
+    if !delivered {
+        eprintln!("{}", record);
+    }
+}
+
+/// Install the process-wide subscriber (once, at module load)
+pub(crate) fn init() {
+    let subscriber = tracing_subscriber::registry().with(SinkLayer);
+    let _ = tracing::subscriber::set_global_default(subscriber);
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Forward parser log records to a JS callback (null restores stderr)
+#[napi(ts_args_type = "handler: ((record: LogRecord) => void) | null", catch_unwind)]
+pub fn set_log_handler(env: Env, handler: Option<JsFunction>) -> Result<()> {
+    let handler: Option<Handler> = match handler {
+        Some(callback) => {
+            let mut tsfn: ThreadsafeFunction<LogRecord, ErrorStrategy::Fatal> = callback
+                .create_threadsafe_function(0, |ctx: ThreadSafeCallContext<LogRecord>| Ok(vec![ctx.value]))?;
+            // Logging must never keep the app alive
+            tsfn.unref(&env)?;
+            Some(Box::new(move |record| {
+                tsfn.call(record, ThreadsafeFunctionCallMode::NonBlocking);
+            }))
+        }
+        None => None,
+    };
+
+    *HANDLER.write().unwrap_or_else(|e| e.into_inner()) = handler;
+    Ok(())
+}
+
+/// Also append log records to a file, rotated past max_bytes (default 5 MiB); null stops
+#[napi(catch_unwind)]
+pub fn set_log_file(path: Option<String>, max_bytes: Option<i64>) -> Result<()> {
+    let file = match path {
+        Some(path) => {
+            let max_bytes = max_bytes.filter(|m| *m > 0).map_or(DEFAULT_MAX_LOG_BYTES, |m| m as u64);
+            Some(
+                LogFile::open(Path::new(&path), max_bytes)
+                    .map_err(|e| io_error("Cannot open log file", &path, e))?,
+            )
+        }
+        None => None,
+    };
+
+    *LOG_FILE.lock().unwrap_or_else(|e| e.into_inner()) = file;
+    Ok(())
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_log_file_rotation() {
+        let dir = TempDir::new("log-test");
+        let path = dir.join("parser.log");
+
+        let record = LogRecord {
+            level: "warn".into(),
+            target: "claude_parser".into(),
+            message: "Parse error".into(),
+            fields: HashMap::from([("line".to_string(), "7".to_string())]),
+            timestamp: "2024-01-01T00:00:00.000Z".into(),
+        };
+        let line = record.to_string();
+        assert!(line.ends_with("WARN  claude_parser: Parse error line=7"));
+
+        let mut file = LogFile::open(&path, line.len() as u64 * 2 + 2).unwrap();
+        for _ in 0..5 {
+            file.write_line(&line).unwrap();
+        }
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 1);
+        assert_eq!(std::fs::read_to_string(dir.join("parser.log.1")).unwrap().lines().count(), 2);
+        assert!(dir.join("parser.log.2").exists());
+    }
+}
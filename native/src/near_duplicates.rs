@@ -0,0 +1,212 @@
+// ============================================
+// NEAR-DUPLICATE SESSIONS
+// Sessions that are (almost) copies of each other - resumed sessions
+// repeating their parent, folders copied between machines - found by
+// MinHash over per-message content fingerprints
+// ============================================
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use rayon::prelude::*;
+
+use crate::errors::invalid_argument;
+use crate::paths::metadata;
+use crate::similarity::{fnv1a, Signature};
+use crate::{collect_session_files, config, extract_text_content, for_each_entry};
+
+const DEFAULT_THRESHOLD: f64 = 0.8;
+
+/// One session of a duplicate group
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateSession {
+    pub session_id: String,
+    pub file_path: String,
+    pub bytes: i64,
+    pub message_count: u32,
+    /// Estimated Jaccard similarity of its messages to the kept session (1 for the kept one)
+    pub similarity: f64,
+}
+
+/// Sessions that are near copies of each other
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateSessionGroup {
+    /// The session with the most messages, which the others mostly repeat
+    pub kept: DuplicateSession,
+    pub duplicates: Vec<DuplicateSession>,
+    /// Bytes of the duplicates
+    pub reclaimable_bytes: i64,
+}
+
+/// Result of find_duplicate_sessions
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateSessionReport {
+    pub files_scanned: u32,
+    /// Most reclaimable bytes first
+    pub groups: Vec<DuplicateSessionGroup>,
+    pub reclaimable_bytes: i64,
+}
+
+/// Content fingerprint of one session file
+struct Fingerprint {
+    session: DuplicateSession,
+    signature: Signature,
+}
+
+/// Hash of each message's role and whitespace-normalized text, so the same
+/// conversation matches whatever uuids and timestamps it was given
+fn fingerprint(path: &Path) -> Option<Fingerprint> {
+    let file_path = path.display().to_string();
+    let mut session_id = None;
+    let mut messages: HashSet<u64> = HashSet::new();
+    let mut message_count = 0;
+    for_each_entry(&file_path, |entry| {
+        if entry.entry_type != "user" && entry.entry_type != "assistant" {
+            return;
+        }
+        let Some(message) = &entry.message else { return };
+        if session_id.is_none() {
+            session_id = entry.session_id.clone();
+        }
+        let text = extract_text_content(&message.content).split_whitespace().collect::<Vec<&str>>().join(" ");
+        if !text.is_empty() {
+            messages.insert(fnv1a(format!("{}\0{}", message.role, text).as_bytes()));
+            message_count += 1;
+        }
+    })
+    .ok()?;
+
+    Some(Fingerprint {
+        signature: Signature::from_shingles(&messages)?,
+        session: DuplicateSession {
+            session_id: session_id.unwrap_or_else(|| path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default()),
+            bytes: metadata(path).map(|m| m.len() as i64).unwrap_or(0),
+            file_path,
+            message_count,
+            similarity: 1.0,
+        },
+    })
+}
+
+/// Each session most likely to be the original, in turn, with the remaining
+/// sessions at least threshold similar to it. Similarity is not transitive,
+/// so a session is only grouped with one it is itself close to
+fn group(mut fingerprints: Vec<Fingerprint>, threshold: f64) -> Vec<DuplicateSessionGroup> {
+    fingerprints.sort_by(|a, b| {
+        let (a, b) = (&a.session, &b.session);
+        b.message_count.cmp(&a.message_count).then(b.bytes.cmp(&a.bytes)).then(a.file_path.cmp(&b.file_path))
+    });
+    let mut grouped = vec![false; fingerprints.len()];
+    let mut groups: Vec<DuplicateSessionGroup> = Vec::new();
+    for i in 0..fingerprints.len() {
+        if grouped[i] {
+            continue;
+        }
+        let kept = &fingerprints[i];
+        let mut duplicates: Vec<DuplicateSession> = Vec::new();
+        for j in i + 1..fingerprints.len() {
+            let similarity = kept.signature.similarity(&fingerprints[j].signature);
+            if !grouped[j] && similarity >= threshold {
+                grouped[j] = true;
+                duplicates.push(DuplicateSession { similarity, ..fingerprints[j].session.clone() });
+            }
+        }
+        if !duplicates.is_empty() {
+            groups.push(DuplicateSessionGroup {
+                reclaimable_bytes: duplicates.iter().map(|d| d.bytes).sum(),
+                kept: kept.session.clone(),
+                duplicates,
+            });
+        }
+    }
+    groups.sort_by(|a, b| b.reclaimable_bytes.cmp(&a.reclaimable_bytes).then_with(|| a.kept.file_path.cmp(&b.kept.file_path)));
+    groups
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Groups of sessions under root_dir whose messages are at least threshold
+/// similar (0..1, default 0.8), with the space deleting the copies would free
+#[napi(catch_unwind)]
+pub fn find_duplicate_sessions(root_dir: String, threshold: Option<f64>) -> Result<DuplicateSessionReport> {
+    let threshold = threshold.unwrap_or(DEFAULT_THRESHOLD);
+    if !(0.0..=1.0).contains(&threshold) {
+        return Err(invalid_argument(format!("Threshold must be between 0 and 1: {}", threshold)));
+    }
+    let files = collect_session_files(&root_dir)?;
+    // Unreadable and empty files have no fingerprint and are left out
+    let fingerprints: Vec<Fingerprint> = config::install(|| files.par_iter().filter_map(|path| fingerprint(path)).collect());
+    let groups = group(fingerprints, threshold);
+    Ok(DuplicateSessionReport {
+        files_scanned: files.len() as u32,
+        reclaimable_bytes: groups.iter().map(|g| g.reclaimable_bytes).sum(),
+        groups,
+    })
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_find_duplicate_sessions() {
+        let root = TempDir::new("near-duplicates");
+        std::fs::create_dir_all(root.join("p")).unwrap();
+        std::fs::create_dir_all(root.join("copy")).unwrap();
+        let line = |session: &str, uuid: usize, text: &str| {
+            let role = if uuid.is_multiple_of(2) { "user" } else { "assistant" };
+            format!(r#"{{"type":"{}","uuid":"{}-{}","sessionId":"{}","message":{{"role":"{}","content":"{}"}}}}"#, role, session, uuid, session, role, text)
+        };
+        let texts: Vec<String> = (0..20).map(|i| format!("message number {}", i)).collect();
+        let session = |id: &str, count: usize| texts[..count].iter().enumerate().map(|(i, t)| line(id, i, t)).collect::<Vec<_>>().join("\n");
+        std::fs::write(root.join("p/a.jsonl"), session("a", 20)).unwrap();
+        // Copied folder: same conversation, whitespace changes only
+        std::fs::write(root.join("copy/a.jsonl"), session("a", 20).replace("message number", "message  number")).unwrap();
+        // Resumed before the last message
+        std::fs::write(root.join("p/b.jsonl"), session("b", 19)).unwrap();
+        std::fs::write(root.join("p/c.jsonl"), line("c", 0, "something else entirely")).unwrap();
+        let root_dir = root.display().to_string();
+
+        let report = find_duplicate_sessions(root_dir.clone(), None).unwrap();
+        assert_eq!((report.files_scanned, report.groups.len()), (4, 1));
+        let group = &report.groups[0];
+        assert_eq!(group.kept.message_count, 20);
+        assert_eq!(group.duplicates.len(), 2);
+        assert!(group.duplicates.iter().any(|d| d.session_id == "b" && d.similarity < 1.0));
+        assert_eq!(report.reclaimable_bytes, group.duplicates.iter().map(|d| d.bytes).sum::<i64>());
+
+        assert_eq!(find_duplicate_sessions(root_dir.clone(), Some(1.0)).unwrap().groups[0].duplicates.len(), 1);
+        assert!(find_duplicate_sessions(root_dir, Some(1.5)).is_err());
+    }
+
+    #[test]
+    fn test_chained_sessions_not_grouped_through_a_middle_one() {
+        let fingerprint = |name: &str, messages: std::ops::Range<u64>, message_count: u32| Fingerprint {
+            signature: Signature::from_shingles(&messages.map(|m| fnv1a(&m.to_le_bytes())).collect()).unwrap(),
+            session: DuplicateSession { session_id: name.into(), file_path: name.into(), bytes: 10, message_count, similarity: 1.0 },
+        };
+        // a ~ b and b ~ c, but a and c share only a sixth of their messages
+        let (a, b, c) = (fingerprint("a", 0..60, 3), fingerprint("b", 20..80, 2), fingerprint("c", 40..100, 1));
+        let threshold = 0.35;
+        assert!(a.signature.similarity(&b.signature) >= threshold && b.signature.similarity(&c.signature) >= threshold);
+        assert!(a.signature.similarity(&c.signature) < threshold);
+
+        let groups = group(vec![c, b, a], threshold);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].kept.session_id, "a");
+        assert_eq!(groups[0].duplicates.iter().map(|d| d.session_id.as_str()).collect::<Vec<_>>(), vec!["b"]);
+        assert_eq!(groups[0].reclaimable_bytes, 10);
+    }
+}
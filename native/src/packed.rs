@@ -0,0 +1,143 @@
+// ============================================
+// PACKED RESULTS
+// Huge message arrays returned as one encoded Buffer, because building
+// 50k V8 objects across NAPI costs more than the parse itself. The same
+// idea for raw_content: one shared UTF-8 Buffer instead of a JsString each
+// ============================================
+
+use std::io::Write;
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use serde::Serialize;
+
+use crate::errors::{internal, unsupported_format};
+use crate::{parse_claude_session, parse_claude_session_with_report, ClaudeMessage, ParseOptions, SkippedLine};
+
+/// Messages whose raw_content lives in one shared Buffer
+#[napi(object)]
+pub struct RawBufferSession {
+    /// raw_content is empty; use raw_content_offset / raw_content_length
+    pub messages: Vec<ClaudeMessage>,
+    pub skipped_lines: Vec<SkippedLine>,
+    /// Concatenated raw_content JSON of all messages
+    pub raw_content: Buffer,
+}
+
+/// Encode any serializable result: "msgpack" (default) or "json-gzip".
+/// Field names are camelCase, same as the plain NAPI objects
+pub(crate) fn pack<T: Serialize>(value: &T, format: Option<&str>) -> Result<Vec<u8>> {
+    match format.unwrap_or("msgpack") {
+        "msgpack" => rmp_serde::to_vec_named(value)
+            .map_err(|e| internal(format!("Cannot encode msgpack: {}", e))),
+        "json-gzip" => {
+            let json = serde_json::to_vec(value)
+                .map_err(|e| internal(format!("Cannot encode JSON: {}", e)))?;
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+            encoder
+                .write_all(&json)
+                .and_then(|_| encoder.finish())
+                .map_err(|e| internal(format!("Cannot compress: {}", e)))
+        }
+        other => Err(unsupported_format(format!("Unknown packed format: {}", other))),
+    }
+}
+
+/// Move every raw_content string into one byte vector, recording where each
+/// message's JSON starts and how long it is
+pub(crate) fn pack_raw_content(messages: &mut [ClaudeMessage]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(messages.iter().map(|m| m.raw_content.len()).sum());
+    for message in messages.iter_mut() {
+        let raw = std::mem::take(&mut message.raw_content);
+        message.raw_content_offset = Some(bytes.len() as u32);
+        message.raw_content_length = Some(raw.len() as u32);
+        bytes.extend_from_slice(raw.as_bytes());
+    }
+    bytes
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// parse_claude_session, returned as a single msgpack or gzipped JSON Buffer
+#[napi(catch_unwind)]
+pub fn parse_claude_session_packed(
+    file_path: String,
+    options: Option<ParseOptions>,
+    format: Option<String>,
+) -> Result<Buffer> {
+    let messages = parse_claude_session(file_path, options)?;
+    pack(&messages, format.as_deref()).map(Buffer::from)
+}
+
+/// parse_claude_session_with_report with raw_content moved into a single Buffer
+#[napi(catch_unwind)]
+pub fn parse_claude_session_with_raw_buffer(
+    file_path: String,
+    options: Option<ParseOptions>,
+) -> Result<RawBufferSession> {
+    let options = ParseOptions { include_raw_content: Some(true), ..options.unwrap_or_default() };
+    let mut parsed = parse_claude_session_with_report(file_path, Some(options))?;
+    let raw_content = pack_raw_content(&mut parsed.messages);
+
+    Ok(RawBufferSession {
+        messages: parsed.messages,
+        skipped_lines: parsed.skipped_lines,
+        raw_content: Buffer::from(raw_content),
+    })
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_pack_round_trips() {
+        let json = r#"{"type":"user","uuid":"u1","message":{"role":"user","content":"hi"}}"#;
+        let messages = vec![crate::entry_to_message(crate::parse_jsonl_line(json).unwrap()).unwrap()];
+
+        let msgpack = pack(&messages, None).unwrap();
+        let decoded: serde_json::Value = rmp_serde::from_slice(&msgpack).unwrap();
+        assert_eq!(decoded[0]["messageId"], "u1");
+
+        let gzip = pack(&messages, Some("json-gzip")).unwrap();
+        let mut text = String::new();
+        flate2::read::GzDecoder::new(&gzip[..]).read_to_string(&mut text).unwrap();
+        let decoded: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(decoded[0]["content"], "hi");
+
+        assert!(pack(&messages, Some("xml")).is_err());
+    }
+
+    #[test]
+    fn test_raw_content_offsets() {
+        let line = |uuid: &str, text: &str| {
+            let json = format!(r#"{{"type":"user","uuid":"{}","message":{{"role":"user","content":"{}"}}}}"#, uuid, text);
+            crate::entry_to_message(crate::parse_jsonl_line(&json).unwrap()).unwrap()
+        };
+        let mut messages = vec![line("a", "first"), line("b", "second one")];
+        let expected: Vec<String> = messages.iter().map(|m| m.raw_content.clone()).collect();
+
+        let bytes = pack_raw_content(&mut messages);
+        for (message, raw) in messages.iter().zip(&expected) {
+            let start = message.raw_content_offset.unwrap() as usize;
+            let end = start + message.raw_content_length.unwrap() as usize;
+            assert_eq!(&bytes[start..end], raw.as_bytes());
+            assert!(message.raw_content.is_empty());
+        }
+
+        let omitted = crate::entry_to_message_with(
+            crate::parse_jsonl_line(r#"{"type":"user","message":{"role":"user","content":"x"}}"#).unwrap(),
+            false,
+        );
+        assert!(omitted.unwrap().raw_content.is_empty());
+    }
+}
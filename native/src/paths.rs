@@ -0,0 +1,190 @@
+// ============================================
+// PLATFORM PATHS
+// File access that survives Windows: paths past MAX_PATH get the \\?\
+// prefix, and files another process holds open (sync clients,
+// antivirus, Claude mid-write) are retried with backoff
+// ============================================
+
+use std::borrow::Cow;
+use std::fs::{File, Metadata, ReadDir};
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+/// Longest directory path Win32 accepts without the \\?\ prefix
+#[cfg_attr(not(windows), allow(dead_code))]
+const MAX_PATH: usize = 248;
+
+/// Waits between attempts on a locked file, about 1.5 s in total
+const RETRY_DELAYS_MS: &[u64] = &[25, 50, 100, 200, 400, 800];
+
+/// ERROR_SHARING_VIOLATION and ERROR_LOCK_VIOLATION
+const LOCK_ERRORS: &[i32] = &[32, 33];
+
+/// The \\?\ form of an absolute Windows path ("C:\a" or "\\server\share\a").
+/// Verbatim paths reach the file system as-is, so / becomes \ and . and ..
+/// are resolved here. None for relative and already prefixed paths
+#[cfg_attr(not(windows), allow(dead_code))]
+fn verbatim(path: &str) -> Option<String> {
+    if path.starts_with(r"\\?\") || path.starts_with(r"\\.\") {
+        return None;
+    }
+    let path = path.replace('/', r"\");
+    let bytes = path.as_bytes();
+    let (prefix, rest, fixed) = if let Some(unc) = path.strip_prefix(r"\\") {
+        // The server and share cannot be climbed out of
+        (r"\\?\UNC\".to_string(), unc, 2)
+    } else if bytes.len() >= 3 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' && bytes[2] == b'\\' {
+        (format!(r"\\?\{}\", &path[..2]), &path[3..], 0)
+    } else {
+        return None;
+    };
+
+    let mut parts: Vec<&str> = Vec::new();
+    for part in rest.split('\\') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                if parts.len() > fixed {
+                    parts.pop();
+                }
+            }
+            _ => parts.push(part),
+        }
+    }
+    Some(format!("{}{}", prefix, parts.join(r"\")))
+}
+
+/// path itself, or on Windows its \\?\ form when it is too long for Win32
+pub(crate) fn long_path(path: &Path) -> Cow<'_, Path> {
+    #[cfg(windows)]
+    if path.as_os_str().len() >= MAX_PATH {
+        let absolute = std::path::absolute(path).ok();
+        if let Some(long) = absolute.as_deref().and_then(Path::to_str).and_then(verbatim) {
+            return Cow::Owned(std::path::PathBuf::from(long));
+        }
+    }
+    Cow::Borrowed(path)
+}
+
+/// Whether err is another process holding the file open without sharing it
+pub(crate) fn is_locked(err: &io::Error) -> bool {
+    cfg!(windows) && err.raw_os_error().is_some_and(|code| LOCK_ERRORS.contains(&code))
+}
+
+fn retry<T>(mut op: impl FnMut() -> io::Result<T>, transient: impl Fn(&io::Error) -> bool, delays: &[u64]) -> io::Result<T> {
+    let mut delays = delays.iter();
+    loop {
+        match op() {
+            Err(e) if transient(&e) => match delays.next() {
+                Some(ms) => std::thread::sleep(Duration::from_millis(*ms)),
+                None => return Err(e),
+            },
+            result => return result,
+        }
+    }
+}
+
+/// Run op again with backoff while the file it touches is locked
+pub(crate) fn retry_locked<T>(op: impl FnMut() -> io::Result<T>) -> io::Result<T> {
+    retry(op, is_locked, RETRY_DELAYS_MS)
+}
+
+/// File::open for long and locked paths
+pub(crate) fn open_file(path: impl AsRef<Path>) -> io::Result<File> {
+    let path = long_path(path.as_ref());
+    retry_locked(|| File::open(&path))
+}
+
+/// std::fs::read for long and locked paths
+pub(crate) fn read_file(path: impl AsRef<Path>) -> io::Result<Vec<u8>> {
+    let path = long_path(path.as_ref());
+    retry_locked(|| std::fs::read(&path))
+}
+
+/// std::fs::read_to_string for long and locked paths
+pub(crate) fn read_to_string(path: impl AsRef<Path>) -> io::Result<String> {
+    let path = long_path(path.as_ref());
+    retry_locked(|| std::fs::read_to_string(&path))
+}
+
+/// std::fs::metadata for long and locked paths
+pub(crate) fn metadata(path: impl AsRef<Path>) -> io::Result<Metadata> {
+    let path = long_path(path.as_ref());
+    retry_locked(|| std::fs::metadata(&path))
+}
+
+/// std::fs::remove_file for long and locked paths
+pub(crate) fn remove_file(path: impl AsRef<Path>) -> io::Result<()> {
+    let path = long_path(path.as_ref());
+    retry_locked(|| std::fs::remove_file(&path))
+}
+
+/// std::fs::remove_dir for long and locked paths
+pub(crate) fn remove_dir(path: impl AsRef<Path>) -> io::Result<()> {
+    let path = long_path(path.as_ref());
+    retry_locked(|| std::fs::remove_dir(&path))
+}
+
+/// std::fs::read_dir for long paths. Entry paths carry the \\?\ prefix when
+/// one was added, so join the entry file names to dir to keep the caller's form
+pub(crate) fn read_dir(dir: &Path) -> io::Result<ReadDir> {
+    std::fs::read_dir(long_path(dir))
+}
+
+/// The path as a string JS can hand back. None for names that are not
+/// valid Unicode, which a JS string could not open again
+pub(crate) fn path_string(path: &Path) -> Option<&str> {
+    path.to_str()
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verbatim_paths() {
+        assert_eq!(verbatim(r"C:\Users\me\.claude\projects").as_deref(), Some(r"\\?\C:\Users\me\.claude\projects"));
+        assert_eq!(verbatim("C:/Users/me/./a/../b.jsonl").as_deref(), Some(r"\\?\C:\Users\me\b.jsonl"));
+        assert_eq!(verbatim(r"\\nas\share\..\..\x").as_deref(), Some(r"\\?\UNC\nas\share\x"));
+        assert_eq!(verbatim(r"\\?\C:\already"), None);
+        assert_eq!(verbatim(r"relative\path"), None);
+        // Short paths and other platforms are left alone
+        assert_eq!(long_path(Path::new("/tmp/a.jsonl")), Path::new("/tmp/a.jsonl"));
+    }
+
+    #[test]
+    fn test_retry_backoff() {
+        let mut attempts = 0;
+        let result = retry(
+            || {
+                attempts += 1;
+                if attempts < 3 { Err(io::Error::from(io::ErrorKind::WouldBlock)) } else { Ok(attempts) }
+            },
+            |e| e.kind() == io::ErrorKind::WouldBlock,
+            &[1, 1, 1],
+        );
+        assert_eq!(result.unwrap(), 3);
+
+        let mut attempts = 0;
+        let result: io::Result<()> = retry(
+            || {
+                attempts += 1;
+                Err(io::Error::from(io::ErrorKind::WouldBlock))
+            },
+            |e| e.kind() == io::ErrorKind::WouldBlock,
+            &[1, 1],
+        );
+        assert!(result.is_err());
+        assert_eq!(attempts, 3);
+
+        // Other errors are not retried
+        let missing = open_file("/nonexistent/paths-test.jsonl").unwrap_err();
+        assert_eq!(missing.kind(), io::ErrorKind::NotFound);
+        assert!(!is_locked(&missing));
+    }
+}
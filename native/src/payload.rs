@@ -0,0 +1,132 @@
+// ============================================
+// PAYLOAD STATISTICS
+// Where the bytes of a session file go - text, tool results, base64
+// images or metadata - to explain huge files and pick truncation options
+// ============================================
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use serde_json::Value;
+
+use crate::{parse_jsonl_line, reader, tool_result_text, ContentItem};
+
+/// Result of get_payload_stats; content categories count the bytes of the
+/// decoded strings, overhead_bytes is whatever JSON around them is left
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PayloadStats {
+    pub file_bytes: i64,
+    pub lines: u32,
+    pub text_bytes: i64,
+    pub thinking_bytes: i64,
+    pub tool_input_bytes: i64,
+    /// Text of tool results (images inside them count as image_bytes)
+    pub tool_result_bytes: i64,
+    /// Base64 image data, in messages and in tool results
+    pub image_bytes: i64,
+    pub image_count: u32,
+    /// Lines that are not messages (file history snapshots, summaries...) or do not parse
+    pub non_message_bytes: i64,
+    pub overhead_bytes: i64,
+    pub largest_line_bytes: i64,
+    /// 1-based, 0 for an empty file
+    pub largest_line_number: u32,
+}
+
+impl PayloadStats {
+    fn image(&mut self, data: &str) {
+        self.image_bytes += data.len() as i64;
+        self.image_count += 1;
+    }
+
+    /// Tool result payload: a string or an array of text and image blocks
+    fn tool_result(&mut self, content: &Value) {
+        if let Value::Array(items) = content {
+            for data in items.iter().filter_map(|item| item.pointer("/source/data").and_then(|d| d.as_str())) {
+                self.image(data);
+            }
+        }
+        self.tool_result_bytes += tool_result_text(content).len() as i64;
+    }
+
+    fn observe_line(&mut self, line_number: usize, line: &str) {
+        let bytes = line.len() as i64 + 1;
+        self.lines += 1;
+        if bytes > self.largest_line_bytes {
+            self.largest_line_bytes = bytes;
+            self.largest_line_number = line_number as u32 + 1;
+        }
+
+        let message = parse_jsonl_line(line)
+            .ok()
+            .filter(|entry| entry.entry_type == "user" || entry.entry_type == "assistant")
+            .and_then(|entry| entry.message);
+        let Some(message) = message else {
+            self.non_message_bytes += bytes;
+            return;
+        };
+        for item in &message.content {
+            match item {
+                ContentItem::Text { text } => self.text_bytes += text.len() as i64,
+                ContentItem::Thinking { thinking, .. } => self.thinking_bytes += thinking.len() as i64,
+                ContentItem::ToolUse { input, .. } => self.tool_input_bytes += input.to_string().len() as i64,
+                ContentItem::ToolResult { content, .. } => self.tool_result(content),
+                ContentItem::Image { source } => self.image(&source.data),
+            }
+        }
+    }
+
+    fn finish(mut self, file_bytes: i64) -> PayloadStats {
+        self.file_bytes = file_bytes;
+        let content = self.text_bytes + self.thinking_bytes + self.tool_input_bytes + self.tool_result_bytes + self.image_bytes;
+        self.overhead_bytes = (file_bytes - self.non_message_bytes - content).max(0);
+        self
+    }
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// How the bytes of one session file split between text, tool traffic,
+/// images and metadata
+#[napi(catch_unwind)]
+pub fn get_payload_stats(file_path: String) -> Result<PayloadStats> {
+    let mut stats = PayloadStats::default();
+    reader::for_each_line(&file_path, reader::DEFAULT_MMAP_THRESHOLD, |line_number, line| {
+        if !line.trim().is_empty() {
+            stats.observe_line(line_number, line);
+        }
+    })?;
+    Ok(stats.finish(reader::file_len(&file_path) as i64))
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_payload_stats() {
+        let tmp = TempDir::new("payload");
+        let path = tmp.join("payload.jsonl");
+        let lines = [
+            r#"{"type":"file-history-snapshot","snapshot":{}}"#,
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"text","text":"look"},{"type":"image","source":{"type":"base64","media_type":"image/png","data":"AAAAAAAA"}}]}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"thinking","thinking":"hm"},{"type":"tool_use","id":"t1","name":"Read","input":{"a":1}}]}}"#,
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":[{"type":"text","text":"png"},{"type":"image","source":{"type":"base64","media_type":"image/png","data":"BBBB"}}]}]}}"#,
+        ];
+        std::fs::write(&path, lines.join("\n")).unwrap();
+
+        let stats = get_payload_stats(path.display().to_string()).unwrap();
+        assert_eq!((stats.lines, stats.image_count, stats.image_bytes), (4, 2, 12));
+        assert_eq!((stats.text_bytes, stats.thinking_bytes, stats.tool_input_bytes, stats.tool_result_bytes), (4, 2, 7, 3));
+        assert_eq!(stats.non_message_bytes, lines[0].len() as i64 + 1);
+        assert_eq!(stats.largest_line_number, 4);
+        assert_eq!(stats.file_bytes, stats.non_message_bytes + stats.overhead_bytes + 28);
+    }
+}
@@ -0,0 +1,179 @@
+// ============================================
+// PERMISSION EVENTS
+// Tool calls the user rejected or that permission settings and hooks
+// blocked - what the agent tried to do but was not allowed to
+// ============================================
+
+use std::collections::HashMap;
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::export::tool_call_summary;
+use crate::title::truncate_words;
+use crate::{for_each_entry, tool_result_text, ContentItem, RawLogEntry};
+
+const REASON_CHARS: usize = 300;
+
+/// Start of the result Claude Code writes when the user rejects a call
+const REJECTED_MARKERS: &[&str] = &["The user doesn't want to proceed with this tool use", "User declined", "User rejected"];
+/// Results of calls blocked by permission settings rather than by a prompt
+const DENIED_MARKERS: &[&str] = &["Permission to use", "has been denied", "haven't granted it yet", "permission denied by"];
+const HOOK_MARKERS: &[&str] = &["blocked by hook", "hook blocked", "PreToolUse:"];
+/// Precedes the user's own words in a rejection
+const FEEDBACK_MARKER: &str = "the user said:";
+
+/// One blocked tool call
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PermissionEvent {
+    /// "rejected" (the user said no), "denied" (permission settings) or "hook"
+    pub kind: String,
+    pub tool: String,
+    /// The tool and its most telling input, e.g. "Bash: rm -rf dist"
+    pub call: String,
+    /// Start of the tool result text
+    pub reason: String,
+    /// What the user typed when rejecting, if anything
+    pub user_feedback: Option<String>,
+    pub tool_use_id: String,
+    /// uuid of the assistant entry that made the call
+    pub message_id: Option<String>,
+    pub timestamp: Option<String>,
+}
+
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolDenials {
+    pub tool: String,
+    pub count: u32,
+}
+
+/// Result of get_permission_events
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PermissionReport {
+    pub rejected: u32,
+    pub denied: u32,
+    pub hook_blocked: u32,
+    /// Most blocked tool first
+    pub by_tool: Vec<ToolDenials>,
+    /// In file order
+    pub events: Vec<PermissionEvent>,
+}
+
+fn classify(text: &str) -> Option<&'static str> {
+    let lower = text.to_lowercase();
+    let has = |markers: &[&str]| markers.iter().any(|m| lower.contains(&m.to_lowercase()));
+    if has(REJECTED_MARKERS) {
+        Some("rejected")
+    } else if has(HOOK_MARKERS) {
+        Some("hook")
+    } else if has(DENIED_MARKERS) {
+        Some("denied")
+    } else {
+        None
+    }
+}
+
+fn user_feedback(text: &str) -> Option<String> {
+    let start = text.to_lowercase().find(FEEDBACK_MARKER)? + FEEDBACK_MARKER.len();
+    let feedback = text.get(start..)?.trim();
+    if feedback.is_empty() { None } else { Some(feedback.to_string()) }
+}
+
+/// A tool call and the entry that made it
+struct ToolCall<'a> {
+    tool: &'a str,
+    call: String,
+    entry: &'a RawLogEntry,
+}
+
+pub(crate) fn permission_report(entries: &[RawLogEntry]) -> PermissionReport {
+    let mut calls: HashMap<&str, ToolCall> = HashMap::new();
+    let mut events = Vec::new();
+
+    for entry in entries {
+        let Some(message) = &entry.message else { continue };
+        for item in &message.content {
+            match item {
+                ContentItem::ToolUse { id, name, input } => {
+                    calls.insert(id, ToolCall { tool: name, call: tool_call_summary(name, input), entry });
+                }
+                ContentItem::ToolResult { tool_use_id, content, is_error: Some(true) } => {
+                    let text = tool_result_text(content);
+                    let Some(kind) = classify(&text) else { continue };
+                    let call = calls.get(tool_use_id.as_str());
+                    let call_entry = call.map_or(entry, |c| c.entry);
+                    events.push(PermissionEvent {
+                        kind: kind.to_string(),
+                        tool: call.map_or("unknown", |c| c.tool).to_string(),
+                        call: call.map_or_else(|| "unknown".to_string(), |c| c.call.clone()),
+                        reason: truncate_words(&text, REASON_CHARS),
+                        user_feedback: user_feedback(&text),
+                        tool_use_id: tool_use_id.clone(),
+                        message_id: call.and_then(|c| c.entry.uuid.clone()),
+                        timestamp: call_entry.timestamp.clone(),
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let count = |kind: &str| events.iter().filter(|e| e.kind == kind).count() as u32;
+    let mut tools: HashMap<&str, u32> = HashMap::new();
+    for event in &events {
+        *tools.entry(&event.tool).or_insert(0) += 1;
+    }
+    let mut by_tool: Vec<ToolDenials> = tools.into_iter().map(|(tool, count)| ToolDenials { tool: tool.to_string(), count }).collect();
+    by_tool.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.tool.cmp(&b.tool)));
+
+    PermissionReport {
+        rejected: count("rejected"),
+        denied: count("denied"),
+        hook_blocked: count("hook"),
+        by_tool,
+        events,
+    }
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Tool calls of a session that were rejected by the user or blocked by settings or hooks
+#[napi(catch_unwind)]
+pub fn get_permission_events(file_path: String) -> Result<PermissionReport> {
+    let mut entries = Vec::new();
+    for_each_entry(&file_path, |entry| entries.push(entry))?;
+    Ok(permission_report(&entries))
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_jsonl_line;
+
+    #[test]
+    fn test_rejections_and_denials() {
+        let lines = [
+            r#"{"type":"assistant","uuid":"a1","message":{"role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Bash","input":{"command":"rm -rf dist"}},{"type":"tool_use","id":"t2","name":"Write","input":{"file_path":"/etc/hosts"}},{"type":"tool_use","id":"t3","name":"Bash","input":{"command":"ls"}}]}}"#,
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","is_error":true,"content":"The user doesn't want to proceed with this tool use. The tool use was rejected. To tell you how to proceed, the user said:\nKeep dist, just rebuild"}]}}"#,
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t2","is_error":true,"content":"Claude requested permissions to write to /etc/hosts, but you haven't granted it yet."}]}}"#,
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t3","is_error":true,"content":"ls: cannot access: No such file"}]}}"#,
+        ];
+        let entries: Vec<RawLogEntry> = lines.iter().map(|l| parse_jsonl_line(l).unwrap()).collect();
+        let report = permission_report(&entries);
+
+        assert_eq!((report.rejected, report.denied, report.hook_blocked), (1, 1, 0));
+        assert_eq!(report.events[0].call, "Bash: rm -rf dist");
+        assert_eq!(report.events[0].user_feedback.as_deref(), Some("Keep dist, just rebuild"));
+        assert_eq!(report.events[1].message_id.as_deref(), Some("a1"));
+        assert_eq!(report.by_tool.len(), 2);
+    }
+}
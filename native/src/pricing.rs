@@ -0,0 +1,85 @@
+// ============================================
+// MODEL PRICING
+// Estimated USD cost of token usage from per-model list prices,
+// overridable by the app when prices change
+// ============================================
+
+use napi_derive::napi;
+
+use crate::TokenUsage;
+
+/// USD per million tokens for models whose name contains `pattern`
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelPrice {
+    /// Matched against the model name, e.g. "opus-4-5" or "sonnet"
+    pub pattern: String,
+    pub input_per_mtok: f64,
+    pub output_per_mtok: f64,
+    pub cache_write_per_mtok: f64,
+    pub cache_read_per_mtok: f64,
+}
+
+/// Public list prices, most specific pattern first
+const DEFAULT_PRICES: &[(&str, f64, f64, f64, f64)] = &[
+    ("opus-4-5", 5.0, 25.0, 6.25, 0.50),
+    ("opus", 15.0, 75.0, 18.75, 1.50),
+    ("sonnet", 3.0, 15.0, 3.75, 0.30),
+    ("haiku-4-5", 1.0, 5.0, 1.25, 0.10),
+    ("3-5-haiku", 0.80, 4.0, 1.0, 0.08),
+    ("haiku", 0.25, 1.25, 0.30, 0.03),
+];
+
+pub(crate) fn default_prices() -> Vec<ModelPrice> {
+    DEFAULT_PRICES
+        .iter()
+        .map(|&(pattern, input, output, cache_write, cache_read)| ModelPrice {
+            pattern: pattern.to_string(),
+            input_per_mtok: input,
+            output_per_mtok: output,
+            cache_write_per_mtok: cache_write,
+            cache_read_per_mtok: cache_read,
+        })
+        .collect()
+}
+
+/// Overrides first, then the defaults; None for unknown models
+pub(crate) fn price_for<'a>(model: &str, overrides: &'a [ModelPrice], defaults: &'a [ModelPrice]) -> Option<&'a ModelPrice> {
+    let model = model.to_lowercase();
+    overrides
+        .iter()
+        .chain(defaults)
+        .find(|price| model.contains(&price.pattern.to_lowercase()))
+}
+
+/// Estimated cost of one response in USD
+pub(crate) fn cost_usd(price: &ModelPrice, usage: &TokenUsage) -> f64 {
+    let per_token = |tokens: i64, per_mtok: f64| tokens as f64 * per_mtok / 1_000_000.0;
+    per_token(usage.input_tokens as i64, price.input_per_mtok)
+        + per_token(usage.output_tokens as i64, price.output_per_mtok)
+        + per_token(usage.cache_creation_input_tokens.unwrap_or(0) as i64, price.cache_write_per_mtok)
+        + per_token(usage.cache_read_input_tokens.unwrap_or(0) as i64, price.cache_read_per_mtok)
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_price_lookup_and_cost() {
+        let defaults = default_prices();
+        assert_eq!(price_for("claude-opus-4-5-20251101", &[], &defaults).unwrap().pattern, "opus-4-5");
+        assert_eq!(price_for("claude-opus-4-1-20250805", &[], &defaults).unwrap().pattern, "opus");
+        assert!(price_for("<synthetic>", &[], &defaults).is_none());
+
+        let custom = ModelPrice { pattern: "sonnet".into(), input_per_mtok: 1.0, output_per_mtok: 2.0, cache_write_per_mtok: 0.0, cache_read_per_mtok: 0.0 };
+        let overrides = [custom];
+        let price = price_for("claude-sonnet-4-5", &overrides, &defaults).unwrap();
+        let usage = TokenUsage { input_tokens: 1_000_000, output_tokens: 500_000, cache_creation_input_tokens: None, cache_read_input_tokens: Some(10) };
+        assert!((cost_usd(price, &usage) - 2.0).abs() < 1e-9);
+    }
+}
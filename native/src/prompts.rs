@@ -0,0 +1,174 @@
+// ============================================
+// PROMPT LIBRARY
+// Mine history for reusable prompts: distinct user prompts with
+// usage counts, exportable as JSON or Markdown
+// ============================================
+
+use std::collections::HashMap;
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{internal, unsupported_format};
+use crate::title::{is_substantive_prompt, strip_tag_blocks};
+use crate::{collect_session_files, extract_text_content, for_each_entry};
+
+const DEFAULT_MIN_LENGTH: u32 = 20;
+
+/// A distinct user prompt and where it was used
+#[napi(object)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(rename_all = "camelCase")]
+pub struct ExtractedPrompt {
+    pub text: String,
+    pub count: u32,
+    /// Session ids the prompt appeared in (first use first)
+    pub sessions: Vec<String>,
+    pub first_used: Option<String>,
+    pub last_used: Option<String>,
+}
+
+/// Grouping key: exact text, or case/whitespace-insensitive when deduping
+fn prompt_key(text: &str, dedupe: bool) -> String {
+    if dedupe {
+        text.split_whitespace()
+            .collect::<Vec<&str>>()
+            .join(" ")
+            .to_lowercase()
+    } else {
+        text.to_string()
+    }
+}
+
+fn update_span(prompt: &mut ExtractedPrompt, timestamp: Option<&String>) {
+    let Some(ts) = timestamp else { return };
+    if prompt.first_used.as_ref().is_none_or(|first| ts < first) {
+        prompt.first_used = Some(ts.clone());
+    }
+    if prompt.last_used.as_ref().is_none_or(|last| ts > last) {
+        prompt.last_used = Some(ts.clone());
+    }
+}
+
+/// Render a prompt list as Markdown (one section per prompt)
+fn prompts_to_markdown(prompts: &[ExtractedPrompt]) -> String {
+    let mut out = String::from("# Prompt Library\n\n");
+    for (i, prompt) in prompts.iter().enumerate() {
+        out.push_str(&format!("## Prompt {}\n\n", i + 1));
+        out.push_str(&format!(
+            "- Used: {} time{} in {} session{}\n",
+            prompt.count,
+            if prompt.count == 1 { "" } else { "s" },
+            prompt.sessions.len(),
+            if prompt.sessions.len() == 1 { "" } else { "s" },
+        ));
+        if let (Some(first), Some(last)) = (&prompt.first_used, &prompt.last_used) {
+            out.push_str(&format!("- First used: {}\n- Last used: {}\n", first, last));
+        }
+
+        // Fence with more backticks than the prompt contains
+        let longest_run = prompt
+            .text
+            .split(|c| c != '`')
+            .map(str::len)
+            .max()
+            .unwrap_or(0);
+        let fence = "`".repeat(longest_run.max(2) + 1);
+        out.push_str(&format!("\n{}text\n{}\n{}\n\n", fence, prompt.text, fence));
+    }
+    out
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Distinct user prompts under root_dir, most used first
+#[napi(catch_unwind)]
+pub fn extract_prompts(
+    root_dir: String,
+    min_length: Option<u32>,
+    dedupe: Option<bool>,
+) -> Result<Vec<ExtractedPrompt>> {
+    let min_length = min_length.unwrap_or(DEFAULT_MIN_LENGTH) as usize;
+    let dedupe = dedupe.unwrap_or(true);
+
+    let mut prompts: Vec<ExtractedPrompt> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+
+    for path in collect_session_files(&root_dir)? {
+        let file_path = path.display().to_string();
+        let _ = for_each_entry(&file_path, |entry| {
+            if !is_substantive_prompt(&entry) {
+                return;
+            }
+            let Some(message) = &entry.message else { return };
+            let text = strip_tag_blocks(&extract_text_content(&message.content)).trim().to_string();
+            if text.chars().count() < min_length {
+                return;
+            }
+
+            let session_id = entry.session_id.clone().unwrap_or_else(|| "unknown".to_string());
+            let key = prompt_key(&text, dedupe);
+            let i = *index.entry(key).or_insert_with(|| {
+                prompts.push(ExtractedPrompt {
+                    text,
+                    count: 0,
+                    sessions: Vec::new(),
+                    first_used: None,
+                    last_used: None,
+                });
+                prompts.len() - 1
+            });
+
+            let prompt = &mut prompts[i];
+            prompt.count += 1;
+            if !prompt.sessions.contains(&session_id) {
+                prompt.sessions.push(session_id);
+            }
+            update_span(prompt, entry.timestamp.as_ref());
+        });
+    }
+
+    prompts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| b.last_used.cmp(&a.last_used)));
+    Ok(prompts)
+}
+
+/// Serialize extracted prompts for saving: format is "json" or "markdown"
+#[napi(catch_unwind)]
+pub fn export_prompt_library(prompts: Vec<ExtractedPrompt>, format: String) -> Result<String> {
+    match format.as_str() {
+        "json" => serde_json::to_string_pretty(&prompts)
+            .map_err(|e| internal(format!("Cannot serialize prompts: {}", e))),
+        "markdown" | "md" => Ok(prompts_to_markdown(&prompts)),
+        other => Err(unsupported_format(format!("Unsupported export format: {}", other))),
+    }
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prompt_key_and_markdown() {
+        assert_eq!(prompt_key("Review  this\nPR", true), prompt_key("review this pr", true));
+        assert_ne!(prompt_key("Review this PR", false), prompt_key("review this pr", false));
+
+        let markdown = prompts_to_markdown(&[ExtractedPrompt {
+            text: "Explain ```code``` please".to_string(),
+            count: 2,
+            sessions: vec!["s1".to_string()],
+            first_used: None,
+            last_used: None,
+        }]);
+        assert!(markdown.contains("- Used: 2 times in 1 session\n"));
+        assert!(markdown.contains("````text\n"));
+    }
+}
@@ -0,0 +1,178 @@
+// ============================================
+// BURN RATE
+// Tokens and messages per hour over recent windows, plus where the
+// current 5-hour usage block is heading, for a live widget
+// ============================================
+
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::errors::invalid_argument;
+use crate::usage::for_each_usage;
+
+/// Length of a Claude usage block
+const BLOCK_HOURS: i64 = 5;
+/// How far back to look for the start of the current block
+const BLOCK_LOOKBACK_HOURS: i64 = 24;
+
+/// Activity within one time window
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateWindow {
+    pub start: String,
+    pub end: String,
+    /// Input + output tokens
+    pub tokens: i64,
+    pub messages: u32,
+    pub tokens_per_hour: f64,
+    pub messages_per_hour: f64,
+}
+
+/// The 5-hour block that is still open, started on the hour of its first message
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsageBlock {
+    pub start: String,
+    pub end: String,
+    pub tokens: i64,
+    pub messages: u32,
+    pub remaining_minutes: u32,
+    /// Block tokens so far plus the current window's rate until the block ends
+    pub projected_tokens: i64,
+}
+
+/// Result of get_burn_rate
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BurnRate {
+    pub window_minutes: u32,
+    /// The last window_minutes
+    pub current: RateWindow,
+    /// The window_minutes before that, for a trend arrow
+    pub previous: RateWindow,
+    /// None when nothing happened in the last 5 hours
+    pub block: Option<UsageBlock>,
+}
+
+/// Messages in time order as (timestamp, tokens)
+fn recent_activity(root_dir: &str, from: DateTime<Utc>, now: DateTime<Utc>) -> Result<Vec<(DateTime<Utc>, i64)>> {
+    let mut activity = Vec::new();
+    for_each_usage(root_dir, Some(from), now, |event| {
+        let tokens = event.usage.map_or(0, |u| u.input_tokens as i64 + u.output_tokens as i64);
+        activity.push((event.timestamp, tokens));
+    })?;
+    activity.sort_by_key(|&(ts, _)| ts);
+    Ok(activity)
+}
+
+fn rate_window(activity: &[(DateTime<Utc>, i64)], start: DateTime<Utc>, end: DateTime<Utc>) -> RateWindow {
+    let in_window = activity.iter().filter(|(ts, _)| *ts > start && *ts <= end);
+    let (tokens, messages) = in_window.fold((0, 0), |(tokens, messages), (_, t)| (tokens + t, messages + 1));
+    let hours = (end - start).num_seconds() as f64 / 3600.0;
+    RateWindow {
+        start: start.to_rfc3339(),
+        end: end.to_rfc3339(),
+        tokens,
+        messages,
+        tokens_per_hour: tokens as f64 / hours,
+        messages_per_hour: messages as f64 / hours,
+    }
+}
+
+/// Blocks open on the hour of the first message after the previous block closed
+fn current_block(activity: &[(DateTime<Utc>, i64)], now: DateTime<Utc>, rate_per_hour: f64) -> Option<UsageBlock> {
+    let block_len = Duration::hours(BLOCK_HOURS);
+    let mut block: Option<(DateTime<Utc>, i64, u32)> = None;
+    for &(ts, tokens) in activity {
+        match &mut block {
+            Some((start, sum, count)) if ts < *start + block_len => {
+                *sum += tokens;
+                *count += 1;
+            }
+            _ => block = Some((ts.duration_trunc(Duration::hours(1)).unwrap_or(ts), tokens, 1)),
+        }
+    }
+
+    let (start, tokens, messages) = block?;
+    let end = start + block_len;
+    if end <= now {
+        return None;
+    }
+    let remaining = end - now;
+    Some(UsageBlock {
+        start: start.to_rfc3339(),
+        end: end.to_rfc3339(),
+        tokens,
+        messages,
+        remaining_minutes: remaining.num_minutes() as u32,
+        projected_tokens: tokens + (rate_per_hour * remaining.num_seconds() as f64 / 3600.0) as i64,
+    })
+}
+
+fn burn_rate(root_dir: &str, window_minutes: u32, now: DateTime<Utc>) -> Result<BurnRate> {
+    if window_minutes == 0 {
+        return Err(invalid_argument("window_minutes must be positive"));
+    }
+    let window = Duration::minutes(window_minutes as i64);
+    let lookback = (window * 2).max(Duration::hours(BLOCK_LOOKBACK_HOURS));
+    let activity = recent_activity(root_dir, now - lookback, now)?;
+
+    let current = rate_window(&activity, now - window, now);
+    let previous = rate_window(&activity, now - window * 2, now - window);
+    let block = current_block(&activity, now, current.tokens_per_hour);
+    Ok(BurnRate { window_minutes, current, previous, block })
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Recent tokens/hour and messages/hour under root_dir over window_minutes
+#[napi(catch_unwind)]
+pub fn get_burn_rate(root_dir: String, window_minutes: u32) -> Result<BurnRate> {
+    burn_rate(&root_dir, window_minutes, Utc::now())
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+    use crate::time::parse_utc;
+
+    #[test]
+    fn test_burn_rate_and_block() {
+        let root = TempDir::new("rate-test");
+        std::fs::create_dir_all(root.join("proj")).unwrap();
+        let line = |id: &str, ts: &str, output: u32| {
+            format!(
+                r#"{{"type":"assistant","sessionId":"s1","timestamp":"{}","message":{{"id":"{}","role":"assistant","content":"x","usage":{{"input_tokens":0,"output_tokens":{}}}}}}}"#,
+                ts, id, output
+            )
+        };
+        let lines = [
+            line("r0", "2024-03-10T02:00:00Z", 5000), // earlier, closed block
+            line("r1", "2024-03-10T09:20:00Z", 1000),
+            line("r2", "2024-03-10T10:40:00Z", 2000),
+            line("r3", "2024-03-10T11:10:00Z", 3000),
+        ];
+        std::fs::write(root.join("proj/s1.jsonl"), lines.join("\n")).unwrap();
+
+        let now = parse_utc("2024-03-10T11:30:00Z").unwrap();
+        let rate = burn_rate(&root.to_string_lossy(), 60, now).unwrap();
+        assert_eq!((rate.current.tokens, rate.current.messages), (5000, 2));
+        assert_eq!((rate.previous.tokens, rate.previous.messages), (0, 0));
+        assert!((rate.current.tokens_per_hour - 5000.0).abs() < 1e-9);
+
+        // Block 09:00-14:00, 2.5 hours left at 5000 tokens/hour
+        let block = rate.block.unwrap();
+        assert_eq!((block.start.as_str(), block.tokens, block.remaining_minutes), ("2024-03-10T09:00:00+00:00", 6000, 150));
+        assert_eq!(block.projected_tokens, 18_500);
+
+        assert!(burn_rate(&root.to_string_lossy(), 0, now).is_err());
+    }
+}
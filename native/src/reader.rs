@@ -0,0 +1,459 @@
+// ============================================
+// LINE READER
+// BufReader for normal files, memory-mapped line splitting for very
+// large ones (no String allocation per line), and parallel chunked
+// parsing for the biggest
+// ============================================
+
+use std::borrow::Cow;
+use std::io::{BufRead, BufReader, Read};
+use std::ops::ControlFlow;
+use std::panic::{self, AssertUnwindSafe};
+
+use memmap2::Mmap;
+use napi::bindgen_prelude::*;
+use rayon::prelude::*;
+
+use crate::errors::{io_error, ErrorCode, HubError};
+use crate::paths::{metadata, open_file};
+
+/// Files at least this big are memory-mapped (64 MiB)
+pub const DEFAULT_MMAP_THRESHOLD: u64 = 64 * 1024 * 1024;
+
+/// Files at least this big are parsed in parallel chunks (32 MiB)
+pub const DEFAULT_PARALLEL_THRESHOLD: u64 = 32 * 1024 * 1024;
+
+/// Chunks smaller than this are not worth a rayon task
+const MIN_CHUNK_BYTES: usize = 1024 * 1024;
+
+/// Resolve a byte threshold from an optional JS value (negative = never)
+fn threshold(option: Option<i64>, default: u64) -> u64 {
+    match option {
+        Some(t) if t < 0 => u64::MAX,
+        Some(t) => t as u64,
+        None => default,
+    }
+}
+
+pub(crate) fn mmap_threshold(option: Option<i64>) -> u64 {
+    threshold(option, DEFAULT_MMAP_THRESHOLD)
+}
+
+pub(crate) fn parallel_threshold(option: Option<i64>) -> u64 {
+    threshold(option, DEFAULT_PARALLEL_THRESHOLD)
+}
+
+/// File size in bytes, 0 if it cannot be read
+pub(crate) fn file_len(file_path: &str) -> u64 {
+    metadata(file_path).map(|m| m.len()).unwrap_or(0)
+}
+
+/// Decode one line, dropping the \r of CRLF files. Invalid UTF-8 is replaced
+/// instead of aborting the whole file
+fn decode_line(bytes: &[u8]) -> Cow<'_, str> {
+    let bytes = bytes.strip_suffix(b"\r").unwrap_or(bytes);
+    String::from_utf8_lossy(bytes)
+}
+
+/// Lines of a reader, decoded like decode_line so a bad byte costs one
+/// line rather than the rest of the file. Stops at the first read error
+pub(crate) fn lossy_lines(reader: impl BufRead) -> impl Iterator<Item = String> {
+    reader.split(b'\n').map_while(std::result::Result::ok).map(|bytes| decode_line(&bytes).into_owned())
+}
+
+/// Text of a panic payload (panic!("...") gives &str or String)
+pub(crate) fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(text) = payload.downcast_ref::<String>() {
+        text.clone()
+    } else if let Some(text) = payload.downcast_ref::<&str>() {
+        text.to_string()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Run one line callback; a panic is re-raised with the file and line number
+/// attached, so the JS exception says which data triggered it
+pub(crate) fn with_line_context<R>(file_path: &str, index: usize, f: impl FnOnce() -> R) -> R {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(result) => result,
+        Err(payload) => panic::resume_unwind(Box::new(format!(
+            "Internal error while parsing {} at line {}: {}",
+            file_path,
+            index + 1,
+            panic_message(&*payload)
+        ))),
+    }
+}
+
+/// Line number with_line_context put in a panic message, if it did
+fn panic_line(message: &str) -> Option<u32> {
+    let rest = message.strip_prefix("Internal error while parsing ")?;
+    let (_, after) = rest.split_once(" at line ")?;
+    after.split(':').next()?.parse().ok()
+}
+
+/// Run f, turning a panic into an INTERNAL error for file_path. For work
+/// outside napi's own catch_unwind, e.g. an AsyncTask's compute, where an
+/// unwinding panic would abort the process
+pub(crate) fn catch_panic<R>(file_path: &str, f: impl FnOnce() -> Result<R>) -> Result<R> {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        let message = panic_message(&*payload);
+        let mut error = HubError::new(ErrorCode::Internal, message.clone()).path(file_path);
+        if let Some(line) = panic_line(&message) {
+            error = error.line(line);
+        }
+        Err(error.into())
+    })
+}
+
+/// One line as handed to callbacks: its text, or its length in bytes when it
+/// exceeded the line limit and was never buffered
+pub(crate) type LineRef<'a> = std::result::Result<&'a str, u64>;
+
+/// Call `f(line_index, line)` for every line of the file (0-based index)
+pub(crate) fn for_each_line(file_path: &str, mmap_threshold: u64, mut f: impl FnMut(usize, &str)) -> Result<()> {
+    for_each_line_bounded(file_path, mmap_threshold, u64::MAX, u64::MAX, |index, line| {
+        if let Ok(line) = line {
+            f(index, line);
+        }
+        ControlFlow::Continue(())
+    })
+}
+
+/// Like for_each_line, but lines longer than max_line_bytes are measured and
+/// discarded instead of buffered, so one huge line cannot exhaust memory.
+/// Nothing past the first read_len bytes is read (u64::MAX = the whole file),
+/// nor anything after f breaks
+pub(crate) fn for_each_line_bounded(
+    file_path: &str,
+    mmap_threshold: u64,
+    max_line_bytes: u64,
+    read_len: u64,
+    mut f: impl FnMut(usize, LineRef) -> ControlFlow<()>,
+) -> Result<()> {
+    let mut f = |index: usize, line: LineRef| with_line_context(file_path, index, || f(index, line));
+
+    let file = open_file(file_path)
+        .map_err(|e| io_error("Cannot open file", file_path, e))?;
+
+    let size = file.metadata().map(|m| m.len()).unwrap_or(0).min(read_len);
+
+    if size > 0 && size >= mmap_threshold {
+        // SAFETY: the map is read-only and dropped before returning. Claude may
+        // append to the file meanwhile, which only grows it past our mapped length
+        let map = unsafe { Mmap::map(&file) }
+            .map_err(|e| io_error("Cannot map file", file_path, e))?;
+        let map = &map[..(size as usize).min(map.len())];
+
+        let mut start = 0;
+        let mut index = 0;
+        for end in memchr::memchr_iter(b'\n', map) {
+            if emit_line(&map[start..end], max_line_bytes, |line| f(index, line)).is_break() {
+                return Ok(());
+            }
+            start = end + 1;
+            index += 1;
+        }
+        if start < map.len() {
+            let _ = emit_line(&map[start..], max_line_bytes, |line| f(index, line));
+        }
+        return Ok(());
+    }
+
+    let mut reader = BufReader::new(file.take(read_len));
+    let mut buf = Vec::new();
+    let mut index = 0;
+    loop {
+        buf.clear();
+        let mut len: u64 = 0;
+        let mut newline = false;
+
+        loop {
+            let available = reader.fill_buf().map_err(|e| {
+                Error::from(HubError::new(ErrorCode::IoError, format!("Error reading line: {}", e)).path(file_path).line(index as u32 + 1))
+            })?;
+            if available.is_empty() {
+                break;
+            }
+
+            let (chunk, found) = match memchr::memchr(b'\n', available) {
+                Some(pos) => (&available[..pos], true),
+                None => (available, false),
+            };
+            len += chunk.len() as u64;
+            if len <= max_line_bytes {
+                buf.extend_from_slice(chunk);
+            } else if !buf.is_empty() {
+                buf = Vec::new();
+            }
+
+            let consumed = chunk.len() + found as usize;
+            reader.consume(consumed);
+            if found {
+                newline = true;
+                break;
+            }
+        }
+
+        if !newline && len == 0 {
+            break;
+        }
+        let flow = if len > max_line_bytes { f(index, Err(len)) } else { f(index, Ok(&decode_line(&buf))) };
+        index += 1;
+        if !newline || flow.is_break() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+fn emit_line<R>(bytes: &[u8], max_line_bytes: u64, f: impl FnOnce(LineRef) -> R) -> R {
+    if bytes.len() as u64 > max_line_bytes {
+        f(Err(bytes.len() as u64))
+    } else {
+        f(Ok(&decode_line(bytes)))
+    }
+}
+
+/// Split data into roughly equal chunks that each end right after a newline
+fn chunk_bounds(data: &[u8], chunks: usize) -> Vec<(usize, usize)> {
+    let mut bounds = Vec::with_capacity(chunks);
+    let mut start = 0;
+
+    for i in 1..chunks {
+        let target = (data.len() * i / chunks).max(start);
+        let end = match memchr::memchr(b'\n', &data[target..]) {
+            Some(pos) => target + pos + 1,
+            None => data.len(),
+        };
+        if end > start {
+            bounds.push((start, end));
+            start = end;
+        }
+        if start >= data.len() {
+            break;
+        }
+    }
+    if start < data.len() {
+        bounds.push((start, data.len()));
+    }
+    bounds
+}
+
+/// Run `f(line_index, line)` over all lines on the rayon pool and hand the
+/// produced values to `keep` in file order. Lines over max_line_bytes arrive
+/// as Err(len). The file is parsed a window at a time, one chunk per thread
+/// and at most max_window_bytes (e.g. a memory budget) per window, so once
+/// keep breaks no further window is read or parsed. Files below
+/// mmap_threshold are streamed window by window rather than read whole
+pub(crate) fn par_filter_map_lines<T, F>(
+    file_path: &str,
+    mmap_threshold: u64,
+    max_line_bytes: u64,
+    read_len: u64,
+    max_window_bytes: u64,
+    f: F,
+    mut keep: impl FnMut(T) -> ControlFlow<()>,
+) -> Result<()>
+where
+    T: Send,
+    F: Fn(usize, LineRef) -> Option<T> + Sync,
+{
+    let file = open_file(file_path)
+        .map_err(|e| io_error("Cannot open file", file_path, e))?;
+    let size = file.metadata().map(|m| m.len()).unwrap_or(0).min(read_len);
+    if size == 0 {
+        return Ok(());
+    }
+
+    let threads = crate::config::install(rayon::current_num_threads).max(1);
+    // A quarter of the file per window keeps every thread busy for a few
+    // windows; the cap bounds what is parsed ahead of keep
+    let chunk_bytes = (size / (threads as u64 * 4)).max(MIN_CHUNK_BYTES as u64).min(max_window_bytes / threads as u64).max(1);
+    let window_bytes = chunk_bytes * threads as u64;
+
+    let mapped = if size >= mmap_threshold {
+        // SAFETY: read-only map, see for_each_line
+        let map = unsafe { Mmap::map(&file) }.map_err(|e| io_error("Cannot map file", file_path, e))?;
+        Some(map)
+    } else {
+        None
+    };
+    let mut stream = BufReader::new((&file).take(size));
+    let read_error = |e: std::io::Error| io_error("Cannot read file", file_path, e);
+
+    let parse_chunk = |chunk: &[u8], first_line: usize| {
+        let mut out = Vec::new();
+        let mut line_start = 0;
+        let mut index = first_line;
+        for pos in memchr::memchr_iter(b'\n', chunk) {
+            emit_line(&chunk[line_start..pos], max_line_bytes, |line| {
+                out.extend(with_line_context(file_path, index, || f(index, line)))
+            });
+            line_start = pos + 1;
+            index += 1;
+        }
+        if line_start < chunk.len() {
+            emit_line(&chunk[line_start..], max_line_bytes, |line| {
+                out.extend(with_line_context(file_path, index, || f(index, line)))
+            });
+        }
+        out
+    };
+
+    let mut offset = 0;
+    let mut line = 0;
+    let mut buffer = Vec::new();
+    loop {
+        // The next window_bytes, extended to the end of the line they stop in
+        let window: &[u8] = match &mapped {
+            Some(map) => {
+                let data = &map[..(size as usize).min(map.len())];
+                if offset >= data.len() {
+                    break;
+                }
+                let target = (offset + window_bytes as usize).min(data.len());
+                let end = memchr::memchr(b'\n', &data[target..]).map_or(data.len(), |pos| target + pos + 1);
+                let window = &data[offset..end];
+                offset = end;
+                window
+            }
+            None => {
+                buffer.clear();
+                (&mut stream).take(window_bytes).read_to_end(&mut buffer).map_err(read_error)?;
+                if buffer.is_empty() {
+                    break;
+                }
+                if !buffer.ends_with(b"\n") {
+                    stream.read_until(b'\n', &mut buffer).map_err(read_error)?;
+                }
+                &buffer
+            }
+        };
+
+        let bounds = chunk_bounds(window, threads);
+        // Line number of each chunk's first line, so errors still report real lines
+        let results: Vec<(usize, Vec<T>)> = crate::config::install(|| {
+            let newlines: Vec<usize> =
+                bounds.par_iter().map(|(start, end)| memchr::memchr_iter(b'\n', &window[*start..*end]).count()).collect();
+            let mut first_lines = Vec::with_capacity(bounds.len());
+            let mut next = line;
+            for count in &newlines {
+                first_lines.push(next);
+                next += count;
+            }
+            let parsed = bounds.par_iter().zip(first_lines.par_iter()).map(|((start, end), first)| parse_chunk(&window[*start..*end], *first));
+            parsed.zip(newlines.par_iter()).map(|(out, count)| (*count, out)).collect()
+        });
+        for (newlines, values) in results {
+            line += newlines;
+            for value in values {
+                if keep(value).is_break() {
+                    return Ok(());
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_mmap_and_buffered_agree() {
+        let tmp = TempDir::new("reader-test");
+        let path = tmp.join("reader-test.jsonl");
+        std::fs::write(&path, "first\r\nsecond\n\nlast").unwrap();
+        let file_path = path.display().to_string();
+
+        let mut buffered = Vec::new();
+        for_each_line(&file_path, u64::MAX, |i, line| buffered.push((i, line.to_string()))).unwrap();
+        let mut mapped = Vec::new();
+        for_each_line(&file_path, 0, |i, line| mapped.push((i, line.to_string()))).unwrap();
+
+        assert_eq!(buffered, mapped);
+        assert_eq!(mapped.len(), 4);
+        assert_eq!(mapped[0].1, "first");
+
+        // Oversized lines are reported by length, the rest still come through
+        let mut bounded = Vec::new();
+        for_each_line_bounded(&file_path, u64::MAX, 5, u64::MAX, |i, line| {
+            bounded.push((i, line.map(str::to_string)));
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+        assert_eq!(bounded[0], (0, Err(6)));
+        assert_eq!(bounded[3], (3, Ok("last".to_string())));
+
+        // Nothing is read after the callback breaks, mapped or not
+        for threshold in [0, u64::MAX] {
+            let mut seen = 0;
+            for_each_line_bounded(&file_path, threshold, u64::MAX, u64::MAX, |i, _| {
+                seen += 1;
+                if i == 1 { ControlFlow::Break(()) } else { ControlFlow::Continue(()) }
+            })
+            .unwrap();
+            assert_eq!(seen, 2);
+        }
+    }
+
+    #[test]
+    fn test_lossy_lines_skip_past_invalid_utf8() {
+        let data: &[u8] = b"one\r\nbad \xff byte\nthree";
+        let lines: Vec<String> = lossy_lines(data).collect();
+        assert_eq!(lines, vec!["one", "bad \u{fffd} byte", "three"]);
+    }
+
+    #[test]
+    fn test_parallel_lines_keep_order() {
+        let data: String = (0..1000).map(|i| format!("line {}\n", i)).collect();
+        let bounds = chunk_bounds(data.as_bytes(), 7);
+        assert_eq!(bounds.first().unwrap().0, 0);
+        assert_eq!(bounds.last().unwrap().1, data.len());
+        assert!(bounds.windows(2).all(|w| w[0].1 == w[1].0 && data.as_bytes()[w[0].1 - 1] == b'\n'));
+
+        let tmp = TempDir::new("reader-par");
+        let path = tmp.join("reader-par.jsonl");
+        std::fs::write(&path, &data).unwrap();
+        let file_path = path.display().to_string();
+        // Streamed and mapped, in one window and in many small ones
+        for (mmap_threshold, window) in [(u64::MAX, u64::MAX), (u64::MAX, 100), (0, 100)] {
+            let mut lines = Vec::new();
+            par_filter_map_lines(&file_path, mmap_threshold, u64::MAX, u64::MAX, window, |i, line| Some((i, line.unwrap().to_string())), |line| {
+                lines.push(line);
+                ControlFlow::Continue(())
+            })
+            .unwrap();
+            assert_eq!(lines.len(), 1000);
+            assert!(lines.iter().enumerate().all(|(i, (n, l))| *n == i && *l == format!("line {}", i)));
+        }
+
+        // Once keep breaks, only the window in flight has been parsed
+        let parsed = std::sync::atomic::AtomicUsize::new(0);
+        let count = |_: usize, _: LineRef| Some(parsed.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+        par_filter_map_lines(&file_path, 0, u64::MAX, u64::MAX, 100, count, |_| ControlFlow::Break(())).unwrap();
+        assert!(parsed.into_inner() < 50);
+
+        // A panicking callback surfaces with the file and line attached
+        let payload = panic::catch_unwind(|| {
+            let _ = for_each_line(&path.display().to_string(), u64::MAX, |i, _| assert!(i != 41, "bad entry"));
+        })
+        .unwrap_err();
+        let message = panic_message(&*payload);
+        assert!(message.contains("at line 42: bad entry"), "{}", message);
+
+        // ... and as an INTERNAL error where it must not unwind further
+        let caught = catch_panic(&path.display().to_string(), || for_each_line(&path.display().to_string(), u64::MAX, |i, _| assert!(i != 41, "bad entry")));
+        let reason = caught.unwrap_err().reason;
+        assert!(reason.starts_with("INTERNAL: ") && reason.contains("at line 42: bad entry"), "{}", reason);
+    }
+}
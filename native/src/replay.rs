@@ -0,0 +1,198 @@
+// ============================================
+// SESSION REPLAY
+// Step through a session message by message, with the files modified
+// and tokens used so far, for a scrubber in the viewer
+// ============================================
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::audit::touched_paths;
+use crate::errors::not_found;
+use crate::{config, entry_to_message, for_each_entry, ClaudeMessage};
+
+static NEXT_REPLAY_ID: AtomicU32 = AtomicU32::new(1);
+static REPLAYS: Mutex<Option<HashMap<u32, Replay>>> = Mutex::new(None);
+
+/// Where a replay stands after a step
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct ReplayState {
+    /// Messages this step added, or every message so far when rewound
+    pub messages: Vec<ClaudeMessage>,
+    /// True when the step went backwards and the state was rebuilt from the start
+    pub rewound: bool,
+    /// Messages replayed so far
+    pub position: u32,
+    pub total_messages: u32,
+    /// Distinct files written or edited so far, first modified first
+    pub files_modified: Vec<String>,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    /// True once every message was replayed
+    pub done: bool,
+}
+
+struct Replay {
+    messages: Vec<ClaudeMessage>,
+    /// Resolved paths written by each message, by message id
+    writes: HashMap<String, Vec<String>>,
+    position: usize,
+    files_modified: Vec<String>,
+    input_tokens: i64,
+    output_tokens: i64,
+}
+
+impl Replay {
+    fn load(file_path: &str) -> Result<Replay> {
+        let mut entries = Vec::new();
+        for_each_entry(file_path, |entry| entries.push(entry))?;
+
+        let mut writes: HashMap<String, Vec<String>> = HashMap::new();
+        for access in touched_paths(&entries).into_iter().filter(|a| a.access == "write") {
+            if let Some(id) = access.message_id {
+                writes.entry(id).or_default().push(access.resolved_path);
+            }
+        }
+        let messages = entries.into_iter().filter_map(entry_to_message).collect();
+        Ok(Replay { messages, writes, position: 0, files_modified: Vec::new(), input_tokens: 0, output_tokens: 0 })
+    }
+
+    /// Replay up to (not including) message index target
+    fn seek(&mut self, target: usize) -> ReplayState {
+        let target = target.min(self.messages.len());
+        let rewound = target < self.position;
+        if rewound {
+            self.position = 0;
+            self.files_modified.clear();
+            self.input_tokens = 0;
+            self.output_tokens = 0;
+        }
+
+        let start = self.position;
+        for message in &self.messages[start..target] {
+            self.input_tokens += message.input_tokens.unwrap_or(0) as i64;
+            self.output_tokens += message.output_tokens.unwrap_or(0) as i64;
+            for path in self.writes.get(&message.message_id).into_iter().flatten() {
+                if !self.files_modified.contains(path) {
+                    self.files_modified.push(path.clone());
+                }
+            }
+        }
+        self.position = target;
+
+        ReplayState {
+            messages: self.messages[start..target].to_vec(),
+            rewound,
+            position: target as u32,
+            total_messages: self.messages.len() as u32,
+            files_modified: self.files_modified.clone(),
+            input_tokens: self.input_tokens,
+            output_tokens: self.output_tokens,
+            done: target == self.messages.len(),
+        }
+    }
+}
+
+fn with_replay<T>(replay: u32, f: impl FnOnce(&mut Replay) -> Result<T>) -> Result<T> {
+    let mut guard = REPLAYS.lock().unwrap_or_else(|e| e.into_inner());
+    let state = guard
+        .get_or_insert_with(HashMap::new)
+        .get_mut(&replay)
+        .ok_or_else(|| not_found(format!("Unknown or closed replay: {}", replay)))?;
+    f(state)
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Load a session for replay, returns a replay id for step_forward and step_to
+#[napi(catch_unwind)]
+pub fn open_replay(file_path: String) -> Result<u32> {
+    let replay = Replay::load(&file_path)?;
+
+    let id = NEXT_REPLAY_ID.fetch_add(1, Ordering::Relaxed);
+    let mut guard = REPLAYS.lock().unwrap_or_else(|e| e.into_inner());
+    let replays = guard.get_or_insert_with(HashMap::new);
+    replays.insert(id, replay);
+    // Replays hold a whole session; same limit as open cursors
+    while replays.len() > config::max_open_cursors() {
+        let Some(oldest) = replays.keys().min().copied() else { break };
+        replays.remove(&oldest);
+    }
+    Ok(id)
+}
+
+/// Replay n more messages (negative n steps back)
+#[napi(catch_unwind)]
+pub fn step_forward(replay: u32, n: i32) -> Result<ReplayState> {
+    with_replay(replay, |state| {
+        let target = (state.position as i64 + n as i64).max(0) as usize;
+        Ok(state.seek(target))
+    })
+}
+
+/// Replay up to and including message_id (or canonical_id), going back if it was already passed
+#[napi(catch_unwind)]
+pub fn step_to(replay: u32, message_id: String) -> Result<ReplayState> {
+    with_replay(replay, |state| {
+        let index = state
+            .messages
+            .iter()
+            .position(|m| m.message_id == message_id || m.canonical_id == message_id)
+            .ok_or_else(|| not_found(format!("Message not found: {}", message_id)))?;
+        Ok(state.seek(index + 1))
+    })
+}
+
+/// Free a replay, returns false if it was already closed
+#[napi(catch_unwind)]
+pub fn close_replay(replay: u32) -> bool {
+    let mut guard = REPLAYS.lock().unwrap_or_else(|e| e.into_inner());
+    guard.get_or_insert_with(HashMap::new).remove(&replay).is_some()
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_step_forward_and_back() {
+        let tmp = TempDir::new("replay");
+        let path = tmp.join("replay.jsonl");
+        std::fs::write(&path, [
+            r#"{"type":"user","uuid":"u1","cwd":"/work","message":{"role":"user","content":"edit it"}}"#,
+            r#"{"type":"assistant","uuid":"a1","message":{"role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Edit","input":{"file_path":"src/a.rs"}}],"usage":{"input_tokens":10,"output_tokens":4}}}"#,
+            r#"{"type":"assistant","uuid":"a2","message":{"role":"assistant","content":[{"type":"tool_use","id":"t2","name":"Write","input":{"file_path":"/work/b.rs"}}],"usage":{"input_tokens":20,"output_tokens":6}}}"#,
+        ].join("\n")).unwrap();
+
+        let id = open_replay(path.display().to_string()).unwrap();
+        let first = step_forward(id, 2).unwrap();
+        assert_eq!((first.position, first.total_messages, first.messages.len()), (2, 3, 2));
+        assert_eq!((first.files_modified.clone(), first.input_tokens), (vec!["/work/src/a.rs".to_string()], 10));
+
+        let end = step_to(id, "a2".to_string()).unwrap();
+        assert!(end.done && !end.rewound);
+        assert_eq!((end.messages.len(), end.files_modified.len(), end.output_tokens), (1, 2, 10));
+
+        let back = step_to(id, "u1".to_string()).unwrap();
+        assert!(back.rewound);
+        assert_eq!((back.position, back.input_tokens, back.files_modified.len()), (1, 0, 0));
+        let by_canonical = step_to(id, first.messages[1].canonical_id.clone()).unwrap();
+        assert_eq!((by_canonical.position, by_canonical.input_tokens), (2, 10));
+
+        assert!(step_to(id, "nope".to_string()).is_err());
+        assert!(close_replay(id));
+        assert!(step_forward(id, 1).is_err());
+    }
+}
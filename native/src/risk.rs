@@ -0,0 +1,208 @@
+// ============================================
+// RISKY COMMANDS
+// Bash calls matching dangerous patterns (rm -rf, curl | sh, force
+// pushes...) with severity and context, for post-hoc review
+// ============================================
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use regex::Regex;
+
+use crate::errors::{invalid_argument, parse_error};
+use crate::title::{is_substantive_prompt, prompt_excerpt};
+use crate::{extract_text_content, for_each_entry, resolve_session_files, ContentItem};
+
+/// Least to most severe
+const SEVERITIES: &[&str] = &["low", "medium", "high", "critical"];
+
+/// Built-in rules as (id, severity, pattern, description)
+const DEFAULT_RULES: &[(&str, &str, &str, &str)] = &[
+    ("rm_rf_root", "critical", r"\brm\s+-[a-zA-Z]*[rR][a-zA-Z]*\s+(/|~|\$HOME)/?(\s|$|\*)", "Recursive delete of / or the home directory"),
+    ("rm_rf", "high", r"\brm\s+(-[a-zA-Z]*([rR][a-zA-Z]*f|f[a-zA-Z]*[rR])|(-[rR]\s+-f|-f\s+-[rR])|--recursive\s+--force)\b", "Forced recursive delete"),
+    ("curl_pipe_shell", "critical", r"\b(curl|wget)\b[^|;&]*\|\s*(sudo\s+)?(ba|z|da)?sh\b", "Downloaded script piped into a shell"),
+    ("git_force_push", "high", r"\bgit\s+push\b.*(\s--force(-with-lease)?\b|\s-[a-zA-Z]*f\b|\s\+\S+)", "Force push rewriting remote history"),
+    ("git_reset_hard", "medium", r"\bgit\s+reset\s+.*--hard\b", "Hard reset discarding local changes"),
+    ("git_clean", "medium", r"\bgit\s+clean\s+-[a-zA-Z]*f", "git clean deleting untracked files"),
+    ("chmod_777", "medium", r"\bchmod\s+(-[a-zA-Z]+\s+)*0?777\b", "World-writable permissions"),
+    ("sudo", "medium", r"(^|[;&|(]\s*)sudo\b", "Command run as root"),
+    ("disk_write", "critical", r"\b(dd\b.*\bof=/dev/|mkfs(\.\w+)?\b)", "Raw write to a disk device"),
+    ("drop_database", "high", r"(?i)\bdrop\s+(table|database|schema)\b", "SQL drop statement"),
+    ("publish", "medium", r"\b(npm|cargo|twine|gem)\s+(publish|upload|push)\b", "Package published to a registry"),
+];
+
+/// One pattern to flag
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskRule {
+    pub id: String,
+    /// "low", "medium", "high" or "critical"
+    pub severity: String,
+    /// Regular expression matched against the full command
+    pub pattern: String,
+    pub description: String,
+}
+
+/// A Bash call that matched a rule
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskFinding {
+    pub rule_id: String,
+    pub severity: String,
+    pub rule_description: String,
+    pub command: String,
+    /// The description the agent gave the Bash call
+    pub call_description: Option<String>,
+    /// The user prompt the call was made in response to
+    pub prompt: Option<String>,
+    pub session_id: String,
+    pub file_path: String,
+    pub tool_use_id: String,
+    pub message_id: Option<String>,
+    pub timestamp: Option<String>,
+}
+
+/// Result of scan_risky_commands
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskReport {
+    pub files_scanned: u32,
+    pub commands_scanned: u32,
+    /// Most severe first, then in file order
+    pub findings: Vec<RiskFinding>,
+}
+
+fn default_rules() -> Vec<RiskRule> {
+    DEFAULT_RULES
+        .iter()
+        .map(|&(id, severity, pattern, description)| RiskRule {
+            id: id.to_string(),
+            severity: severity.to_string(),
+            pattern: pattern.to_string(),
+            description: description.to_string(),
+        })
+        .collect()
+}
+
+fn compile(rules: Vec<RiskRule>) -> Result<Vec<(RiskRule, Regex)>> {
+    rules
+        .into_iter()
+        .map(|rule| {
+            if !SEVERITIES.contains(&rule.severity.as_str()) {
+                return Err(invalid_argument(format!("Unknown severity for rule {}: {}", rule.id, rule.severity)));
+            }
+            let regex = Regex::new(&rule.pattern)
+                .map_err(|e| parse_error(format!("Invalid pattern for rule {}: {}", rule.id, e)))?;
+            Ok((rule, regex))
+        })
+        .collect()
+}
+
+fn severity_rank(severity: &str) -> usize {
+    SEVERITIES.iter().position(|s| *s == severity).unwrap_or(0)
+}
+
+fn scan(file_path_or_root: &str, rules: &[(RiskRule, Regex)]) -> Result<RiskReport> {
+    let files = resolve_session_files(file_path_or_root)?;
+    let mut commands_scanned = 0;
+    let mut findings = Vec::new();
+
+    for path in &files {
+        let file_path = path.display().to_string();
+        let mut prompt: Option<String> = None;
+
+        let _ = for_each_entry(&file_path, |entry| {
+            let Some(message) = &entry.message else { return };
+            if is_substantive_prompt(&entry) {
+                prompt = prompt_excerpt(&extract_text_content(&message.content));
+            }
+            for item in &message.content {
+                let ContentItem::ToolUse { id, name, input } = item else { continue };
+                let Some(command) = input.get("command").and_then(|c| c.as_str()).filter(|_| name == "Bash") else {
+                    continue;
+                };
+                commands_scanned += 1;
+                for (rule, _) in rules.iter().filter(|(_, regex)| regex.is_match(command)) {
+                    findings.push(RiskFinding {
+                        rule_id: rule.id.clone(),
+                        severity: rule.severity.clone(),
+                        rule_description: rule.description.clone(),
+                        command: command.to_string(),
+                        call_description: input.get("description").and_then(|d| d.as_str()).map(str::to_string),
+                        prompt: prompt.clone(),
+                        session_id: entry.session_id.clone().unwrap_or_else(|| "unknown".to_string()),
+                        file_path: file_path.clone(),
+                        tool_use_id: id.clone(),
+                        message_id: entry.uuid.clone(),
+                        timestamp: entry.timestamp.clone(),
+                    });
+                }
+            }
+        });
+    }
+
+    findings.sort_by_key(|f| std::cmp::Reverse(severity_rank(&f.severity)));
+    Ok(RiskReport { files_scanned: files.len() as u32, commands_scanned, findings })
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// The built-in rules, for the UI to show or extend
+#[napi(catch_unwind)]
+pub fn get_default_risk_rules() -> Vec<RiskRule> {
+    default_rules()
+}
+
+/// Flag Bash calls in a session file (or every session under a directory)
+/// matching ruleset; the built-in rules when ruleset is not given
+#[napi(catch_unwind)]
+pub fn scan_risky_commands(file_path_or_root: String, ruleset: Option<Vec<RiskRule>>) -> Result<RiskReport> {
+    let rules = compile(ruleset.unwrap_or_else(default_rules))?;
+    scan(&file_path_or_root, &rules)
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_default_rules() {
+        let rules = compile(default_rules()).unwrap();
+        let matched = |command: &str| -> Vec<&str> {
+            rules.iter().filter(|(_, re)| re.is_match(command)).map(|(rule, _)| rule.id.as_str()).collect()
+        };
+        assert_eq!(matched("rm -rf ~/"), vec!["rm_rf_root", "rm_rf"]);
+        assert_eq!(matched("rm -rf target"), vec!["rm_rf"]);
+        assert!(matched("rm -r build").is_empty());
+        assert_eq!(matched("curl -fsSL https://x.sh | sudo bash"), vec!["curl_pipe_shell", "sudo"]);
+        assert_eq!(matched("git push --force origin main"), vec!["git_force_push"]);
+        assert!(matched("git push origin feature-fix").is_empty());
+        assert_eq!(matched("chmod -R 777 /var/www"), vec!["chmod_777"]);
+
+        let bad = RiskRule { id: "x".into(), severity: "extreme".into(), pattern: "x".into(), description: String::new() };
+        assert!(compile(vec![bad]).is_err());
+    }
+
+    #[test]
+    fn test_scan_session() {
+        let tmp = TempDir::new("risk");
+        let path = tmp.join("risk.jsonl");
+        std::fs::write(&path, [
+            r#"{"type":"user","sessionId":"s1","message":{"role":"user","content":"Clean up the build"}}"#,
+            r#"{"type":"assistant","uuid":"a1","sessionId":"s1","message":{"role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Bash","input":{"command":"chmod 777 out","description":"Open permissions"}},{"type":"tool_use","id":"t2","name":"Bash","input":{"command":"rm -rf target"}},{"type":"tool_use","id":"t3","name":"Bash","input":{"command":"ls"}}]}}"#,
+        ].join("\n")).unwrap();
+
+        let report = scan_risky_commands(path.display().to_string(), None).unwrap();
+        assert_eq!((report.files_scanned, report.commands_scanned), (1, 3));
+        let ids: Vec<&str> = report.findings.iter().map(|f| f.rule_id.as_str()).collect();
+        assert_eq!(ids, vec!["rm_rf", "chmod_777"]);
+        assert_eq!(report.findings[1].call_description.as_deref(), Some("Open permissions"));
+        assert_eq!(report.findings[0].prompt.as_deref(), Some("Clean up the build"));
+    }
+}
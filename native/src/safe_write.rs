@@ -0,0 +1,127 @@
+// ============================================
+// SAFE WRITES
+// Every file the addon produces goes through write_file: contents land
+// in a temp file that is fsynced and moved into place, so readers and
+// crashes never see half a file, and existing files are kept unless
+// the caller asks to overwrite them (FILE_EXISTS otherwise)
+// ============================================
+
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use napi::bindgen_prelude::*;
+
+use crate::errors::{io_error, ErrorCode, HubError};
+use crate::paths::{long_path, retry_locked};
+
+static NEXT_TEMP_ID: AtomicU64 = AtomicU64::new(0);
+
+fn file_exists(path: &Path) -> Error {
+    HubError::new(ErrorCode::FileExists, "File already exists").path(path).into()
+}
+
+/// Hidden sibling of path; the ".tmp." infix is what find_stale_artifacts looks for
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let id = NEXT_TEMP_ID.fetch_add(1, Ordering::Relaxed);
+    path.with_file_name(format!(".{}.tmp.{}-{}", name, std::process::id(), id))
+}
+
+fn write_synced(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut file = File::create(path)?;
+    file.write_all(contents)?;
+    file.sync_all()
+}
+
+/// Move tmp over target. Replacing a file another process has open fails on
+/// Windows until it lets go
+fn replace(tmp: &Path, target: &Path) -> std::io::Result<()> {
+    retry_locked(|| std::fs::rename(tmp, target))
+}
+
+/// Move tmp to target unless target exists (AlreadyExists). A hard link
+/// fails atomically if the name is taken, where exists-then-rename could
+/// replace a file created in between
+fn place_new(tmp: &Path, target: &Path) -> std::io::Result<()> {
+    match retry_locked(|| std::fs::hard_link(tmp, target)) {
+        Ok(()) => {
+            std::fs::remove_file(tmp).ok();
+            Ok(())
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => Err(e),
+        // File systems without hard links (FAT, some network shares)
+        Err(_) if target.exists() => Err(std::io::ErrorKind::AlreadyExists.into()),
+        Err(_) => replace(tmp, target),
+    }
+}
+
+/// Write contents to path atomically, creating missing parent directories.
+/// Without overwrite an existing file is left alone and FILE_EXISTS returned
+pub(crate) fn write_file(path: &Path, contents: &[u8], overwrite: bool) -> Result<()> {
+    let target = long_path(path);
+    if !overwrite && target.exists() {
+        return Err(file_exists(path));
+    }
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(long_path(parent)).map_err(|e| io_error("Cannot create directory", parent, e))?;
+    }
+
+    let tmp_path = temp_path(&target);
+    if let Err(e) = write_synced(&tmp_path, contents) {
+        std::fs::remove_file(&tmp_path).ok();
+        return Err(io_error("Cannot write file", path, e));
+    }
+    let placed = if overwrite { replace(&tmp_path, &target) } else { place_new(&tmp_path, &target) };
+    if let Err(e) = placed {
+        std::fs::remove_file(&tmp_path).ok();
+        return Err(match e.kind() {
+            std::io::ErrorKind::AlreadyExists => file_exists(path),
+            _ => io_error("Cannot write file", path, e),
+        });
+    }
+    // Persist the rename itself; directories cannot be opened for this on Windows
+    #[cfg(unix)]
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        File::open(parent).and_then(|dir| dir.sync_all()).ok();
+    }
+    Ok(())
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_write_file() {
+        let dir = TempDir::new("safe-write");
+        let path = dir.join("nested/out.json");
+
+        write_file(&path, b"one", false).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "one");
+
+        let err = write_file(&path, b"two", false).unwrap_err();
+        assert!(err.reason.starts_with("FILE_EXISTS: "));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "one");
+        // A file that appears after the early check is still not replaced
+        let tmp = temp_path(&path);
+        std::fs::write(&tmp, "two").unwrap();
+        assert_eq!(place_new(&tmp, &path).unwrap_err().kind(), std::io::ErrorKind::AlreadyExists);
+        std::fs::remove_file(tmp).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "one");
+
+        write_file(&path, b"two", true).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "two");
+        // No temp files are left next to the target
+        assert_eq!(std::fs::read_dir(dir.join("nested")).unwrap().count(), 1);
+
+        // The parent is a file
+        assert!(write_file(&dir.join("nested/out.json/child"), b"x", true).is_err());
+    }
+}
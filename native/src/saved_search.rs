@@ -0,0 +1,232 @@
+// ============================================
+// SAVED SEARCHES
+// Named search_history queries kept in the sidecar store and re-run
+// incrementally, so smart folders only rescan sessions that changed
+// ============================================
+
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use rayon::prelude::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{invalid_argument, not_found};
+use crate::paths::metadata;
+use crate::search::{paginate, Matcher, SearchHit, SearchOptions, SearchPage};
+use crate::store::{now_millis, read_store, update_store, MetadataStore};
+use crate::{collect_session_files, config};
+use history_core::{thinking_policy, ThinkingPolicy};
+
+/// Hits of the last run of each saved search, by name
+static RESULTS: Mutex<Option<HashMap<String, CachedResults>>> = Mutex::new(None);
+
+/// A named query, e.g. "failing tests" = "role:user tests failed"
+#[napi(object)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+pub struct SavedSearch {
+    pub name: String,
+    /// search_history query, filters included
+    pub query: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Result of run_saved_search
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavedSearchResult {
+    pub name: String,
+    pub page: SearchPage,
+    /// Session files read this run; unchanged files reuse the previous hits
+    pub files_scanned: u32,
+    /// Sessions matching now that did not on the previous run (every match on
+    /// the first, and after the root, hit shape or thinking policy changed)
+    pub new_session_ids: Vec<String>,
+}
+
+/// Hits of one file, valid while its size and mtime are unchanged
+#[derive(Clone)]
+struct CachedFile {
+    len: u64,
+    modified: Option<SystemTime>,
+    hits: Vec<SearchHit>,
+}
+
+struct CachedResults {
+    root_dir: String,
+    matcher: Matcher,
+    /// Hit text depends on how thinking is extracted
+    thinking: ThinkingPolicy,
+    files: HashMap<PathBuf, CachedFile>,
+}
+
+impl MetadataStore {
+    /// Create or replace the query of a saved search
+    pub fn save_search(&mut self, name: &str, query: &str, now: i64) -> SavedSearch {
+        match self.saved_searches.iter_mut().find(|s| s.name == name) {
+            Some(saved) => {
+                saved.query = query.to_string();
+                saved.updated_at = now;
+                saved.clone()
+            }
+            None => {
+                let saved = SavedSearch { name: name.to_string(), query: query.to_string(), created_at: now, updated_at: now };
+                self.saved_searches.push(saved.clone());
+                saved
+            }
+        }
+    }
+
+    /// Returns false if there was no search with that name
+    pub fn delete_saved_search(&mut self, name: &str) -> bool {
+        let before = self.saved_searches.len();
+        self.saved_searches.retain(|s| s.name != name);
+        self.saved_searches.len() != before
+    }
+}
+
+fn stamp(path: &PathBuf) -> (u64, Option<SystemTime>) {
+    metadata(path).map(|m| (m.len(), m.modified().ok())).unwrap_or((0, None))
+}
+
+/// Run a saved search over root_dir, rescanning only files that are new or
+/// changed since its previous run over the same root with the same hit
+/// shape and thinking policy
+fn run(saved: &SavedSearch, root_dir: &str, options: &SearchOptions, thinking: ThinkingPolicy) -> Result<SavedSearchResult> {
+    let matcher = Matcher::new(&saved.query, options)?;
+    let files = collect_session_files(root_dir)?;
+
+    // A copy of the previous hits, so other searches are not held up by this scan
+    let previous = RESULTS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .and_then(|results| results.get(&saved.name))
+        .filter(|r| r.root_dir == root_dir && r.matcher == matcher && r.thinking == thinking)
+        .map(|r| r.files.clone());
+    let first_run = previous.is_none();
+    let mut cached = previous.unwrap_or_default();
+    let known: HashSet<String> = cached.values().flat_map(|f| f.hits.iter().map(|h| h.session_id.clone())).collect();
+
+    let stamps: Vec<(u64, Option<SystemTime>)> = files.iter().map(stamp).collect();
+    let stale: Vec<usize> = (0..files.len())
+        .filter(|&i| cached.get(&files[i]).map(|f| (f.len, f.modified)) != Some(stamps[i]))
+        .collect();
+    let rescanned: Vec<Vec<SearchHit>> = config::install(|| stale.par_iter().map(|&i| matcher.file_hits(&files[i])).collect());
+    for (i, hits) in stale.iter().zip(rescanned) {
+        let (len, modified) = stamps[*i];
+        cached.insert(files[*i].clone(), CachedFile { len, modified, hits });
+    }
+    // Sessions deleted since the last run drop out
+    let listed: HashSet<&PathBuf> = files.iter().collect();
+    cached.retain(|path, _| listed.contains(path));
+
+    let hits: Vec<SearchHit> = files.iter().filter_map(|path| cached.get(path)).flat_map(|f| f.hits.iter().cloned()).collect();
+    let mut new_session_ids: Vec<String> = Vec::new();
+    for hit in &hits {
+        if (first_run || !known.contains(&hit.session_id)) && !new_session_ids.contains(&hit.session_id) {
+            new_session_ids.push(hit.session_id.clone());
+        }
+    }
+    let page = paginate(hits, options)?;
+    let result = CachedResults { root_dir: root_dir.to_string(), matcher, thinking, files: cached };
+    RESULTS.lock().unwrap_or_else(|e| e.into_inner()).get_or_insert_with(HashMap::new).insert(saved.name.clone(), result);
+
+    Ok(SavedSearchResult { name: saved.name.clone(), page, files_scanned: stale.len() as u32, new_session_ids })
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Save query under name, replacing the query of an existing search
+#[napi(catch_unwind)]
+pub fn save_search(name: String, query: String) -> Result<SavedSearch> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(invalid_argument("Saved search name is empty"));
+    }
+    // Reject unknown filters now rather than on every run
+    Matcher::new(&query, &SearchOptions::default())?;
+    let now = now_millis();
+    update_store(|store| store.save_search(&name, &query, now))
+}
+
+/// Every saved search, in creation order
+#[napi(catch_unwind)]
+pub fn list_saved_searches() -> Result<Vec<SavedSearch>> {
+    read_store(|store| store.saved_searches.clone())
+}
+
+/// Delete a saved search. Returns false if it did not exist
+#[napi(catch_unwind)]
+pub fn delete_saved_search(name: String) -> Result<bool> {
+    if let Some(results) = RESULTS.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        results.remove(&name);
+    }
+    update_store(|store| store.delete_saved_search(&name))
+}
+
+/// Run a saved search over root_dir like search_history. Repeated runs only
+/// rescan sessions added or changed since the previous one
+#[napi(catch_unwind)]
+pub fn run_saved_search(name: String, root_dir: String, options: Option<SearchOptions>) -> Result<SavedSearchResult> {
+    let saved = read_store(|store| store.saved_searches.iter().find(|s| s.name == name).cloned())?
+        .ok_or_else(|| not_found(format!("Unknown saved search: {}", name)))?;
+    run(&saved, &root_dir, &options.unwrap_or_default(), thinking_policy())
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_saved_search_runs_incrementally() {
+        let mut store = MetadataStore::default();
+        store.save_search("failing", "tests failed", 1);
+        let saved = store.save_search("failing", "tests failed role:user", 2);
+        assert_eq!((store.saved_searches.len(), saved.created_at, saved.updated_at), (1, 1, 2));
+
+        let root = TempDir::new("saved-search");
+        std::fs::create_dir_all(root.join("p")).unwrap();
+        let line = |session: &str, text: &str| {
+            format!(r#"{{"type":"user","uuid":"{}-1","sessionId":"{}","timestamp":"2024-01-01T10:00:00Z","message":{{"role":"user","content":"{}"}}}}"#, session, session, text)
+        };
+        std::fs::write(root.join("p/a.jsonl"), line("a", "the tests failed again")).unwrap();
+        std::fs::write(root.join("p/b.jsonl"), line("b", "all green")).unwrap();
+        let root_dir = root.display().to_string();
+        let options = SearchOptions::default();
+
+        let first = run(&saved, &root_dir, &options, ThinkingPolicy::Include).unwrap();
+        assert_eq!((first.files_scanned, first.page.total_hits), (2, 1));
+        assert_eq!(first.new_session_ids, vec!["a"]);
+
+        let again = run(&saved, &root_dir, &options, ThinkingPolicy::Include).unwrap();
+        assert_eq!((again.files_scanned, again.page.total_hits), (0, 1));
+        assert!(again.new_session_ids.is_empty());
+
+        std::fs::write(root.join("p/c.jsonl"), line("c", "two tests failed")).unwrap();
+        let arrived = run(&saved, &root_dir, &options, ThinkingPolicy::Include).unwrap();
+        assert_eq!((arrived.files_scanned, arrived.page.total_hits), (1, 2));
+        assert_eq!(arrived.new_session_ids, vec!["c"]);
+
+        // Hits extracted under another thinking policy are not reused
+        let excluded = run(&saved, &root_dir, &options, ThinkingPolicy::Exclude).unwrap();
+        assert_eq!((excluded.files_scanned, excluded.page.total_hits), (3, 2));
+        assert_eq!(excluded.new_session_ids, vec!["a", "c"]);
+
+        assert!(store.delete_saved_search("failing"));
+        assert!(!store.delete_saved_search("failing"));
+    }
+}
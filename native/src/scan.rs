@@ -0,0 +1,294 @@
+// ============================================
+// DIRECTORY SCANNING
+// Finding the session files under a root: symlinked folders are followed
+// with cycle detection, and entries that fail or hang (network drives)
+// are skipped and reported instead of failing the whole scan
+// ============================================
+
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::fs::FileType;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use std::time::Duration;
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::config;
+use crate::errors::{ErrorCode, HubError};
+use crate::paths::{long_path, metadata, path_string, read_dir};
+
+/// Folder levels below the root that are listed (project, session, subagents)
+const MAX_DEPTH: usize = 3;
+
+/// Options for scan_session_files; missing fields use the configure() settings
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct ScanOptions {
+    /// Descend into symlinked folders and include symlinked files (default true)
+    pub follow_symlinks: Option<bool>,
+    /// Milliseconds one listing or stat may take before the path is skipped
+    /// (default 10000, 0 = wait forever)
+    pub timeout_ms: Option<u32>,
+}
+
+/// A path the scan could not look into
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippedPath {
+    pub path: String,
+    /// "unreadable", "timeout", "cycle" (a link to a folder already scanned),
+    /// "non_unicode" (a name JS could not pass back) or, for import_ci_artifacts,
+    /// "exists" (imported before)
+    pub reason: String,
+    /// The operating system error, for unreadable paths
+    pub message: Option<String>,
+}
+
+/// Result of scan_session_files
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionScan {
+    /// Sorted
+    pub files: Vec<String>,
+    pub skipped: Vec<SkippedPath>,
+    pub dirs_scanned: u32,
+}
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Runs file system calls on a helper thread so a hung one can be given up
+/// on. A thread stuck past the timeout is left to finish and replaced
+struct Worker {
+    timeout: Option<Duration>,
+    jobs: Option<Sender<Job>>,
+}
+
+impl Worker {
+    fn new(timeout_ms: u32) -> Worker {
+        Worker { timeout: (timeout_ms > 0).then(|| Duration::from_millis(timeout_ms as u64)), jobs: None }
+    }
+
+    /// Result of op, or None when it did not finish within the timeout
+    fn run<T: Send + 'static>(&mut self, op: impl FnOnce() -> T + Send + 'static) -> Option<T> {
+        let Some(timeout) = self.timeout else { return Some(op()) };
+        let (done, result) = mpsc::channel();
+        let job: Job = Box::new(move || {
+            done.send(op()).ok();
+        });
+        let jobs = self.jobs.get_or_insert_with(spawn_worker);
+        if let Err(mpsc::SendError(job)) = jobs.send(job) {
+            // The thread could not be started: no timeout then
+            self.jobs = None;
+            job();
+        }
+        let value = result.recv_timeout(timeout).ok();
+        if value.is_none() {
+            self.jobs = None;
+        }
+        value
+    }
+}
+
+fn spawn_worker() -> Sender<Job> {
+    let (jobs, queue) = mpsc::channel::<Job>();
+    // On failure queue is dropped, which the first send reports
+    std::thread::Builder::new()
+        .name("history-hub-scan".to_string())
+        .spawn(move || {
+            for job in queue {
+                job();
+            }
+        })
+        .ok();
+    jobs
+}
+
+type Listing = std::io::Result<Vec<std::io::Result<(OsString, FileType)>>>;
+
+fn list(dir: &Path) -> Listing {
+    Ok(read_dir(dir)?.map(|entry| entry.and_then(|e| Ok((e.file_name(), e.file_type()?)))).collect())
+}
+
+/// Session files found under a root, and what was left out
+pub(crate) struct Scanned {
+    pub files: Vec<PathBuf>,
+    pub skipped: Vec<SkippedPath>,
+    pub dirs_scanned: u32,
+}
+
+struct Scan {
+    worker: Worker,
+    follow_symlinks: bool,
+    /// Canonical folders entered so far, so a symlink loop is walked once
+    visited: HashSet<PathBuf>,
+    found: Scanned,
+}
+
+impl Scan {
+    fn skip(&mut self, path: &Path, reason: &str, message: Option<String>) {
+        self.found.skipped.push(SkippedPath { path: path.display().to_string(), reason: reason.to_string(), message });
+    }
+
+    /// Whether dir is a folder not seen under another path yet
+    fn enter(&mut self, dir: &Path) -> bool {
+        // Without symlinks every folder has a single path
+        if !self.follow_symlinks {
+            return true;
+        }
+        let owned = dir.to_path_buf();
+        match self.worker.run(move || std::fs::canonicalize(long_path(&owned))) {
+            Some(Ok(canonical)) => {
+                let new = self.visited.insert(canonical);
+                if !new {
+                    self.skip(dir, "cycle", None);
+                }
+                new
+            }
+            Some(Err(e)) => {
+                self.skip(dir, "unreadable", Some(e.to_string()));
+                false
+            }
+            None => {
+                self.skip(dir, "timeout", None);
+                false
+            }
+        }
+    }
+
+    fn walk(&mut self, dir: &Path, depth: usize) {
+        let owned = dir.to_path_buf();
+        let listing = match self.worker.run(move || list(&owned)) {
+            Some(Ok(listing)) => listing,
+            Some(Err(e)) => return self.skip(dir, "unreadable", Some(e.to_string())),
+            None => return self.skip(dir, "timeout", None),
+        };
+        self.found.dirs_scanned += 1;
+
+        for entry in listing {
+            let (name, file_type) = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    self.skip(dir, "unreadable", Some(e.to_string()));
+                    continue;
+                }
+            };
+            let path = dir.join(name);
+            let mut is_dir = file_type.is_dir();
+            if file_type.is_symlink() {
+                if !self.follow_symlinks {
+                    continue;
+                }
+                let target = path.clone();
+                match self.worker.run(move || metadata(&target)) {
+                    Some(Ok(metadata)) => is_dir = metadata.is_dir(),
+                    Some(Err(e)) => {
+                        self.skip(&path, "unreadable", Some(e.to_string()));
+                        continue;
+                    }
+                    None => {
+                        self.skip(&path, "timeout", None);
+                        continue;
+                    }
+                }
+            }
+
+            if is_dir {
+                if depth < MAX_DEPTH && self.enter(&path) {
+                    self.walk(&path, depth + 1);
+                }
+            } else if path.extension().is_some_and(|ext| ext == "jsonl") {
+                // Returned paths go through JS strings and must open again
+                if path_string(&path).is_none() {
+                    self.skip(&path, "non_unicode", None);
+                } else {
+                    self.found.files.push(path);
+                }
+            }
+        }
+    }
+}
+
+/// Every .jsonl file up to MAX_DEPTH folders below root, sorted. Only a
+/// root that is missing, or does not answer in time, fails the scan
+pub(crate) fn scan(root: &Path, follow_symlinks: bool, timeout_ms: u32) -> Result<Scanned> {
+    let mut worker = Worker::new(timeout_ms);
+    let owned = root.to_path_buf();
+    let Some(is_dir) = worker.run(move || long_path(&owned).is_dir()) else {
+        return Err(HubError::new(ErrorCode::IoError, "Timed out reading directory").path(root).into());
+    };
+    if !is_dir {
+        return Err(HubError::new(ErrorCode::FileNotFound, format!("Not a directory: {}", root.display())).path(root).into());
+    }
+
+    let found = Scanned { files: Vec::new(), skipped: Vec::new(), dirs_scanned: 0 };
+    let mut scan = Scan { worker, follow_symlinks, visited: HashSet::new(), found };
+    if scan.enter(root) {
+        scan.walk(root, 0);
+    }
+    scan.found.files.sort();
+    Ok(scan.found)
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Session files under root_dir with the paths skipped on the way
+/// (unreadable, too slow, symlink loops), as every root-wide function sees them
+#[napi(catch_unwind)]
+pub fn scan_session_files(root_dir: String, options: Option<ScanOptions>) -> Result<SessionScan> {
+    let options = options.unwrap_or_default();
+    let follow_symlinks = options.follow_symlinks.unwrap_or_else(config::follow_symlinks);
+    let timeout_ms = options.timeout_ms.unwrap_or_else(config::scan_timeout_ms);
+    let scanned = scan(Path::new(&root_dir), follow_symlinks, timeout_ms)?;
+    Ok(SessionScan {
+        files: scanned.files.iter().map(|f| f.display().to_string()).collect(),
+        skipped: scanned.skipped,
+        dirs_scanned: scanned.dirs_scanned,
+    })
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_worker_timeout() {
+        let mut worker = Worker::new(20);
+        assert_eq!(worker.run(|| 1), Some(1));
+        assert_eq!(worker.run(|| std::thread::sleep(Duration::from_millis(500))), None);
+        // The stuck thread was replaced
+        assert_eq!(worker.run(|| 2), Some(2));
+        assert_eq!(Worker::new(0).run(|| 3), Some(3));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_symlinks() {
+        let root = TempDir::new("scan");
+        std::fs::create_dir_all(root.join("p")).unwrap();
+        let elsewhere = TempDir::new("scan-elsewhere");
+        std::fs::write(root.join("p/a.jsonl"), "{}").unwrap();
+        std::fs::write(elsewhere.join("b.jsonl"), "{}").unwrap();
+        std::os::unix::fs::symlink(&elsewhere, root.join("linked")).unwrap();
+        std::os::unix::fs::symlink(&root, root.join("p/loop")).unwrap();
+        std::os::unix::fs::symlink(root.join("missing"), root.join("dangling")).unwrap();
+
+        let scanned = scan(&root, true, 1000).unwrap();
+        assert_eq!(scanned.files, vec![root.join("linked/b.jsonl"), root.join("p/a.jsonl")]);
+        let mut reasons: Vec<&str> = scanned.skipped.iter().map(|s| s.reason.as_str()).collect();
+        reasons.sort();
+        assert_eq!(reasons, vec!["cycle", "unreadable"]);
+
+        let plain = scan(&root, false, 0).unwrap();
+        assert_eq!((plain.files.len(), plain.skipped.len(), plain.dirs_scanned), (1, 0, 2));
+        assert!(scan(&root.join("nope"), true, 1000).is_err());
+    }
+}
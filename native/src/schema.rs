@@ -0,0 +1,85 @@
+// ============================================
+// OUTPUT SCHEMAS
+// JSON Schema for the objects the addon returns, so the TS frontend
+// and other tools can validate and codegen against the native contract
+// ============================================
+
+use std::collections::HashMap;
+
+use napi_derive::napi;
+use schemars::{schema_for, JsonSchema};
+
+use crate::failures::{ErrorReport, SessionHealth};
+use crate::prompts::ExtractedPrompt;
+use crate::saved_search::SavedSearch;
+use crate::similarity::SimilarPrompt;
+use crate::storage::StorageReport;
+use crate::store::Bookmark;
+use crate::tags::SessionTags;
+use crate::terms::TermFrequency;
+use crate::time::ParsedTimestamp;
+use crate::topics::TopicCluster;
+use crate::usage::{TeamUsage, UsageStats};
+use crate::{ClaudeMessage, ClaudeSession, ContentItem, ParsedSession};
+
+fn schema<T: JsonSchema>() -> serde_json::Value {
+    schema_for!(T).to_value()
+}
+
+/// Schemas keyed by type name. Objects use the camelCase keys JS sees;
+/// ContentItem keeps the snake_case keys of the raw_content JSON
+pub(crate) fn output_schemas() -> HashMap<String, serde_json::Value> {
+    [
+        ("ClaudeMessage", schema::<ClaudeMessage>()),
+        ("ClaudeSession", schema::<ClaudeSession>()),
+        ("ContentItem", schema::<ContentItem>()),
+        ("ParsedSession", schema::<ParsedSession>()),
+        ("SessionHealth", schema::<SessionHealth>()),
+        ("ErrorReport", schema::<ErrorReport>()),
+        ("ParsedTimestamp", schema::<ParsedTimestamp>()),
+        ("TermFrequency", schema::<TermFrequency>()),
+        ("TopicCluster", schema::<TopicCluster>()),
+        ("SimilarPrompt", schema::<SimilarPrompt>()),
+        ("ExtractedPrompt", schema::<ExtractedPrompt>()),
+        ("Bookmark", schema::<Bookmark>()),
+        ("SessionTags", schema::<SessionTags>()),
+        ("SavedSearch", schema::<SavedSearch>()),
+        ("StorageReport", schema::<StorageReport>()),
+        ("UsageStats", schema::<UsageStats>()),
+        ("TeamUsage", schema::<TeamUsage>()),
+    ]
+    .into_iter()
+    .map(|(name, schema)| (name.to_string(), schema))
+    .collect()
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// JSON Schema of every output type, keyed by type name
+#[napi(catch_unwind)]
+pub fn get_output_schemas() -> HashMap<String, serde_json::Value> {
+    output_schemas()
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schemas_use_js_field_names() {
+        let schemas = output_schemas();
+        let message = &schemas["ClaudeMessage"]["properties"];
+        assert!(message.get("messageId").is_some() && message.get("message_id").is_none());
+        assert!(schemas["Bookmark"]["properties"].get("createdAt").is_some());
+
+        // Raw content blocks keep their on-disk names
+        let content = serde_json::to_string(&schemas["ContentItem"]).unwrap();
+        assert!(content.contains("tool_use_id"));
+    }
+}
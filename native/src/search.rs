@@ -0,0 +1,510 @@
+// ============================================
+// HISTORY SEARCH
+// Full-text search over every session with structural filters
+// (has:image, tool:Edit...), relevance or recency ranking, offset
+// pagination and optional per-session grouping
+// ============================================
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use rayon::prelude::*;
+use whatlang::Lang;
+
+use crate::errors::invalid_argument;
+use crate::language::{detect, parse_language};
+use crate::store::read_store;
+use crate::title::truncate_words;
+use crate::{collect_session_files, config, entry_to_message_with, for_each_entry, ClaudeMessage, ContentItem};
+
+const DEFAULT_LIMIT: u32 = 50;
+const DEFAULT_HITS_PER_SESSION: u32 = 3;
+const DEFAULT_SNIPPET_CHARS: u32 = 160;
+/// Messages longer than this many characters score lower per match
+const LENGTH_NORM_CHARS: f64 = 500.0;
+
+/// Options for search_history
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct SearchOptions {
+    /// Hits (or sessions when grouped) per page (default 50)
+    pub limit: Option<u32>,
+    /// Hits (or sessions) to skip (default 0)
+    pub offset: Option<u32>,
+    /// "relevance" (default) or "recency"
+    pub sort: Option<String>,
+    /// Page over sessions, each with its best hits (default false)
+    pub group_by_session: Option<bool>,
+    /// Hits kept per session when grouped (default 3)
+    pub hits_per_session: Option<u32>,
+    /// Messages before and after each hit returned with it (default 0)
+    pub context_messages: Option<u32>,
+    /// Characters of snippets and context excerpts (default 160)
+    pub snippet_chars: Option<u32>,
+}
+
+/// A message next to a hit
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextMessage {
+    pub message_id: String,
+    pub message_index: u32,
+    pub role: String,
+    pub timestamp: String,
+    pub excerpt: String,
+}
+
+/// One matching message
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHit {
+    pub session_id: String,
+    pub file_path: String,
+    pub message_id: String,
+    pub canonical_id: String,
+    /// 0-based position among the session's messages, as parse_claude_session returns them
+    pub message_index: u32,
+    pub role: String,
+    pub timestamp: String,
+    /// Text around the first match
+    pub snippet: String,
+    /// Occurrences of the query words
+    pub match_count: u32,
+    pub score: f64,
+    /// Up to context_messages messages before the hit, oldest first
+    pub context_before: Vec<ContextMessage>,
+    pub context_after: Vec<ContextMessage>,
+}
+
+/// The hits of one session
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionHits {
+    pub session_id: String,
+    pub file_path: String,
+    pub hit_count: u32,
+    pub best_score: f64,
+    pub last_timestamp: String,
+    /// Best first, at most hits_per_session
+    pub hits: Vec<SearchHit>,
+}
+
+/// One page of search_history results
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchPage {
+    /// All matching messages
+    pub total_hits: u32,
+    /// All sessions with a match
+    pub total_sessions: u32,
+    pub offset: u32,
+    pub has_more: bool,
+    /// Empty when grouped
+    pub hits: Vec<SearchHit>,
+    /// Empty unless grouped
+    pub groups: Vec<SessionHits>,
+}
+
+/// How much text to return with each hit
+#[derive(PartialEq)]
+struct HitShape {
+    context: usize,
+    snippet_chars: usize,
+}
+
+/// key:value tokens of a query; every one given must hold
+#[derive(Default)]
+struct Filters {
+    has: Vec<String>,
+    tools: Vec<String>,
+    roles: Vec<String>,
+    models: Vec<String>,
+    projects: Vec<String>,
+    languages: Vec<Lang>,
+    tags: Vec<String>,
+    /// Sessions carrying every tag: filter, read from the store when the query is parsed
+    tagged: HashSet<String>,
+    sidechain: bool,
+}
+
+impl Filters {
+    /// Take a token if it is a filter; Ok(false) leaves it a search word
+    fn add(&mut self, token: &str) -> Result<bool> {
+        let Some((key, value)) = token.split_once(':').filter(|(_, value)| !value.is_empty()) else { return Ok(false) };
+        let value = value.to_lowercase();
+        match key.to_lowercase().as_str() {
+            "has" if ["image", "thinking", "tool"].contains(&value.as_str()) => self.has.push(value),
+            "is" if value == "sidechain" => self.sidechain = true,
+            "has" | "is" => return Err(invalid_argument(format!("Unknown filter: {}", token))),
+            "tool" => self.tools.push(value),
+            "role" => self.roles.push(value),
+            "model" => self.models.push(value),
+            "project" => self.projects.push(value),
+            "lang" => self.languages.push(parse_language(&value)?),
+            "tag" => self.tags.push(value),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// project: filters match the project directory name or the session's cwd
+    fn matches_project(&self, project: &str, cwd: Option<&str>) -> bool {
+        let project = project.to_lowercase();
+        let cwd = cwd.unwrap_or("").to_lowercase();
+        self.projects.iter().all(|p| project.contains(p.as_str()) || cwd.contains(p.as_str()))
+    }
+
+    fn matches(&self, message: &ClaudeMessage, tools: &[String]) -> bool {
+        let model = message.model.as_deref().unwrap_or("").to_lowercase();
+        self.has.iter().all(|has| match has.as_str() {
+            "image" => message.has_images,
+            "thinking" => message.has_thinking,
+            _ => message.has_tool_use,
+        }) && self.tools.iter().all(|t| tools.iter().any(|name| name.to_lowercase() == *t))
+            && self.roles.iter().all(|r| message.role == *r)
+            && self.models.iter().all(|m| model.contains(m.as_str()))
+            && (!self.sidechain || message.is_sidechain == Some(true))
+            && self.matches_language(&message.content)
+            && (self.tags.is_empty() || self.tagged.contains(&message.session_id))
+    }
+
+    /// Detection is only run when a lang: filter asks for it
+    fn matches_language(&self, content: &str) -> bool {
+        if self.languages.is_empty() {
+            return true;
+        }
+        let detected = detect(content).map(|(lang, _)| lang);
+        self.languages.iter().all(|lang| detected == Some(*lang))
+    }
+}
+
+/// Lowercased search words, all of which must appear, and filters
+struct Query {
+    phrase: String,
+    words: Vec<String>,
+    filters: Filters,
+}
+
+impl Query {
+    /// None for a query with neither words nor filters
+    fn parse(query: &str) -> Result<Option<Query>> {
+        let mut filters = Filters::default();
+        let mut words = Vec::new();
+        let mut has_filters = false;
+        for token in query.split_whitespace() {
+            if filters.add(token)? {
+                has_filters = true;
+            } else {
+                words.push(token.to_lowercase());
+            }
+        }
+        if words.is_empty() && !has_filters {
+            return Ok(None);
+        }
+        if let Some((first, rest)) = filters.tags.split_first() {
+            filters.tagged = read_store(|store| {
+                let mut tagged: HashSet<String> = store.find_sessions_by_tag(first).into_iter().collect();
+                for tag in rest {
+                    let with_tag: HashSet<String> = store.find_sessions_by_tag(tag).into_iter().collect();
+                    tagged.retain(|s| with_tag.contains(s));
+                }
+                tagged
+            })?;
+        }
+        Ok(Some(Query { phrase: words.join(" "), words, filters }))
+    }
+
+    /// Occurrences and score of a message, None if a word is missing
+    fn score(&self, content: &str) -> Option<(u32, f64)> {
+        let lower = content.to_lowercase();
+        let mut matches = 0;
+        for word in &self.words {
+            let count = lower.matches(word.as_str()).count() as u32;
+            if count == 0 {
+                return None;
+            }
+            matches += count;
+        }
+        // The exact phrase counts double on top of its words
+        let phrase = if self.words.len() > 1 { lower.matches(self.phrase.as_str()).count() as f64 * 2.0 } else { 0.0 };
+        let length = content.chars().count() as f64 / LENGTH_NORM_CHARS;
+        Some((matches, (matches as f64 + phrase) / (1.0 + length.ln_1p())))
+    }
+}
+
+/// One line of up to chars characters around the first match of word (the start without one)
+fn snippet(content: &str, word: Option<&str>, chars: usize) -> String {
+    let lower = content.to_lowercase();
+    let start_byte = word.and_then(|word| lower.find(word)).unwrap_or(0);
+    // Lowercasing can shift byte offsets, so work in chars from here
+    let start_char = lower[..start_byte].chars().count().saturating_sub(chars / 4);
+    let text: String = content.chars().skip(start_char).take(chars).collect();
+    let text = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+    if start_char > 0 { format!("…{}", text) } else { text }
+}
+
+fn search_file(path: &Path, query: &Query, shape: &HitShape) -> Vec<SearchHit> {
+    let file_path = path.display().to_string();
+    let project = path.parent().and_then(|p| p.file_name()).map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let mut cwd: Option<String> = None;
+    // Every message of the file, kept only when hits need context
+    let mut excerpts: Vec<ContextMessage> = Vec::new();
+    let mut hits = Vec::new();
+    let mut index = 0;
+    // An unreadable file just has no hits
+    let _ = for_each_entry(&file_path, |entry| {
+        if cwd.is_none() {
+            cwd = entry.cwd.clone();
+        }
+        let tools: Vec<String> = match &entry.message {
+            Some(message) if !query.filters.tools.is_empty() => message
+                .content
+                .iter()
+                .filter_map(|item| match item {
+                    ContentItem::ToolUse { name, .. } => Some(name.clone()),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        let Some(message) = entry_to_message_with(entry, false) else { return };
+        let message_index = index;
+        index += 1;
+        if shape.context > 0 {
+            excerpts.push(ContextMessage {
+                message_id: message.message_id.clone(),
+                message_index,
+                role: message.role.clone(),
+                timestamp: message.timestamp.clone(),
+                excerpt: truncate_words(&message.content, shape.snippet_chars),
+            });
+        }
+
+        if !query.filters.matches_project(&project, cwd.as_deref()) || !query.filters.matches(&message, &tools) {
+            return;
+        }
+        let Some((match_count, score)) = query.score(&message.content) else { return };
+        hits.push(SearchHit {
+            snippet: snippet(&message.content, query.words.first().map(String::as_str), shape.snippet_chars),
+            session_id: message.session_id,
+            file_path: file_path.clone(),
+            message_id: message.message_id,
+            canonical_id: message.canonical_id,
+            message_index,
+            role: message.role,
+            timestamp: message.timestamp,
+            match_count,
+            score,
+            context_before: Vec::new(),
+            context_after: Vec::new(),
+        });
+    });
+
+    for hit in hits.iter_mut().filter(|_| shape.context > 0) {
+        let at = hit.message_index as usize;
+        hit.context_before = excerpts[at.saturating_sub(shape.context)..at].to_vec();
+        hit.context_after = excerpts.iter().skip(at + 1).take(shape.context).cloned().collect();
+    }
+    hits
+}
+
+fn compare(a: &SearchHit, b: &SearchHit, by_recency: bool) -> Ordering {
+    let relevance = b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal);
+    let recency = b.timestamp.cmp(&a.timestamp);
+    if by_recency { recency.then(relevance) } else { relevance.then(recency) }
+}
+
+/// Sessions with their hits, in the same order as the hits they contain
+fn group(hits: Vec<SearchHit>, per_session: usize) -> Vec<SessionHits> {
+    let mut order: Vec<String> = Vec::new();
+    let mut groups: HashMap<String, SessionHits> = HashMap::new();
+    for hit in hits {
+        let group = groups.entry(hit.file_path.clone()).or_insert_with(|| {
+            order.push(hit.file_path.clone());
+            SessionHits {
+                session_id: hit.session_id.clone(),
+                file_path: hit.file_path.clone(),
+                hit_count: 0,
+                best_score: hit.score,
+                last_timestamp: hit.timestamp.clone(),
+                hits: Vec::new(),
+            }
+        });
+        group.hit_count += 1;
+        group.best_score = group.best_score.max(hit.score);
+        if hit.timestamp > group.last_timestamp {
+            group.last_timestamp = hit.timestamp.clone();
+        }
+        if group.hits.len() < per_session {
+            group.hits.push(hit);
+        }
+    }
+    order.into_iter().filter_map(|path| groups.remove(&path)).collect()
+}
+
+/// A parsed query with the hit shape of its options, reusable across files
+pub(crate) struct Matcher {
+    source: String,
+    query: Option<Query>,
+    shape: HitShape,
+}
+
+impl Matcher {
+    pub(crate) fn new(query: &str, options: &SearchOptions) -> Result<Matcher> {
+        Ok(Matcher {
+            source: query.to_string(),
+            query: Query::parse(query)?,
+            shape: HitShape {
+                context: options.context_messages.unwrap_or(0) as usize,
+                snippet_chars: options.snippet_chars.unwrap_or(DEFAULT_SNIPPET_CHARS).max(1) as usize,
+            },
+        })
+    }
+
+    fn tagged(&self) -> Option<&HashSet<String>> {
+        self.query.as_ref().map(|q| &q.filters.tagged)
+    }
+
+    /// Hits of one session file, in message order
+    pub(crate) fn file_hits(&self, path: &Path) -> Vec<SearchHit> {
+        match &self.query {
+            Some(query) => search_file(path, query, &self.shape),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Same query text, hit shape and tagged sessions, so earlier hits are still valid
+impl PartialEq for Matcher {
+    fn eq(&self, other: &Matcher) -> bool {
+        self.source == other.source && self.shape == other.shape && self.tagged() == other.tagged()
+    }
+}
+
+/// Rank, count and cut one page out of every hit of a search
+pub(crate) fn paginate(mut hits: Vec<SearchHit>, options: &SearchOptions) -> Result<SearchPage> {
+    let by_recency = match options.sort.as_deref().unwrap_or("relevance") {
+        "relevance" => false,
+        "recency" => true,
+        other => return Err(invalid_argument(format!("Unknown sort: {}", other))),
+    };
+    let limit = options.limit.unwrap_or(DEFAULT_LIMIT) as usize;
+    let offset = options.offset.unwrap_or(0) as usize;
+    hits.sort_by(|a, b| compare(a, b, by_recency));
+    let total_hits = hits.len() as u32;
+    let total_sessions = hits.iter().map(|h| h.file_path.as_str()).collect::<HashSet<_>>().len() as u32;
+
+    let page = |total: usize| (offset.min(total), (offset + limit).min(total));
+    if options.group_by_session == Some(true) {
+        let groups = group(hits, options.hits_per_session.unwrap_or(DEFAULT_HITS_PER_SESSION) as usize);
+        let (start, end) = page(groups.len());
+        return Ok(SearchPage {
+            total_hits,
+            total_sessions,
+            offset: offset as u32,
+            has_more: end < groups.len(),
+            hits: Vec::new(),
+            groups: groups.into_iter().skip(start).take(end - start).collect(),
+        });
+    }
+    let (start, end) = page(hits.len());
+    Ok(SearchPage {
+        total_hits,
+        total_sessions,
+        offset: offset as u32,
+        has_more: end < hits.len(),
+        hits: hits.into_iter().skip(start).take(end - start).collect(),
+        groups: Vec::new(),
+    })
+}
+
+fn search(files: &[PathBuf], query: &str, options: &SearchOptions) -> Result<SearchPage> {
+    let matcher = Matcher::new(query, options)?;
+    let hits: Vec<SearchHit> = config::install(|| files.par_iter().flat_map_iter(|path| matcher.file_hits(path)).collect());
+    paginate(hits, options)
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Messages under root_dir containing every word of query (case-insensitive),
+/// one page at a time. Filter tokens narrow the scan: has:image, has:thinking,
+/// has:tool, tool:<name>, role:<role>, model:<part>, project:<part>, is:sidechain,
+/// lang:<ISO 639-3 code or English name>, tag:<manual or automatic tag>
+#[napi(catch_unwind)]
+pub fn search_history(root_dir: String, query: String, options: Option<SearchOptions>) -> Result<SearchPage> {
+    let files = collect_session_files(&root_dir)?;
+    search(&files, &query, &options.unwrap_or_default())
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_search_pages_and_groups() {
+        let root = TempDir::new("search");
+        std::fs::create_dir_all(root.join("p")).unwrap();
+        let line = |uuid: &str, session: &str, ts: &str, text: &str| {
+            format!(r#"{{"type":"user","uuid":"{}","sessionId":"{}","timestamp":"{}","message":{{"role":"user","content":"{}"}}}}"#, uuid, session, ts, text)
+        };
+        std::fs::write(root.join("p/a.jsonl"), [
+            line("a1", "a", "2024-01-01T10:00:00Z", "fix the login bug, the login page"),
+            line("a2", "a", "2024-01-01T11:00:00Z", "unrelated"),
+            line("a3", "a", "2024-01-01T12:00:00Z", "Login works"),
+        ].join("\n")).unwrap();
+        let edit = r#"{"type":"assistant","uuid":"b2","sessionId":"b","timestamp":"2024-02-01T10:01:00Z","message":{"role":"assistant","model":"claude-opus-4","content":[{"type":"text","text":"Editing"},{"type":"tool_use","id":"t1","name":"Edit","input":{}}]}}"#;
+        std::fs::write(root.join("p/b.jsonl"), [line("b1", "b", "2024-02-01T10:00:00Z", "login bug again"), edit.to_string()].join("\n")).unwrap();
+        let root_dir = root.display().to_string();
+
+        let page = search_history(root_dir.clone(), "login bug".into(), None).unwrap();
+        assert_eq!((page.total_hits, page.total_sessions), (2, 2));
+        assert_eq!(page.hits[0].message_id, "a1");
+        assert!(page.hits[0].snippet.starts_with("fix the login bug"));
+        assert!(page.hits[0].context_after.is_empty());
+
+        let options = SearchOptions { context_messages: Some(1), snippet_chars: Some(8), ..Default::default() };
+        let page = search_history(root_dir.clone(), "works".into(), Some(options)).unwrap();
+        let hit = &page.hits[0];
+        assert_eq!((hit.message_index, hit.snippet.as_str()), (2, "…n works"));
+        assert_eq!(hit.context_before.iter().map(|c| c.message_id.as_str()).collect::<Vec<_>>(), vec!["a2"]);
+        assert!(hit.context_after.is_empty());
+
+        let options = SearchOptions { sort: Some("recency".into()), limit: Some(2), ..Default::default() };
+        let page = search_history(root_dir.clone(), "LOGIN".into(), Some(options.clone())).unwrap();
+        assert_eq!(page.hits.iter().map(|h| h.message_id.as_str()).collect::<Vec<_>>(), vec!["b1", "a3"]);
+        assert!(page.has_more);
+        let next = search_history(root_dir.clone(), "login".into(), Some(SearchOptions { offset: Some(2), ..options })).unwrap();
+        assert_eq!((next.hits.len(), next.has_more), (1, false));
+
+        let grouped = SearchOptions { group_by_session: Some(true), hits_per_session: Some(1), ..Default::default() };
+        let page = search_history(root_dir.clone(), "login".into(), Some(grouped)).unwrap();
+        assert!(page.hits.is_empty());
+        assert_eq!(page.groups[0].session_id, "a");
+        assert_eq!((page.groups[0].hit_count, page.groups[0].hits.len()), (2, 1));
+        assert_eq!(page.groups[0].last_timestamp, "2024-01-01T12:00:00Z");
+
+        // Filters alone match every message that passes them
+        let page = search_history(root_dir.clone(), "project:p role:user".into(), None).unwrap();
+        assert_eq!(page.total_hits, 4);
+        assert_eq!(search_history(root_dir.clone(), "login project:other".into(), None).unwrap().total_hits, 0);
+        // Messages too short to classify match no language
+        assert_eq!(search_history(root_dir.clone(), "login lang:english".into(), None).unwrap().total_hits, 0);
+        assert!(search_history(root_dir.clone(), "lang:klingon".into(), None).is_err());
+        let page = search_history(root_dir.clone(), "tool:edit model:opus editing".into(), None).unwrap();
+        assert_eq!(page.hits.iter().map(|h| h.message_id.as_str()).collect::<Vec<_>>(), vec!["b2"]);
+        assert_eq!(search_history(root_dir.clone(), "has:tool role:user".into(), None).unwrap().total_hits, 0);
+        assert!(search_history(root_dir.clone(), "has:video".into(), None).is_err());
+
+        assert!(search_history(root_dir, "x".into(), Some(SearchOptions { sort: Some("size".into()), ..Default::default() })).is_err());
+    }
+}
@@ -0,0 +1,285 @@
+// ============================================
+// SESSION LISTING
+// The session list one page at a time: files are ordered by a cheap
+// key (mtime, first timestamp or size) and only the page is summarized.
+// Cursors name the last session shown, so new sessions do not shift pages.
+// Page sessions whose automatic tags are stale get tagged again
+// ============================================
+
+use std::collections::HashMap;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use rayon::prelude::*;
+use serde::Deserialize;
+
+use crate::ci::CiMetadata;
+use crate::errors::invalid_argument;
+use crate::paths::{metadata, open_file};
+use crate::reader::lossy_lines;
+use crate::store::{normalize_tags, now_millis, read_store, update_store};
+use crate::tag_rules::{session_auto_tags, TagRules};
+use crate::tags::SessionTags;
+use crate::time::{parse_utc, DEFAULT_IDLE_THRESHOLD_SECS};
+use crate::{collect_session_files, config, fast, ClaudeSession};
+
+const DEFAULT_LIMIT: u32 = 50;
+const SORTS: &[&str] = &["last_activity", "created", "size"];
+
+/// Lines read looking for the first timestamp when sorting by creation
+const CREATED_SCAN_LINES: usize = 50;
+
+/// One entry of a list_sessions page
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct ListedSession {
+    /// Project folder name, e.g. "-home-me-app"
+    pub project: String,
+    pub bytes: i64,
+    /// File modification time (RFC 3339), i.e. the last activity
+    pub modified_at: Option<String>,
+    /// Fast summary: counts, timestamps, tokens, flags and cwd
+    pub summary: ClaudeSession,
+    /// "automation" for sessions imported by import_ci_artifacts, else "interactive"
+    pub category: String,
+    /// The CI run of an automation session
+    pub ci: Option<CiMetadata>,
+    /// Manual and automatic tags; the automatic ones are recomputed when
+    /// the file or the tag rules changed since they were stored
+    pub tags: Vec<String>,
+}
+
+/// Result of list_sessions
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct SessionListPage {
+    pub sort_by: String,
+    pub sessions: Vec<ListedSession>,
+    /// Sessions under the root, on every page
+    pub total_sessions: u32,
+    /// Pass back as cursor for the next page, None on the last one
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Stamp {
+    #[serde(default)]
+    timestamp: Option<String>,
+}
+
+struct Candidate {
+    path: PathBuf,
+    file_path: String,
+    /// Larger first
+    key: i64,
+    bytes: i64,
+    modified: Option<DateTime<Utc>>,
+}
+
+/// Timestamp of the first entry that has one, near the top of the file
+fn first_timestamp(path: &Path) -> Option<DateTime<Utc>> {
+    let reader = BufReader::new(open_file(path).ok()?);
+    lossy_lines(reader)
+        .take(CREATED_SCAN_LINES)
+        .find_map(|line| serde_json::from_str::<Stamp>(&line).ok()?.timestamp.as_deref().and_then(parse_utc))
+}
+
+fn candidate(path: PathBuf, sort_by: &str) -> Candidate {
+    let metadata = metadata(&path).ok();
+    let bytes = metadata.as_ref().map(|m| m.len() as i64).unwrap_or(0);
+    let modified: Option<DateTime<Utc>> = metadata.and_then(|m| m.modified().ok()).map(|t: SystemTime| t.into());
+    let key = match sort_by {
+        "size" => bytes,
+        // Sessions without timestamps fall back to their mtime
+        "created" => first_timestamp(&path).or(modified).map(|t| t.timestamp_millis()).unwrap_or(0),
+        _ => modified.map(|t| t.timestamp_millis()).unwrap_or(0),
+    };
+    Candidate { file_path: path.display().to_string(), path, key, bytes, modified }
+}
+
+fn encode_cursor(sort_by: &str, last: &Candidate) -> String {
+    let raw = format!("{}\n{}\n{}", sort_by, last.key, last.file_path);
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(raw)
+}
+
+/// Sort key and file path of the last session of the previous page
+fn decode_cursor(cursor: &str, sort_by: &str) -> Result<(i64, String)> {
+    let invalid = || invalid_argument(format!("Invalid session cursor: {}", cursor));
+    let raw = base64::engine::general_purpose::URL_SAFE_NO_PAD.decode(cursor).map_err(|_| invalid())?;
+    let raw = String::from_utf8(raw).map_err(|_| invalid())?;
+    let mut parts = raw.splitn(3, '\n');
+    let (Some(sort), Some(key), Some(file_path)) = (parts.next(), parts.next(), parts.next()) else { return Err(invalid()) };
+    if sort != sort_by {
+        return Err(invalid_argument(format!("Cursor was made for sort_by \"{}\", not \"{}\"", sort, sort_by)));
+    }
+    Ok((key.parse().map_err(|_| invalid())?, file_path.to_string()))
+}
+
+/// What a page needs from the store
+#[derive(Default)]
+struct Labels {
+    ci_runs: HashMap<String, CiMetadata>,
+    tags: HashMap<String, SessionTags>,
+    /// None when the stored rules do not compile; tags are then shown as stored
+    rules: Option<TagRules>,
+}
+
+/// The listed session, and its tags when they had to be recomputed
+fn summarize(candidate: &Candidate, labels: &Labels) -> Option<(ListedSession, Option<SessionTags>)> {
+    // A file deleted since it was listed drops out of the page
+    let summary = fast::scan_summary(&candidate.file_path, DEFAULT_IDLE_THRESHOLD_SECS).ok()?;
+    let ci = labels.ci_runs.get(&summary.session_id).cloned();
+    let stored = labels.tags.get(&summary.session_id);
+    let retagged = labels.rules.as_ref().and_then(|rules| {
+        let source = rules.source_of(&candidate.path);
+        if stored.is_some_and(|t| t.auto_tags_source == source) {
+            return None;
+        }
+        let (Some(session_id), auto_tags) = session_auto_tags(&candidate.path, rules).ok()? else { return None };
+        let tags = stored.map(|t| t.tags.clone()).unwrap_or_default();
+        Some(SessionTags { session_id, tags, auto_tags, auto_tags_source: source, updated_at: 0 })
+    });
+    let tags = retagged.as_ref().or(stored).map(|t| normalize_tags([t.tags.clone(), t.auto_tags.clone()].concat())).unwrap_or_default();
+    let listed = ListedSession {
+        category: if ci.is_some() { "automation" } else { "interactive" }.to_string(),
+        ci,
+        project: candidate.path.parent().and_then(|p| p.file_name()).map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+        bytes: candidate.bytes,
+        modified_at: candidate.modified.map(|t| t.to_rfc3339()),
+        tags,
+        summary,
+    };
+    Some((listed, retagged))
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// One page of the sessions under root_dir, newest or largest first.
+/// sort_by: "last_activity" (default), "created" or "size"; cursor is the
+/// next_cursor of the previous page; limit defaults to 50
+#[napi(catch_unwind)]
+pub fn list_sessions(root_dir: String, sort_by: Option<String>, cursor: Option<String>, limit: Option<u32>) -> Result<SessionListPage> {
+    let sort_by = sort_by.unwrap_or_else(|| "last_activity".to_string());
+    if !SORTS.contains(&sort_by.as_str()) {
+        return Err(invalid_argument(format!("Unknown session sort: {}", sort_by)));
+    }
+    let after = cursor.as_deref().map(|c| decode_cursor(c, &sort_by)).transpose()?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).max(1) as usize;
+
+    let files = collect_session_files(&root_dir)?;
+    let total_sessions = files.len() as u32;
+    let mut candidates: Vec<Candidate> = config::install(|| files.into_par_iter().map(|path| candidate(path, &sort_by)).collect());
+    candidates.sort_by(|a, b| b.key.cmp(&a.key).then_with(|| a.file_path.cmp(&b.file_path)));
+
+    let start = match &after {
+        Some((key, file_path)) => candidates.partition_point(|c| c.key > *key || (c.key == *key && c.file_path <= *file_path)),
+        None => 0,
+    };
+    let end = (start + limit).min(candidates.len());
+    let page = &candidates[start..end];
+    let next_cursor = (end < candidates.len()).then(|| encode_cursor(&sort_by, &candidates[end - 1]));
+    // An unreadable store only loses the CI labels and tags
+    let labels = read_store(|store| Labels {
+        ci_runs: store.ci_metadata(),
+        tags: store.session_tags.iter().map(|t| (t.session_id.clone(), t.clone())).collect(),
+        rules: TagRules::compile(&store.tag_rules).ok(),
+    })
+    .unwrap_or_default();
+    let listed: Vec<(ListedSession, Option<SessionTags>)> =
+        config::install(|| page.par_iter().filter_map(|c| summarize(c, &labels)).collect());
+    let (sessions, retagged): (Vec<ListedSession>, Vec<Option<SessionTags>>) = listed.into_iter().unzip();
+    let retagged: Vec<SessionTags> = retagged.into_iter().flatten().collect();
+    if !retagged.is_empty() {
+        let now = now_millis();
+        // Failing to save only means tagging them again next time
+        let _ = update_store(|store| {
+            for t in retagged {
+                store.set_auto_tags(&t.session_id, t.auto_tags, t.auto_tags_source, now);
+            }
+        });
+    }
+
+    Ok(SessionListPage { sort_by, sessions, total_sessions, next_cursor })
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{StoreDir, TempDir};
+    use std::time::Duration;
+
+    #[test]
+    fn test_list_sessions_pages() {
+        let root = TempDir::new("sessions");
+        std::fs::create_dir_all(root.join("p")).unwrap();
+        let line = |session: &str, day: u32, text: &str| {
+            format!(r#"{{"type":"user","uuid":"{}-1","sessionId":"{}","timestamp":"2024-01-{:02}T10:00:00Z","message":{{"role":"user","content":"{}"}}}}"#, session, session, day, text)
+        };
+        let now = SystemTime::now();
+        // Created a, c, b (newest first) but last active c, b, a
+        for (session, day, text, idle_secs) in [("a", 3, "x", 300), ("b", 1, "a much longer prompt", 60), ("c", 2, "mid", 0)] {
+            let path = root.join(format!("p/{}.jsonl", session));
+            std::fs::write(&path, line(session, day, text)).unwrap();
+            let file = std::fs::File::options().write(true).open(&path).unwrap();
+            file.set_modified(now - Duration::from_secs(idle_secs)).unwrap();
+        }
+        let root_dir = root.display().to_string();
+        let ids = |page: &SessionListPage| page.sessions.iter().map(|s| s.summary.session_id.clone()).collect::<Vec<_>>();
+
+        let first = list_sessions(root_dir.clone(), None, None, Some(2)).unwrap();
+        assert_eq!((ids(&first), first.total_sessions), (vec!["c".to_string(), "b".to_string()], 3));
+        assert_eq!((first.sessions[0].project.as_str(), first.sessions[0].category.as_str()), ("p", "interactive"));
+        let rest = list_sessions(root_dir.clone(), None, first.next_cursor.clone(), Some(2)).unwrap();
+        assert_eq!((ids(&rest), rest.next_cursor), (vec!["a".to_string()], None));
+
+        let created = list_sessions(root_dir.clone(), Some("created".into()), None, None).unwrap();
+        assert_eq!(ids(&created), vec!["a", "c", "b"]);
+        let size = list_sessions(root_dir.clone(), Some("size".into()), None, Some(1)).unwrap();
+        assert_eq!(ids(&size), vec!["b"]);
+
+        assert!(list_sessions(root_dir.clone(), Some("size".into()), first.next_cursor, None).is_err());
+        assert!(list_sessions(root_dir.clone(), None, Some("garbage!".into()), None).is_err());
+        assert!(list_sessions(root_dir, Some("title".into()), None, None).is_err());
+    }
+
+    #[test]
+    fn test_list_sessions_applies_tag_rules() {
+        let root = TempDir::new("sessions-tags");
+        std::fs::create_dir_all(root.join("p")).unwrap();
+        let _store = StoreDir::set(&root.join("store"));
+        let path = root.join("p/s.jsonl");
+        std::fs::write(&path, r#"{"type":"user","sessionId":"tagged-s","message":{"role":"user","content":"Prod is down again"}}"#).unwrap();
+        let rule = crate::tag_rules::TagRule {
+            name: "incidents".into(),
+            tag: "incident".into(),
+            content: Some("is down".into()),
+            tool: None,
+            project: None,
+            updated_at: None,
+        };
+        let listed_tags = || list_sessions(root.display().to_string(), None, None, None).unwrap().sessions[0].tags.clone();
+
+        crate::tag_rules::save_tag_rule(rule).unwrap();
+        assert_eq!(listed_tags(), vec!["incident"]);
+        let stored = crate::tags::get_session_tags("tagged-s".into()).unwrap();
+        assert_eq!(stored.auto_tags, vec!["incident"]);
+        assert!(stored.auto_tags_source.is_some());
+
+        // A changed file is tagged again on the next listing
+        std::fs::write(&path, r#"{"type":"user","sessionId":"tagged-s","message":{"role":"user","content":"All good now"}}"#).unwrap();
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(5)).unwrap();
+        assert_eq!(listed_tags(), Vec::<String>::new());
+    }
+}
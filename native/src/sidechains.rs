@@ -0,0 +1,141 @@
+// ============================================
+// SIDECHAINS
+// Subagent (Task) runs inside a session grouped by their root entry,
+// with the tokens each one consumed
+// ============================================
+
+use std::collections::HashMap;
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::title::prompt_excerpt;
+use crate::{extract_text_content, for_each_entry, ContentItem, RawLogEntry};
+
+/// One subagent run
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SidechainGroup {
+    /// uuid of the first entry of the run
+    pub root_uuid: String,
+    /// subagent_type of the Task call that launched it, when it can be matched
+    pub subagent_type: Option<String>,
+    /// Excerpt of the prompt the subagent was given
+    pub prompt: Option<String>,
+    pub message_count: u32,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub first_timestamp: Option<String>,
+    pub last_timestamp: Option<String>,
+}
+
+/// Groups in order of their first entry; a sidechain entry whose parent is
+/// not a sidechain entry starts a new group
+pub(crate) fn group_sidechains(entries: &[RawLogEntry]) -> Vec<SidechainGroup> {
+    // Task prompt text -> subagent_type, from the main thread's tool calls
+    let mut task_types: HashMap<String, String> = HashMap::new();
+    let mut group_of: HashMap<&str, usize> = HashMap::new();
+    let mut groups: Vec<SidechainGroup> = Vec::new();
+
+    for entry in entries {
+        let Some(message) = &entry.message else { continue };
+        if entry.is_sidechain != Some(true) {
+            for item in &message.content {
+                if let ContentItem::ToolUse { name, input, .. } = item {
+                    let prompt = input.get("prompt").and_then(|v| v.as_str());
+                    let subagent_type = input.get("subagent_type").and_then(|v| v.as_str());
+                    if let (true, Some(prompt), Some(subagent_type)) = (name == "Task", prompt, subagent_type) {
+                        task_types.insert(prompt.trim().to_string(), subagent_type.to_string());
+                    }
+                }
+            }
+            continue;
+        }
+
+        let parent_group = entry.parent_uuid.as_deref().and_then(|p| group_of.get(p).copied());
+        let index = match parent_group {
+            Some(index) => index,
+            None => {
+                let prompt = extract_text_content(&message.content);
+                groups.push(SidechainGroup {
+                    root_uuid: entry.uuid.clone().unwrap_or_default(),
+                    subagent_type: task_types.get(prompt.trim()).cloned(),
+                    prompt: prompt_excerpt(&prompt),
+                    message_count: 0,
+                    input_tokens: 0,
+                    output_tokens: 0,
+                    first_timestamp: None,
+                    last_timestamp: None,
+                });
+                groups.len() - 1
+            }
+        };
+        if let Some(uuid) = &entry.uuid {
+            group_of.insert(uuid, index);
+        }
+
+        let group = &mut groups[index];
+        group.message_count += 1;
+        if let Some(usage) = &message.usage {
+            group.input_tokens += usage.input_tokens as i64;
+            group.output_tokens += usage.output_tokens as i64;
+        }
+        if let Some(ts) = &entry.timestamp {
+            if group.first_timestamp.is_none() {
+                group.first_timestamp = Some(ts.clone());
+            }
+            group.last_timestamp = Some(ts.clone());
+        }
+    }
+    groups
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Subagent runs of a session with their message counts and token totals
+#[napi(catch_unwind)]
+pub fn get_sidechain_groups(file_path: String) -> Result<Vec<SidechainGroup>> {
+    let mut entries = Vec::new();
+    for_each_entry(&file_path, |entry| entries.push(entry))?;
+    Ok(group_sidechains(&entries))
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_sidechain_groups_and_session_split() {
+        let tmp = TempDir::new("sidechains");
+        let path = tmp.join("sidechains.jsonl");
+        std::fs::write(&path, [
+            r#"{"type":"user","uuid":"u1","message":{"role":"user","content":"Review the code"}}"#,
+            r#"{"type":"assistant","uuid":"a1","parentUuid":"u1","message":{"role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Task","input":{"subagent_type":"code-reviewer","prompt":"Review src/"}}],"usage":{"input_tokens":100,"output_tokens":10}}}"#,
+            r#"{"type":"user","uuid":"s1","parentUuid":null,"isSidechain":true,"message":{"role":"user","content":"Review src/"}}"#,
+            r#"{"type":"assistant","uuid":"s2","parentUuid":"s1","isSidechain":true,"message":{"role":"assistant","content":"ok","usage":{"input_tokens":40,"output_tokens":4}}}"#,
+            r#"{"type":"user","uuid":"x1","parentUuid":null,"isSidechain":true,"message":{"role":"user","content":"Search docs"}}"#,
+            r#"{"type":"assistant","uuid":"x2","parentUuid":"x1","isSidechain":true,"message":{"role":"assistant","content":"found","usage":{"input_tokens":7,"output_tokens":3}}}"#,
+        ].join("\n")).unwrap();
+        let file_path = path.display().to_string();
+
+        let groups = get_sidechain_groups(file_path.clone()).unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].subagent_type.as_deref(), Some("code-reviewer"));
+        assert_eq!((groups[0].message_count, groups[0].input_tokens, groups[0].output_tokens), (2, 40, 4));
+        assert_eq!((groups[1].root_uuid.as_str(), groups[1].subagent_type.as_ref()), ("x1", None));
+
+        for fast in [false, true] {
+            let options = crate::SummaryOptions { fast: Some(fast), ..Default::default() };
+            let summary = crate::get_session_summary(file_path.clone(), Some(options)).unwrap();
+            assert_eq!((summary.main_input_tokens, summary.main_output_tokens), (100, 10));
+            assert_eq!((summary.sidechain_input_tokens, summary.sidechain_output_tokens), (47, 7));
+        }
+    }
+}
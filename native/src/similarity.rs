@@ -0,0 +1,167 @@
+// ============================================
+// PROMPT SIMILARITY
+// Word shingles + MinHash signatures to find past prompts that
+// look like a new one ("I solved this with Claude before")
+// ============================================
+
+use std::collections::HashSet;
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use schemars::JsonSchema;
+
+use crate::title::{is_substantive_prompt, prompt_excerpt, strip_tag_blocks};
+use crate::{collect_session_files, entry_canonical_id, extract_text_content, for_each_entry};
+
+const SHINGLE_SIZE: usize = 3;
+const NUM_HASHES: usize = 64;
+const DEFAULT_THRESHOLD: f64 = 0.5;
+
+/// A past prompt similar to the query text
+#[napi(object)]
+#[derive(Debug, Clone, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+pub struct SimilarPrompt {
+    pub session_id: String,
+    pub file_path: String,
+    pub message_id: String,
+    pub canonical_id: String,
+    pub timestamp: Option<String>,
+    pub excerpt: String,
+    /// Estimated Jaccard similarity (0..1)
+    pub similarity: f64,
+}
+
+pub(crate) use history_core::fnv1a;
+
+/// splitmix64 finalizer - cheap way to derive independent hash functions
+fn mix(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e3779b97f4a7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+    x ^ (x >> 31)
+}
+
+/// Lowercased alphanumeric word tokens
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+/// Hashed word n-gram shingles (falls back to single words for short text)
+pub(crate) fn shingles(text: &str) -> HashSet<u64> {
+    let tokens = tokenize(text);
+    let size = if tokens.len() < SHINGLE_SIZE { 1 } else { SHINGLE_SIZE };
+
+    tokens
+        .windows(size)
+        .map(|window| fnv1a(window.join(" ").as_bytes()))
+        .collect()
+}
+
+/// MinHash signature of a shingle set
+#[derive(Debug, Clone, PartialEq)]
+pub struct Signature(pub Vec<u64>);
+
+impl Signature {
+    pub fn from_shingles(shingles: &HashSet<u64>) -> Option<Signature> {
+        if shingles.is_empty() {
+            return None;
+        }
+
+        let mins = (0..NUM_HASHES as u64)
+            .map(|seed| {
+                shingles
+                    .iter()
+                    .map(|s| mix(s ^ mix(seed)))
+                    .min()
+                    .unwrap_or(u64::MAX)
+            })
+            .collect();
+        Some(Signature(mins))
+    }
+
+    pub fn from_text(text: &str) -> Option<Signature> {
+        Signature::from_shingles(&shingles(text))
+    }
+
+    /// Estimated Jaccard similarity: fraction of matching minimums
+    pub fn similarity(&self, other: &Signature) -> f64 {
+        let same = self.0.iter().zip(&other.0).filter(|(a, b)| a == b).count();
+        same as f64 / NUM_HASHES as f64
+    }
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Find past user prompts similar to `text` across all sessions under root_dir
+#[napi(catch_unwind)]
+pub fn find_similar_prompts(
+    root_dir: String,
+    text: String,
+    threshold: Option<f64>,
+) -> Result<Vec<SimilarPrompt>> {
+    let threshold = threshold.unwrap_or(DEFAULT_THRESHOLD);
+    let Some(query) = Signature::from_text(&text) else {
+        return Ok(Vec::new());
+    };
+
+    let mut results = Vec::new();
+
+    for path in collect_session_files(&root_dir)? {
+        let file_path = path.display().to_string();
+
+        // Unreadable files are skipped rather than failing the whole scan
+        let _ = for_each_entry(&file_path, |entry| {
+            if !is_substantive_prompt(&entry) {
+                return;
+            }
+            let Some(message) = &entry.message else { return };
+            let prompt = strip_tag_blocks(&extract_text_content(&message.content));
+
+            let Some(signature) = Signature::from_text(&prompt) else { return };
+            let similarity = query.similarity(&signature);
+            if similarity < threshold {
+                return;
+            }
+
+            results.push(SimilarPrompt {
+                session_id: entry.session_id.clone().unwrap_or_else(|| "unknown".to_string()),
+                file_path: file_path.clone(),
+                message_id: entry.uuid.clone().unwrap_or_else(|| "unknown".to_string()),
+                canonical_id: entry_canonical_id(&entry),
+                timestamp: entry.timestamp.clone(),
+                excerpt: prompt_excerpt(&prompt).unwrap_or_default(),
+                similarity,
+            });
+        });
+    }
+
+    results.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+    Ok(results)
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minhash_similarity() {
+        let a = Signature::from_text("how do I fix the borrow checker error in my parser loop").unwrap();
+        let b = Signature::from_text("How do I fix the borrow checker error in my parser?").unwrap();
+        let c = Signature::from_text("write a haiku about autumn leaves falling").unwrap();
+
+        assert_eq!(a.similarity(&a), 1.0);
+        assert!(a.similarity(&b) > 0.5);
+        assert!(a.similarity(&c) < 0.2);
+        assert!(Signature::from_text("  ").is_none());
+    }
+}
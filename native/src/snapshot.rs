@@ -0,0 +1,127 @@
+// ============================================
+// READ SNAPSHOTS
+// Consistent reads of files Claude Code is still appending to: stop at
+// the last complete line, optionally on a private copy of the file
+// ============================================
+
+use std::io::{Read, Seek, SeekFrom};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use napi::bindgen_prelude::*;
+
+use crate::errors::{io_error, unsupported_format};
+use crate::paths::open_file;
+
+/// Accepted values of ParseOptions.snapshot
+const STRATEGIES: &[&str] = &["none", "length", "copy"];
+
+/// Bytes read per step while looking back for the last newline
+const TAIL_CHUNK: u64 = 64 * 1024;
+
+static NEXT_SNAPSHOT_ID: AtomicU32 = AtomicU32::new(1);
+
+/// What to read for one parse; a copied file is deleted on drop
+pub(crate) struct Snapshot {
+    pub path: String,
+    /// Bytes to read from path (u64::MAX for all of it)
+    pub read_len: u64,
+    /// The strategy that was actually used
+    pub strategy: &'static str,
+    copy: Option<PathBuf>,
+}
+
+impl Drop for Snapshot {
+    fn drop(&mut self) {
+        if let Some(copy) = &self.copy {
+            let _ = std::fs::remove_file(copy);
+        }
+    }
+}
+
+/// Length of the file up to and including its last newline, so a line
+/// still being written is left out
+fn complete_len(file_path: &str) -> Result<u64> {
+    let mut file = open_file(file_path).map_err(|e| io_error("Cannot open file", file_path, e))?;
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+
+    let mut end = len;
+    let mut buf = Vec::new();
+    while end > 0 {
+        let start = end.saturating_sub(TAIL_CHUNK);
+        buf.resize((end - start) as usize, 0);
+        file.seek(SeekFrom::Start(start))
+            .and_then(|_| file.read_exact(&mut buf))
+            .map_err(|e| io_error("Cannot read file", file_path, e))?;
+        if let Some(pos) = memchr::memrchr(b'\n', &buf) {
+            return Ok(start + pos as u64 + 1);
+        }
+        end = start;
+    }
+    Ok(0)
+}
+
+/// Prepare a read of file_path with strategy: "none" reads the live file,
+/// "length" stops at the last complete line as of now, "copy" does the same
+/// on a temporary copy, falling back to "length" when it cannot be written
+pub(crate) fn take(file_path: &str, strategy: Option<&str>) -> Result<Snapshot> {
+    let strategy = strategy.unwrap_or("none");
+    if !STRATEGIES.contains(&strategy) {
+        return Err(unsupported_format(format!("Unknown snapshot strategy: {}", strategy)));
+    }
+    let live = |read_len, strategy| Snapshot { path: file_path.to_string(), read_len, strategy, copy: None };
+
+    match strategy {
+        "none" => Ok(live(u64::MAX, "none")),
+        "copy" => {
+            let copy = std::env::temp_dir().join(format!(
+                "claude-parser-snapshot-{}-{}.jsonl",
+                std::process::id(),
+                NEXT_SNAPSHOT_ID.fetch_add(1, Ordering::Relaxed)
+            ));
+            match std::fs::copy(file_path, &copy) {
+                Ok(_) => {
+                    let path = copy.display().to_string();
+                    let read_len = complete_len(&path)?;
+                    Ok(Snapshot { path, read_len, strategy: "copy", copy: Some(copy) })
+                }
+                Err(e) => {
+                    tracing::warn!(file = file_path, "Cannot copy file for snapshot, reading up to its length: {}", e);
+                    let _ = std::fs::remove_file(&copy);
+                    Ok(live(complete_len(file_path)?, "length"))
+                }
+            }
+        }
+        _ => Ok(live(complete_len(file_path)?, "length")),
+    }
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_snapshot_stops_at_last_complete_line() {
+        let tmp = TempDir::new("snapshot");
+        let path = tmp.join("snapshot.jsonl");
+        std::fs::write(&path, "one\ntwo\nthr").unwrap();
+        let file_path = path.display().to_string();
+
+        assert_eq!(take(&file_path, None).unwrap().read_len, u64::MAX);
+        assert_eq!(take(&file_path, Some("length")).unwrap().read_len, 8);
+
+        let copy = take(&file_path, Some("copy")).unwrap();
+        assert_eq!((copy.strategy, copy.read_len), ("copy", 8));
+        let copied = PathBuf::from(&copy.path);
+        assert!(copied.exists());
+        drop(copy);
+        assert!(!copied.exists());
+
+        assert!(take(&file_path, Some("lock")).is_err());
+    }
+}
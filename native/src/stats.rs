@@ -0,0 +1,110 @@
+// ============================================
+// ROLE STATISTICS
+// Per-role text size figures for the session list: how much each side
+// wrote, the longest message and how often code blocks appear
+// ============================================
+
+use napi_derive::napi;
+use schemars::JsonSchema;
+
+use crate::{ContentItem, RawLogEntry};
+
+/// Text statistics of one role's messages. Only messages with text count;
+/// pure tool calls and tool results are left out
+#[napi(object)]
+#[derive(Debug, Clone, Default, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+pub struct RoleStats {
+    pub message_count: u32,
+    pub total_chars: i64,
+    pub average_chars: f64,
+    pub longest_chars: i64,
+    pub longest_message_id: Option<String>,
+    /// Messages containing at least one ``` fenced block
+    pub code_block_messages: u32,
+}
+
+/// User and assistant RoleStats accumulated entry by entry
+#[derive(Debug, Default)]
+pub(crate) struct RoleStatsTracker {
+    user: RoleStats,
+    assistant: RoleStats,
+}
+
+impl RoleStatsTracker {
+    pub fn observe(&mut self, entry: &RawLogEntry) {
+        let stats = match entry.entry_type.as_str() {
+            "user" => &mut self.user,
+            "assistant" => &mut self.assistant,
+            _ => return,
+        };
+        let Some(message) = &entry.message else { return };
+
+        let mut chars = 0;
+        let mut has_text = false;
+        let mut has_code = false;
+        for item in &message.content {
+            if let ContentItem::Text { text } = item {
+                has_text = true;
+                chars += text.chars().count() as i64;
+                has_code |= text.contains("```");
+            }
+        }
+        if !has_text {
+            return;
+        }
+
+        stats.message_count += 1;
+        stats.total_chars += chars;
+        if chars > stats.longest_chars || stats.longest_message_id.is_none() {
+            stats.longest_chars = chars;
+            stats.longest_message_id = entry.uuid.clone();
+        }
+        if has_code {
+            stats.code_block_messages += 1;
+        }
+    }
+
+    /// (user, assistant)
+    pub fn finish(self) -> (RoleStats, RoleStats) {
+        let average = |mut stats: RoleStats| {
+            stats.average_chars = if stats.message_count > 0 {
+                stats.total_chars as f64 / stats.message_count as f64
+            } else {
+                0.0
+            };
+            stats
+        };
+        (average(self.user), average(self.assistant))
+    }
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_role_stats() {
+        let lines = [
+            r#"{"type":"user","uuid":"u1","message":{"role":"user","content":"short"}}"#,
+            r#"{"type":"user","uuid":"u2","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t","content":"ok"}]}}"#,
+            r#"{"type":"user","uuid":"u3","message":{"role":"user","content":"see ```rs\nfn main() {}\n```"}}"#,
+            r#"{"type":"assistant","uuid":"a1","message":{"role":"assistant","content":[{"type":"text","text":"done"}]}}"#,
+        ];
+        let mut tracker = RoleStatsTracker::default();
+        for line in lines {
+            tracker.observe(&crate::parse_jsonl_line(line).unwrap());
+        }
+        let (user, assistant) = tracker.finish();
+
+        assert_eq!(user.message_count, 2);
+        assert_eq!(user.longest_message_id.as_deref(), Some("u3"));
+        assert_eq!(user.code_block_messages, 1);
+        assert_eq!(user.average_chars, user.total_chars as f64 / 2.0);
+        assert_eq!(assistant.total_chars, 4);
+    }
+}
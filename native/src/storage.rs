@@ -0,0 +1,228 @@
+// ============================================
+// STORAGE REPORT
+// Disk usage of session files per project and per session, monthly
+// growth, and what archiving old sessions would free up
+// ============================================
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::SystemTime;
+
+use chrono::{DateTime, Utc};
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use schemars::JsonSchema;
+
+use crate::collect_session_files;
+use crate::paths::metadata;
+
+const DEFAULT_LARGEST_LIMIT: u32 = 10;
+const DEFAULT_ARCHIVE_DAYS: u32 = 90;
+
+/// Options for get_storage_report
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct StorageOptions {
+    /// Entries in largest_files (default 10)
+    pub largest_limit: Option<u32>,
+    /// Sessions untouched for this many days count as reclaimable (default 90)
+    pub archive_older_than_days: Option<u32>,
+}
+
+/// Size of one session file
+#[napi(object)]
+#[derive(Debug, Clone, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+pub struct SessionUsage {
+    pub file_path: String,
+    /// File name without .jsonl (the session id)
+    pub session_id: String,
+    pub project: String,
+    pub bytes: i64,
+    /// Last modification time (RFC 3339)
+    pub modified: Option<String>,
+}
+
+/// Sessions of one project directory, largest first
+#[napi(object)]
+#[derive(Debug, Clone, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+pub struct ProjectUsage {
+    /// Directory name below root_dir
+    pub project: String,
+    pub bytes: i64,
+    pub session_count: u32,
+    pub last_modified: Option<String>,
+    pub sessions: Vec<SessionUsage>,
+}
+
+/// Sessions last modified in one month
+#[napi(object)]
+#[derive(Debug, Clone, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+pub struct MonthlyUsage {
+    /// "2024-01"
+    pub month: String,
+    pub bytes: i64,
+    pub session_count: u32,
+    /// Total size of sessions modified up to the end of this month
+    pub cumulative_bytes: i64,
+}
+
+/// Result of get_storage_report
+#[napi(object)]
+#[derive(Debug, Clone, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+pub struct StorageReport {
+    pub total_bytes: i64,
+    pub session_count: u32,
+    /// Largest projects first
+    pub projects: Vec<ProjectUsage>,
+    pub largest_files: Vec<SessionUsage>,
+    /// Oldest month first
+    pub growth: Vec<MonthlyUsage>,
+    pub archive_older_than_days: u32,
+    /// Bytes freed by archiving sessions older than archive_older_than_days
+    pub reclaimable_bytes: i64,
+    pub reclaimable_sessions: u32,
+}
+
+/// First path component below root, "" for files directly in root
+fn project_of(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .ok()
+        .and_then(|rel| rel.parent())
+        .and_then(|parent| parent.components().next())
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn build_report(root_dir: &str, options: &StorageOptions, now: DateTime<Utc>) -> Result<StorageReport> {
+    let root = Path::new(root_dir);
+    let largest_limit = options.largest_limit.unwrap_or(DEFAULT_LARGEST_LIMIT) as usize;
+    let archive_days = options.archive_older_than_days.unwrap_or(DEFAULT_ARCHIVE_DAYS);
+    let cutoff = now - chrono::Duration::days(archive_days as i64);
+
+    let mut report = StorageReport {
+        total_bytes: 0,
+        session_count: 0,
+        projects: Vec::new(),
+        largest_files: Vec::new(),
+        growth: Vec::new(),
+        archive_older_than_days: archive_days,
+        reclaimable_bytes: 0,
+        reclaimable_sessions: 0,
+    };
+    let mut projects: BTreeMap<String, ProjectUsage> = BTreeMap::new();
+    let mut months: BTreeMap<String, (i64, u32)> = BTreeMap::new();
+    let mut sessions = Vec::new();
+
+    for path in collect_session_files(root_dir)? {
+        let Ok(metadata) = metadata(&path) else { continue };
+        let bytes = metadata.len() as i64;
+        let modified: Option<DateTime<Utc>> = metadata.modified().ok().map(|t: SystemTime| t.into());
+
+        report.total_bytes += bytes;
+        report.session_count += 1;
+        if let Some(modified) = modified {
+            let month = months.entry(modified.format("%Y-%m").to_string()).or_default();
+            month.0 += bytes;
+            month.1 += 1;
+            if modified < cutoff {
+                report.reclaimable_bytes += bytes;
+                report.reclaimable_sessions += 1;
+            }
+        }
+
+        sessions.push(SessionUsage {
+            file_path: path.display().to_string(),
+            session_id: path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default(),
+            project: project_of(root, &path),
+            bytes,
+            modified: modified.map(|m| m.to_rfc3339()),
+        });
+    }
+
+    for session in &sessions {
+        let project = projects.entry(session.project.clone()).or_insert_with(|| ProjectUsage {
+            project: session.project.clone(),
+            bytes: 0,
+            session_count: 0,
+            last_modified: None,
+            sessions: Vec::new(),
+        });
+        project.bytes += session.bytes;
+        project.session_count += 1;
+        // RFC 3339 in UTC sorts chronologically as text
+        if session.modified > project.last_modified {
+            project.last_modified = session.modified.clone();
+        }
+        project.sessions.push(session.clone());
+    }
+
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.bytes));
+    sessions.truncate(largest_limit);
+    report.largest_files = sessions;
+
+    report.projects = projects.into_values().collect();
+    for project in &mut report.projects {
+        project.sessions.sort_by_key(|s| std::cmp::Reverse(s.bytes));
+    }
+    report.projects.sort_by_key(|p| std::cmp::Reverse(p.bytes));
+
+    let mut cumulative = 0;
+    report.growth = months
+        .into_iter()
+        .map(|(month, (bytes, session_count))| {
+            cumulative += bytes;
+            MonthlyUsage { month, bytes, session_count, cumulative_bytes: cumulative }
+        })
+        .collect();
+
+    Ok(report)
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Disk usage of all sessions under root_dir (largest files, growth, reclaimable space)
+#[napi(catch_unwind)]
+pub fn get_storage_report(root_dir: String, options: Option<StorageOptions>) -> Result<StorageReport> {
+    build_report(&root_dir, &options.unwrap_or_default(), Utc::now())
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_storage_report() {
+        let root = TempDir::new("storage-test");
+        std::fs::create_dir_all(root.join("proj-a")).unwrap();
+        std::fs::create_dir_all(root.join("proj-b")).unwrap();
+        std::fs::write(root.join("proj-a/s1.jsonl"), "x".repeat(300)).unwrap();
+        std::fs::write(root.join("proj-a/s2.jsonl"), "x".repeat(100)).unwrap();
+        std::fs::write(root.join("proj-b/s3.jsonl"), "x".repeat(200)).unwrap();
+
+        let options = StorageOptions { largest_limit: Some(2), archive_older_than_days: Some(30) };
+        let root_dir = root.to_string_lossy().to_string();
+
+        let report = build_report(&root_dir, &options, Utc::now()).unwrap();
+        assert_eq!((report.total_bytes, report.session_count), (600, 3));
+        assert_eq!(report.projects[0].project, "proj-a");
+        assert_eq!(report.projects[0].sessions[0].session_id, "s1");
+        assert_eq!(report.largest_files.iter().map(|s| s.bytes).collect::<Vec<_>>(), vec![300, 200]);
+        assert_eq!(report.growth.last().unwrap().cumulative_bytes, 600);
+        assert_eq!(report.reclaimable_sessions, 0);
+
+        // Seen from a year later everything is reclaimable
+        let later = build_report(&root_dir, &options, Utc::now() + chrono::Duration::days(365)).unwrap();
+        assert_eq!((later.reclaimable_bytes, later.reclaimable_sessions), (600, 3));
+    }
+}
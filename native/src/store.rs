@@ -0,0 +1,573 @@
+// ============================================
+// SIDECAR METADATA STORE
+// Hub-owned data (bookmarks, notes, tags and tag rules, session titles,
+// issue links, CI sessions) kept in a JSON file in the app data dir - Claude's own
+// history files are never modified
+// ============================================
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::canonical_message_id;
+use crate::ci::CiSession;
+use crate::errors::{internal, invalid_argument, io_error, unsupported_format, ErrorCode, HubError};
+use crate::index::get_full_message;
+use crate::issues::IssueLink;
+use crate::paths::read_to_string;
+use crate::safe_write::write_file;
+use crate::saved_search::SavedSearch;
+use crate::tag_rules::TagRule;
+use crate::tags::SessionTags;
+use crate::title::SessionTitle;
+
+const STORE_FILE_NAME: &str = "metadata.json";
+const STORE_VERSION: u32 = 1;
+
+/// Directory override set from JS (usually Electron's userData path)
+static STORE_DIR: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// Serializes load-modify-save cycles within this process
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+// ============================================
+// DATA STRUCTURES
+// ============================================
+
+/// Bookmark / annotation attached to a single message
+#[napi(object)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+pub struct Bookmark {
+    pub session_id: String,
+    pub message_id: String,
+    /// ClaudeMessage.canonical_id of the message
+    #[serde(default)]
+    pub canonical_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub created_at: i64, // Unix epoch millis
+    pub updated_at: i64,
+}
+
+/// Filter for list_bookmarks (all fields optional, combined with AND)
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct BookmarkFilter {
+    pub session_id: Option<String>,
+    pub tag: Option<String>,
+    /// Case-insensitive match against the note text
+    pub query: Option<String>,
+}
+
+/// Entries per kind, written by export_metadata or added and updated by import_metadata
+#[napi(object)]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetadataCounts {
+    pub bookmarks: u32,
+    pub session_tags: u32,
+    pub saved_searches: u32,
+    pub ci_sessions: u32,
+    pub issue_links: u32,
+    pub tag_rules: u32,
+    pub session_titles: u32,
+}
+
+/// Options for import_metadata
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct ImportMetadataOptions {
+    /// Replace the whole store instead of merging into it (default false)
+    pub replace: Option<bool>,
+}
+
+/// On-disk layout of the sidecar file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetadataStore {
+    pub version: u32,
+    #[serde(default)]
+    pub bookmarks: Vec<Bookmark>,
+    #[serde(default)]
+    pub session_tags: Vec<SessionTags>,
+    #[serde(default)]
+    pub saved_searches: Vec<SavedSearch>,
+    #[serde(default)]
+    pub ci_sessions: Vec<CiSession>,
+    #[serde(default)]
+    pub issue_links: Vec<IssueLink>,
+    #[serde(default)]
+    pub tag_rules: Vec<TagRule>,
+    #[serde(default)]
+    pub session_titles: Vec<SessionTitle>,
+}
+
+impl Default for MetadataStore {
+    fn default() -> Self {
+        MetadataStore {
+            version: STORE_VERSION,
+            bookmarks: Vec::new(),
+            session_tags: Vec::new(),
+            saved_searches: Vec::new(),
+            ci_sessions: Vec::new(),
+            issue_links: Vec::new(),
+            tag_rules: Vec::new(),
+            session_titles: Vec::new(),
+        }
+    }
+}
+
+impl MetadataStore {
+    /// Insert a bookmark, or update note/tags if the message is already bookmarked
+    pub fn upsert_bookmark(
+        &mut self,
+        session_id: String,
+        message_id: String,
+        canonical_id: String,
+        note: Option<String>,
+        tags: Vec<String>,
+        now: i64,
+    ) -> Bookmark {
+        let tags = normalize_tags(tags);
+
+        if let Some(existing) = self
+            .bookmarks
+            .iter_mut()
+            .find(|b| b.session_id == session_id && (b.is_for(&message_id) || b.canonical_id == canonical_id))
+        {
+            existing.note = note;
+            existing.tags = tags;
+            existing.updated_at = now;
+            return existing.clone();
+        }
+
+        let bookmark = Bookmark {
+            canonical_id,
+            session_id,
+            message_id,
+            note,
+            tags,
+            created_at: now,
+            updated_at: now,
+        };
+        self.bookmarks.push(bookmark.clone());
+        bookmark
+    }
+
+    /// Remove a bookmark, returns true if one existed
+    pub fn remove_bookmark(&mut self, session_id: &str, message_id: &str) -> bool {
+        let before = self.bookmarks.len();
+        self.bookmarks
+            .retain(|b| !(b.session_id == session_id && b.is_for(message_id)));
+        self.bookmarks.len() != before
+    }
+
+    /// Bookmarks matching the filter, newest first
+    pub fn list_bookmarks(&self, filter: &BookmarkFilter) -> Vec<Bookmark> {
+        let query = filter.query.as_ref().map(|q| q.to_lowercase());
+        let tag = filter.tag.as_ref().map(|t| t.trim().to_lowercase());
+
+        let mut result: Vec<Bookmark> = self
+            .bookmarks
+            .iter()
+            .filter(|b| filter.session_id.as_ref().is_none_or(|sid| &b.session_id == sid))
+            .filter(|b| tag.as_ref().is_none_or(|t| b.tags.contains(t)))
+            .filter(|b| {
+                query.as_ref().is_none_or(|q| {
+                    b.note.as_ref().is_some_and(|n| n.to_lowercase().contains(q))
+                })
+            })
+            .cloned()
+            .collect();
+
+        result.sort_by_key(|b| std::cmp::Reverse(b.updated_at));
+        result
+    }
+
+    fn counts(&self) -> MetadataCounts {
+        MetadataCounts {
+            bookmarks: self.bookmarks.len() as u32,
+            session_tags: self.session_tags.len() as u32,
+            saved_searches: self.saved_searches.len() as u32,
+            ci_sessions: self.ci_sessions.len() as u32,
+            issue_links: self.issue_links.len() as u32,
+            tag_rules: self.tag_rules.len() as u32,
+            session_titles: self.session_titles.len() as u32,
+        }
+    }
+
+    /// Merge another store into this one; of two entries for the same
+    /// message, session or name the more recently updated wins
+    pub fn merge(&mut self, other: MetadataStore) -> MetadataCounts {
+        MetadataCounts {
+            bookmarks: merge_entries(&mut self.bookmarks, other.bookmarks, |b| (b.session_id.clone(), b.canonical_id.clone()), |b| b.updated_at),
+            session_tags: merge_entries(&mut self.session_tags, other.session_tags, |t| (t.session_id.clone(), String::new()), |t| t.updated_at),
+            saved_searches: merge_entries(&mut self.saved_searches, other.saved_searches, |s| (s.name.clone(), String::new()), |s| s.updated_at),
+            ci_sessions: merge_entries(&mut self.ci_sessions, other.ci_sessions, |c| (c.session_id.clone(), String::new()), |c| c.imported_at),
+            issue_links: merge_entries(&mut self.issue_links, other.issue_links, |l| (l.session_id.clone(), l.url.clone()), |l| l.created_at),
+            tag_rules: merge_entries(&mut self.tag_rules, other.tag_rules, |r| (r.name.clone(), String::new()), |r| r.updated_at.unwrap_or(0)),
+            session_titles: merge_entries(&mut self.session_titles, other.session_titles, |t| (t.session_id.clone(), String::new()), |t| t.updated_at),
+        }
+    }
+}
+
+/// Add or overwrite entries of into with newer ones from other, returns how many changed
+fn merge_entries<T>(into: &mut Vec<T>, other: Vec<T>, key: impl Fn(&T) -> (String, String), updated_at: impl Fn(&T) -> i64) -> u32 {
+    let mut changed = 0;
+    for entry in other {
+        match into.iter_mut().find(|existing| key(existing) == key(&entry)) {
+            Some(existing) if updated_at(&entry) > updated_at(existing) => *existing = entry,
+            Some(_) => continue,
+            None => into.push(entry),
+        }
+        changed += 1;
+    }
+    changed
+}
+
+impl Bookmark {
+    /// True when id is the bookmarked message's id or canonical id
+    fn is_for(&self, id: &str) -> bool {
+        self.message_id == id || self.canonical_id == id
+    }
+}
+
+fn bookmark_canonical_id(session_id: &str, message_id: &str) -> String {
+    canonical_message_id(Some(session_id), Some(message_id), "", None)
+}
+
+/// "m-" and 16 hex digits, as made by canonical_message_id
+fn is_canonical_id(id: &str) -> bool {
+    id.strip_prefix("m-").is_some_and(|hex| hex.len() == 16 && hex.bytes().all(|b| b.is_ascii_hexdigit()))
+}
+
+/// Message id and canonical id of the message a bookmark is for. With the
+/// session file they come from the parsed message, found by uuid or
+/// canonical id; without it, message_id must be one of the two
+pub(crate) fn bookmark_ids(session_id: &str, message_id: &str, file_path: Option<&str>) -> Result<(String, String)> {
+    if let Some(file_path) = file_path {
+        let message = get_full_message(file_path.to_string(), message_id.to_string())?
+            .ok_or_else(|| Error::from(HubError::new(ErrorCode::NotFound, format!("Message not found: {}", message_id)).path(file_path)))?;
+        // Messages without a uuid are all "unknown"; their canonical id tells them apart
+        let id = if message.message_id == "unknown" { message.canonical_id.clone() } else { message.message_id };
+        return Ok((id, message.canonical_id));
+    }
+    if is_canonical_id(message_id) {
+        return Ok((message_id.to_string(), message_id.to_string()));
+    }
+    if message_id == "unknown" {
+        return Err(invalid_argument("Message has no uuid; bookmark it by canonical_id or pass its session file"));
+    }
+    Ok((message_id.to_string(), bookmark_canonical_id(session_id, message_id)))
+}
+
+/// Lowercase, trim and dedupe tags while keeping their order
+pub(crate) fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if !tag.is_empty() && !result.contains(&tag) {
+            result.push(tag);
+        }
+    }
+    result
+}
+
+// ============================================
+// PERSISTENCE
+// ============================================
+
+pub fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// Platform default data dir: HISTORY_HUB_DATA_DIR, then the OS app data location
+fn default_store_dir() -> PathBuf {
+    if let Ok(dir) = std::env::var("HISTORY_HUB_DATA_DIR") {
+        return PathBuf::from(dir);
+    }
+
+    let home = std::env::var("HOME")
+        .or_else(|_| std::env::var("USERPROFILE"))
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| std::env::temp_dir());
+
+    if cfg!(target_os = "windows") {
+        std::env::var("APPDATA")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| home.join("AppData").join("Roaming"))
+            .join("history-hub")
+    } else if cfg!(target_os = "macos") {
+        home.join("Library").join("Application Support").join("history-hub")
+    } else {
+        std::env::var("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| home.join(".local").join("share"))
+            .join("history-hub")
+    }
+}
+
+/// Directory of the store file, where other hub-owned files live too
+pub(crate) fn store_dir() -> PathBuf {
+    STORE_DIR
+        .lock()
+        .ok()
+        .and_then(|guard| guard.clone())
+        .unwrap_or_else(default_store_dir)
+}
+
+/// Swap the directory override, returning the previous one
+#[cfg(test)]
+pub(crate) fn replace_store_dir(dir: Option<PathBuf>) -> Option<PathBuf> {
+    std::mem::replace(&mut *STORE_DIR.lock().unwrap_or_else(|e| e.into_inner()), dir)
+}
+
+fn store_file_path() -> PathBuf {
+    store_dir().join(STORE_FILE_NAME)
+}
+
+fn load_store(path: &PathBuf) -> Result<MetadataStore> {
+    if !path.exists() {
+        return Ok(MetadataStore::default());
+    }
+
+    let data = read_to_string(path)
+        .map_err(|e| io_error("Cannot read metadata store", path, e))?;
+
+    if data.trim().is_empty() {
+        return Ok(MetadataStore::default());
+    }
+
+    let mut store: MetadataStore = serde_json::from_str(&data)
+        .map_err(|e| Error::from(HubError::new(ErrorCode::ParseError, format!("Corrupted metadata store: {}", e)).path(path)))?;
+    // Bookmarks saved before canonical ids existed; uuid-less ones cannot be told apart
+    for bookmark in store.bookmarks.iter_mut().filter(|b| b.canonical_id.is_empty() && b.message_id != "unknown") {
+        bookmark.canonical_id = bookmark_canonical_id(&bookmark.session_id, &bookmark.message_id);
+    }
+    Ok(store)
+}
+
+fn save_store(path: &Path, store: &MetadataStore, overwrite: bool) -> Result<()> {
+    let json = serde_json::to_string_pretty(store)
+        .map_err(|e| internal(format!("Cannot serialize metadata store: {}", e)))?;
+    // Atomic, so a crash never leaves a half-written store
+    write_file(path, json.as_bytes(), overwrite)
+}
+
+/// Read-only access to the store
+pub fn read_store<T>(f: impl FnOnce(&MetadataStore) -> T) -> Result<T> {
+    let _guard = STORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let store = load_store(&store_file_path())?;
+    Ok(f(&store))
+}
+
+/// Load, modify and persist the store as one step
+pub fn update_store<T>(f: impl FnOnce(&mut MetadataStore) -> T) -> Result<T> {
+    let _guard = STORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let path = store_file_path();
+    let mut store = load_store(&path)?;
+    let result = f(&mut store);
+    save_store(&path, &store, true)?;
+    Ok(result)
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Point the metadata store at a directory (e.g. Electron's app.getPath('userData'))
+#[napi(catch_unwind)]
+pub fn set_store_path(dir: String) -> Result<()> {
+    let mut guard = STORE_DIR
+        .lock()
+        .map_err(|_| internal("Metadata store lock poisoned"))?;
+    *guard = Some(PathBuf::from(dir));
+    Ok(())
+}
+
+/// Get the metadata store file currently in use
+#[napi(catch_unwind)]
+pub fn get_store_path() -> String {
+    store_file_path().display().to_string()
+}
+
+/// Write bookmarks, tags, tag rules, session titles, saved searches, issue
+/// links and CI session records to path, to carry them to another machine along with
+/// the raw history. An existing file is only replaced with overwrite
+#[napi(catch_unwind)]
+pub fn export_metadata(path: String, overwrite: Option<bool>) -> Result<MetadataCounts> {
+    let store = read_store(|store| store.clone())?;
+    save_store(&PathBuf::from(path), &store, overwrite.unwrap_or(false))?;
+    Ok(store.counts())
+}
+
+/// Merge a file written by export_metadata into the store (or replace the
+/// store with it). Counts are the entries added or updated
+#[napi(catch_unwind)]
+pub fn import_metadata(path: String, options: Option<ImportMetadataOptions>) -> Result<MetadataCounts> {
+    let path = PathBuf::from(path);
+    if !path.is_file() {
+        return Err(HubError::new(ErrorCode::FileNotFound, "Metadata file not found").path(&path).into());
+    }
+    let imported = load_store(&path)?;
+    if imported.version > STORE_VERSION {
+        return Err(unsupported_format(format!("Metadata file is from a newer version ({})", imported.version)));
+    }
+    let replace = options.unwrap_or_default().replace.unwrap_or(false);
+    update_store(|store| {
+        if replace {
+            let counts = imported.counts();
+            *store = MetadataStore { version: STORE_VERSION, ..imported };
+            counts
+        } else {
+            store.merge(imported)
+        }
+    })
+}
+
+/// Bookmark a message by message_id or canonical_id (updates note/tags if
+/// it is already bookmarked). Pass the session file to record the ids of the
+/// parsed message; messages without a uuid need it or their canonical_id
+#[napi(catch_unwind)]
+pub fn add_bookmark(
+    session_id: String,
+    message_id: String,
+    note: Option<String>,
+    tags: Option<Vec<String>>,
+    file_path: Option<String>,
+) -> Result<Bookmark> {
+    let (message_id, canonical_id) = bookmark_ids(&session_id, &message_id, file_path.as_deref())?;
+    let now = now_millis();
+    update_store(|store| {
+        store.upsert_bookmark(session_id, message_id, canonical_id, note, tags.unwrap_or_default(), now)
+    })
+}
+
+/// Remove a bookmark, returns false if the message was not bookmarked
+#[napi(catch_unwind)]
+pub fn remove_bookmark(session_id: String, message_id: String) -> Result<bool> {
+    update_store(|store| store.remove_bookmark(&session_id, &message_id))
+}
+
+/// List bookmarks, newest first
+#[napi(catch_unwind)]
+pub fn list_bookmarks(filter: Option<BookmarkFilter>) -> Result<Vec<Bookmark>> {
+    let filter = filter.unwrap_or_default();
+    read_store(|store| store.list_bookmarks(&filter))
+}
+
+/// All bookmarks/notes for one session, in the order they were created
+#[napi(catch_unwind)]
+pub fn get_annotations_for_session(session_id: String) -> Result<Vec<Bookmark>> {
+    let filter = BookmarkFilter {
+        session_id: Some(session_id),
+        ..Default::default()
+    };
+    read_store(|store| {
+        let mut result = store.list_bookmarks(&filter);
+        result.sort_by_key(|b| b.created_at);
+        result
+    })
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_upsert_and_filter_bookmarks() {
+        let mut store = MetadataStore::default();
+        let upsert = |store: &mut MetadataStore, id: &str, note: Option<&str>, tags: Vec<String>, now: i64| {
+            let (message_id, canonical_id) = bookmark_ids("s1", id, None).unwrap();
+            store.upsert_bookmark("s1".into(), message_id, canonical_id, note.map(String::from), tags, now)
+        };
+        upsert(&mut store, "m1", None, vec!["Rust ".into()], 1);
+        upsert(&mut store, "m2", Some("fix for lifetimes"), vec![], 2);
+        let updated = upsert(&mut store, "m1", Some("note"), vec!["rust".into(), "todo".into()], 3);
+
+        assert_eq!(store.bookmarks.len(), 2);
+        assert_eq!(updated.created_at, 1);
+        assert_eq!(updated.tags, vec!["rust", "todo"]);
+
+        let by_tag = store.list_bookmarks(&BookmarkFilter { tag: Some("TODO".into()), ..Default::default() });
+        assert_eq!(by_tag.len(), 1);
+        assert_eq!(by_tag[0].message_id, "m1");
+
+        let by_query = store.list_bookmarks(&BookmarkFilter { query: Some("Lifetimes".into()), ..Default::default() });
+        assert_eq!(by_query[0].message_id, "m2");
+
+        // The canonical id finds the same bookmark
+        let canonical = by_tag[0].canonical_id.clone();
+        assert_eq!(canonical, canonical_message_id(Some("s1"), Some("m1"), "", None));
+        assert_eq!(upsert(&mut store, &canonical, None, vec![], 4).message_id, "m1");
+
+        assert!(store.remove_bookmark("s1", "m2"));
+        assert!(!store.remove_bookmark("s1", "m2"));
+        assert!(store.remove_bookmark("s1", &canonical));
+    }
+
+    #[test]
+    fn test_bookmark_ids_for_messages_without_uuid() {
+        let tmp = TempDir::new("store-bookmark-ids");
+        let path = tmp.join("s1.jsonl");
+        std::fs::write(&path, [
+            r#"{"type":"user","sessionId":"s1","timestamp":"2026-01-01T00:00:00Z","message":{"role":"user","content":"first"}}"#,
+            r#"{"type":"user","sessionId":"s1","timestamp":"2026-01-01T00:00:01Z","message":{"role":"user","content":"second"}}"#,
+        ].join("\n")).unwrap();
+        let file_path = path.to_string_lossy().to_string();
+        let messages = crate::parse_claude_session(file_path.clone(), None).unwrap();
+        let (first, second) = (&messages[0].canonical_id, &messages[1].canonical_id);
+        assert_ne!(first, second);
+
+        // Each resolves to its own message instead of a shared "unknown"
+        assert_eq!(bookmark_ids("s1", first, Some(&file_path)).unwrap(), (first.clone(), first.clone()));
+        assert_eq!(bookmark_ids("s1", second, Some(&file_path)).unwrap(), (second.clone(), second.clone()));
+        assert_eq!(bookmark_ids("s1", second, None).unwrap(), (second.clone(), second.clone()));
+        assert!(bookmark_ids("s1", "unknown", None).is_err());
+        assert!(bookmark_ids("s1", "nope", Some(&file_path)).unwrap_err().reason.starts_with("NOT_FOUND"));
+
+        let mut store = MetadataStore::default();
+        for id in [first, second] {
+            let (message_id, canonical_id) = bookmark_ids("s1", id, Some(&file_path)).unwrap();
+            store.upsert_bookmark("s1".into(), message_id, canonical_id, None, vec![], 1);
+        }
+        assert_eq!(store.bookmarks.len(), 2);
+    }
+
+    #[test]
+    fn test_merge_stores() {
+        let mut local = MetadataStore::default();
+        local.upsert_bookmark("s1".into(), "m1".into(), bookmark_canonical_id("s1", "m1"), Some("old".into()), vec![], 10);
+        local.upsert_bookmark("s1".into(), "m2".into(), bookmark_canonical_id("s1", "m2"), Some("mine".into()), vec![], 30);
+
+        let mut other = MetadataStore::default();
+        other.upsert_bookmark("s1".into(), "m1".into(), bookmark_canonical_id("s1", "m1"), Some("new".into()), vec![], 20);
+        other.upsert_bookmark("s1".into(), "m2".into(), bookmark_canonical_id("s1", "m2"), Some("stale".into()), vec![], 5);
+        other.upsert_bookmark("s2".into(), "m3".into(), bookmark_canonical_id("s2", "m3"), None, vec![], 5);
+        other.set_session_tags("s2", vec!["infra".into()], 5);
+        local.set_session_title("s1", Some("Mine".into()), 10);
+        other.set_session_title("s1", Some("Theirs".into()), 20);
+
+        let counts = local.merge(other);
+        let expected = MetadataCounts { bookmarks: 2, session_tags: 1, saved_searches: 0, ci_sessions: 0, issue_links: 0, tag_rules: 0, session_titles: 1 };
+        assert_eq!(counts, expected);
+        assert_eq!(local.session_title("s1"), Some("Theirs"));
+        let notes: Vec<Option<&str>> = local.bookmarks.iter().map(|b| b.note.as_deref()).collect();
+        assert_eq!(notes, vec![Some("new"), Some("mine"), None]);
+        assert_eq!(local.find_sessions_by_tag("infra"), vec!["s2"]);
+    }
+}
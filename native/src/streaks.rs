@@ -0,0 +1,119 @@
+// ============================================
+// STREAKS
+// Consecutive local days with any session activity, for the
+// stats panel
+// ============================================
+
+use std::collections::BTreeSet;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::time::resolve_timezone;
+use crate::usage::for_each_usage;
+
+/// Result of get_streaks; dates are local YYYY-MM-DD
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreakStats {
+    pub timezone: String,
+    /// Days in a row up to today, or up to yesterday while today is still empty
+    pub current_streak: u32,
+    pub longest_streak: u32,
+    pub longest_streak_start: Option<String>,
+    pub longest_streak_end: Option<String>,
+    pub active_days: u32,
+    pub first_active_date: Option<String>,
+    pub last_active_date: Option<String>,
+}
+
+fn date_string(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+fn streaks(root_dir: &str, tz: Tz, now: DateTime<Utc>) -> Result<StreakStats> {
+    let mut days: BTreeSet<NaiveDate> = BTreeSet::new();
+    for_each_usage(root_dir, None, now, |event| {
+        days.insert(event.timestamp.with_timezone(&tz).date_naive());
+    })?;
+
+    // Runs of consecutive days as (start, end, length)
+    let mut runs: Vec<(NaiveDate, NaiveDate, u32)> = Vec::new();
+    for &day in &days {
+        match runs.last_mut() {
+            Some((_, end, len)) if end.succ_opt() == Some(day) => {
+                *end = day;
+                *len += 1;
+            }
+            _ => runs.push((day, day, 1)),
+        }
+    }
+
+    let today = now.with_timezone(&tz).date_naive();
+    let current_streak = match runs.last() {
+        Some(&(_, end, len)) if end == today || end.succ_opt() == Some(today) => len,
+        _ => 0,
+    };
+    // Earliest run wins ties
+    let longest = runs.iter().fold(None, |best: Option<&(NaiveDate, NaiveDate, u32)>, run| match best {
+        Some(b) if b.2 >= run.2 => Some(b),
+        _ => Some(run),
+    });
+
+    Ok(StreakStats {
+        timezone: tz.name().to_string(),
+        current_streak,
+        longest_streak: longest.map_or(0, |run| run.2),
+        longest_streak_start: longest.map(|run| date_string(run.0)),
+        longest_streak_end: longest.map(|run| date_string(run.1)),
+        active_days: days.len() as u32,
+        first_active_date: days.first().copied().map(date_string),
+        last_active_date: days.last().copied().map(date_string),
+    })
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Current and longest daily usage streaks under root_dir, days in timezone (default UTC)
+#[napi(catch_unwind)]
+pub fn get_streaks(root_dir: String, timezone: Option<String>) -> Result<StreakStats> {
+    streaks(&root_dir, resolve_timezone(timezone.as_deref())?, Utc::now())
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+    use crate::time::parse_utc;
+
+    #[test]
+    fn test_current_and_longest_streak() {
+        let root = TempDir::new("streaks-test");
+        std::fs::create_dir_all(root.join("proj")).unwrap();
+        let days = ["2024-03-01", "2024-03-02", "2024-03-03", "2024-03-05", "2024-03-08", "2024-03-09"];
+        let lines: Vec<String> = days
+            .iter()
+            .map(|d| format!(r#"{{"type":"user","sessionId":"s1","timestamp":"{}T12:00:00Z","message":{{"role":"user","content":"hi"}}}}"#, d))
+            .collect();
+        std::fs::write(root.join("proj/s1.jsonl"), lines.join("\n")).unwrap();
+        let root_dir = root.to_string_lossy().to_string();
+
+        // Nothing yet on the 10th: the streak from the 8th is still alive
+        let stats = streaks(&root_dir, Tz::UTC, parse_utc("2024-03-10T08:00:00Z").unwrap()).unwrap();
+        assert_eq!((stats.current_streak, stats.longest_streak, stats.active_days), (2, 3, 6));
+        assert_eq!(stats.longest_streak_start.as_deref(), Some("2024-03-01"));
+        assert_eq!(stats.longest_streak_end.as_deref(), Some("2024-03-03"));
+        assert_eq!(stats.last_active_date.as_deref(), Some("2024-03-09"));
+
+        let broken = streaks(&root_dir, Tz::UTC, parse_utc("2024-03-11T08:00:00Z").unwrap()).unwrap();
+        assert_eq!(broken.current_streak, 0);
+    }
+}
@@ -0,0 +1,353 @@
+// ============================================
+// TAG RULES
+// User-defined rules (content, tool and project patterns -> tag) kept
+// in the sidecar store and evaluated with the built-in auto tags while
+// a session is read. list_sessions re-tags the sessions of each page
+// whose file or rules changed, so tag: searches and smart folders pick
+// them up without curation
+// ============================================
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use rayon::prelude::*;
+use regex::{Regex, RegexBuilder};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{invalid_argument, parse_error};
+use crate::paths::metadata;
+use crate::similarity::fnv1a;
+use crate::store::{normalize_tags, now_millis, read_store, update_store, MetadataStore};
+use crate::tags::AutoTagTally;
+use crate::{config, for_each_entry, resolve_session_files, tool_result_text, ContentItem, RawLogEntry};
+
+/// Adds `tag` to sessions matching every pattern that is set, e.g.
+/// { tag: "db-migration", content: "migrat(e|ion)", tool: "^Bash$" }
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+pub struct TagRule {
+    /// Unique; saving a rule with the same name replaces it
+    pub name: String,
+    pub tag: String,
+    /// Case-insensitive regex over message text, tool inputs and tool output
+    #[serde(default)]
+    pub content: Option<String>,
+    /// Case-insensitive regex over the names of the tools called
+    #[serde(default)]
+    pub tool: Option<String>,
+    /// Case-insensitive regex over the project folder name or the cwd
+    #[serde(default)]
+    pub project: Option<String>,
+    /// Set by save_tag_rule
+    #[serde(default)]
+    pub updated_at: Option<i64>,
+}
+
+/// Sessions given one tag by apply_tag_rules
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagCount {
+    pub tag: String,
+    pub sessions: u32,
+}
+
+/// Result of apply_tag_rules
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagRuleRun {
+    pub sessions_scanned: u32,
+    /// Sessions that got at least one automatic tag
+    pub sessions_tagged: u32,
+    /// Sorted by tag
+    pub tags: Vec<TagCount>,
+}
+
+struct CompiledRule {
+    tag: String,
+    content: Option<Regex>,
+    tool: Option<Regex>,
+    project: Option<Regex>,
+}
+
+pub(crate) struct TagRules {
+    rules: Vec<CompiledRule>,
+    /// Hash of the rule definitions, part of the tags' source
+    fingerprint: u64,
+}
+
+/// Rules a session matches so far, entry by entry
+pub(crate) struct RuleMatcher<'a> {
+    rules: &'a TagRules,
+    content: Vec<bool>,
+    tool: Vec<bool>,
+    cwd: Option<String>,
+}
+
+fn compile_pattern(rule: &TagRule, field: &str, pattern: &Option<String>) -> Result<Option<Regex>> {
+    pattern
+        .as_deref()
+        .map(|p| {
+            RegexBuilder::new(p)
+                .case_insensitive(true)
+                .build()
+                .map_err(|e| parse_error(format!("Invalid {} pattern for tag rule {}: {}", field, rule.name, e)))
+        })
+        .transpose()
+}
+
+impl TagRules {
+    pub(crate) fn compile(rules: &[TagRule]) -> Result<TagRules> {
+        let compiled = rules
+            .iter()
+            .map(|rule| {
+                let tag = normalize_tags(vec![rule.tag.clone()]).pop();
+                let Some(tag) = tag else { return Err(invalid_argument(format!("Tag rule {} has no tag", rule.name))) };
+                if rule.content.is_none() && rule.tool.is_none() && rule.project.is_none() {
+                    return Err(invalid_argument(format!("Tag rule {} has no pattern", rule.name)));
+                }
+                Ok(CompiledRule {
+                    tag,
+                    content: compile_pattern(rule, "content", &rule.content)?,
+                    tool: compile_pattern(rule, "tool", &rule.tool)?,
+                    project: compile_pattern(rule, "project", &rule.project)?,
+                })
+            })
+            .collect::<Result<_>>()?;
+        let definitions = serde_json::to_string(rules).unwrap_or_default();
+        Ok(TagRules { rules: compiled, fingerprint: fnv1a(definitions.as_bytes()) })
+    }
+
+    pub(crate) fn matcher(&self) -> RuleMatcher<'_> {
+        RuleMatcher { rules: self, content: vec![false; self.rules.len()], tool: vec![false; self.rules.len()], cwd: None }
+    }
+
+    /// Size and mtime of a session file with the rules' fingerprint; tags
+    /// stored with another source are stale
+    pub(crate) fn source_of(&self, path: &Path) -> Option<String> {
+        let metadata = metadata(path).ok()?;
+        let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?.as_millis();
+        Some(format!("{}:{}:{:016x}", metadata.len(), modified, self.fingerprint))
+    }
+}
+
+impl RuleMatcher<'_> {
+    /// Whether a content pattern is still unmatched, so text is worth building
+    fn wants_content(&self) -> bool {
+        self.rules.rules.iter().zip(&self.content).any(|(rule, hit)| rule.content.is_some() && !hit)
+    }
+
+    fn match_content(&mut self, text: &str) {
+        for (rule, hit) in self.rules.rules.iter().zip(self.content.iter_mut()) {
+            if !*hit && rule.content.as_ref().is_some_and(|re| re.is_match(text)) {
+                *hit = true;
+            }
+        }
+    }
+
+    pub(crate) fn observe(&mut self, entry: &RawLogEntry) {
+        if self.cwd.is_none() {
+            self.cwd = entry.cwd.clone();
+        }
+        let Some(message) = &entry.message else { return };
+        for item in &message.content {
+            match item {
+                ContentItem::Text { text } => self.match_content(text),
+                ContentItem::ToolUse { name, input, .. } => {
+                    for (rule, hit) in self.rules.rules.iter().zip(self.tool.iter_mut()) {
+                        if !*hit && rule.tool.as_ref().is_some_and(|re| re.is_match(name)) {
+                            *hit = true;
+                        }
+                    }
+                    if self.wants_content() {
+                        self.match_content(&input.to_string());
+                    }
+                }
+                ContentItem::ToolResult { content, .. } if self.wants_content() => self.match_content(&tool_result_text(content)),
+                _ => {}
+            }
+        }
+    }
+
+    /// Tags of the rules the session matched, in rule order. project is the
+    /// name of the folder holding the session file
+    pub(crate) fn finish(self, project: &str) -> Vec<String> {
+        let cwd = self.cwd.as_deref().unwrap_or("");
+        let mut tags: Vec<String> = Vec::new();
+        for (i, rule) in self.rules.rules.iter().enumerate() {
+            let matched = rule.project.as_ref().is_none_or(|re| re.is_match(project) || re.is_match(cwd))
+                && (rule.tool.is_none() || self.tool[i])
+                && (rule.content.is_none() || self.content[i]);
+            if matched && !tags.contains(&rule.tag) {
+                tags.push(rule.tag.clone());
+            }
+        }
+        tags
+    }
+}
+
+impl MetadataStore {
+    /// Create or replace the rule with the same name
+    pub fn save_tag_rule(&mut self, rule: TagRule, now: i64) -> TagRule {
+        let rule = TagRule { updated_at: Some(now), ..rule };
+        match self.tag_rules.iter_mut().find(|r| r.name == rule.name) {
+            Some(existing) => *existing = rule.clone(),
+            None => self.tag_rules.push(rule.clone()),
+        }
+        rule
+    }
+
+    /// Returns false if there was no rule with that name
+    pub fn delete_tag_rule(&mut self, name: &str) -> bool {
+        let before = self.tag_rules.len();
+        self.tag_rules.retain(|r| r.name != name);
+        self.tag_rules.len() != before
+    }
+}
+
+/// Session id and every automatic tag (built-in and from rules) of a session file
+pub(crate) fn session_auto_tags(path: &Path, rules: &TagRules) -> Result<(Option<String>, Vec<String>)> {
+    let mut builtin = AutoTagTally::default();
+    let mut matcher = rules.matcher();
+    let mut session_id: Option<String> = None;
+    for_each_entry(&path.display().to_string(), |entry| {
+        if session_id.is_none() {
+            session_id = entry.session_id.clone();
+        }
+        builtin.observe(&entry);
+        matcher.observe(&entry);
+    })?;
+    let project = path.parent().and_then(|p| p.file_name()).map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let mut tags = builtin.finish();
+    tags.extend(matcher.finish(&project));
+    Ok((session_id, normalize_tags(tags)))
+}
+
+/// The store's rules, compiled
+pub(crate) fn stored_rules() -> Result<TagRules> {
+    TagRules::compile(&read_store(|store| store.tag_rules.clone())?)
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Add or replace a tag rule; its patterns are checked first
+#[napi(catch_unwind)]
+pub fn save_tag_rule(rule: TagRule) -> Result<TagRule> {
+    let name = rule.name.trim().to_string();
+    if name.is_empty() {
+        return Err(invalid_argument("Tag rule name is empty"));
+    }
+    TagRules::compile(std::slice::from_ref(&rule))?;
+    let now = now_millis();
+    update_store(|store| store.save_tag_rule(TagRule { name, ..rule }, now))
+}
+
+/// Delete a tag rule. Returns false if it did not exist; tags it added stay
+/// until the sessions are tagged again
+#[napi(catch_unwind)]
+pub fn delete_tag_rule(name: String) -> Result<bool> {
+    update_store(|store| store.delete_tag_rule(&name))
+}
+
+/// Every tag rule, in creation order
+#[napi(catch_unwind)]
+pub fn list_tag_rules() -> Result<Vec<TagRule>> {
+    read_store(|store| store.tag_rules.clone())
+}
+
+/// Recompute the automatic tags (built-in and from the tag rules) of a
+/// session file or of every session under a directory, and store them
+#[napi(catch_unwind)]
+pub fn apply_tag_rules(file_path_or_root: String) -> Result<TagRuleRun> {
+    let rules = stored_rules()?;
+    let files = resolve_session_files(&file_path_or_root)?;
+    // Unreadable files and files without a session id are left as they are
+    let tagged: Vec<(String, Vec<String>, Option<String>)> = config::install(|| {
+        files
+            .par_iter()
+            .filter_map(|path| match session_auto_tags(path, &rules) {
+                Ok((Some(session_id), tags)) => Some((session_id, tags, rules.source_of(path))),
+                _ => None,
+            })
+            .collect()
+    });
+
+    let mut counts: BTreeMap<String, u32> = BTreeMap::new();
+    for tag in tagged.iter().flat_map(|(_, tags, _)| tags) {
+        *counts.entry(tag.clone()).or_default() += 1;
+    }
+    let sessions_tagged = tagged.iter().filter(|(_, tags, _)| !tags.is_empty()).count() as u32;
+    let now = now_millis();
+    update_store(|store| {
+        for (session_id, tags, source) in tagged {
+            store.set_auto_tags(&session_id, tags, source, now);
+        }
+    })?;
+
+    Ok(TagRuleRun {
+        sessions_scanned: files.len() as u32,
+        sessions_tagged,
+        tags: counts.into_iter().map(|(tag, sessions)| TagCount { tag, sessions }).collect(),
+    })
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    fn rule(name: &str, tag: &str, content: Option<&str>, tool: Option<&str>, project: Option<&str>) -> TagRule {
+        TagRule {
+            name: name.into(),
+            tag: tag.into(),
+            content: content.map(String::from),
+            tool: tool.map(String::from),
+            project: project.map(String::from),
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn test_tag_rules() {
+        let tmp = TempDir::new("tag-rules");
+        let dir = tmp.join("-work-api");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("s.jsonl");
+        let lines = [
+            r#"{"type":"user","sessionId":"s","cwd":"/work/api","message":{"role":"user","content":"Prod is down, users get 502s"}}"#,
+            r#"{"type":"assistant","sessionId":"s","message":{"role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Bash","input":{"command":"npx prisma migrate deploy"}}]}}"#,
+        ];
+        std::fs::write(&path, lines.join("\n")).unwrap();
+
+        let rules = TagRules::compile(&[
+            rule("migrations", "DB-Migration", Some(r"migrate|migration"), Some("^bash$"), None),
+            rule("incidents", "incident", Some(r"\b(prod(uction)? is down|outage|5\d\d)"), None, None),
+            rule("web only", "frontend", None, None, Some("web")),
+            rule("edits", "edits", None, Some("^Edit$"), None),
+        ])
+        .unwrap();
+        let (session_id, tags) = session_auto_tags(&path, &rules).unwrap();
+        assert_eq!(session_id.as_deref(), Some("s"));
+        assert_eq!(tags, vec!["db-migration", "incident"]);
+
+        assert!(TagRules::compile(&[rule("empty", "x", None, None, None)]).is_err());
+        assert!(TagRules::compile(&[rule("bad", "x", Some("("), None, None)]).is_err());
+
+        let mut store = MetadataStore::default();
+        store.save_tag_rule(rule("incidents", "incident", Some("outage"), None, None), 1);
+        let saved = store.save_tag_rule(rule("incidents", "incident", Some("down"), None, None), 2);
+        assert_eq!((store.tag_rules.len(), saved.updated_at), (1, Some(2)));
+        assert!(store.delete_tag_rule("incidents"));
+        assert!(!store.delete_tag_rule("incidents"));
+    }
+}
@@ -0,0 +1,256 @@
+// ============================================
+// SESSION TAGS
+// Manual labels persisted in the sidecar store plus automatic
+// tags derived from session content
+// ============================================
+
+use std::path::Path;
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{ErrorCode, HubError};
+use crate::store::{normalize_tags, now_millis, read_store, update_store, MetadataStore};
+use crate::tag_rules::{session_auto_tags, stored_rules};
+use crate::{ContentItem, RawLogEntry};
+
+/// Tags attached to one session
+#[napi(object)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+pub struct SessionTags {
+    pub session_id: String,
+    /// Tags set by the user
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Tags derived from content and the tag rules
+    #[serde(default)]
+    pub auto_tags: Vec<String>,
+    /// Size, mtime and tag rules auto_tags were computed from; listing
+    /// the session recomputes them once any of these changes
+    #[serde(default)]
+    pub auto_tags_source: Option<String>,
+    pub updated_at: i64,
+}
+
+impl MetadataStore {
+    fn session_tags_entry(&mut self, session_id: &str, now: i64) -> &mut SessionTags {
+        let index = match self.session_tags.iter().position(|t| t.session_id == session_id) {
+            Some(index) => index,
+            None => {
+                self.session_tags.push(SessionTags {
+                    session_id: session_id.to_string(),
+                    tags: Vec::new(),
+                    auto_tags: Vec::new(),
+                    auto_tags_source: None,
+                    updated_at: now,
+                });
+                self.session_tags.len() - 1
+            }
+        };
+        &mut self.session_tags[index]
+    }
+
+    /// Replace the manual tags of a session
+    pub fn set_session_tags(&mut self, session_id: &str, tags: Vec<String>, now: i64) -> SessionTags {
+        let entry = self.session_tags_entry(session_id, now);
+        entry.tags = normalize_tags(tags);
+        entry.updated_at = now;
+        entry.clone()
+    }
+
+    /// Replace the automatic tags of a session, with what they were computed from
+    pub fn set_auto_tags(&mut self, session_id: &str, tags: Vec<String>, source: Option<String>, now: i64) -> SessionTags {
+        let entry = self.session_tags_entry(session_id, now);
+        entry.auto_tags = normalize_tags(tags);
+        entry.auto_tags_source = source;
+        entry.updated_at = now;
+        entry.clone()
+    }
+
+    /// Session ids carrying the tag (manual or automatic)
+    pub fn find_sessions_by_tag(&self, tag: &str) -> Vec<String> {
+        let tag = tag.trim().to_lowercase();
+        self.session_tags
+            .iter()
+            .filter(|t| t.tags.contains(&tag) || t.auto_tags.contains(&tag))
+            .map(|t| t.session_id.clone())
+            .collect()
+    }
+}
+
+// ============================================
+// AUTOMATIC TAG DETECTION
+// ============================================
+
+/// Map a code fence language or file extension to a canonical language tag
+fn language_for(token: &str) -> Option<&'static str> {
+    let lang = match token.to_lowercase().as_str() {
+        "rs" | "rust" => "rust",
+        "py" | "python" => "python",
+        "ts" | "tsx" | "typescript" => "typescript",
+        "js" | "jsx" | "mjs" | "cjs" | "javascript" => "javascript",
+        "go" | "golang" => "go",
+        "java" => "java",
+        "kt" | "kotlin" => "kotlin",
+        "swift" => "swift",
+        "rb" | "ruby" => "ruby",
+        "php" => "php",
+        "c" | "h" => "c",
+        "cpp" | "cc" | "cxx" | "hpp" | "c++" => "cpp",
+        "cs" | "csharp" => "csharp",
+        "sh" | "bash" | "zsh" | "shell" => "shell",
+        "sql" => "sql",
+        "html" | "htm" => "html",
+        "css" | "scss" | "sass" => "css",
+        _ => return None,
+    };
+    Some(lang)
+}
+
+/// Languages used in ```lang code fences
+fn fence_languages(text: &str) -> Vec<&'static str> {
+    text.lines()
+        .filter_map(|line| line.trim_start().strip_prefix("```"))
+        .filter_map(|rest| rest.split_whitespace().next())
+        .filter_map(language_for)
+        .collect()
+}
+
+/// Language of a file path passed to a tool (Read/Edit/Write...)
+fn path_language(input: &serde_json::Value) -> Option<&'static str> {
+    ["file_path", "path", "notebook_path"]
+        .iter()
+        .filter_map(|key| input.get(key).and_then(|v| v.as_str()))
+        .filter_map(|p| Path::new(p).extension().and_then(|e| e.to_str()))
+        .find_map(language_for)
+}
+
+/// Built-in automatic tags of a session, entry by entry
+#[derive(Default)]
+pub(crate) struct AutoTagTally {
+    tags: Vec<String>,
+}
+
+impl AutoTagTally {
+    fn push(&mut self, tag: &str) {
+        if !self.tags.iter().any(|t| t == tag) {
+            self.tags.push(tag.to_string());
+        }
+    }
+
+    pub(crate) fn observe(&mut self, entry: &RawLogEntry) {
+        if entry.is_sidechain == Some(true) {
+            self.push("subagents");
+        }
+
+        let Some(message) = &entry.message else { return };
+        for item in &message.content {
+            match item {
+                ContentItem::Text { text } => fence_languages(text).into_iter().for_each(|lang| self.push(lang)),
+                ContentItem::ToolUse { input, .. } => {
+                    if let Some(lang) = path_language(input) {
+                        self.push(lang);
+                    }
+                }
+                ContentItem::ToolResult { is_error: Some(true), .. } => self.push("has-errors"),
+                ContentItem::Image { .. } => self.push("has-images"),
+                ContentItem::Thinking { .. } => self.push("has-thinking"),
+                _ => {}
+            }
+        }
+    }
+
+    pub(crate) fn finish(self) -> Vec<String> {
+        self.tags
+    }
+}
+
+/// Derive tags from session entries: languages plus a few content flags
+pub fn derive_auto_tags(entries: &[RawLogEntry]) -> Vec<String> {
+    let mut tally = AutoTagTally::default();
+    entries.iter().for_each(|entry| tally.observe(entry));
+    tally.finish()
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Replace the user-defined tags of a session
+#[napi(catch_unwind)]
+pub fn set_session_tags(session_id: String, tags: Vec<String>) -> Result<SessionTags> {
+    let now = now_millis();
+    update_store(|store| store.set_session_tags(&session_id, tags, now))
+}
+
+/// Get manual and automatic tags of a session (empty if never tagged)
+#[napi(catch_unwind)]
+pub fn get_session_tags(session_id: String) -> Result<SessionTags> {
+    read_store(|store| {
+        store
+            .session_tags
+            .iter()
+            .find(|t| t.session_id == session_id)
+            .cloned()
+            .unwrap_or(SessionTags {
+                session_id: session_id.clone(),
+                tags: Vec::new(),
+                auto_tags: Vec::new(),
+                auto_tags_source: None,
+                updated_at: 0,
+            })
+    })
+}
+
+/// Find session ids carrying a tag (manual or automatic)
+#[napi(catch_unwind)]
+pub fn find_sessions_by_tag(tag: String) -> Result<Vec<String>> {
+    read_store(|store| store.find_sessions_by_tag(&tag))
+}
+
+/// Detect automatic tags (built-in and from tag rules) for a session file
+/// and persist them in the store
+#[napi(catch_unwind)]
+pub fn detect_session_tags(file_path: String) -> Result<SessionTags> {
+    let path = Path::new(&file_path);
+    let rules = stored_rules()?;
+    let (session_id, tags) = session_auto_tags(path, &rules)?;
+    let session_id = session_id
+        .ok_or_else(|| Error::from(HubError::new(ErrorCode::InvalidArgument, "Session file has no sessionId").path(&file_path)))?;
+    let source = rules.source_of(path);
+    let now = now_millis();
+
+    update_store(|store| store.set_auto_tags(&session_id, tags, source, now))
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_jsonl_line;
+
+    #[test]
+    fn test_derive_auto_tags() {
+        let lines = [
+            r#"{"type":"user","sessionId":"s","message":{"role":"user","content":"Fix this:\n```py\nprint(1)\n```"}}"#,
+            r#"{"type":"assistant","sessionId":"s","message":{"role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Edit","input":{"file_path":"/src/main.rs"}}]}}"#,
+            r#"{"type":"user","sessionId":"s","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"boom","is_error":true}]}}"#,
+        ];
+        let entries: Vec<RawLogEntry> = lines.iter().map(|l| parse_jsonl_line(l).unwrap()).collect();
+
+        assert_eq!(derive_auto_tags(&entries), vec!["python", "rust", "has-errors"]);
+
+        let mut store = MetadataStore::default();
+        store.set_auto_tags("s", derive_auto_tags(&entries), None, 1);
+        store.set_session_tags("s", vec!["Urgent".into()], 2);
+        assert_eq!(store.find_sessions_by_tag("rust"), vec!["s"]);
+        assert_eq!(store.find_sessions_by_tag("urgent"), vec!["s"]);
+        assert!(store.find_sessions_by_tag("go").is_empty());
+    }
+}
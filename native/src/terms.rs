@@ -0,0 +1,167 @@
+// ============================================
+// TERM EXTRACTION
+// Tokenizing with stopword filtering for the keyword based
+// analytics (topics, word clouds)
+// ============================================
+
+use std::collections::{HashMap, HashSet};
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use schemars::JsonSchema;
+
+use crate::time::{resolve_timezone, TimeRange};
+use crate::title::{is_substantive_prompt, strip_code_blocks, strip_tag_blocks};
+use crate::{collect_session_files, extract_text_content, for_each_entry};
+
+/// Common English words plus conversational filler that carries no topic
+const STOPWORDS: &[&str] = &[
+    "a", "about", "above", "after", "again", "all", "also", "am", "an", "and", "any", "are", "as",
+    "at", "be", "because", "been", "before", "being", "below", "between", "both", "but", "by",
+    "can", "could", "did", "do", "does", "doing", "don", "done", "down", "during", "each", "else",
+    "etc", "even", "every", "few", "for", "from", "further", "get", "getting", "go", "going",
+    "got", "had", "has", "have", "having", "he", "her", "here", "hers", "him", "his", "how", "i",
+    "if", "in", "into", "is", "isn", "it", "its", "itself", "just", "know", "let", "like", "make",
+    "me", "might", "more", "most", "much", "must", "my", "need", "needs", "no", "nor", "not",
+    "now", "of", "off", "ok", "okay", "on", "once", "one", "only", "or", "other", "our", "out",
+    "over", "own", "please", "same", "see", "she", "should", "so", "some", "still", "such",
+    "sure", "than", "thank", "thanks", "that", "the", "their", "them", "then", "there", "these",
+    "they", "thing", "this", "those", "through", "to", "too", "try", "two", "under", "until",
+    "up", "us", "use", "using", "very", "want", "was", "way", "we", "well", "were", "what",
+    "when", "where", "which", "while", "who", "why", "will", "with", "without", "would", "yes",
+    "yet", "you", "your", "yours",
+];
+
+const MIN_TERM_CHARS: usize = 3;
+const MAX_TERM_CHARS: usize = 40;
+const DEFAULT_MAX_TERMS: u32 = 100;
+const MAX_NGRAM_SIZE: u32 = 3;
+
+/// Options for get_term_frequencies
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct TermFrequencyOptions {
+    /// Only prompts at or after this ISO timestamp or YYYY-MM-DD date
+    pub from: Option<String>,
+    /// Only prompts before this ISO timestamp or YYYY-MM-DD date
+    pub to: Option<String>,
+    /// IANA timezone for date-only bounds (default UTC)
+    pub timezone: Option<String>,
+    /// Words per term, 1-3 (default 1)
+    pub ngram_size: Option<u32>,
+    /// Number of terms returned (default 100)
+    pub max_terms: Option<u32>,
+    /// Drop terms seen fewer times than this (default 1)
+    pub min_count: Option<u32>,
+}
+
+/// How often a term (or n-gram) appears in user prompts
+#[napi(object)]
+#[derive(Debug, Clone, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+pub struct TermFrequency {
+    pub term: String,
+    pub count: u32,
+    /// Number of distinct sessions using the term
+    pub session_count: u32,
+}
+
+pub(crate) fn is_stopword(word: &str) -> bool {
+    STOPWORDS.binary_search(&word).is_ok()
+}
+
+/// Lowercased content words: stopwords, numbers and very short/long tokens removed
+pub(crate) fn terms(text: &str) -> Vec<String> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+        .map(|w| w.trim_matches(|c| c == '-' || c == '_'))
+        .filter(|w| {
+            let len = w.chars().count();
+            (MIN_TERM_CHARS..=MAX_TERM_CHARS).contains(&len)
+        })
+        .filter(|w| w.chars().any(|c| c.is_alphabetic()))
+        .map(|w| w.to_lowercase())
+        .filter(|w| !is_stopword(w))
+        .collect()
+}
+
+/// Word n-grams over the filtered terms of a text
+pub(crate) fn ngrams(text: &str, size: usize) -> Vec<String> {
+    let words = terms(text);
+    if size <= 1 {
+        return words;
+    }
+    words.windows(size).map(|w| w.join(" ")).collect()
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Top terms/n-grams of user prompts under root_dir (powers the word cloud)
+#[napi(catch_unwind)]
+pub fn get_term_frequencies(
+    root_dir: String,
+    options: Option<TermFrequencyOptions>,
+) -> Result<Vec<TermFrequency>> {
+    let options = options.unwrap_or_default();
+    let size = options.ngram_size.unwrap_or(1).clamp(1, MAX_NGRAM_SIZE) as usize;
+    let max_terms = options.max_terms.unwrap_or(DEFAULT_MAX_TERMS) as usize;
+    let min_count = options.min_count.unwrap_or(1);
+    let tz = resolve_timezone(options.timezone.as_deref())?;
+    let range = TimeRange::new(options.from.as_deref(), options.to.as_deref(), tz)?;
+
+    let mut counts: HashMap<String, (u32, HashSet<String>)> = HashMap::new();
+
+    for path in collect_session_files(&root_dir)? {
+        let file_path = path.display().to_string();
+        let _ = for_each_entry(&file_path, |entry| {
+            if !is_substantive_prompt(&entry) || !range.contains(entry.timestamp.as_deref()) {
+                return;
+            }
+            let Some(message) = &entry.message else { return };
+            let text = strip_tag_blocks(&strip_code_blocks(&extract_text_content(&message.content)));
+            let session_id = entry.session_id.clone().unwrap_or_else(|| file_path.clone());
+
+            for term in ngrams(&text, size) {
+                let (count, sessions) = counts.entry(term).or_default();
+                *count += 1;
+                if !sessions.contains(&session_id) {
+                    sessions.insert(session_id.clone());
+                }
+            }
+        });
+    }
+
+    let mut result: Vec<TermFrequency> = counts
+        .into_iter()
+        .filter(|(_, (count, _))| *count >= min_count)
+        .map(|(term, (count, sessions))| TermFrequency {
+            term,
+            count,
+            session_count: sessions.len() as u32,
+        })
+        .collect();
+
+    result.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.term.cmp(&b.term)));
+    result.truncate(max_terms);
+    Ok(result)
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stopwords_sorted_and_filtered() {
+        assert!(STOPWORDS.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(
+            terms("Can you please fix the React hydration error in 2024?"),
+            vec!["fix", "react", "hydration", "error"]
+        );
+        assert_eq!(ngrams("fix the react hydration error", 2), vec!["fix react", "react hydration", "hydration error"]);
+    }
+}
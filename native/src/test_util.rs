@@ -0,0 +1,77 @@
+// ============================================
+// TEST HELPERS
+// Scratch directories for unit tests: unique per test and process, and
+// removed when the guard drops, even when an assertion fails first. The
+// metadata store directory is process-wide, so tests that use it take
+// turns through StoreDir
+// ============================================
+
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+use crate::store::replace_store_dir;
+
+static NEXT_DIR_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Held by the test currently pointing the store somewhere
+static STORE_TEST_LOCK: Mutex<()> = Mutex::new(());
+
+/// An empty directory under the system temp dir, deleted on drop
+pub(crate) struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    /// name says which test owns it, e.g. "conflicts"
+    pub(crate) fn new(name: &str) -> TempDir {
+        let id = NEXT_DIR_ID.fetch_add(1, Ordering::Relaxed);
+        let path = std::env::temp_dir().join(format!("history-hub-{}-{}-{}", name, std::process::id(), id));
+        // Left over by an earlier run that reused the pid
+        std::fs::remove_dir_all(&path).ok();
+        std::fs::create_dir_all(&path).unwrap();
+        TempDir { path }
+    }
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl AsRef<Path> for TempDir {
+    fn as_ref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        std::fs::remove_dir_all(&self.path).ok();
+    }
+}
+
+/// The metadata store pointed at a test's directory until the guard drops,
+/// when the previous directory is restored
+pub(crate) struct StoreDir {
+    previous: Option<PathBuf>,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl StoreDir {
+    pub(crate) fn set(dir: &Path) -> StoreDir {
+        // A test that failed while holding the lock has already restored its store
+        let lock = STORE_TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        StoreDir { previous: replace_store_dir(Some(dir.to_path_buf())), _lock: lock }
+    }
+}
+
+impl Drop for StoreDir {
+    fn drop(&mut self) {
+        replace_store_dir(self.previous.take());
+    }
+}
@@ -0,0 +1,272 @@
+// ============================================
+// TIME AND TIMEZONES
+// Claude writes UTC ISO timestamps; anything bucketed by day/week
+// must convert to the user's IANA timezone first
+// ============================================
+
+use chrono::{DateTime, Datelike, NaiveDate, SecondsFormat, TimeZone, Timelike, Utc};
+use chrono_tz::Tz;
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use schemars::JsonSchema;
+
+use crate::errors::{invalid_argument, parse_error};
+use crate::ClaudeMessage;
+
+/// A timestamp broken down in a given timezone
+#[napi(object)]
+#[derive(Debug, Clone, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+pub struct ParsedTimestamp {
+    pub epoch_millis: i64,
+    /// Normalized RFC 3339 UTC string
+    pub utc: String,
+    /// RFC 3339 string with the local offset
+    pub local: String,
+    pub timezone: String,
+    /// Local calendar day, YYYY-MM-DD
+    pub date: String,
+    /// Local hour of day, 0-23
+    pub hour: u32,
+    /// Local weekday, 0 = Monday ... 6 = Sunday
+    pub weekday: u32,
+    /// ISO week, YYYY-Www
+    pub week: String,
+    /// Local month, YYYY-MM
+    pub month: String,
+}
+
+/// Parse an RFC 3339 timestamp as written in session files
+pub(crate) fn parse_utc(timestamp: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp.trim())
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// IANA name to timezone (None means UTC)
+pub(crate) fn resolve_timezone(timezone: Option<&str>) -> Result<Tz> {
+    match timezone.map(str::trim).filter(|tz| !tz.is_empty()) {
+        None => Ok(Tz::UTC),
+        Some(name) => name
+            .parse::<Tz>()
+            .map_err(|_| invalid_argument(format!("Unknown timezone: {}", name))),
+    }
+}
+
+/// Range bound: full RFC 3339 timestamp, or a YYYY-MM-DD date meaning local midnight
+pub(crate) fn parse_bound(bound: &str, tz: Tz) -> Result<DateTime<Utc>> {
+    if let Some(dt) = parse_utc(bound) {
+        return Ok(dt);
+    }
+
+    NaiveDate::parse_from_str(bound.trim(), "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .and_then(|midnight| tz.from_local_datetime(&midnight).earliest())
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok_or_else(|| parse_error(format!("Invalid date: {}", bound)))
+}
+
+/// Optional [from, to) bounds resolved in a timezone
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct TimeRange {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl TimeRange {
+    pub fn new(from: Option<&str>, to: Option<&str>, tz: Tz) -> Result<TimeRange> {
+        Ok(TimeRange {
+            from: from.map(|f| parse_bound(f, tz)).transpose()?,
+            to: to.map(|t| parse_bound(t, tz)).transpose()?,
+        })
+    }
+
+    pub fn is_unbounded(&self) -> bool {
+        self.from.is_none() && self.to.is_none()
+    }
+
+    /// Entries without a timestamp only pass an unbounded range
+    pub fn contains(&self, timestamp: Option<&str>) -> bool {
+        match timestamp.and_then(parse_utc) {
+            Some(dt) => self.from.is_none_or(|f| dt >= f) && self.to.is_none_or(|t| dt < t),
+            None => self.is_unbounded(),
+        }
+    }
+}
+
+pub(crate) fn breakdown(dt: &DateTime<Utc>, tz: Tz) -> ParsedTimestamp {
+    let local = dt.with_timezone(&tz);
+    let iso_week = local.iso_week();
+
+    ParsedTimestamp {
+        epoch_millis: dt.timestamp_millis(),
+        utc: format_utc(dt),
+        local: local.to_rfc3339_opts(SecondsFormat::Millis, false),
+        timezone: tz.name().to_string(),
+        date: local.format("%Y-%m-%d").to_string(),
+        hour: local.hour(),
+        weekday: local.weekday().num_days_from_monday(),
+        week: format!("{}-W{:02}", iso_week.year(), iso_week.week()),
+        month: local.format("%Y-%m").to_string(),
+    }
+}
+
+// ============================================
+// SESSION DURATION
+// ============================================
+
+/// Gaps longer than this count as idle, not active time
+pub(crate) const DEFAULT_IDLE_THRESHOLD_SECS: u32 = 300;
+
+/// Collects entry timestamps for wall-clock and active durations
+#[derive(Default)]
+pub(crate) struct ActivityTracker {
+    times: Vec<DateTime<Utc>>,
+}
+
+impl ActivityTracker {
+    pub fn observe(&mut self, timestamp: &str) {
+        if let Some(dt) = parse_utc(timestamp) {
+            self.times.push(dt);
+        }
+    }
+
+    /// (duration, active) in seconds; active sums the gaps of at most idle_threshold_secs
+    pub fn finish(mut self, idle_threshold_secs: u32) -> (Option<i64>, Option<i64>) {
+        if self.times.is_empty() {
+            return (None, None);
+        }
+        // Sidechain entries can be written out of order
+        self.times.sort_unstable();
+        let duration = (self.times[self.times.len() - 1] - self.times[0]).num_seconds();
+        let active = self
+            .times
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).num_seconds())
+            .filter(|gap| *gap <= idle_threshold_secs as i64)
+            .sum();
+        (Some(duration), Some(active))
+    }
+}
+
+// ============================================
+// MONOTONICITY REPAIR
+// ============================================
+
+fn format_utc(dt: &DateTime<Utc>) -> String {
+    dt.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// Fill missing/invalid timestamps: midpoint of the known neighbours, or the
+/// nearest known one at either end. Filled messages get timestamp_inferred = true
+pub(crate) fn infer_missing_timestamps(messages: &mut [ClaudeMessage]) {
+    let parsed: Vec<Option<DateTime<Utc>>> = messages.iter().map(|m| parse_utc(&m.timestamp)).collect();
+
+    for i in 0..messages.len() {
+        if parsed[i].is_some() {
+            continue;
+        }
+
+        let before = parsed[..i].iter().rev().find_map(|t| *t);
+        let after = parsed[i + 1..].iter().find_map(|t| *t);
+        let inferred = match (before, after) {
+            (Some(b), Some(a)) if a > b => Some(b + (a - b) / 2),
+            (Some(b), _) => Some(b),
+            (None, Some(a)) => Some(a),
+            (None, None) => None,
+        };
+
+        if let Some(dt) = inferred {
+            messages[i].timestamp = format_utc(&dt);
+            messages[i].timestamp_inferred = true;
+        }
+    }
+}
+
+/// Stable sort by timestamp; unparseable timestamps keep their relative position
+/// by borrowing the last valid timestamp seen before them
+pub(crate) fn sort_by_timestamp(messages: &mut Vec<ClaudeMessage>) {
+    let mut last: Option<DateTime<Utc>> = None;
+    let mut keyed: Vec<(Option<DateTime<Utc>>, ClaudeMessage)> = messages
+        .drain(..)
+        .map(|m| {
+            if let Some(dt) = parse_utc(&m.timestamp) {
+                last = Some(dt);
+            }
+            (last, m)
+        })
+        .collect();
+
+    keyed.sort_by_key(|(key, _)| *key);
+    messages.extend(keyed.into_iter().map(|(_, m)| m));
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Parse an ISO timestamp and break it down in an IANA timezone (default UTC)
+#[napi(catch_unwind)]
+pub fn parse_timestamp(timestamp: String, timezone: Option<String>) -> Result<ParsedTimestamp> {
+    let tz = resolve_timezone(timezone.as_deref())?;
+    let dt = parse_utc(&timestamp)
+        .ok_or_else(|| parse_error(format!("Invalid timestamp: {}", timestamp)))?;
+    Ok(breakdown(&dt, tz))
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_day_bucketing() {
+        let tz = resolve_timezone(Some("America/Los_Angeles")).unwrap();
+        let parsed = parse_timestamp("2024-03-02T05:30:00.000Z".into(), Some("America/Los_Angeles".into())).unwrap();
+        assert_eq!(parsed.date, "2024-03-01");
+        assert_eq!(parsed.hour, 21);
+        assert_eq!(parsed.weekday, 4);
+        assert_eq!(parsed.week, "2024-W09");
+
+        // Local midnight of a date bound, not UTC midnight
+        let range = TimeRange::new(Some("2024-03-02"), None, tz).unwrap();
+        assert!(!range.contains(Some("2024-03-02T05:30:00Z")));
+        assert!(range.contains(Some("2024-03-02T08:00:00Z")));
+
+        // 10:00 -> 10:04 active, 30 minute break, 10:34 -> 10:35 active
+        let mut activity = ActivityTracker::default();
+        for ts in ["2024-03-02T10:00:00Z", "2024-03-02T10:34:00Z", "2024-03-02T10:04:00Z", "2024-03-02T10:35:00Z"] {
+            activity.observe(ts);
+        }
+        assert_eq!(activity.finish(DEFAULT_IDLE_THRESHOLD_SECS), (Some(35 * 60), Some(5 * 60)));
+
+        assert!(resolve_timezone(Some("Mars/Olympus")).is_err());
+    }
+
+    #[test]
+    fn test_infer_and_sort_timestamps() {
+        let line = |uuid: &str, ts: Option<&str>| {
+            let ts = ts.map(|t| format!(r#","timestamp":"{}""#, t)).unwrap_or_default();
+            let json = format!(r#"{{"type":"user","uuid":"{}"{},"message":{{"role":"user","content":"hi"}}}}"#, uuid, ts);
+            crate::entry_to_message(crate::parse_jsonl_line(&json).unwrap()).unwrap()
+        };
+        let mut messages = vec![
+            line("a", Some("2024-01-01T10:00:00Z")),
+            line("b", None),
+            line("c", Some("2024-01-01T10:00:10Z")),
+            line("d", Some("2024-01-01T09:00:00Z")),
+        ];
+
+        infer_missing_timestamps(&mut messages);
+        assert_eq!(messages[1].timestamp, "2024-01-01T10:00:05.000Z");
+        assert!(messages[1].timestamp_inferred && !messages[0].timestamp_inferred);
+
+        sort_by_timestamp(&mut messages);
+        let order: Vec<&str> = messages.iter().map(|m| m.message_id.as_str()).collect();
+        assert_eq!(order, vec!["d", "a", "b", "c"]);
+    }
+}
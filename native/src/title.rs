@@ -0,0 +1,257 @@
+// ============================================
+// SESSION TITLES
+// Short human readable titles for the session list, derived from
+// the first real user prompt when Claude wrote no summary entry,
+// or set by the user and persisted in the sidecar store
+// ============================================
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use serde::{Deserialize, Serialize};
+
+use crate::store::{now_millis, read_store, update_store, MetadataStore};
+use crate::{extract_text_content, for_each_entry, ContentItem, RawLogEntry};
+
+const MAX_TITLE_CHARS: usize = 60;
+const MAX_EXCERPT_CHARS: usize = 200;
+
+/// Prefixes of user messages that Claude Code generates itself
+const WRAPPER_PREFIXES: &[&str] = &[
+    "<command-name>",
+    "<command-message>",
+    "<command-args>",
+    "<local-command-stdout>",
+    "<local-command-stderr>",
+    "<bash-input>",
+    "<bash-stdout>",
+    "<bash-stderr>",
+    "Caveat: The messages below were generated",
+    "[Request interrupted",
+    "This session is being continued from a previous conversation",
+];
+
+/// Remove ```fenced``` code blocks (pasted code makes terrible titles)
+pub(crate) fn strip_code_blocks(text: &str) -> String {
+    let mut in_fence = false;
+    text.lines()
+        .filter(|line| {
+            if line.trim_start().starts_with("```") {
+                in_fence = !in_fence;
+                return false;
+            }
+            !in_fence
+        })
+        .collect::<Vec<&str>>()
+        .join("\n")
+}
+
+/// Remove <tag>...</tag> blocks such as <system-reminder> injected into prompts
+pub(crate) fn strip_tag_blocks(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('<') {
+        let after = &rest[start + 1..];
+        let name_len = after
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+            .unwrap_or(after.len());
+        let name = &after[..name_len];
+
+        if name.is_empty() || !after[name_len..].starts_with('>') {
+            result.push_str(&rest[..start + 1]);
+            rest = after;
+            continue;
+        }
+
+        let closing = format!("</{}>", name);
+        match rest.find(&closing) {
+            Some(end) => {
+                result.push_str(&rest[..start]);
+                rest = &rest[end + closing.len()..];
+            }
+            None => {
+                result.push_str(&rest[..start + 1]);
+                rest = after;
+            }
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Collapse whitespace and cut at a word boundary with an ellipsis
+pub(crate) fn truncate_words(text: &str, max_chars: usize) -> String {
+    let collapsed = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+    if collapsed.chars().count() <= max_chars {
+        return collapsed;
+    }
+
+    let cut: String = collapsed.chars().take(max_chars).collect();
+    // Prefer cutting at the last space unless it throws away too much
+    let cut = match cut.rfind(' ') {
+        Some(pos) if cut[..pos].chars().count() >= max_chars * 3 / 5 => cut[..pos].to_string(),
+        _ => cut,
+    };
+
+    format!("{}…", cut.trim_end_matches(|c: char| c.is_ascii_punctuation() || c == ' '))
+}
+
+/// True for user text typed by a human (not meta, command output or tool results)
+pub(crate) fn is_substantive_prompt(entry: &RawLogEntry) -> bool {
+    if entry.entry_type != "user" || entry.is_meta == Some(true) || entry.is_sidechain == Some(true) {
+        return false;
+    }
+
+    let Some(message) = &entry.message else { return false };
+    if message.content.iter().any(|item| matches!(item, ContentItem::ToolResult { .. })) {
+        return false;
+    }
+
+    let text = extract_text_content(&message.content);
+    let text = text.trim_start();
+    !text.is_empty() && !WRAPPER_PREFIXES.iter().any(|p| text.starts_with(p))
+}
+
+/// Turn a prompt into a one line title, None if nothing readable is left
+pub(crate) fn title_from_prompt(text: &str) -> Option<String> {
+    let cleaned = strip_tag_blocks(&strip_code_blocks(text));
+    let first_line = cleaned.lines().map(str::trim).find(|line| !line.is_empty())?;
+    Some(truncate_words(first_line, MAX_TITLE_CHARS))
+}
+
+/// Preview of a user prompt for the session list
+pub(crate) fn prompt_excerpt(text: &str) -> Option<String> {
+    let cleaned = strip_tag_blocks(&strip_code_blocks(text));
+    let excerpt = truncate_words(&cleaned, MAX_EXCERPT_CHARS);
+    if excerpt.is_empty() { None } else { Some(excerpt) }
+}
+
+/// Preview of an assistant reply - text blocks only, thinking and tool calls skipped
+pub(crate) fn reply_excerpt(content_items: &[ContentItem]) -> Option<String> {
+    let text = content_items
+        .iter()
+        .filter_map(|item| match item {
+            ContentItem::Text { text } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<&str>>()
+        .join("\n\n");
+    let excerpt = truncate_words(&text, MAX_EXCERPT_CHARS);
+    if excerpt.is_empty() { None } else { Some(excerpt) }
+}
+
+/// Title the user gave a session
+#[napi(object)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionTitle {
+    pub session_id: String,
+    pub title: String,
+    pub updated_at: i64,
+}
+
+impl MetadataStore {
+    /// Set the title of a session, or clear it with None or a blank title
+    pub fn set_session_title(&mut self, session_id: &str, title: Option<String>, now: i64) -> Option<SessionTitle> {
+        let title = title.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+        self.session_titles.retain(|t| t.session_id != session_id);
+        let title = SessionTitle { session_id: session_id.to_string(), title: title?, updated_at: now };
+        self.session_titles.push(title.clone());
+        Some(title)
+    }
+
+    pub fn session_title(&self, session_id: &str) -> Option<&str> {
+        self.session_titles.iter().find(|t| t.session_id == session_id).map(|t| t.title.as_str())
+    }
+}
+
+/// Title from a summary entry if present, otherwise from the first real prompt
+pub fn derive_title(entries: &[RawLogEntry]) -> Option<String> {
+    let summary = entries
+        .iter()
+        .rev()
+        .filter(|e| e.entry_type == "summary")
+        .find_map(|e| e.summary.as_ref().map(|s| s.trim().to_string()))
+        .filter(|s| !s.is_empty());
+
+    if summary.is_some() {
+        return summary;
+    }
+
+    entries
+        .iter()
+        .filter(|e| is_substantive_prompt(e))
+        .filter_map(|e| e.message.as_ref())
+        .find_map(|m| title_from_prompt(&extract_text_content(&m.content)))
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Generate a short title for a session (summary entry, else first user prompt)
+#[napi(catch_unwind)]
+pub fn generate_session_title(file_path: String) -> Result<Option<String>> {
+    let mut entries = Vec::new();
+    for_each_entry(&file_path, |entry| entries.push(entry))?;
+    Ok(derive_title(&entries))
+}
+
+/// Give a session a title of the user's own; None or a blank title clears it
+#[napi(catch_unwind)]
+pub fn set_session_title(session_id: String, title: Option<String>) -> Result<Option<SessionTitle>> {
+    let now = now_millis();
+    update_store(|store| store.set_session_title(&session_id, title, now))
+}
+
+/// The title the user gave a session, if any
+#[napi(catch_unwind)]
+pub fn get_session_title(session_id: String) -> Result<Option<String>> {
+    read_store(|store| store.session_title(&session_id).map(String::from))
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_jsonl_line;
+
+    #[test]
+    fn test_title_skips_wrappers_and_code() {
+        let lines = [
+            r#"{"type":"user","message":{"role":"user","content":"<command-name>/clear</command-name>"}}"#,
+            r#"{"type":"user","isMeta":true,"message":{"role":"user","content":"Caveat: meta"}}"#,
+            r#"{"type":"user","message":{"role":"user","content":"```rust\nfn main() {}\n```\nWhy does this refuse to compile when I add a lifetime parameter to the struct?"}}"#,
+        ];
+        let entries: Vec<RawLogEntry> = lines.iter().map(|l| parse_jsonl_line(l).unwrap()).collect();
+
+        assert_eq!(
+            derive_title(&entries).unwrap(),
+            "Why does this refuse to compile when I add a lifetime…"
+        );
+    }
+
+    #[test]
+    fn test_set_and_clear_session_title() {
+        let mut store = MetadataStore::default();
+        store.set_session_title("s", Some(" Lifetime fixes ".into()), 1);
+        let renamed = store.set_session_title("s", Some("Borrow checker".into()), 2).unwrap();
+        assert_eq!((store.session_titles.len(), renamed.updated_at), (1, 2));
+        assert_eq!(store.session_title("s"), Some("Borrow checker"));
+        assert!(store.set_session_title("s", Some("  ".into()), 3).is_none());
+        assert_eq!(store.session_title("s"), None);
+    }
+
+    #[test]
+    fn test_title_prefers_summary() {
+        let lines = [
+            r#"{"type":"summary","summary":"Fix lifetime errors","leafUuid":"x"}"#,
+            r#"{"type":"user","message":{"role":"user","content":"hello"}}"#,
+        ];
+        let entries: Vec<RawLogEntry> = lines.iter().map(|l| parse_jsonl_line(l).unwrap()).collect();
+        assert_eq!(derive_title(&entries).unwrap(), "Fix lifetime errors");
+    }
+}
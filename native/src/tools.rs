@@ -0,0 +1,164 @@
+// ============================================
+// TOOL INPUT STATISTICS
+// Counts of the values passed to one tool across all sessions: the
+// most run commands, most read files, most common patterns...
+// ============================================
+
+use std::collections::HashMap;
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::title::truncate_words;
+use crate::{collect_session_files, for_each_entry, ContentItem};
+
+/// Values listed per field
+const TOP_VALUES: usize = 20;
+/// Longer values (heredocs, file contents) are cut before counting
+const MAX_VALUE_CHARS: usize = 200;
+
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValueCount {
+    pub value: String,
+    pub count: u32,
+}
+
+/// Counts for one input field, e.g. Bash "command" or Read "file_path"
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputFieldStats {
+    pub field: String,
+    /// Calls that set the field
+    pub calls: u32,
+    pub distinct: u32,
+    /// Most frequent first, at most 20
+    pub top: Vec<ValueCount>,
+}
+
+/// Result of get_tool_input_stats
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolInputStats {
+    pub tool_name: String,
+    pub calls: u32,
+    pub sessions: u32,
+    /// Fields in order of how many calls set them
+    pub fields: Vec<InputFieldStats>,
+}
+
+/// First word of a shell command, skipping VAR=value prefixes
+fn program(command: &str) -> Option<String> {
+    command
+        .split_whitespace()
+        .find(|word| !word.contains('='))
+        .map(|word| word.rsplit('/').next().unwrap_or(word).to_string())
+}
+
+/// Countable (field, value) pairs of one call; Bash also gets a derived "program" field
+fn input_values(tool_name: &str, input: &serde_json::Value) -> Vec<(String, String)> {
+    let Some(object) = input.as_object() else { return Vec::new() };
+    let mut values: Vec<(String, String)> = object
+        .iter()
+        .filter_map(|(key, value)| {
+            let text = match value {
+                serde_json::Value::String(s) => s.trim().to_string(),
+                serde_json::Value::Number(n) => n.to_string(),
+                serde_json::Value::Bool(b) => b.to_string(),
+                _ => return None,
+            };
+            (!text.is_empty()).then(|| (key.clone(), truncate_words(&text, MAX_VALUE_CHARS)))
+        })
+        .collect();
+
+    if tool_name == "Bash" {
+        if let Some(name) = object.get("command").and_then(|c| c.as_str()).and_then(program) {
+            values.push(("program".to_string(), name));
+        }
+    }
+    values
+}
+
+fn tool_input_stats(root_dir: &str, tool_name: &str) -> Result<ToolInputStats> {
+    let mut calls = 0;
+    let mut sessions = 0;
+    let mut fields: HashMap<String, HashMap<String, u32>> = HashMap::new();
+
+    for path in collect_session_files(root_dir)? {
+        let before = calls;
+        let _ = for_each_entry(&path.display().to_string(), |entry| {
+            let Some(message) = &entry.message else { return };
+            for item in &message.content {
+                let ContentItem::ToolUse { name, input, .. } = item else { continue };
+                if name != tool_name {
+                    continue;
+                }
+                calls += 1;
+                for (field, value) in input_values(name, input) {
+                    *fields.entry(field).or_default().entry(value).or_insert(0) += 1;
+                }
+            }
+        });
+        if calls > before {
+            sessions += 1;
+        }
+    }
+
+    let mut fields: Vec<InputFieldStats> = fields
+        .into_iter()
+        .map(|(field, counts)| {
+            let mut top: Vec<ValueCount> = counts.into_iter().map(|(value, count)| ValueCount { value, count }).collect();
+            top.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+            let distinct = top.len() as u32;
+            let calls = top.iter().map(|v| v.count).sum();
+            top.truncate(TOP_VALUES);
+            InputFieldStats { field, calls, distinct, top }
+        })
+        .collect();
+    fields.sort_by(|a, b| b.calls.cmp(&a.calls).then_with(|| a.field.cmp(&b.field)));
+
+    Ok(ToolInputStats { tool_name: tool_name.to_string(), calls, sessions, fields })
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Most common input values of tool_name (e.g. "Bash", "Read", "Grep") under root_dir
+#[napi(catch_unwind)]
+pub fn get_tool_input_stats(root_dir: String, tool_name: String) -> Result<ToolInputStats> {
+    tool_input_stats(&root_dir, &tool_name)
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_bash_input_stats() {
+        let root = TempDir::new("tools-test");
+        std::fs::create_dir_all(root.join("proj")).unwrap();
+        let call = |id: &str, command: &str| {
+            format!(
+                r#"{{"type":"assistant","message":{{"role":"assistant","content":[{{"type":"tool_use","id":"{}","name":"Bash","input":{{"command":"{}","timeout":60000}}}}]}}}}"#,
+                id, command
+            )
+        };
+        std::fs::write(root.join("proj/a.jsonl"), [call("1", "cargo test"), call("2", "cargo test"), call("3", "RUST_LOG=debug /usr/bin/cargo run")].join("\n")).unwrap();
+        std::fs::write(root.join("proj/b.jsonl"), call("4", "git status")).unwrap();
+
+        let stats = tool_input_stats(&root.to_string_lossy(), "Bash").unwrap();
+        assert_eq!((stats.calls, stats.sessions), (4, 2));
+
+        let field = |name: &str| stats.fields.iter().find(|f| f.field == name).unwrap();
+        assert_eq!(field("command").top[0], ValueCount { value: "cargo test".into(), count: 2 });
+        assert_eq!(field("command").distinct, 3);
+        assert_eq!(field("program").top[0], ValueCount { value: "cargo".into(), count: 3 });
+        assert_eq!(field("timeout").top[0].value, "60000");
+    }
+}
@@ -0,0 +1,256 @@
+// ============================================
+// TOP-N QUERIES
+// Ranked sessions, files and thinking blocks under a root, each with
+// the session (and message) to deep-link to
+// ============================================
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::errors::invalid_argument;
+use crate::pricing::{cost_usd, default_prices, price_for};
+use crate::time::{parse_utc, ActivityTracker, DEFAULT_IDLE_THRESHOLD_SECS};
+use crate::usage::period_start;
+use crate::{collect_session_files, extract_text_content, for_each_entry, title, ContentItem};
+
+/// Metrics accepted by get_top
+const METRICS: &[&str] = &["cost", "duration", "tool_errors", "edited_files", "thinking"];
+
+/// Thinking excerpts used as labels
+const LABEL_CHARS: usize = 120;
+
+/// Tools whose input names the file they change
+const EDIT_TOOLS: &[&str] = &["Edit", "MultiEdit", "Write", "NotebookEdit"];
+
+/// One ranked item; sessions, files or thinking blocks depending on the metric
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TopEntry {
+    /// 1 for the highest value
+    pub rank: u32,
+    /// USD, seconds, errors, edits or thinking characters
+    pub value: f64,
+    /// Session title, edited file path or thinking excerpt
+    pub label: String,
+    pub session_id: String,
+    pub file_path: String,
+    /// The thinking message, or None for session-level metrics
+    pub message_id: Option<String>,
+    /// Last activity of the session, or the time of the message
+    pub timestamp: Option<String>,
+}
+
+/// Per-file state while scanning one session
+struct SessionScan {
+    session_id: String,
+    file_path: String,
+    title: Option<String>,
+    last_timestamp: Option<String>,
+    cost: f64,
+    tool_errors: u32,
+    activity: ActivityTracker,
+    priced_responses: HashSet<String>,
+}
+
+impl SessionScan {
+    fn entry(&self, value: f64) -> TopEntry {
+        TopEntry {
+            rank: 0,
+            value,
+            label: self.title.clone().unwrap_or_else(|| self.session_id.clone()),
+            session_id: self.session_id.clone(),
+            file_path: self.file_path.clone(),
+            message_id: None,
+            timestamp: self.last_timestamp.clone(),
+        }
+    }
+}
+
+/// Most edits to one file, credited to the latest session that made one
+struct FileEdits {
+    edits: u32,
+    session_id: String,
+    file_path: String,
+    timestamp: Option<String>,
+}
+
+fn edited_path(name: &str, input: &serde_json::Value) -> Option<String> {
+    if !EDIT_TOOLS.contains(&name) {
+        return None;
+    }
+    ["file_path", "notebook_path"]
+        .iter()
+        .find_map(|key| input.get(*key).and_then(|v| v.as_str()))
+        .map(str::to_string)
+}
+
+fn top(root_dir: &str, metric: &str, n: u32, period: &str, now: DateTime<Utc>) -> Result<Vec<TopEntry>> {
+    if !METRICS.contains(&metric) {
+        return Err(invalid_argument(format!("Unknown metric: {}", metric)));
+    }
+    let from = period_start(period, now)?;
+    let defaults = default_prices();
+
+    let mut ranked: Vec<TopEntry> = Vec::new();
+    let mut files: HashMap<String, FileEdits> = HashMap::new();
+
+    for path in collect_session_files(root_dir)? {
+        let file_path = path.display().to_string();
+        let mut scan = SessionScan {
+            session_id: file_path.clone(),
+            file_path: file_path.clone(),
+            title: None,
+            last_timestamp: None,
+            cost: 0.0,
+            tool_errors: 0,
+            activity: ActivityTracker::default(),
+            priced_responses: HashSet::new(),
+        };
+
+        let _ = for_each_entry(&file_path, |entry| {
+            if let Some(sid) = &entry.session_id {
+                scan.session_id = sid.clone();
+            }
+            if scan.title.is_none() && title::is_substantive_prompt(&entry) {
+                scan.title = entry.message.as_ref().and_then(|m| title::title_from_prompt(&extract_text_content(&m.content)));
+            }
+            let Some(ts) = entry.timestamp.clone() else { return };
+            let Some(dt) = parse_utc(&ts) else { return };
+            if from.is_some_and(|f| dt < f) || dt > now {
+                return;
+            }
+            scan.activity.observe(&ts);
+            scan.last_timestamp = Some(ts.clone());
+
+            let Some(message) = &entry.message else { return };
+            if let (Some(usage), Some(model)) = (&message.usage, &message.model) {
+                let first_time = message.id.as_ref().is_none_or(|id| scan.priced_responses.insert(id.clone()));
+                if first_time {
+                    scan.cost += price_for(model, &[], &defaults).map_or(0.0, |price| cost_usd(price, usage));
+                }
+            }
+
+            for item in &message.content {
+                match item {
+                    ContentItem::ToolResult { is_error: Some(true), .. } => scan.tool_errors += 1,
+                    ContentItem::ToolUse { name, input, .. } if metric == "edited_files" => {
+                        let Some(edited) = edited_path(name, input) else { continue };
+                        let file = files.entry(edited).or_insert_with(|| FileEdits {
+                            edits: 0,
+                            session_id: String::new(),
+                            file_path: String::new(),
+                            timestamp: None,
+                        });
+                        file.edits += 1;
+                        if file.timestamp.as_deref().is_none_or(|t| t <= ts.as_str()) {
+                            file.session_id = scan.session_id.clone();
+                            file.file_path = scan.file_path.clone();
+                            file.timestamp = Some(ts.clone());
+                        }
+                    }
+                    ContentItem::Thinking { thinking, .. } if metric == "thinking" => {
+                        ranked.push(TopEntry {
+                            message_id: entry.uuid.clone(),
+                            timestamp: Some(ts.clone()),
+                            label: title::truncate_words(thinking, LABEL_CHARS),
+                            ..scan.entry(thinking.chars().count() as f64)
+                        });
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        match metric {
+            "cost" => ranked.push(scan.entry(scan.cost)),
+            "tool_errors" => ranked.push(scan.entry(scan.tool_errors as f64)),
+            "duration" => {
+                let value = std::mem::take(&mut scan.activity).finish(DEFAULT_IDLE_THRESHOLD_SECS).0.unwrap_or(0);
+                ranked.push(scan.entry(value as f64));
+            }
+            _ => {}
+        }
+    }
+
+    ranked.extend(files.into_iter().map(|(path, file)| TopEntry {
+        rank: 0,
+        value: file.edits as f64,
+        label: path,
+        session_id: file.session_id,
+        file_path: file.file_path,
+        message_id: None,
+        timestamp: file.timestamp,
+    }));
+
+    ranked.retain(|entry| entry.value > 0.0);
+    ranked.sort_by(|a, b| b.value.total_cmp(&a.value).then_with(|| a.label.cmp(&b.label)));
+    ranked.truncate(n as usize);
+    for (i, entry) in ranked.iter_mut().enumerate() {
+        entry.rank = i as u32 + 1;
+    }
+    Ok(ranked)
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// The n highest entries under root_dir for a metric ("cost", "duration",
+/// "tool_errors", "edited_files" or "thinking") over "day", "week", "month" or "all"
+#[napi(catch_unwind)]
+pub fn get_top(root_dir: String, metric: String, n: u32, period: String) -> Result<Vec<TopEntry>> {
+    top(&root_dir, &metric, n, &period, Utc::now())
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_top_metrics() {
+        let root = TempDir::new("top-test");
+        std::fs::create_dir_all(root.join("proj")).unwrap();
+        std::fs::write(
+            root.join("proj/a.jsonl"),
+            r#"{"type":"user","sessionId":"a","timestamp":"2024-03-10T10:00:00Z","message":{"role":"user","content":"Fix the parser"}}
+{"type":"assistant","uuid":"a2","sessionId":"a","timestamp":"2024-03-10T10:30:00Z","message":{"id":"r1","role":"assistant","model":"claude-sonnet-4","content":[{"type":"thinking","thinking":"long thought here"},{"type":"tool_use","id":"t1","name":"Edit","input":{"file_path":"/src/parser.rs"}}],"usage":{"input_tokens":1000000,"output_tokens":0}}}
+{"type":"user","sessionId":"a","timestamp":"2024-03-10T10:31:00Z","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"failed","is_error":true}]}}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("proj/b.jsonl"),
+            r#"{"type":"user","sessionId":"b","timestamp":"2024-03-10T11:00:00Z","message":{"role":"user","content":"Add docs"}}
+{"type":"assistant","uuid":"b2","sessionId":"b","timestamp":"2024-03-10T11:05:00Z","message":{"id":"r2","role":"assistant","model":"claude-opus-4-1","content":[{"type":"thinking","thinking":"hm"},{"type":"tool_use","id":"t2","name":"Write","input":{"file_path":"/src/parser.rs"}},{"type":"tool_use","id":"t3","name":"Write","input":{"file_path":"/README.md"}}],"usage":{"input_tokens":1000000,"output_tokens":0}}}"#,
+        )
+        .unwrap();
+        let root_dir = root.to_string_lossy().to_string();
+        let now = parse_utc("2024-03-11T00:00:00Z").unwrap();
+
+        let cost = top(&root_dir, "cost", 5, "all", now).unwrap();
+        assert_eq!((cost[0].session_id.as_str(), cost[0].label.as_str(), cost[0].value), ("b", "Add docs", 15.0));
+
+        let duration = top(&root_dir, "duration", 1, "all", now).unwrap();
+        assert_eq!((duration.len(), duration[0].session_id.as_str(), duration[0].value), (1, "a", 1860.0));
+
+        let errors = top(&root_dir, "tool_errors", 5, "all", now).unwrap();
+        assert_eq!(errors.iter().map(|e| e.session_id.as_str()).collect::<Vec<_>>(), vec!["a"]);
+
+        let files = top(&root_dir, "edited_files", 5, "all", now).unwrap();
+        assert_eq!((files[0].label.as_str(), files[0].value, files[0].session_id.as_str()), ("/src/parser.rs", 2.0, "b"));
+        assert_eq!(files[1].rank, 2);
+
+        let thinking = top(&root_dir, "thinking", 1, "all", now).unwrap();
+        assert_eq!(thinking[0].message_id.as_deref(), Some("a2"));
+
+        assert!(top(&root_dir, "cost", 5, "day", parse_utc("2024-03-20T00:00:00Z").unwrap()).unwrap().is_empty());
+        assert!(top(&root_dir, "tokens", 5, "all", now).is_err());
+    }
+}
@@ -0,0 +1,309 @@
+// ============================================
+// TOPIC CLUSTERING
+// Offline TF-IDF + spherical k-means over session text to group
+// sessions into topics for a "browse by topic" view
+// ============================================
+
+use std::collections::HashMap;
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use schemars::JsonSchema;
+
+use crate::terms::terms;
+use crate::title::{derive_title, is_substantive_prompt, strip_code_blocks, strip_tag_blocks};
+use crate::{collect_session_files, extract_text_content, for_each_entry, RawLogEntry};
+
+const DEFAULT_MAX_KEYWORDS: u32 = 5;
+const MAX_VOCABULARY: usize = 5000;
+const KMEANS_ITERATIONS: usize = 20;
+
+/// Clustering options
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct ClusterOptions {
+    /// Number of topics (default: sqrt(sessions / 2), at least 1)
+    pub num_clusters: Option<u32>,
+    /// Keywords reported per topic (default 5)
+    pub max_keywords: Option<u32>,
+}
+
+/// Session belonging to a topic
+#[napi(object)]
+#[derive(Debug, Clone, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+pub struct ClusterMember {
+    pub session_id: String,
+    pub file_path: String,
+    pub title: Option<String>,
+    /// Cosine similarity to the topic centroid
+    pub score: f64,
+}
+
+/// A group of sessions about the same thing
+#[napi(object)]
+#[derive(Debug, Clone, JsonSchema)]
+#[schemars(rename_all = "camelCase")]
+pub struct TopicCluster {
+    pub cluster_id: u32,
+    pub keywords: Vec<String>,
+    pub sessions: Vec<ClusterMember>,
+}
+
+struct SessionDoc {
+    session_id: String,
+    file_path: String,
+    title: Option<String>,
+    terms: Vec<String>,
+}
+
+/// Human written text of a session: prompts and assistant replies, code removed
+fn session_text(entries: &[RawLogEntry]) -> String {
+    entries
+        .iter()
+        .filter(|e| is_substantive_prompt(e) || e.entry_type == "assistant")
+        .filter_map(|e| e.message.as_ref())
+        .map(|m| strip_tag_blocks(&strip_code_blocks(&extract_text_content(&m.content))))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+type SparseVector = Vec<(usize, f64)>;
+
+fn dot(sparse: &SparseVector, dense: &[f64]) -> f64 {
+    sparse.iter().map(|(i, w)| w * dense[*i]).sum()
+}
+
+fn normalize(dense: &mut [f64]) {
+    let norm = dense.iter().map(|w| w * w).sum::<f64>().sqrt();
+    if norm > 0.0 {
+        dense.iter_mut().for_each(|w| *w /= norm);
+    }
+}
+
+/// Build L2-normalized TF-IDF vectors and the vocabulary they index into
+fn tfidf(docs: &[SessionDoc]) -> (Vec<String>, Vec<SparseVector>) {
+    let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+    for doc in docs {
+        let mut seen: Vec<&str> = doc.terms.iter().map(String::as_str).collect();
+        seen.sort_unstable();
+        seen.dedup();
+        for term in seen {
+            *document_frequency.entry(term).or_default() += 1;
+        }
+    }
+
+    // Terms found in a single session or in most sessions do not separate topics
+    let n = docs.len();
+    let max_df = if n > 2 { (n as f64 * 0.6).ceil() as usize } else { n };
+    let min_df = if n > 3 { 2 } else { 1 };
+    let mut vocabulary: Vec<(&str, usize)> = document_frequency
+        .into_iter()
+        .filter(|(_, df)| *df >= min_df && *df <= max_df)
+        .collect();
+    vocabulary.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    vocabulary.truncate(MAX_VOCABULARY);
+
+    let index: HashMap<&str, usize> = vocabulary.iter().enumerate().map(|(i, (t, _))| (*t, i)).collect();
+    let idf: Vec<f64> = vocabulary
+        .iter()
+        .map(|(_, df)| (1.0 + n as f64 / *df as f64).ln())
+        .collect();
+
+    let vectors = docs
+        .iter()
+        .map(|doc| {
+            let mut counts: HashMap<usize, f64> = HashMap::new();
+            for term in &doc.terms {
+                if let Some(i) = index.get(term.as_str()) {
+                    *counts.entry(*i).or_default() += 1.0;
+                }
+            }
+            let mut vector: SparseVector = counts
+                .into_iter()
+                .map(|(i, tf)| (i, (1.0 + tf.ln()) * idf[i]))
+                .collect();
+            vector.sort_by_key(|(i, _)| *i);
+
+            let norm = vector.iter().map(|(_, w)| w * w).sum::<f64>().sqrt();
+            if norm > 0.0 {
+                vector.iter_mut().for_each(|(_, w)| *w /= norm);
+            }
+            vector
+        })
+        .collect();
+
+    (vocabulary.into_iter().map(|(t, _)| t.to_string()).collect(), vectors)
+}
+
+/// Spherical k-means with deterministic farthest-point seeding
+fn kmeans(vectors: &[SparseVector], dims: usize, k: usize) -> (Vec<usize>, Vec<Vec<f64>>) {
+    let to_dense = |v: &SparseVector| {
+        let mut dense = vec![0.0; dims];
+        v.iter().for_each(|(i, w)| dense[*i] = *w);
+        dense
+    };
+
+    let mut seeds = vec![0usize];
+    let mut best_sim: Vec<f64> = vectors.iter().map(|v| dot(v, &to_dense(&vectors[0]))).collect();
+    while seeds.len() < k {
+        let next = (0..vectors.len())
+            .filter(|i| !seeds.contains(i))
+            .min_by(|a, b| best_sim[*a].total_cmp(&best_sim[*b]))
+            .unwrap_or(0);
+        let centroid = to_dense(&vectors[next]);
+        for (i, v) in vectors.iter().enumerate() {
+            best_sim[i] = best_sim[i].max(dot(v, &centroid));
+        }
+        seeds.push(next);
+    }
+
+    let mut centroids: Vec<Vec<f64>> = seeds.iter().map(|i| to_dense(&vectors[*i])).collect();
+    let mut assignment = vec![usize::MAX; vectors.len()];
+
+    for _ in 0..KMEANS_ITERATIONS {
+        let mut changed = false;
+        for (i, v) in vectors.iter().enumerate() {
+            let best = (0..k)
+                .max_by(|a, b| dot(v, &centroids[*a]).total_cmp(&dot(v, &centroids[*b])).then(b.cmp(a)))
+                .unwrap_or(0);
+            if assignment[i] != best {
+                assignment[i] = best;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+
+        for (c, centroid) in centroids.iter_mut().enumerate() {
+            let mut sum = vec![0.0; dims];
+            for (v, _) in vectors.iter().zip(&assignment).filter(|(_, a)| **a == c) {
+                v.iter().for_each(|(i, w)| sum[*i] += w);
+            }
+            normalize(&mut sum);
+            // Keep the old centroid for clusters that lost all members
+            if sum.iter().any(|w| *w != 0.0) {
+                *centroid = sum;
+            }
+        }
+    }
+
+    (assignment, centroids)
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Group sessions under root_dir into topics with representative keywords
+#[napi(catch_unwind)]
+pub fn cluster_sessions(root_dir: String, options: Option<ClusterOptions>) -> Result<Vec<TopicCluster>> {
+    let options = options.unwrap_or_default();
+    let max_keywords = options.max_keywords.unwrap_or(DEFAULT_MAX_KEYWORDS) as usize;
+
+    let mut docs = Vec::new();
+    for path in collect_session_files(&root_dir)? {
+        let file_path = path.display().to_string();
+        let mut entries = Vec::new();
+        if for_each_entry(&file_path, |entry| entries.push(entry)).is_err() {
+            continue;
+        }
+
+        let doc_terms = terms(&session_text(&entries));
+        if doc_terms.is_empty() {
+            continue;
+        }
+
+        docs.push(SessionDoc {
+            session_id: entries
+                .iter()
+                .find_map(|e| e.session_id.clone())
+                .unwrap_or_else(|| "unknown".to_string()),
+            file_path,
+            title: derive_title(&entries),
+            terms: doc_terms,
+        });
+    }
+
+    if docs.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let (vocabulary, vectors) = tfidf(&docs);
+    let default_k = ((docs.len() as f64 / 2.0).sqrt().round() as usize).max(1);
+    let k = options
+        .num_clusters
+        .map(|n| n as usize)
+        .unwrap_or(default_k)
+        .clamp(1, docs.len());
+
+    let (assignment, centroids) = kmeans(&vectors, vocabulary.len(), k);
+
+    let mut clusters: Vec<TopicCluster> = centroids
+        .iter()
+        .enumerate()
+        .map(|(c, centroid)| {
+            let mut weighted: Vec<(usize, f64)> = centroid.iter().copied().enumerate().filter(|(_, w)| *w > 0.0).collect();
+            weighted.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+            let mut sessions: Vec<ClusterMember> = docs
+                .iter()
+                .zip(&vectors)
+                .zip(&assignment)
+                .filter(|(_, a)| **a == c)
+                .map(|((doc, vector), _)| ClusterMember {
+                    session_id: doc.session_id.clone(),
+                    file_path: doc.file_path.clone(),
+                    title: doc.title.clone(),
+                    score: dot(vector, centroid),
+                })
+                .collect();
+            sessions.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+            TopicCluster {
+                cluster_id: c as u32,
+                keywords: weighted.iter().take(max_keywords).map(|(i, _)| vocabulary[*i].clone()).collect(),
+                sessions,
+            }
+        })
+        .filter(|cluster| !cluster.sessions.is_empty())
+        .collect();
+
+    clusters.sort_by_key(|c| std::cmp::Reverse(c.sessions.len()));
+    Ok(clusters)
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc(id: &str, text: &str) -> SessionDoc {
+        SessionDoc {
+            session_id: id.to_string(),
+            file_path: String::new(),
+            title: None,
+            terms: terms(text),
+        }
+    }
+
+    #[test]
+    fn test_kmeans_separates_topics() {
+        let docs = vec![
+            doc("a", "postgres migration index query postgres schema"),
+            doc("b", "postgres query slow index schema migration"),
+            doc("c", "react component hooks state render component"),
+            doc("d", "react hooks render state props component"),
+        ];
+        let (vocabulary, vectors) = tfidf(&docs);
+        let (assignment, _) = kmeans(&vectors, vocabulary.len(), 2);
+
+        assert_eq!(assignment[0], assignment[1]);
+        assert_eq!(assignment[2], assignment[3]);
+        assert_ne!(assignment[0], assignment[2]);
+    }
+}
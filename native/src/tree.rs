@@ -0,0 +1,295 @@
+// ============================================
+// CONVERSATION TREE
+// The uuid/parentUuid tree of a session with its anomalies (missing
+// parents, cycles, several roots) reported, and optionally repaired
+// ============================================
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::time::parse_utc;
+use crate::{for_each_entry, RawLogEntry};
+
+/// Options for build_conversation_tree
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct TreeOptions {
+    /// Attach orphans and cycle members to the message logged just before
+    /// them (default false: they stay roots)
+    pub repair: Option<bool>,
+}
+
+/// One entry of the tree, in file order
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeNode {
+    pub uuid: String,
+    /// Parent in the returned tree (after repair)
+    pub parent_uuid: Option<String>,
+    /// parentUuid as logged
+    pub logged_parent_uuid: Option<String>,
+    /// "user", "assistant", "system", ...
+    pub entry_type: String,
+    pub timestamp: Option<String>,
+    pub is_sidechain: bool,
+    /// 0 for roots
+    pub depth: u32,
+    pub child_count: u32,
+    /// True when repair gave the node a different parent than logged
+    pub repaired: bool,
+}
+
+/// A node whose parentUuid is not in the file
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrphanNode {
+    pub uuid: String,
+    pub missing_parent_uuid: String,
+    /// Where repair attached it, None without repair
+    pub attached_to: Option<String>,
+}
+
+/// What is wrong with the tree as logged
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeDiagnostics {
+    pub orphans: Vec<OrphanNode>,
+    /// uuids of each parent cycle, starting with the member whose link was cut
+    pub cycles: Vec<Vec<String>>,
+    /// Roots as logged (no parent, missing parent or cut out of a cycle)
+    pub root_uuids: Vec<String>,
+    pub multiple_roots: bool,
+    /// uuids logged more than once; only the first entry is used
+    pub duplicate_uuids: Vec<String>,
+}
+
+/// Result of build_conversation_tree
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversationTree {
+    pub nodes: Vec<TreeNode>,
+    /// Roots of the returned tree (after repair)
+    pub root_uuids: Vec<String>,
+    pub diagnostics: TreeDiagnostics,
+}
+
+/// Walk up from node; true if ancestor is on the way
+fn is_ancestor(parents: &[Option<usize>], ancestor: usize, mut node: usize) -> bool {
+    loop {
+        if node == ancestor {
+            return true;
+        }
+        match parents[node] {
+            Some(parent) => node = parent,
+            None => return false,
+        }
+    }
+}
+
+/// Find parent cycles and cut each one at its earliest member
+fn cut_cycles(parents: &mut [Option<usize>]) -> Vec<Vec<usize>> {
+    // 0 = unvisited, 1 = on the current path, 2 = done
+    let mut state = vec![0u8; parents.len()];
+    let mut cycles = Vec::new();
+
+    for start in 0..parents.len() {
+        let mut path = Vec::new();
+        let mut node = Some(start);
+        while let Some(n) = node.filter(|n| state[*n] == 0) {
+            state[n] = 1;
+            path.push(n);
+            node = parents[n];
+        }
+        if let Some(n) = node.filter(|n| state[*n] == 1) {
+            let mut cycle = path[path.iter().position(|p| *p == n).unwrap_or(0)..].to_vec();
+            let first = cycle.iter().position(|m| cycle.iter().all(|o| m <= o)).unwrap_or(0);
+            cycle.rotate_left(first);
+            parents[cycle[0]] = None;
+            cycles.push(cycle);
+        }
+        for n in path {
+            state[n] = 2;
+        }
+    }
+    cycles
+}
+
+pub(crate) fn build_tree(entries: &[RawLogEntry], repair: bool) -> ConversationTree {
+    let mut nodes: Vec<&RawLogEntry> = Vec::new();
+    let mut by_uuid: HashMap<&str, usize> = HashMap::new();
+    let mut duplicate_uuids: Vec<String> = Vec::new();
+    for entry in entries {
+        let Some(uuid) = entry.uuid.as_deref() else { continue };
+        if by_uuid.contains_key(uuid) {
+            if !duplicate_uuids.iter().any(|d| d == uuid) {
+                duplicate_uuids.push(uuid.to_string());
+            }
+            continue;
+        }
+        by_uuid.insert(uuid, nodes.len());
+        nodes.push(entry);
+    }
+
+    let mut parents: Vec<Option<usize>> = Vec::with_capacity(nodes.len());
+    let mut orphans: Vec<usize> = Vec::new();
+    for (i, entry) in nodes.iter().enumerate() {
+        let parent = entry.parent_uuid.as_deref().and_then(|p| by_uuid.get(p).copied());
+        if entry.parent_uuid.is_some() && parent.is_none() {
+            orphans.push(i);
+        }
+        parents.push(parent);
+    }
+    let cycles = cut_cycles(&mut parents);
+    let logged_roots: Vec<usize> = (0..nodes.len()).filter(|i| parents[*i].is_none()).collect();
+
+    let mut attached: HashMap<usize, usize> = HashMap::new();
+    if repair {
+        // Chronological order; entries without a timestamp keep their place in the file
+        let mut last: Option<DateTime<Utc>> = None;
+        let mut keyed: Vec<(Option<DateTime<Utc>>, usize)> = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                last = entry.timestamp.as_deref().and_then(parse_utc).or(last);
+                (last, i)
+            })
+            .collect();
+        keyed.sort();
+        let order: Vec<usize> = keyed.into_iter().map(|(_, i)| i).collect();
+
+        let mut broken: Vec<usize> = orphans.iter().copied().chain(cycles.iter().map(|c| c[0])).collect();
+        broken.sort_by_key(|b| order.iter().position(|o| o == b));
+        for node in broken {
+            let position = order.iter().position(|o| *o == node).unwrap_or(0);
+            let target = order[..position].iter().rev().copied().find(|c| !is_ancestor(&parents, node, *c));
+            if let Some(target) = target {
+                parents[node] = Some(target);
+                attached.insert(node, target);
+            }
+        }
+    }
+
+    let mut depths: Vec<Option<u32>> = vec![None; nodes.len()];
+    let mut child_counts = vec![0u32; nodes.len()];
+    for i in 0..nodes.len() {
+        if let Some(parent) = parents[i] {
+            child_counts[parent] += 1;
+        }
+        // Climb to a root or an already known depth, then fill in the way back
+        let mut chain = Vec::new();
+        let mut node = i;
+        let mut depth = loop {
+            if let Some(depth) = depths[node] {
+                break depth;
+            }
+            match parents[node] {
+                Some(parent) => {
+                    chain.push(node);
+                    node = parent;
+                }
+                None => {
+                    depths[node] = Some(0);
+                    break 0;
+                }
+            }
+        };
+        for n in chain.into_iter().rev() {
+            depth += 1;
+            depths[n] = Some(depth);
+        }
+    }
+
+    let uuid = |i: usize| nodes[i].uuid.clone().unwrap_or_default();
+    let tree_nodes: Vec<TreeNode> = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| TreeNode {
+            uuid: uuid(i),
+            parent_uuid: parents[i].map(uuid),
+            logged_parent_uuid: entry.parent_uuid.clone(),
+            entry_type: entry.entry_type.clone(),
+            timestamp: entry.timestamp.clone(),
+            is_sidechain: entry.is_sidechain == Some(true),
+            depth: depths[i].unwrap_or(0),
+            child_count: child_counts[i],
+            repaired: attached.contains_key(&i),
+        })
+        .collect();
+
+    ConversationTree {
+        root_uuids: (0..nodes.len()).filter(|i| parents[*i].is_none()).map(uuid).collect(),
+        diagnostics: TreeDiagnostics {
+            orphans: orphans
+                .iter()
+                .map(|&i| OrphanNode {
+                    uuid: uuid(i),
+                    missing_parent_uuid: nodes[i].parent_uuid.clone().unwrap_or_default(),
+                    attached_to: attached.get(&i).map(|&t| uuid(t)),
+                })
+                .collect(),
+            cycles: cycles.iter().map(|cycle| cycle.iter().map(|&i| uuid(i)).collect()).collect(),
+            multiple_roots: logged_roots.len() > 1,
+            root_uuids: logged_roots.into_iter().map(uuid).collect(),
+            duplicate_uuids,
+        },
+        nodes: tree_nodes,
+    }
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// The message tree of a session with diagnostics on orphans, cycles and
+/// multiple roots
+#[napi(catch_unwind)]
+pub fn build_conversation_tree(file_path: String, options: Option<TreeOptions>) -> Result<ConversationTree> {
+    let repair = options.unwrap_or_default().repair.unwrap_or(false);
+    let mut entries = Vec::new();
+    for_each_entry(&file_path, |entry| entries.push(entry))?;
+    Ok(build_tree(&entries, repair))
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_jsonl_line;
+
+    #[test]
+    fn test_anomalies_and_repair() {
+        let lines = [
+            r#"{"type":"user","uuid":"a","timestamp":"2024-01-01T10:00:00Z"}"#,
+            r#"{"type":"assistant","uuid":"b","parentUuid":"a","timestamp":"2024-01-01T10:00:01Z"}"#,
+            r#"{"type":"user","uuid":"c","parentUuid":"gone","timestamp":"2024-01-01T10:00:02Z"}"#,
+            r#"{"type":"assistant","uuid":"d","parentUuid":"c","timestamp":"2024-01-01T10:00:03Z"}"#,
+            r#"{"type":"user","uuid":"x","parentUuid":"y","timestamp":"2024-01-01T10:00:04Z"}"#,
+            r#"{"type":"assistant","uuid":"y","parentUuid":"x","timestamp":"2024-01-01T10:00:05Z"}"#,
+            r#"{"type":"assistant","uuid":"b","parentUuid":"a"}"#,
+        ];
+        let entries: Vec<RawLogEntry> = lines.iter().map(|l| parse_jsonl_line(l).unwrap()).collect();
+
+        let tree = build_tree(&entries, false);
+        let diagnostics = &tree.diagnostics;
+        assert_eq!(diagnostics.orphans[0].missing_parent_uuid, "gone");
+        assert_eq!(diagnostics.cycles, vec![vec!["x".to_string(), "y".to_string()]]);
+        assert_eq!(diagnostics.root_uuids, vec!["a", "c", "x"]);
+        assert!(diagnostics.multiple_roots);
+        assert_eq!(diagnostics.duplicate_uuids, vec!["b"]);
+        assert_eq!(tree.nodes.iter().map(|n| n.depth).collect::<Vec<_>>(), vec![0, 1, 0, 1, 0, 1]);
+
+        let repaired = build_tree(&entries, true);
+        assert_eq!(repaired.root_uuids, vec!["a"]);
+        assert_eq!(repaired.diagnostics.orphans[0].attached_to.as_deref(), Some("b"));
+        // x is attached after d, the message logged just before it
+        assert_eq!(repaired.nodes[4].parent_uuid.as_deref(), Some("d"));
+        assert!(repaired.nodes[4].repaired && !repaired.nodes[5].repaired);
+        assert_eq!(repaired.nodes.iter().map(|n| n.depth).collect::<Vec<_>>(), vec![0, 1, 2, 3, 4, 5]);
+    }
+}
@@ -0,0 +1,629 @@
+// ============================================
+// USAGE REPORTS
+// Token and message totals per model, project and day over a period,
+// rendered as a shareable Markdown or HTML report, and merged across
+// the stats files of a whole team
+// ============================================
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+use chrono::{DateTime, Duration, Utc};
+use chrono_tz::Tz;
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use whatlang::Lang;
+
+use crate::errors::{internal, invalid_argument, io_error, unsupported_format, ErrorCode, HubError};
+use crate::language::{parse_language, session_languages};
+use crate::paths::read_to_string;
+use crate::safe_write::write_file;
+use crate::similarity::fnv1a;
+use crate::time::{parse_utc, resolve_timezone};
+use crate::{collect_session_files, for_each_entry};
+
+/// Width of the longest bar in the HTML chart
+const BAR_WIDTH_PX: f64 = 300.0;
+
+/// Options for get_usage_stats / generate_usage_report
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct UsageOptions {
+    /// IANA timezone for day buckets (default UTC)
+    pub timezone: Option<String>,
+    /// Name recorded in the stats for team reports (default $USER / %USERNAME%)
+    pub user: Option<String>,
+    /// Let write_usage_stats replace an existing output file (default false)
+    pub overwrite: Option<bool>,
+    /// Only count sessions whose primary language is this ISO 639-3 code
+    /// or English name (see detect_session_languages)
+    pub language: Option<String>,
+}
+
+/// Options for export_stats_only
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct StatsExportOptions {
+    /// "day", "week", "month" or "all" (default "all")
+    pub period: Option<String>,
+    pub timezone: Option<String>,
+    /// Name recorded in the stats (default none; the login name is not
+    /// filled in, so the file does not identify whoever exported it)
+    pub user: Option<String>,
+    /// Replace project paths with stable "project-1a2b3c4d" ids (default false)
+    pub redact_projects: Option<bool>,
+    /// Replace an existing output file (default false)
+    pub overwrite: Option<bool>,
+}
+
+/// Totals for one model, project, day or the whole period
+#[napi(object)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageRow {
+    /// Model name, project path, YYYY-MM-DD date or "total"
+    pub key: String,
+    pub sessions: u32,
+    pub messages: u32,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_creation_tokens: i64,
+    pub cache_read_tokens: i64,
+}
+
+/// Usage of one machine over a period; serializable so it can be shared and merged
+#[napi(object)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageStats {
+    /// Who produced the stats, see UsageOptions.user
+    #[serde(default)]
+    pub user: Option<String>,
+    /// "day", "week", "month" or "all"
+    pub period: String,
+    pub from: Option<String>,
+    pub to: String,
+    pub timezone: String,
+    pub totals: UsageRow,
+    /// Most output tokens first
+    pub by_model: Vec<UsageRow>,
+    pub by_project: Vec<UsageRow>,
+    /// Oldest day first
+    pub daily: Vec<UsageRow>,
+}
+
+/// One member's totals in a team report
+#[napi(object)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UserUsage {
+    /// From the stats file, else the file name
+    pub user: String,
+    pub source_path: String,
+    pub period: String,
+    pub totals: UsageRow,
+    pub by_model: Vec<UsageRow>,
+}
+
+/// Several members' stats merged; rows are summed by key
+#[napi(object)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamUsage {
+    /// Earliest start and latest end of the merged periods
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub totals: UsageRow,
+    /// Most output tokens first
+    pub users: Vec<UserUsage>,
+    pub by_model: Vec<UsageRow>,
+    pub by_project: Vec<UsageRow>,
+    /// Oldest day first
+    pub daily: Vec<UsageRow>,
+}
+
+/// Row plus the sessions seen for it
+#[derive(Default)]
+struct RowBuilder {
+    row: UsageRow,
+    sessions: HashSet<String>,
+}
+
+impl RowBuilder {
+    /// Count one message and its tokens (None when already counted)
+    fn add(&mut self, session_id: &str, tokens: Option<&crate::TokenUsage>) {
+        if !self.sessions.contains(session_id) {
+            self.sessions.insert(session_id.to_string());
+        }
+        self.row.messages += 1;
+        if let Some(usage) = tokens {
+            self.row.input_tokens += usage.input_tokens as i64;
+            self.row.output_tokens += usage.output_tokens as i64;
+            self.row.cache_creation_tokens += usage.cache_creation_input_tokens.unwrap_or(0) as i64;
+            self.row.cache_read_tokens += usage.cache_read_input_tokens.unwrap_or(0) as i64;
+        }
+    }
+
+    fn finish(self, key: String) -> UsageRow {
+        UsageRow { key, sessions: self.sessions.len() as u32, ..self.row }
+    }
+}
+
+/// Start of a rolling period ending now, None for "all"
+pub(crate) fn period_start(period: &str, now: DateTime<Utc>) -> Result<Option<DateTime<Utc>>> {
+    match period {
+        "day" => Ok(Some(now - Duration::days(1))),
+        "week" => Ok(Some(now - Duration::days(7))),
+        "month" => Ok(Some(now - Duration::days(30))),
+        "all" => Ok(None),
+        other => Err(invalid_argument(format!("Unknown period: {}", other))),
+    }
+}
+
+/// One user or assistant message seen by for_each_usage
+pub(crate) struct UsageEvent<'a> {
+    pub file_path: &'a str,
+    pub session_id: &'a str,
+    /// cwd of the session, else the directory of its file
+    pub project: &'a str,
+    pub model: Option<&'a str>,
+    pub timestamp: DateTime<Utc>,
+    /// None for user messages and for repeats of an already counted response
+    pub usage: Option<&'a crate::TokenUsage>,
+}
+
+/// Feed every timestamped message under root_dir within [from, now] to `f`
+pub(crate) fn for_each_usage(
+    root_dir: &str,
+    from: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+    mut f: impl FnMut(&UsageEvent),
+) -> Result<()> {
+    // Claude Code repeats the usage of one API response on every entry it
+    // splits into, and resumed or forked sessions copy those entries into
+    // other files, so responses are counted once across the whole scan
+    let mut counted_responses: HashSet<(Option<String>, Option<String>)> = HashSet::new();
+    for path in collect_session_files(root_dir)? {
+        let file_path = path.display().to_string();
+        let fallback_project = path.parent().map(|p| p.display().to_string()).unwrap_or_default();
+        let mut cwd: Option<String> = None;
+
+        let _ = for_each_entry(&file_path, |entry| {
+            if cwd.is_none() {
+                cwd = entry.cwd.clone();
+            }
+            if entry.entry_type != "user" && entry.entry_type != "assistant" {
+                return;
+            }
+            let Some(message) = &entry.message else { return };
+            let Some(dt) = entry.timestamp.as_deref().and_then(parse_utc) else { return };
+            if from.is_some_and(|f| dt < f) || dt > now {
+                return;
+            }
+
+            let usage = message.usage.as_ref().filter(|_| match (&message.id, &entry.request_id) {
+                (None, None) => true,
+                (id, request_id) => counted_responses.insert((id.clone(), request_id.clone())),
+            });
+            f(&UsageEvent {
+                file_path: &file_path,
+                session_id: entry.session_id.as_deref().unwrap_or(&file_path),
+                project: cwd.as_deref().unwrap_or(&fallback_project),
+                model: message.model.as_deref(),
+                timestamp: dt,
+                usage,
+            });
+        });
+    }
+    Ok(())
+}
+
+fn collect_usage(root_dir: &str, period: &str, tz: Tz, now: DateTime<Utc>, language: Option<Lang>) -> Result<UsageStats> {
+    let from = period_start(period, now)?;
+    // Whether each session file is in the language, detected once per file
+    let mut in_language: HashMap<String, bool> = HashMap::new();
+
+    let mut totals = RowBuilder::default();
+    let mut models: HashMap<String, RowBuilder> = HashMap::new();
+    let mut projects: HashMap<String, RowBuilder> = HashMap::new();
+    let mut days: BTreeMap<String, RowBuilder> = BTreeMap::new();
+
+    for_each_usage(root_dir, from, now, |event| {
+        if let Some(lang) = language {
+            let keep = in_language.entry(event.file_path.to_string()).or_insert_with(|| {
+                let primary = session_languages(event.file_path).ok().and_then(|l| l.primary);
+                primary.as_deref() == Some(lang.code())
+            });
+            if !*keep {
+                return;
+            }
+        }
+        let day = event.timestamp.with_timezone(&tz).format("%Y-%m-%d").to_string();
+        totals.add(event.session_id, event.usage);
+        projects.entry(event.project.to_string()).or_default().add(event.session_id, event.usage);
+        days.entry(day).or_default().add(event.session_id, event.usage);
+        if let Some(model) = event.model {
+            models.entry(model.to_string()).or_default().add(event.session_id, event.usage);
+        }
+    })?;
+
+    let sorted = |rows: HashMap<String, RowBuilder>| {
+        let mut rows: Vec<UsageRow> = rows.into_iter().map(|(key, b)| b.finish(key)).collect();
+        rows.sort_by(|a, b| b.output_tokens.cmp(&a.output_tokens).then_with(|| a.key.cmp(&b.key)));
+        rows
+    };
+
+    Ok(UsageStats {
+        user: None,
+        period: period.to_string(),
+        from: from.map(|f| f.to_rfc3339()),
+        to: now.to_rfc3339(),
+        timezone: tz.name().to_string(),
+        totals: totals.finish("total".to_string()),
+        by_model: sorted(models),
+        by_project: sorted(projects),
+        daily: days.into_iter().map(|(key, b)| b.finish(key)).collect(),
+    })
+}
+
+/// Add the numbers of `rows` into `into`, matching by key
+fn merge_rows(into: &mut BTreeMap<String, UsageRow>, rows: &[UsageRow]) {
+    for row in rows {
+        let merged = into.entry(row.key.clone()).or_insert_with(|| UsageRow { key: row.key.clone(), ..Default::default() });
+        merged.sessions += row.sessions;
+        merged.messages += row.messages;
+        merged.input_tokens += row.input_tokens;
+        merged.output_tokens += row.output_tokens;
+        merged.cache_creation_tokens += row.cache_creation_tokens;
+        merged.cache_read_tokens += row.cache_read_tokens;
+    }
+}
+
+fn by_output_tokens(rows: BTreeMap<String, UsageRow>) -> Vec<UsageRow> {
+    let mut rows: Vec<UsageRow> = rows.into_values().collect();
+    rows.sort_by_key(|r| std::cmp::Reverse(r.output_tokens));
+    rows
+}
+
+pub(crate) fn merge_team(stats: Vec<(String, UsageStats)>) -> TeamUsage {
+    let mut totals = BTreeMap::new();
+    let mut models = BTreeMap::new();
+    let mut projects = BTreeMap::new();
+    let mut days = BTreeMap::new();
+    let mut users = Vec::new();
+    let mut from: Option<String> = None;
+    let mut to: Option<String> = None;
+    let mut unbounded = false;
+
+    for (source_path, stats) in stats {
+        merge_rows(&mut totals, std::slice::from_ref(&stats.totals));
+        merge_rows(&mut models, &stats.by_model);
+        merge_rows(&mut projects, &stats.by_project);
+        merge_rows(&mut days, &stats.daily);
+
+        // RFC 3339 in UTC compares chronologically as text
+        match &stats.from {
+            Some(f) if !unbounded && from.as_ref().is_none_or(|cur| f < cur) => from = Some(f.clone()),
+            None => unbounded = true,
+            _ => {}
+        }
+        if to.as_ref().is_none_or(|cur| &stats.to > cur) {
+            to = Some(stats.to.clone());
+        }
+
+        let user = stats.user.clone().unwrap_or_else(|| {
+            std::path::Path::new(&source_path).file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default()
+        });
+        users.push(UserUsage { user, source_path, period: stats.period, totals: stats.totals, by_model: stats.by_model });
+    }
+    users.sort_by_key(|u| std::cmp::Reverse(u.totals.output_tokens));
+
+    TeamUsage {
+        from: if unbounded { None } else { from },
+        to,
+        totals: UsageRow { key: "total".to_string(), ..totals.remove("total").unwrap_or_default() },
+        users,
+        by_model: by_output_tokens(models),
+        by_project: by_output_tokens(projects),
+        daily: days.into_values().collect(),
+    }
+}
+
+/// Same path, same id on every machine, without revealing the path
+fn redacted_project(path: &str) -> String {
+    format!("project-{:08x}", fnv1a(path.as_bytes()) as u32)
+}
+
+fn write_json(output_path: &str, stats: &UsageStats, overwrite: bool) -> Result<()> {
+    let json = serde_json::to_string_pretty(stats)
+        .map_err(|e| internal(format!("Cannot serialize usage stats: {}", e)))?;
+    write_file(Path::new(output_path), json.as_bytes(), overwrite)
+}
+
+// ============================================
+// RENDERING
+// ============================================
+
+/// 1234567 -> "1,234,567"
+fn thousands(value: i64) -> String {
+    let digits = value.unsigned_abs().to_string();
+    let mut out = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    if value < 0 { format!("-{}", out) } else { out }
+}
+
+fn period_title(stats: &UsageStats) -> String {
+    match stats.period.as_str() {
+        "day" => "last 24 hours".to_string(),
+        "week" => "last 7 days".to_string(),
+        "month" => "last 30 days".to_string(),
+        _ => "all time".to_string(),
+    }
+}
+
+const TABLE_HEADERS: [&str; 6] = ["Sessions", "Messages", "Input tokens", "Output tokens", "Cache write", "Cache read"];
+
+fn row_cells(row: &UsageRow) -> [String; 6] {
+    [
+        row.sessions.to_string(),
+        row.messages.to_string(),
+        thousands(row.input_tokens),
+        thousands(row.output_tokens),
+        thousands(row.cache_creation_tokens),
+        thousands(row.cache_read_tokens),
+    ]
+}
+
+fn markdown_table(out: &mut String, first_header: &str, rows: &[UsageRow]) {
+    out.push_str(&format!("| {} | {} |\n", first_header, TABLE_HEADERS.join(" | ")));
+    out.push_str(&format!("|---|{}\n", "---:|".repeat(TABLE_HEADERS.len())));
+    for row in rows {
+        out.push_str(&format!("| {} | {} |\n", row.key.replace('|', "\\|"), row_cells(row).join(" | ")));
+    }
+    out.push('\n');
+}
+
+pub(crate) fn render_markdown(stats: &UsageStats) -> String {
+    let mut out = format!("# Claude Code usage - {}\n\n", period_title(stats));
+    let from = stats.from.as_deref().map(|f| f.get(..10).unwrap_or(f)).unwrap_or("start");
+    out.push_str(&format!("_{} to {} ({})_\n\n", from, stats.to.get(..10).unwrap_or(&stats.to), stats.timezone));
+
+    out.push_str("## Totals\n\n");
+    markdown_table(&mut out, "", std::slice::from_ref(&stats.totals));
+    out.push_str("## By model\n\n");
+    markdown_table(&mut out, "Model", &stats.by_model);
+    out.push_str("## By project\n\n");
+    markdown_table(&mut out, "Project", &stats.by_project);
+
+    out.push_str("## Daily\n\n");
+    markdown_table(&mut out, "Date", &stats.daily);
+    if !stats.daily.is_empty() {
+        // Renders as a chart on GitHub and in most Markdown viewers with Mermaid
+        let dates: Vec<String> = stats.daily.iter().map(|d| format!("\"{}\"", d.key)).collect();
+        let tokens: Vec<String> = stats.daily.iter().map(|d| (d.input_tokens + d.output_tokens).to_string()).collect();
+        out.push_str("```mermaid\nxychart-beta\n    title \"Tokens per day\"\n");
+        out.push_str(&format!("    x-axis [{}]\n    bar [{}]\n```\n", dates.join(", "), tokens.join(", ")));
+    }
+    out
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn html_table(out: &mut String, title: &str, first_header: &str, rows: &[UsageRow]) {
+    out.push_str(&format!("<h2>{}</h2>\n<table>\n<tr><th>{}</th>", title, first_header));
+    for header in TABLE_HEADERS {
+        out.push_str(&format!("<th>{}</th>", header));
+    }
+    out.push_str("</tr>\n");
+    for row in rows {
+        out.push_str(&format!("<tr><td>{}</td>", html_escape(&row.key)));
+        for cell in row_cells(row) {
+            out.push_str(&format!("<td class=\"n\">{}</td>", cell));
+        }
+        out.push_str("</tr>\n");
+    }
+    out.push_str("</table>\n");
+}
+
+pub(crate) fn render_html(stats: &UsageStats) -> String {
+    let title = format!("Claude Code usage - {}", period_title(stats));
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+         <style>body{{font-family:sans-serif;margin:2em}}table{{border-collapse:collapse;margin-bottom:1.5em}}\
+         th,td{{border:1px solid #ddd;padding:4px 8px}}td.n{{text-align:right}}.bar{{background:#6366f1;height:12px}}</style>\n\
+         </head>\n<body>\n<h1>{}</h1>\n",
+        html_escape(&title),
+        html_escape(&title)
+    );
+
+    html_table(&mut out, "Totals", "", std::slice::from_ref(&stats.totals));
+    html_table(&mut out, "By model", "Model", &stats.by_model);
+    html_table(&mut out, "By project", "Project", &stats.by_project);
+    html_table(&mut out, "Daily", "Date", &stats.daily);
+
+    let max = stats.daily.iter().map(|d| d.input_tokens + d.output_tokens).max().unwrap_or(0).max(1);
+    out.push_str("<h2>Tokens per day</h2>\n<table>\n");
+    for day in &stats.daily {
+        let tokens = day.input_tokens + day.output_tokens;
+        let width = (tokens as f64 / max as f64 * BAR_WIDTH_PX).round() as i64;
+        out.push_str(&format!(
+            "<tr><td>{}</td><td><div class=\"bar\" style=\"width:{}px\"></div></td><td class=\"n\">{}</td></tr>\n",
+            day.key,
+            width,
+            thousands(tokens)
+        ));
+    }
+    out.push_str("</table>\n");
+
+    // Raw numbers for anyone who wants to re-chart them
+    let data = serde_json::to_string(&stats.daily).unwrap_or_default().replace("</", "<\\/");
+    out.push_str(&format!("<script type=\"application/json\" id=\"daily-usage\">{}</script>\n", data));
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Usage totals under root_dir for "day", "week", "month" (rolling) or "all"
+#[napi(catch_unwind)]
+pub fn get_usage_stats(root_dir: String, period: String, options: Option<UsageOptions>) -> Result<UsageStats> {
+    let options = options.unwrap_or_default();
+    let tz = resolve_timezone(options.timezone.as_deref())?;
+    let language = options.language.as_deref().map(parse_language).transpose()?;
+    let user = options
+        .user
+        .or_else(|| std::env::var("USER").ok())
+        .or_else(|| std::env::var("USERNAME").ok());
+    Ok(UsageStats { user, ..collect_usage(&root_dir, &period, tz, Utc::now(), language)? })
+}
+
+/// Write get_usage_stats as JSON to output_path, for aggregate_team_usage elsewhere
+#[napi(catch_unwind)]
+pub fn write_usage_stats(
+    root_dir: String,
+    period: String,
+    output_path: String,
+    options: Option<UsageOptions>,
+) -> Result<UsageStats> {
+    let overwrite = options.as_ref().and_then(|o| o.overwrite).unwrap_or(false);
+    let stats = get_usage_stats(root_dir, period, options)?;
+    write_json(&output_path, &stats, overwrite)?;
+    Ok(stats)
+}
+
+/// Write aggregate metrics only - counts, tokens, models, days and (optionally
+/// redacted) project names, never any message text - safe to hand to finance
+#[napi(catch_unwind)]
+pub fn export_stats_only(root_dir: String, output: String, options: Option<StatsExportOptions>) -> Result<UsageStats> {
+    let options = options.unwrap_or_default();
+    let period = options.period.unwrap_or_else(|| "all".to_string());
+    let tz = resolve_timezone(options.timezone.as_deref())?;
+    let mut stats = UsageStats { user: options.user, ..collect_usage(&root_dir, &period, tz, Utc::now(), None)? };
+
+    if options.redact_projects.unwrap_or(false) {
+        for row in &mut stats.by_project {
+            row.key = redacted_project(&row.key);
+        }
+    }
+    write_json(&output, &stats, options.overwrite.unwrap_or(false))?;
+    Ok(stats)
+}
+
+/// Merge stats files written by write_usage_stats on each member's machine
+#[napi(catch_unwind)]
+pub fn aggregate_team_usage(paths: Vec<String>) -> Result<TeamUsage> {
+    let mut stats = Vec::with_capacity(paths.len());
+    for path in paths {
+        let text = read_to_string(&path).map_err(|e| io_error("Cannot open file", &path, e))?;
+        let parsed: UsageStats = serde_json::from_str(&text)
+            .map_err(|e| Error::from(HubError::new(ErrorCode::ParseError, format!("Invalid usage stats: {}", e)).path(&path)))?;
+        stats.push((path, parsed));
+    }
+    Ok(merge_team(stats))
+}
+
+/// Shareable usage report; format is "markdown" or "html"
+#[napi(catch_unwind)]
+pub fn generate_usage_report(
+    root_dir: String,
+    period: String,
+    format: String,
+    options: Option<UsageOptions>,
+) -> Result<String> {
+    let render = match format.as_str() {
+        "markdown" => render_markdown,
+        "html" => render_html,
+        other => return Err(unsupported_format(format!("Unknown report format: {}", other))),
+    };
+    Ok(render(&get_usage_stats(root_dir, period, options)?))
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_usage_report() {
+        let root = TempDir::new("usage-test");
+        std::fs::create_dir_all(root.join("proj")).unwrap();
+        std::fs::write(
+            root.join("proj/s1.jsonl"),
+            r#"{"type":"user","sessionId":"s1","cwd":"/work/app","timestamp":"2024-01-01T10:00:00Z","message":{"role":"user","content":"hi"}}
+{"type":"assistant","sessionId":"s1","timestamp":"2024-01-01T10:00:05Z","message":{"id":"r1","role":"assistant","model":"opus","content":"a","usage":{"input_tokens":1000,"output_tokens":200}}}
+{"type":"assistant","sessionId":"s1","timestamp":"2024-01-01T10:00:06Z","message":{"id":"r1","role":"assistant","model":"opus","content":"b","usage":{"input_tokens":1000,"output_tokens":200}}}
+{"type":"assistant","sessionId":"s1","timestamp":"2023-12-01T10:00:00Z","message":{"id":"r0","role":"assistant","model":"haiku","content":"old","usage":{"input_tokens":5,"output_tokens":5}}}"#,
+        )
+        .unwrap();
+
+        let now = parse_utc("2024-01-03T00:00:00Z").unwrap();
+        let stats = collect_usage(&root.to_string_lossy(), "week", Tz::UTC, now, None).unwrap();
+        assert_eq!((stats.totals.messages, stats.totals.sessions), (3, 1));
+        // The split response is counted once
+        assert_eq!((stats.totals.input_tokens, stats.totals.output_tokens), (1000, 200));
+        assert_eq!(stats.by_model.iter().map(|r| r.key.as_str()).collect::<Vec<_>>(), vec!["opus"]);
+        assert_eq!(stats.by_project[0].key, "/work/app");
+        assert_eq!(stats.daily[0].key, "2024-01-01");
+
+        let markdown = render_markdown(&stats);
+        assert!(markdown.starts_with("# Claude Code usage - last 7 days\n\n_2023-12-27 to 2024-01-03 (UTC)_"));
+        assert!(markdown.contains("| opus | 1 | 2 | 1,000 | 200 | 0 | 0 |"));
+        assert!(markdown.contains("x-axis [\"2024-01-01\"]\n    bar [1200]"));
+        assert!(render_html(&stats).contains("<td>/work/app</td>"));
+        assert!(collect_usage(&root.to_string_lossy(), "year", Tz::UTC, now, None).is_err());
+        // "hi" is too short to tell, so the session has no primary language
+        let vietnamese = collect_usage(&root.to_string_lossy(), "week", Tz::UTC, now, Some(whatlang::Lang::Vie)).unwrap();
+        assert_eq!(vietnamese.totals.messages, 0);
+
+        let other = UsageStats { user: Some("bob".into()), ..stats.clone() };
+        let team = merge_team(vec![("alice.json".into(), stats), ("b.json".into(), other)]);
+        assert_eq!(team.users.iter().map(|u| u.user.as_str()).collect::<Vec<_>>(), vec!["alice", "bob"]);
+        assert_eq!((team.totals.key.as_str(), team.totals.output_tokens), ("total", 400));
+        assert_eq!(team.by_model[0].messages, 4);
+        assert_eq!(redacted_project("/work/app"), redacted_project("/work/app"));
+        assert!(redacted_project("/work/app").starts_with("project-") && redacted_project("/work/app").len() == 16);
+
+        // The stats-only export names no one unless asked to
+        let output = root.join("stats.json");
+        let redact = StatsExportOptions { redact_projects: Some(true), ..Default::default() };
+        let exported = export_stats_only(root.display().to_string(), output.display().to_string(), Some(redact)).unwrap();
+        assert_eq!(exported.user, None);
+        assert!(exported.by_project[0].key.starts_with("project-"));
+        assert!(!std::fs::read_to_string(&output).unwrap().contains("/work/app"));
+    }
+
+    #[test]
+    fn test_response_shared_across_files_counted_once() {
+        let root = TempDir::new("usage-shared");
+        std::fs::create_dir_all(root.join("proj")).unwrap();
+        let line = |session: &str, id: &str, request: &str| {
+            format!(
+                r#"{{"type":"assistant","sessionId":"{}","requestId":"{}","timestamp":"2024-01-01T10:00:05Z","message":{{"id":"{}","role":"assistant","model":"opus","content":"a","usage":{{"input_tokens":100,"output_tokens":20}}}}}}"#,
+                session, request, id
+            )
+        };
+        // A resumed session copies the original response; another request reuses the id
+        std::fs::write(root.join("proj/s1.jsonl"), line("s1", "r1", "q1")).unwrap();
+        std::fs::write(root.join("proj/s2.jsonl"), [line("s2", "r1", "q1"), line("s2", "r1", "q2")].join("\n")).unwrap();
+
+        let now = parse_utc("2024-01-03T00:00:00Z").unwrap();
+        let stats = collect_usage(&root.to_string_lossy(), "week", Tz::UTC, now, None).unwrap();
+        assert_eq!((stats.totals.messages, stats.totals.sessions), (3, 2));
+        assert_eq!((stats.totals.input_tokens, stats.totals.output_tokens), (200, 40));
+    }
+}
@@ -0,0 +1,217 @@
+// ============================================
+// SESSION WATCHER
+// Poll a projects directory for created, changed, deleted and moved
+// session files; a rename or rewrite keeps its identity as session_moved
+// ============================================
+
+use std::collections::HashMap;
+use std::io::BufReader;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use napi::bindgen_prelude::*;
+use napi::threadsafe_function::{ErrorStrategy, ThreadSafeCallContext, ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi::JsFunction;
+use napi_derive::napi;
+
+use crate::follow::file_identity;
+use crate::paths::{metadata, open_file};
+use crate::reader::lossy_lines;
+use crate::{collect_session_files, fast, index};
+
+const DEFAULT_POLL_INTERVAL_MS: u32 = 1000;
+
+/// Lines read looking for a sessionId before giving up
+const SESSION_ID_LINES: usize = 20;
+
+static NEXT_WATCHER_ID: AtomicU32 = AtomicU32::new(1);
+/// Stop flag of each running watcher
+static WATCHERS: Mutex<Option<HashMap<u32, Arc<AtomicBool>>>> = Mutex::new(None);
+
+/// A change under the watched directory
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionFileEvent {
+    /// "session_created", "session_modified", "session_deleted" or "session_moved"
+    pub kind: String,
+    pub file_path: String,
+    /// Where a moved session was before
+    pub previous_path: Option<String>,
+    pub session_id: Option<String>,
+}
+
+/// Options for watch_sessions
+#[napi(object)]
+#[derive(Debug, Clone, Default)]
+pub struct WatchOptions {
+    /// How often the directory is scanned (default 1000 ms)
+    pub poll_interval_ms: Option<u32>,
+}
+
+/// What identifies a session file between scans
+#[derive(Debug, Clone, PartialEq)]
+struct FileStamp {
+    /// (device, inode), None where the platform has none
+    identity: Option<(u64, u64)>,
+    len: u64,
+    modified: Option<SystemTime>,
+    session_id: Option<String>,
+}
+
+pub(crate) fn read_session_id(file_path: &str) -> Option<String> {
+    let file = open_file(file_path).ok()?;
+    lossy_lines(BufReader::new(file))
+        .take(SESSION_ID_LINES)
+        .find_map(|line| fast::peek_entry(line.as_bytes()).and_then(|peek| peek.session_id).map(|id| id.into_owned()))
+}
+
+/// Stamp every session file, reusing known session ids of unchanged files
+fn scan(root_dir: &str, previous: &HashMap<String, FileStamp>) -> Result<HashMap<String, FileStamp>> {
+    let mut stamps = HashMap::new();
+    for path in collect_session_files(root_dir)? {
+        let Ok(metadata) = metadata(&path) else { continue };
+        let file_path = path.display().to_string();
+        let identity = file_identity(&metadata);
+        let known = previous.get(&file_path).filter(|old| old.identity == identity && old.session_id.is_some());
+        let session_id = match known {
+            Some(old) => old.session_id.clone(),
+            None => read_session_id(&file_path),
+        };
+        stamps.insert(file_path, FileStamp { identity, len: metadata.len(), modified: metadata.modified().ok(), session_id });
+    }
+    Ok(stamps)
+}
+
+fn same_session(a: &FileStamp, b: &FileStamp) -> bool {
+    (a.identity.is_some() && a.identity == b.identity) || (a.session_id.is_some() && a.session_id == b.session_id)
+}
+
+/// Changes from old to new; a vanished file matching a new one by inode or
+/// sessionId is reported as moved instead of deleted and created
+fn diff(old: &HashMap<String, FileStamp>, new: &HashMap<String, FileStamp>) -> Vec<SessionFileEvent> {
+    let event = |kind: &str, file_path: &str, previous_path: Option<&str>, stamp: &FileStamp| SessionFileEvent {
+        kind: kind.to_string(),
+        file_path: file_path.to_string(),
+        previous_path: previous_path.map(str::to_string),
+        session_id: stamp.session_id.clone(),
+    };
+
+    let mut removed: Vec<(&String, &FileStamp)> = old.iter().filter(|(path, _)| !new.contains_key(*path)).collect();
+    let mut added: Vec<(&String, &FileStamp)> = new.iter().filter(|(path, _)| !old.contains_key(*path)).collect();
+    let mut changed: Vec<(&String, &FileStamp)> = new
+        .iter()
+        .filter(|(path, stamp)| old.get(*path).is_some_and(|o| (o.len, o.modified, o.identity) != (stamp.len, stamp.modified, stamp.identity)))
+        .collect();
+    removed.sort_by_key(|(path, _)| *path);
+    added.sort_by_key(|(path, _)| *path);
+    changed.sort_by_key(|(path, _)| *path);
+
+    let mut events = Vec::new();
+    let mut created = Vec::new();
+    for (path, stamp) in added {
+        match removed.iter().position(|(_, old_stamp)| same_session(old_stamp, stamp)) {
+            Some(i) => {
+                let (previous, _) = removed.remove(i);
+                events.push(event("session_moved", path, Some(previous), stamp));
+            }
+            None => created.push(event("session_created", path, None, stamp)),
+        }
+    }
+    events.extend(removed.into_iter().map(|(path, stamp)| event("session_deleted", path, None, stamp)));
+    events.extend(created);
+    events.extend(changed.into_iter().map(|(path, stamp)| event("session_modified", path, None, stamp)));
+    events
+}
+
+fn with_watchers<T>(f: impl FnOnce(&mut HashMap<u32, Arc<AtomicBool>>) -> T) -> T {
+    let mut guard = WATCHERS.lock().unwrap_or_else(|e| e.into_inner());
+    f(guard.get_or_insert_with(HashMap::new))
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Call callback with every change to the session files under root_dir
+/// until stop_watching is called, returns the watcher id
+#[napi(ts_args_type = "rootDir: string, callback: (event: SessionFileEvent) => void, options?: WatchOptions | undefined | null", catch_unwind)]
+pub fn watch_sessions(root_dir: String, callback: JsFunction, options: Option<WatchOptions>) -> Result<u32> {
+    let options = options.unwrap_or_default();
+    let interval = Duration::from_millis(options.poll_interval_ms.unwrap_or(DEFAULT_POLL_INTERVAL_MS).max(10) as u64);
+    let mut stamps = scan(&root_dir, &HashMap::new())?;
+
+    let tsfn: ThreadsafeFunction<SessionFileEvent, ErrorStrategy::Fatal> =
+        callback.create_threadsafe_function(0, |ctx: ThreadSafeCallContext<SessionFileEvent>| Ok(vec![ctx.value]))?;
+    let stop = Arc::new(AtomicBool::new(false));
+    let id = NEXT_WATCHER_ID.fetch_add(1, Ordering::Relaxed);
+    with_watchers(|watchers| watchers.insert(id, stop.clone()));
+
+    std::thread::spawn(move || {
+        while !stop.load(Ordering::Relaxed) {
+            std::thread::sleep(interval);
+            // An unreadable root (e.g. mid-rename) is retried on the next tick
+            let Ok(next) = scan(&root_dir, &stamps) else { continue };
+            for event in diff(&stamps, &next) {
+                // Byte offsets of a moved or rewritten file are no longer valid
+                index::forget(event.previous_path.as_deref().unwrap_or(&event.file_path));
+                tsfn.call(event, ThreadsafeFunctionCallMode::Blocking);
+            }
+            stamps = next;
+        }
+    });
+    Ok(id)
+}
+
+/// Stop a watcher, returns false if it was already stopped
+#[napi(catch_unwind)]
+pub fn stop_watching(watcher: u32) -> bool {
+    match with_watchers(|watchers| watchers.remove(&watcher)) {
+        Some(stop) => {
+            stop.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn test_rename_and_rewrite_are_moves() {
+        let root = TempDir::new("watch");
+        let project = root.join("-work-app");
+        std::fs::create_dir_all(&project).unwrap();
+        let line = |sid: &str| format!(r#"{{"type":"user","sessionId":"{}","message":{{"role":"user","content":"hi"}}}}"#, sid);
+        std::fs::write(project.join("a.jsonl"), line("s1")).unwrap();
+        std::fs::write(project.join("b.jsonl"), line("s2")).unwrap();
+        let root_dir = root.display().to_string();
+        let first = scan(&root_dir, &HashMap::new()).unwrap();
+        assert_eq!(first.values().filter(|s| s.session_id.is_some()).count(), 2);
+
+        // a is renamed, b is rewritten as a new file (new inode, same sessionId)
+        std::fs::rename(project.join("a.jsonl"), project.join("a2.jsonl")).unwrap();
+        std::fs::remove_file(project.join("b.jsonl")).unwrap();
+        std::fs::write(project.join("b-resumed.jsonl"), format!("{}\n{}", line("s2"), line("s2"))).unwrap();
+        std::fs::write(project.join("c.jsonl"), line("s3")).unwrap();
+
+        let events = diff(&first, &scan(&root_dir, &first).unwrap());
+        let summary: Vec<(&str, String, Option<String>)> = events
+            .iter()
+            .map(|e| (e.kind.as_str(), e.file_path.rsplit('/').next().unwrap().to_string(), e.session_id.clone()))
+            .collect();
+        assert_eq!(summary, vec![
+            ("session_moved", "a2.jsonl".to_string(), Some("s1".to_string())),
+            ("session_moved", "b-resumed.jsonl".to_string(), Some("s2".to_string())),
+            ("session_created", "c.jsonl".to_string(), Some("s3".to_string())),
+        ]);
+        assert!(events[0].previous_path.as_deref().unwrap().ends_with("a.jsonl"));
+    }
+}
@@ -0,0 +1,160 @@
+// ============================================
+// WEB ACTIVITY
+// WebSearch and WebFetch calls of a session paired with their results,
+// so users can audit what the agent looked up online
+// ============================================
+
+use std::collections::HashMap;
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::title::truncate_words;
+use crate::{for_each_entry, tool_result_text, ContentItem, RawLogEntry};
+
+const SUMMARY_CHARS: usize = 300;
+
+/// One WebSearch or WebFetch call
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebLookup {
+    /// "search" or "fetch"
+    pub kind: String,
+    pub tool_use_id: String,
+    /// uuid of the assistant entry that made the call
+    pub message_id: Option<String>,
+    pub timestamp: Option<String>,
+    /// Search terms
+    pub query: Option<String>,
+    /// Fetched URL
+    pub url: Option<String>,
+    /// What the agent asked about the fetched page
+    pub prompt: Option<String>,
+    /// Links found in the result, in order
+    pub result_urls: Vec<String>,
+    /// Start of the result text, None while no result was written
+    pub summary: Option<String>,
+    pub is_error: bool,
+}
+
+/// Result of extract_web_activity
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebActivity {
+    /// Distinct search terms, first use first
+    pub queries: Vec<String>,
+    /// Distinct fetched URLs, first use first
+    pub visited_urls: Vec<String>,
+    pub lookups: Vec<WebLookup>,
+}
+
+/// http(s) links in free text, without surrounding quotes or brackets
+fn find_urls(text: &str) -> Vec<String> {
+    let mut urls: Vec<String> = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("http") {
+        let candidate = &rest[start..];
+        let end = candidate
+            .find(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | '<' | '>' | ')' | ']' | '`'))
+            .unwrap_or(candidate.len());
+        let url = candidate[..end].trim_end_matches(['.', ',', ';']);
+        if (url.starts_with("https://") || url.starts_with("http://")) && !urls.iter().any(|u| u == url) {
+            urls.push(url.to_string());
+        }
+        rest = &candidate[end.max(4)..];
+    }
+    urls
+}
+
+fn push_unique(list: &mut Vec<String>, value: Option<&String>) {
+    if let Some(value) = value {
+        if !list.contains(value) {
+            list.push(value.clone());
+        }
+    }
+}
+
+pub(crate) fn web_activity(entries: &[RawLogEntry]) -> WebActivity {
+    let mut lookups: Vec<WebLookup> = Vec::new();
+    let mut by_tool_id: HashMap<String, usize> = HashMap::new();
+
+    for entry in entries {
+        let Some(message) = &entry.message else { continue };
+        for item in &message.content {
+            match item {
+                ContentItem::ToolUse { id, name, input } if name == "WebSearch" || name == "WebFetch" => {
+                    let text = |key: &str| input.get(key).and_then(|v| v.as_str()).map(str::to_string);
+                    by_tool_id.insert(id.clone(), lookups.len());
+                    lookups.push(WebLookup {
+                        kind: if name == "WebSearch" { "search" } else { "fetch" }.to_string(),
+                        tool_use_id: id.clone(),
+                        message_id: entry.uuid.clone(),
+                        timestamp: entry.timestamp.clone(),
+                        query: text("query"),
+                        url: text("url"),
+                        prompt: text("prompt"),
+                        result_urls: Vec::new(),
+                        summary: None,
+                        is_error: false,
+                    });
+                }
+                ContentItem::ToolResult { tool_use_id, content, is_error } => {
+                    let Some(&index) = by_tool_id.get(tool_use_id) else { continue };
+                    let text = tool_result_text(content);
+                    let lookup = &mut lookups[index];
+                    lookup.result_urls = find_urls(&text);
+                    lookup.summary = Some(truncate_words(&text, SUMMARY_CHARS));
+                    lookup.is_error = *is_error == Some(true);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut queries = Vec::new();
+    let mut visited_urls = Vec::new();
+    for lookup in &lookups {
+        push_unique(&mut queries, lookup.query.as_ref());
+        push_unique(&mut visited_urls, lookup.url.as_ref());
+    }
+    WebActivity { queries, visited_urls, lookups }
+}
+
+// ============================================
+// EXPORTED FUNCTIONS
+// ============================================
+
+/// Searches and fetched pages of a session with their results
+#[napi(catch_unwind)]
+pub fn extract_web_activity(file_path: String) -> Result<WebActivity> {
+    let mut entries = Vec::new();
+    for_each_entry(&file_path, |entry| entries.push(entry))?;
+    Ok(web_activity(&entries))
+}
+
+// ============================================
+// TESTS
+// ============================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_jsonl_line;
+
+    #[test]
+    fn test_web_lookups_paired_with_results() {
+        let lines = [
+            r#"{"type":"assistant","uuid":"a1","message":{"role":"assistant","content":[{"type":"tool_use","id":"w1","name":"WebSearch","input":{"query":"serde untagged enum"}},{"type":"tool_use","id":"w2","name":"WebFetch","input":{"url":"https://serde.rs/enum-representations.html","prompt":"How does untagged work?"}}]}}"#,
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"w1","content":"Links: [{\"title\":\"Enum\",\"url\":\"https://serde.rs/enum-representations.html\"},{\"url\":\"https://docs.rs/serde\"}]. See https://docs.rs/serde."}]}}"#,
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"w2","content":"Request failed with status 403","is_error":true}]}}"#,
+        ];
+        let entries: Vec<RawLogEntry> = lines.iter().map(|l| parse_jsonl_line(l).unwrap()).collect();
+        let activity = web_activity(&entries);
+
+        assert_eq!(activity.queries, vec!["serde untagged enum"]);
+        assert_eq!(activity.visited_urls, vec!["https://serde.rs/enum-representations.html"]);
+        assert_eq!(activity.lookups[0].result_urls, vec!["https://serde.rs/enum-representations.html", "https://docs.rs/serde"]);
+        assert!(activity.lookups[1].is_error);
+        assert_eq!(activity.lookups[1].prompt.as_deref(), Some("How does untagged work?"));
+    }
+}